@@ -0,0 +1,26 @@
+//! Integration step over 100k voxels (target: 60 TPS)
+//!
+//! `cargo bench --bench voxel_integrate`
+
+use adaptive_entity_engine::voxel::Voxel;
+use adaptive_entity_engine::voxel_soa::{self, Position, Velocity};
+use bevy_ecs::prelude::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn populate(count: i32) -> World {
+    let mut world = World::new();
+    world.spawn_batch((0..count).map(|i| {
+        (Voxel::new(), Position([i % 100, i / 100 % 100, i / 10_000]), Velocity([1, 0, -1]))
+    }));
+    world
+}
+
+fn bench_integrate(c: &mut Criterion) {
+    let mut world = populate(100_000);
+    c.bench_function("integrate 100k voxels", |b| {
+        b.iter(|| voxel_soa::integrate(&mut world, 1.0 / 60.0, false))
+    });
+}
+
+criterion_group!(benches, bench_integrate);
+criterion_main!(benches);
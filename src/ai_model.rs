@@ -1,29 +1,232 @@
+use crate::gpu::GpuCompute;
+use crate::memory::SemanticMemory;
+use crate::tokenizer::{BpeTokenizer, TextNormalizer, SPECIAL_TOKENS};
 use nalgebra::{DMatrix, DVector};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Минимальный размер матрицы, начиная с которого выгоднее считать на GPU
+const GPU_MIN_ELEMENTS: usize = 16 * 1024;
 
 /// Простая нейронная сеть с поддержкой fp64 для высокоточного обучения
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AIModel {
     pub layers: Vec<Layer>,
     pub learning_rate: f64,
+    /// Шагов обучения, градиенты которых усредняются перед обновлением весов (1 — обновление на каждом шаге)
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
     pub vocab: HashMap<String, usize>,
     pub reverse_vocab: HashMap<usize, String>,
     pub embedding_dim: usize,
     pub hidden_dim: usize,
     pub context_length: usize,
+    #[serde(default)]
+    pub optimizer: Optimizer,
+    #[serde(default)]
+    pub optimizer_state: OptimizerState,
+    /// Subword-токенизатор; если не обучен, используется словарь слов
+    #[serde(default)]
+    pub tokenizer: Option<BpeTokenizer>,
+    /// Первый скрытый слой — GRU вместо полносвязного слоя над склеенными эмбеддингами
+    #[serde(default)]
+    pub recurrent: bool,
+    /// GPU-бэкенд для полносвязных слоёв; `None` — вычисления на CPU
+    #[serde(skip)]
+    pub gpu: Option<Arc<GpuCompute>>,
+    /// Точность вычислений (веса всегда хранятся в f64)
+    #[serde(default)]
+    pub precision: Precision,
+    /// Scheduled sampling при обучении (`None` — всегда teacher forcing)
+    #[serde(default)]
+    pub scheduled_sampling: Option<ScheduledSampling>,
+    /// Скрытые слои; первый определяет `hidden_dim` (пусто — два слоя Tanh по `hidden_dim`)
+    #[serde(default)]
+    pub hidden_layers: Vec<HiddenLayerSpec>,
+    /// Нормализация текста перед токенизацией (`None` — только нижний регистр, как в старых моделях)
+    #[serde(default)]
+    pub normalizer: Option<TextNormalizer>,
+    /// Записывать loss каждого шага в `TrainingReport::step_losses`
+    #[serde(skip)]
+    pub record_step_losses: bool,
+    /// Флаги паузы/остановки, проверяемые между примерами (выставляет `TrainingHandle`)
+    #[serde(skip)]
+    pub training_control: Option<Arc<TrainingControl>>,
+    /// Отложенная выборка: loss на ней считается после каждой эпохи
+    #[serde(skip)]
+    pub validation_texts: Vec<String>,
+    /// Зерно генератора случайных чисел (`None` — случайное при каждом запуске)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(skip)]
+    rng: ModelRng,
+}
+
+/// Генератор случайных чисел модели: инициализация весов, dropout, сэмплирование
+///
+/// Общий для `&self`-методов, поэтому под мьютексом; клон продолжает ту же
+/// последовательность с текущего состояния.
+#[derive(Debug)]
+struct ModelRng(Mutex<StdRng>);
+
+impl ModelRng {
+    fn new(seed: Option<u64>) -> Self {
+        Self(Mutex::new(seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64)))
+    }
+    
+    fn with<R>(&self, f: impl FnOnce(&mut StdRng) -> R) -> R {
+        let mut rng = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut rng)
+    }
+}
+
+impl Default for ModelRng {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Clone for ModelRng {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.with(|rng| rng.clone())))
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Layer {
-    pub weights: Vec<Vec<f64>>,
+    /// Матрица весов outputs × inputs: столбец j — связи от j-го входа
+    #[serde(with = "weights_serde")]
+    pub weights: DMatrix<f64>,
     pub biases: Vec<f64>,
     pub activation: ActivationType,
+    #[serde(default)]
+    pub kind: LayerKind,
+    /// Вероятность dropout выхода слоя (только при обучении)
+    #[serde(default)]
+    pub dropout: f64,
+    /// Нормализация предактиваций слоя (без обучаемых параметров, только Dense)
+    #[serde(default)]
+    pub layer_norm: bool,
+    /// f32-копия весов для вычислений в режиме `Precision::F32`
+    #[serde(skip)]
+    pub compute_weights: Option<DMatrix<f32>>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+impl Layer {
+    /// Слой со случайными весами в диапазоне ±0.1
+    fn random(inputs: usize, outputs: usize, activation: ActivationType, kind: LayerKind, rng: &ModelRng) -> Self {
+        Self {
+            weights: rng.with(|rng| DMatrix::from_fn(outputs, inputs, |_, _| rng.gen_range(-0.1..0.1))),
+            biases: vec![0.0; outputs],
+            activation,
+            kind,
+            dropout: 0.0,
+            layer_norm: false,
+            compute_weights: None,
+        }
+    }
+    
+    /// Количество входов слоя
+    pub fn inputs(&self) -> usize {
+        self.weights.ncols()
+    }
+    
+    /// Размер выхода слоя (у GRU — размер скрытого состояния)
+    pub fn outputs(&self) -> usize {
+        match self.kind {
+            LayerKind::Dense => self.biases.len(),
+            LayerKind::Gru => self.biases.len() / 3,
+        }
+    }
+    
+    /// Веса от входа `column` (столбец матрицы непрерывен в памяти)
+    fn column_mut(&mut self, column: usize) -> &mut [f64] {
+        let rows = self.weights.nrows();
+        &mut self.weights.as_mut_slice()[column * rows..(column + 1) * rows]
+    }
+    
+    /// f32-копия весов, если она есть и совпадает по размеру с мастер-весами
+    fn compute_weights(&self) -> Option<&DMatrix<f32>> {
+        self.compute_weights
+            .as_ref()
+            .filter(|weights| weights.shape() == self.weights.shape())
+    }
+    
+    /// Перенос обновлённого столбца мастер-весов в f32-копию
+    fn sync_compute_column(&mut self, column: usize) {
+        let rows = self.weights.nrows();
+        if self.compute_weights().is_none() {
+            return;
+        }
+        let source = &self.weights.as_slice()[column * rows..(column + 1) * rows];
+        if let Some(weights) = &mut self.compute_weights {
+            let target = &mut weights.as_mut_slice()[column * rows..(column + 1) * rows];
+            target.iter_mut().zip(source).for_each(|(t, &s)| *t = s as f32);
+        }
+    }
+}
+
+/// Сериализация весов в прежнем формате: список векторов по входам
+mod weights_serde {
+    use nalgebra::DMatrix;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    
+    pub fn serialize<S: Serializer>(weights: &DMatrix<f64>, serializer: S) -> Result<S::Ok, S::Error> {
+        let columns: Vec<&[f64]> = weights.as_slice().chunks(weights.nrows().max(1)).collect();
+        columns.serialize(serializer)
+    }
+    
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DMatrix<f64>, D::Error> {
+        let columns: Vec<Vec<f64>> = Vec::deserialize(deserializer)?;
+        let rows = columns.first().map_or(0, Vec::len);
+        if columns.iter().any(|column| column.len() != rows) {
+            return Err(D::Error::custom("строки весов разной длины"));
+        }
+        Ok(DMatrix::from_iterator(rows, columns.len(), columns.into_iter().flatten()))
+    }
+}
+
+/// Тип слоя
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum LayerKind {
+    /// Полносвязный слой
+    #[default]
+    Dense,
+    /// GRU: столбцы весов — [вход; скрытое состояние], строки — гейты [z | r | n]
+    Gru,
+}
+
+/// Точность вычислений при обучении и генерации
+///
+/// В режиме `F32` веса по-прежнему хранятся и обновляются в f64 (мастер-веса),
+/// а матричные умножения полносвязных слоёв выполняются над их f32-копиями.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Precision {
+    #[default]
+    F64,
+    F32,
+}
+
+impl Precision {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Precision::F64 => "fp64",
+            Precision::F32 => "fp32 (мастер-веса fp64)",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ActivationType {
     ReLU,
     Tanh,
@@ -31,16 +234,913 @@ pub enum ActivationType {
     Softmax,
 }
 
+/// Описание скрытого слоя: размер и функция активации
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HiddenLayerSpec {
+    pub size: usize,
+    pub activation: ActivationType,
+}
+
+impl HiddenLayerSpec {
+    pub fn new(size: usize, activation: ActivationType) -> Self {
+        Self { size, activation }
+    }
+    
+    /// Разбор списка вида `"256 tanh, 128 relu"` (активация по умолчанию — tanh)
+    pub fn parse_list(spec: &str) -> Result<Vec<Self>, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let mut words = part.split_whitespace();
+                let size = words
+                    .next()
+                    .and_then(|size| size.parse::<usize>().ok())
+                    .ok_or_else(|| format!("✗ Некорректный размер слоя: '{}'", part))?;
+                let activation = match words.next().map(str::to_lowercase).as_deref() {
+                    None | Some("tanh") => ActivationType::Tanh,
+                    Some("relu") => ActivationType::ReLU,
+                    Some("sigmoid") => ActivationType::Sigmoid,
+                    Some(other) => return Err(format!("✗ Неизвестная активация: '{}'", other)),
+                };
+                Ok(Self::new(size, activation))
+            })
+            .collect()
+    }
+}
+
+/// Построитель модели с произвольным набором скрытых слоёв
+///
+/// `AIModel::new` соответствует двум Tanh-слоям по `hidden_dim`.
+#[derive(Clone)]
+pub struct AIModelBuilder {
+    embedding_dim: usize,
+    context_length: usize,
+    hidden_layers: Vec<HiddenLayerSpec>,
+    recurrent: bool,
+    learning_rate: f64,
+    batch_size: usize,
+    optimizer: Optimizer,
+    vocabulary: Option<Vocabulary>,
+    seed: Option<u64>,
+}
+
+impl Default for AIModelBuilder {
+    fn default() -> Self {
+        Self {
+            embedding_dim: 128,
+            context_length: 8,
+            hidden_layers: Vec::new(),
+            recurrent: false,
+            learning_rate: 0.001,
+            batch_size: 1,
+            optimizer: Optimizer::default(),
+            vocabulary: None,
+            seed: None,
+        }
+    }
+}
+
+impl AIModelBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn embedding_dim(mut self, embedding_dim: usize) -> Self {
+        self.embedding_dim = embedding_dim;
+        self
+    }
+    
+    pub fn context_length(mut self, context_length: usize) -> Self {
+        self.context_length = context_length;
+        self
+    }
+    
+    /// Добавление скрытого слоя (в порядке от входа к выходу)
+    pub fn hidden_layer(mut self, size: usize, activation: ActivationType) -> Self {
+        self.hidden_layers.push(HiddenLayerSpec::new(size, activation));
+        self
+    }
+    
+    pub fn hidden_layers(mut self, layers: impl IntoIterator<Item = HiddenLayerSpec>) -> Self {
+        self.hidden_layers.extend(layers);
+        self
+    }
+    
+    /// Первый скрытый слой — GRU
+    pub fn recurrent(mut self, recurrent: bool) -> Self {
+        self.recurrent = recurrent;
+        self
+    }
+    
+    pub fn learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+    
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+    
+    pub fn optimizer(mut self, optimizer: Optimizer) -> Self {
+        self.optimizer = optimizer;
+        self
+    }
+    
+    /// Фиксированное зерно: одинаковые веса, dropout и сэмплирование при каждом запуске
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+    
+    /// Словарь вместо встроенного списка слов
+    pub fn vocabulary(mut self, vocabulary: Vocabulary) -> Self {
+        self.vocabulary = Some(vocabulary);
+        self
+    }
+    
+    /// Проверка размеров и создание модели со случайными весами
+    pub fn build(self) -> Result<AIModel, String> {
+        if self.embedding_dim == 0 {
+            return Err("✗ Размер эмбеддинга должен быть больше нуля".to_string());
+        }
+        if self.context_length == 0 {
+            return Err("✗ Длина контекста должна быть больше нуля".to_string());
+        }
+        if !(self.learning_rate.is_finite() && self.learning_rate > 0.0) {
+            return Err("✗ Скорость обучения должна быть положительным числом".to_string());
+        }
+        if self.batch_size == 0 {
+            return Err("✗ Размер батча должен быть больше нуля".to_string());
+        }
+        let first = self
+            .hidden_layers
+            .first()
+            .ok_or("✗ Нужен хотя бы один скрытый слой")?;
+        for (idx, layer) in self.hidden_layers.iter().enumerate() {
+            if layer.size == 0 {
+                return Err(format!("✗ Скрытый слой {}: размер должен быть больше нуля", idx + 1));
+            }
+            if layer.activation == ActivationType::Softmax {
+                return Err(format!("✗ Скрытый слой {}: Softmax допустим только на выходе", idx + 1));
+            }
+        }
+        
+        let mut model = AIModel {
+            layers: Vec::new(),
+            learning_rate: self.learning_rate,
+            batch_size: self.batch_size,
+            vocab: HashMap::new(),
+            reverse_vocab: HashMap::new(),
+            embedding_dim: self.embedding_dim,
+            hidden_dim: first.size,
+            context_length: self.context_length,
+            optimizer: self.optimizer,
+            optimizer_state: OptimizerState::default(),
+            tokenizer: None,
+            recurrent: self.recurrent,
+            gpu: None,
+            precision: Precision::F64,
+            scheduled_sampling: None,
+            hidden_layers: self.hidden_layers,
+            normalizer: Some(TextNormalizer::default()),
+            record_step_losses: false,
+            training_control: None,
+            validation_texts: Vec::new(),
+            seed: self.seed,
+            rng: ModelRng::new(self.seed),
+        };
+        match self.vocabulary {
+            Some(vocabulary) => {
+                model.replace_vocab(vocabulary.tokens);
+                model.tokenizer = vocabulary.tokenizer;
+            }
+            None => {
+                model.init_vocab();
+                model.init_layers();
+            }
+        }
+        Ok(model)
+    }
+}
+
+/// Оптимизатор для обновления весов
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Optimizer {
+    /// Обычный стохастический градиентный спуск
+    Sgd,
+    /// SGD с моментом
+    Momentum { beta: f64 },
+    /// Adam: адаптивные оценки первого и второго моментов
+    Adam { beta1: f64, beta2: f64, epsilon: f64 },
+}
+
+impl Optimizer {
+    pub fn momentum() -> Self {
+        Optimizer::Momentum { beta: 0.9 }
+    }
+    
+    pub fn adam() -> Self {
+        Optimizer::Adam { beta1: 0.9, beta2: 0.999, epsilon: 1e-8 }
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            Optimizer::Sgd => "SGD",
+            Optimizer::Momentum { .. } => "SGD + momentum",
+            Optimizer::Adam { .. } => "Adam",
+        }
+    }
+    
+    /// Обновление одного вектора параметров
+    fn step(&self, params: &mut [f64], grads: &[f64], m: &mut [f64], v: &mut [f64], lr: f64, t: u64) {
+        match *self {
+            Optimizer::Sgd => {
+                for (p, g) in params.iter_mut().zip(grads) {
+                    *p -= lr * g;
+                }
+            }
+            Optimizer::Momentum { beta } => {
+                for ((p, g), m) in params.iter_mut().zip(grads).zip(m.iter_mut()) {
+                    *m = beta * *m + g;
+                    *p -= lr * *m;
+                }
+            }
+            Optimizer::Adam { beta1, beta2, epsilon } => {
+                let bias1 = 1.0 - beta1.powi(t as i32);
+                let bias2 = 1.0 - beta2.powi(t as i32);
+                for (((p, g), m), v) in params.iter_mut().zip(grads).zip(m.iter_mut()).zip(v.iter_mut()) {
+                    *m = beta1 * *m + (1.0 - beta1) * g;
+                    *v = beta2 * *v + (1.0 - beta2) * g * g;
+                    *p -= lr * (*m / bias1) / ((*v / bias2).sqrt() + epsilon);
+                }
+            }
+        }
+    }
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::adam()
+    }
+}
+
+/// Моменты оптимизатора для одного слоя (по столбцу весов на каждый вход)
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct LayerMoments {
+    pub weights: Vec<Vec<f64>>,
+    pub biases: Vec<f64>,
+}
+
+impl LayerMoments {
+    /// Столбец весов (или смещения при `column == None`), при необходимости расширенный нулями
+    fn slot(&mut self, column: Option<usize>, len: usize) -> &mut Vec<f64> {
+        let slot = match column {
+            Some(column) => {
+                if self.weights.len() <= column {
+                    self.weights.resize(column + 1, Vec::new());
+                }
+                &mut self.weights[column]
+            }
+            None => &mut self.biases,
+        };
+        if slot.len() < len {
+            slot.resize(len, 0.0);
+        }
+        slot
+    }
+}
+
+/// Состояние оптимизатора: номер шага и моменты для каждого параметра
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct OptimizerState {
+    pub step: u64,
+    pub first_moment: Vec<LayerMoments>,
+    pub second_moment: Vec<LayerMoments>,
+}
+
+impl OptimizerState {
+    fn ensure_layers(&mut self, layers: usize) {
+        if self.first_moment.len() < layers {
+            self.first_moment.resize(layers, LayerMoments::default());
+            self.second_moment.resize(layers, LayerMoments::default());
+        }
+    }
+    
+    fn moments(&mut self, layer: usize, column: Option<usize>, len: usize) -> (&mut [f64], &mut [f64]) {
+        let m = self.first_moment[layer].slot(column, len);
+        let v = self.second_moment[layer].slot(column, len);
+        (&mut m[..len], &mut v[..len])
+    }
+}
+
+/// Параметры генерации текста
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationConfig {
+    /// Температура: < 1 делает выбор увереннее, > 1 — разнообразнее, 0 — жадный выбор
+    pub temperature: f64,
+    /// Выбор только из k самых вероятных токенов (0 — без ограничения)
+    pub top_k: usize,
+    /// Nucleus sampling: минимальный набор токенов с суммарной вероятностью >= top_p
+    pub top_p: f64,
+    /// Максимальное количество генерируемых токенов
+    pub max_tokens: usize,
+    /// Минимальное количество токенов: до него `<END>` не выбирается
+    #[serde(default)]
+    pub min_tokens: usize,
+    /// Строки, на которых генерация останавливается (в ответ не попадают)
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        Self {
+            temperature: 0.8,
+            top_k: 40,
+            top_p: 0.9,
+            max_tokens: 50,
+            min_tokens: 0,
+            stop_sequences: Vec::new(),
+        }
+    }
+}
+
+impl GenerationConfig {
+    /// Позиция самого раннего вхождения стоп-последовательности
+    fn find_stop(&self, text: &str) -> Option<usize> {
+        self.stop_sequences
+            .iter()
+            .filter(|stop| !stop.is_empty())
+            .filter_map(|stop| text.find(stop.as_str()))
+            .min()
+    }
+    
+    /// Длина текста, которую можно отдавать потребителю
+    ///
+    /// Хвост, совпадающий с началом стоп-последовательности, придерживается:
+    /// следующий токен может её завершить.
+    fn safe_len(&self, text: &str) -> usize {
+        text.char_indices()
+            .map(|(pos, _)| pos)
+            .find(|&pos| {
+                let tail = &text[pos..];
+                self.stop_sequences.iter().any(|stop| stop.starts_with(tail))
+            })
+            .unwrap_or(text.len())
+    }
+}
+
+/// Scheduled sampling: против exposure bias на вход иногда подаётся
+/// предсказание модели вместо истинного токена
+///
+/// После `warmup_epochs` эпох чистого teacher forcing вероятность подмены
+/// линейно растёт до `max_ratio` к последней эпохе.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledSampling {
+    pub max_ratio: f64,
+    pub warmup_epochs: usize,
+}
+
+impl ScheduledSampling {
+    pub fn new(max_ratio: f64) -> Self {
+        Self { max_ratio, warmup_epochs: 0 }
+    }
+    
+    /// Вероятность подать собственное предсказание на эпохе `epoch` (с нуля)
+    pub fn ratio(&self, epoch: usize, total_epochs: usize) -> f64 {
+        if epoch < self.warmup_epochs || total_epochs <= self.warmup_epochs {
+            return 0.0;
+        }
+        let progress = (epoch - self.warmup_epochs + 1) as f64 / (total_epochs - self.warmup_epochs) as f64;
+        self.max_ratio.clamp(0.0, 1.0) * progress.min(1.0)
+    }
+}
+
+/// Настройки чекпоинтов обучения
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointConfig {
+    /// Директория для файлов чекпоинтов
+    pub dir: PathBuf,
+    /// Запись чекпоинта каждые N эпох (и всегда после последней)
+    pub every_epochs: usize,
+    /// Сколько последних чекпоинтов хранить (0 — все)
+    pub keep_last: usize,
+}
+
+impl CheckpointConfig {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ..Default::default()
+        }
+    }
+    
+    /// Запись чекпоинта и удаление устаревших
+    pub fn save(&self, model: &AIModel, epoch: usize, total_epochs: usize) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("checkpoint_{:05}.json", epoch));
+        let tmp = path.with_extension("json.tmp");
+        
+        // Пишем во временный файл, чтобы сбой не оставил битый чекпоинт
+        let checkpoint = CheckpointRef { epoch, total_epochs, model };
+        std::fs::write(&tmp, serde_json::to_string(&checkpoint)?)?;
+        std::fs::rename(&tmp, &path)?;
+        
+        if self.keep_last > 0 {
+            let files = self.list();
+            for old in files.iter().take(files.len().saturating_sub(self.keep_last)) {
+                std::fs::remove_file(old)?;
+            }
+        }
+        Ok(path)
+    }
+    
+    /// Последний записанный чекпоинт
+    pub fn latest(&self) -> Option<PathBuf> {
+        self.list().pop()
+    }
+    
+    /// Файлы чекпоинтов, отсортированные по номеру эпохи
+    pub fn list(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                        name.starts_with("checkpoint_") && name.ends_with(".json")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+    
+    fn clear(&self) -> std::io::Result<()> {
+        for path in self.list() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("checkpoints"),
+            every_epochs: 1,
+            keep_last: 3,
+        }
+    }
+}
+
+/// Чекпоинт: модель (вместе с состоянием оптимизатора) и номер эпохи
+#[derive(Deserialize)]
+pub struct Checkpoint {
+    /// Количество завершённых эпох
+    pub epoch: usize,
+    pub total_epochs: usize,
+    pub model: AIModel,
+}
+
+impl Checkpoint {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let data = std::fs::read_to_string(path)?;
+        let mut checkpoint: Checkpoint = serde_json::from_str(&data)?;
+        checkpoint.model.rng = ModelRng::new(checkpoint.model.seed);
+        Ok(checkpoint)
+    }
+}
+
+/// Словарь модели отдельно от весов
+///
+/// Позволяет переиспользовать словарь (в том числе расширенный через
+/// `add_to_vocab`) в других моделях без переноса весов.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Vocabulary {
+    /// Токены в порядке индексов
+    pub tokens: Vec<String>,
+    #[serde(default)]
+    pub tokenizer: Option<BpeTokenizer>,
+}
+
+impl Vocabulary {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+    
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let data = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+}
+
+/// Автосохранение модели после успешного обучения
+///
+/// Каждый запуск пишет `model_<unix-время>.json` и рядом словарь с токенизатором
+/// `model_<unix-время>.vocab.json`; старые пары удаляются сверх `keep_last`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoSaveConfig {
+    pub dir: PathBuf,
+    /// Сколько последних сохранений хранить (0 — все)
+    pub keep_last: usize,
+}
+
+impl AutoSaveConfig {
+    pub fn new(dir: impl Into<PathBuf>, keep_last: usize) -> Self {
+        Self { dir: dir.into(), keep_last }
+    }
+    
+    /// Запись модели с меткой времени `stamp` и удаление устаревших сохранений
+    pub fn save(&self, model: &AIModel, stamp: u64) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(&self.dir)?;
+        // Одинаковая ширина метки сохраняет порядок при сортировке имён
+        let path = self.dir.join(format!("model_{:012}.json", stamp));
+        let tmp = path.with_extension("json.tmp");
+        model.save(&tmp)?;
+        std::fs::rename(&tmp, &path)?;
+        model.save_vocab(Self::vocab_path(&path))?;
+        
+        if self.keep_last > 0 {
+            let files = self.list();
+            for old in files.iter().take(files.len().saturating_sub(self.keep_last)) {
+                std::fs::remove_file(old)?;
+                let vocab = Self::vocab_path(old);
+                if vocab.exists() {
+                    std::fs::remove_file(vocab)?;
+                }
+            }
+        }
+        Ok(path)
+    }
+    
+    /// Файл словаря, сохранённый рядом с моделью
+    pub fn vocab_path(model_path: &Path) -> PathBuf {
+        model_path.with_extension("vocab.json")
+    }
+    
+    /// Сохранённые модели от старых к новым (без файлов словаря)
+    pub fn list(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                        name.starts_with("model_") && name.ends_with(".json") && !name.ends_with(".vocab.json")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+}
+
+/// История loss за один запуск обучения
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrainingReport {
+    /// Эпоха, с которой начался запуск (не 0 при продолжении с чекпоинта)
+    pub start_epoch: usize,
+    /// Средний loss каждой пройденной эпохи
+    pub epoch_losses: Vec<f64>,
+    /// Loss каждого шага; пусто, если `AIModel::record_step_losses` выключен
+    pub step_losses: Vec<f64>,
+    /// Обучение остановлено через `TrainingHandle::cancel` (незавершённая эпоха не учтена)
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Loss на отложенной выборке после обучения (`AIModel::evaluate`), если она была
+    #[serde(default)]
+    pub validation_loss: Option<f64>,
+    /// Loss на `AIModel::validation_texts` после каждой эпохи (пусто без валидации)
+    #[serde(default)]
+    pub validation_losses: Vec<f64>,
+}
+
+/// Loss одной эпохи для графика обучения
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EpochLoss {
+    /// Номер эпохи с единицы
+    pub epoch: usize,
+    pub train: f64,
+    pub validation: Option<f64>,
+}
+
+impl TrainingReport {
+    fn starting_at(start_epoch: usize) -> Self {
+        Self { start_epoch, ..Default::default() }
+    }
+    
+    /// Запись итогов эпохи
+    fn push_epoch(&mut self, train: f64, validation: Option<f64>) {
+        self.epoch_losses.push(train);
+        if let Some(validation) = validation {
+            self.validation_losses.push(validation);
+            self.validation_loss = Some(validation);
+        }
+    }
+    
+    /// Loss по эпохам для графика
+    pub fn loss_history(&self) -> Vec<EpochLoss> {
+        self.epoch_losses
+            .iter()
+            .enumerate()
+            .map(|(idx, &train)| EpochLoss {
+                epoch: self.start_epoch + idx + 1,
+                train,
+                validation: self.validation_losses.get(idx).copied(),
+            })
+            .collect()
+    }
+    
+    /// Loss последней эпохи
+    pub fn final_loss(&self) -> Option<f64> {
+        self.epoch_losses.last().copied()
+    }
+    
+    /// Наименьший loss и номер эпохи (с единицы), на которой он достигнут
+    pub fn best_epoch(&self) -> Option<(usize, f64)> {
+        self.epoch_losses
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(idx, &loss)| (self.start_epoch + idx + 1, loss))
+    }
+    
+    /// Loss последней эпохи меньше, чем первой
+    pub fn improved(&self) -> bool {
+        match (self.epoch_losses.first(), self.epoch_losses.last()) {
+            (Some(first), Some(last)) => self.epoch_losses.len() > 1 && last < first,
+            _ => false,
+        }
+    }
+}
+
+/// Разделяемое состояние фонового обучения
+#[derive(Debug, Default)]
+pub struct TrainingControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    epoch: AtomicUsize,
+    total_epochs: AtomicUsize,
+    /// Биты f64 последнего loss эпохи
+    loss: AtomicU64,
+    history: Mutex<Vec<EpochLoss>>,
+}
+
+impl TrainingControl {
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+    
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+    
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+    
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+    
+    /// Пройденные и всего эпох
+    pub fn progress(&self) -> (usize, usize) {
+        (self.epoch.load(Ordering::Relaxed), self.total_epochs.load(Ordering::Relaxed))
+    }
+    
+    /// Loss последней завершённой эпохи
+    pub fn last_loss(&self) -> Option<f64> {
+        (self.progress().0 > 0).then(|| f64::from_bits(self.loss.load(Ordering::Relaxed)))
+    }
+    
+    /// Loss всех завершённых эпох текущего запуска
+    pub fn loss_history(&self) -> Vec<EpochLoss> {
+        self.history.lock().map(|history| history.clone()).unwrap_or_default()
+    }
+    
+    fn record_epoch(&self, epoch: usize, total_epochs: usize, loss: f64, validation: Option<f64>) {
+        if let Ok(mut history) = self.history.lock() {
+            history.push(EpochLoss { epoch, train: loss, validation });
+        }
+        self.loss.store(loss.to_bits(), Ordering::Relaxed);
+        self.total_epochs.store(total_epochs, Ordering::Relaxed);
+        self.epoch.store(epoch, Ordering::Relaxed);
+    }
+    
+    /// Ожидание снятия паузы; `false`, если обучение остановлено
+    fn wait_while_paused(&self) -> bool {
+        while self.is_paused() && !self.is_cancelled() {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        !self.is_cancelled()
+    }
+}
+
+/// Обучение в фоновом потоке с паузой и остановкой
+///
+/// Поток держит блокировку модели до конца обучения, в том числе на паузе.
+pub struct TrainingHandle {
+    control: Arc<TrainingControl>,
+    thread: JoinHandle<Result<TrainingReport, String>>,
+}
+
+impl TrainingHandle {
+    /// Запуск произвольного обучения модели в отдельном потоке
+    pub fn spawn(
+        model: Arc<Mutex<AIModel>>,
+        job: impl FnOnce(&mut AIModel) -> Result<TrainingReport, String> + Send + 'static,
+    ) -> Self {
+        let control = Arc::new(TrainingControl::default());
+        let thread_control = Arc::clone(&control);
+        let thread = std::thread::spawn(move || {
+            let mut model = model.lock().map_err(|_| "Модель заблокирована упавшим потоком".to_string())?;
+            model.training_control = Some(thread_control);
+            let result = job(&mut model);
+            model.training_control = None;
+            result
+        });
+        Self { control, thread }
+    }
+    
+    pub fn pause(&self) {
+        self.control.pause();
+    }
+    
+    pub fn resume(&self) {
+        self.control.resume();
+    }
+    
+    /// Остановка после текущего примера
+    pub fn cancel(&self) {
+        self.control.cancel();
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        self.control.is_paused()
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+    
+    pub fn control(&self) -> &TrainingControl {
+        &self.control
+    }
+    
+    /// Ожидание завершения потока
+    pub fn join(self) -> Result<TrainingReport, String> {
+        self.thread
+            .join()
+            .unwrap_or_else(|_| Err("Поток обучения завершился с паникой".to_string()))
+    }
+}
+
+/// Сериализуемая ссылка на чекпоинт (без клонирования модели)
+#[derive(Serialize)]
+struct CheckpointRef<'a> {
+    epoch: usize,
+    total_epochs: usize,
+    model: &'a AIModel,
+}
+
+/// Активации прямого прохода, сохранённые для backward
+struct ForwardTrace {
+    activations: Vec<Vec<f64>>,
+    /// Состояние каждого слоя (индекс совпадает с `activations`)
+    layers: Vec<LayerTrace>,
+    gru_steps: Vec<GruStep>,
+}
+
+/// Промежуточные значения слоя, нужные для backward
+#[derive(Default)]
+struct LayerTrace {
+    /// Выход функции активации до dropout
+    activated: Vec<f64>,
+    /// Маска inverted dropout (0 или 1 / (1 - p))
+    dropout_mask: Option<Vec<f64>>,
+    /// Нормализованные предактивации и стандартное отклонение
+    normalized: Option<(Vec<f64>, f64)>,
+}
+
+/// Промежуточные значения одного шага GRU
+struct GruStep {
+    x: Vec<f64>,
+    hidden: Vec<f64>,
+    z: Vec<f64>,
+    r: Vec<f64>,
+    n: Vec<f64>,
+    /// U_n * h до умножения на reset-гейт
+    candidate_hidden: Vec<f64>,
+}
+
+impl GruStep {
+    fn output(&self) -> Vec<f64> {
+        (0..self.hidden.len())
+            .map(|i| (1.0 - self.z[i]) * self.n[i] + self.z[i] * self.hidden[i])
+            .collect()
+    }
+}
+
+/// Градиент слоя: разреженные столбцы весов и смещения
+struct LayerGradient {
+    columns: Vec<(usize, Vec<f64>)>,
+    biases: Vec<f64>,
+}
+
+/// Сумма градиентов шагов мини-батча по слоям
+#[derive(Default)]
+struct GradientBatch {
+    columns: Vec<BTreeMap<usize, Vec<f64>>>,
+    biases: Vec<Vec<f64>>,
+    steps: usize,
+}
+
+impl GradientBatch {
+    fn add(&mut self, gradients: Vec<LayerGradient>) {
+        if gradients.is_empty() {
+            return;
+        }
+        if self.columns.len() < gradients.len() {
+            self.columns.resize_with(gradients.len(), BTreeMap::new);
+            self.biases.resize_with(gradients.len(), Vec::new);
+        }
+        for ((columns, biases), gradient) in self.columns.iter_mut().zip(&mut self.biases).zip(gradients) {
+            for (column, grad) in gradient.columns {
+                let sum = columns.entry(column).or_insert_with(|| vec![0.0; grad.len()]);
+                sum.iter_mut().zip(&grad).for_each(|(s, g)| *s += g);
+            }
+            if biases.is_empty() {
+                *biases = gradient.biases;
+            } else {
+                biases.iter_mut().zip(&gradient.biases).for_each(|(s, g)| *s += g);
+            }
+        }
+        self.steps += 1;
+    }
+    
+    /// Средний градиент накопленных шагов; батч опустошается
+    fn take_mean(&mut self) -> Vec<LayerGradient> {
+        let scale = 1.0 / self.steps.max(1) as f64;
+        self.steps = 0;
+        std::mem::take(&mut self.columns)
+            .into_iter()
+            .zip(std::mem::take(&mut self.biases))
+            .map(|(columns, mut biases)| {
+                biases.iter_mut().for_each(|b| *b *= scale);
+                let columns = columns
+                    .into_iter()
+                    .map(|(column, mut grad)| {
+                        grad.iter_mut().for_each(|g| *g *= scale);
+                        (column, grad)
+                    })
+                    .collect();
+                LayerGradient { columns, biases }
+            })
+            .collect()
+    }
+}
+
+fn default_batch_size() -> usize {
+    1
+}
+
 impl AIModel {
     pub fn new(embedding_dim: usize, hidden_dim: usize, context_length: usize) -> Self {
         let mut model = Self {
             layers: Vec::new(),
             learning_rate: 0.001,
+            batch_size: 1,
             vocab: HashMap::new(),
             reverse_vocab: HashMap::new(),
             embedding_dim,
             hidden_dim,
             context_length,
+            optimizer: Optimizer::default(),
+            optimizer_state: OptimizerState::default(),
+            tokenizer: None,
+            recurrent: false,
+            gpu: None,
+            precision: Precision::F64,
+            scheduled_sampling: None,
+            hidden_layers: vec![HiddenLayerSpec::new(hidden_dim, ActivationType::Tanh); 2],
+            normalizer: Some(TextNormalizer::default()),
+            record_step_losses: false,
+            training_control: None,
+            validation_texts: Vec::new(),
+            seed: None,
+            rng: ModelRng::default(),
         };
         
         // Инициализация базового словаря
@@ -422,116 +1522,330 @@ impl AIModel {
             "together", "apart", "suddenly", "immediately", "gradually",
             
             // Служебные токены и специальные символы
-            "<PAD>", "<START>", "<END>", "<UNK>", "<MASK>", "<SEP>", "<CLS>",
+            "<PAD>", "<START>", "<END>", "<UNK>", "<MASK>", "<SEP>", "<CLS>", "<NUM>",
             "!", "?", ".", ",", ";", ":", "-", "–", "—",
             "(", ")", "[", "]", "{", "}", "\"", "'", "`",
             "/", "\\", "|", "@", "#", "$", "%", "^", "&", "*", "+", "=", "<", ">", "~",
         ];
         
-        for (idx, word) in base_words.iter().enumerate() {
-            self.vocab.insert(word.to_string(), idx);
-            self.reverse_vocab.insert(idx, word.to_string());
+        // Повторы в списке пропускаются, чтобы индексы шли без пропусков
+        for word in base_words {
+            if !self.vocab.contains_key(word) {
+                let idx = self.vocab.len();
+                self.vocab.insert(word.to_string(), idx);
+                self.reverse_vocab.insert(idx, word.to_string());
+            }
         }
     }
     
     fn init_layers(&mut self) {
-        let mut rng = rand::thread_rng();
         let vocab_size = self.vocab.len();
         
-        // Embedding layer
-        let embedding_layer = Layer {
-            weights: (0..vocab_size)
-                .map(|_| (0..self.embedding_dim)
-                    .map(|_| rng.gen_range(-0.1..0.1))
-                    .collect())
-                .collect(),
-            biases: vec![0.0; self.embedding_dim],
-            activation: ActivationType::ReLU,
-        };
+        // Embedding layer: столбец на каждый токен
+        let embedding_layer = Layer::random(vocab_size, self.embedding_dim, ActivationType::ReLU, LayerKind::Dense, &self.rng);
         
-        // Hidden layer 1
-        let hidden1 = Layer {
-            weights: (0..self.embedding_dim * self.context_length)
-                .map(|_| (0..self.hidden_dim)
-                    .map(|_| rng.gen_range(-0.1..0.1))
-                    .collect())
-                .collect(),
-            biases: vec![0.0; self.hidden_dim],
-            activation: ActivationType::Tanh,
-        };
-        
-        // Hidden layer 2
-        let hidden2 = Layer {
-            weights: (0..self.hidden_dim)
-                .map(|_| (0..self.hidden_dim)
-                    .map(|_| rng.gen_range(-0.1..0.1))
-                    .collect())
-                .collect(),
-            biases: vec![0.0; self.hidden_dim],
-            activation: ActivationType::Tanh,
-        };
+        // Скрытые слои: первый — над контекстом (или GRU), остальные — по списку
+        self.layers.push(embedding_layer);
+        self.layers.push(self.first_hidden_layer());
+        let specs = self.hidden_specs();
+        for pair in specs.windows(2) {
+            self.layers.push(Layer::random(pair[0].size, pair[1].size, pair[1].activation, LayerKind::Dense, &self.rng));
+        }
         
         // Output layer
-        let output_layer = Layer {
-            weights: (0..self.hidden_dim)
-                .map(|_| (0..vocab_size)
-                    .map(|_| rng.gen_range(-0.1..0.1))
-                    .collect())
-                .collect(),
-            biases: vec![0.0; vocab_size],
-            activation: ActivationType::Softmax,
-        };
-        
-        self.layers.push(embedding_layer);
-        self.layers.push(hidden1);
-        self.layers.push(hidden2);
-        self.layers.push(output_layer);
+        let last_hidden = specs.last().map_or(self.hidden_dim, |spec| spec.size);
+        self.layers.push(Layer::random(last_hidden, vocab_size, ActivationType::Softmax, LayerKind::Dense, &self.rng));
+    }
+    
+    /// Описание скрытых слоёв (для моделей, сохранённых до появления списка, — два Tanh-слоя)
+    pub fn hidden_specs(&self) -> Vec<HiddenLayerSpec> {
+        if self.hidden_layers.is_empty() {
+            vec![HiddenLayerSpec::new(self.hidden_dim, ActivationType::Tanh); 2]
+        } else {
+            self.hidden_layers.clone()
+        }
+    }
+    
+    /// Первый скрытый слой: полносвязный над контекстом или GRU
+    fn first_hidden_layer(&self) -> Layer {
+        if self.recurrent {
+            Layer::random(self.embedding_dim + self.hidden_dim, 3 * self.hidden_dim, ActivationType::Tanh, LayerKind::Gru, &self.rng)
+        } else {
+            let activation = self.hidden_specs()[0].activation;
+            Layer::random(self.embedding_dim * self.context_length, self.hidden_dim, activation, LayerKind::Dense, &self.rng)
+        }
+    }
+    
+    /// Включение/выключение рекуррентного (GRU) слоя
+    ///
+    /// Пересоздаёт первый скрытый слой, остальные веса сохраняются.
+    pub fn set_recurrent(&mut self, recurrent: bool) {
+        if self.recurrent == recurrent || self.layers.len() < 2 {
+            self.recurrent = recurrent;
+            return;
+        }
+        self.recurrent = recurrent;
+        self.layers[1] = self.first_hidden_layer();
+        self.optimizer_state = OptimizerState::default();
     }
     
     /// Прямое распространение
     pub fn forward(&self, input_tokens: &[usize]) -> Vec<f64> {
+        self.forward_trace(input_tokens, false).activations.pop().unwrap_or_default()
+    }
+    
+    /// Эмбеддинг токена (нули для неизвестного индекса)
+    fn embedding(&self, token: usize) -> Vec<f64> {
+        let weights = &self.layers[0].weights;
+        if token < weights.ncols() {
+            weights.column(token).iter().copied().collect()
+        } else {
+            vec![0.0; self.embedding_dim]
+        }
+    }
+    
+    /// Прямое распространение с сохранением активаций всех слоёв (для backward)
+    ///
+    /// В режиме обучения применяется dropout.
+    fn forward_trace(&self, input_tokens: &[usize], training: bool) -> ForwardTrace {
         let mut activations = Vec::new();
         
         // Embedding
         for &token in input_tokens.iter().take(self.context_length) {
-            if token < self.layers[0].weights.len() {
-                activations.extend_from_slice(&self.layers[0].weights[token]);
-            } else {
-                activations.extend(vec![0.0; self.embedding_dim]);
-            }
+            activations.extend(self.embedding(token));
         }
         
-        // Дополняем до нужной длины
-        while activations.len() < self.embedding_dim * self.context_length {
-            activations.push(0.0);
+        // Дополняем до нужной длины (GRU обрабатывает только реальные токены)
+        if !self.recurrent {
+            activations.resize(self.embedding_dim * self.context_length, 0.0);
         }
         
         // Проход через скрытые слои
+        let mut trace = ForwardTrace {
+            activations: vec![activations],
+            layers: vec![LayerTrace::default()],
+            gru_steps: Vec::new(),
+        };
         for layer in self.layers.iter().skip(1) {
-            activations = self.apply_layer(&activations, layer);
+            let input = trace.activations.last().unwrap();
+            let mut state = match layer.kind {
+                LayerKind::Dense => self.apply_dense(input, layer),
+                LayerKind::Gru => {
+                    let hidden = vec![0.0; layer.biases.len() / 3];
+                    let (activated, steps) = Self::apply_gru(input, layer, hidden);
+                    trace.gru_steps = steps;
+                    LayerTrace { activated, ..Default::default() }
+                }
+            };
+            
+            let mut next = state.activated.clone();
+            if training && layer.dropout > 0.0 {
+                let mask = self.dropout_mask(next.len(), layer.dropout);
+                next.iter_mut().zip(&mask).for_each(|(x, m)| *x *= m);
+                state.dropout_mask = Some(mask);
+            }
+            
+            trace.activations.push(next);
+            trace.layers.push(state);
         }
         
-        activations
+        trace
     }
     
-    fn apply_layer(&self, input: &[f64], layer: &Layer) -> Vec<f64> {
-        let output_size = layer.biases.len();
-        let input_size = if layer.weights.is_empty() { 0 } else { layer.weights[0].len() };
+    /// Проход остатка сети (после рекуррентного слоя) от скрытого состояния
+    fn forward_from_hidden(&self, hidden: &[f64]) -> Vec<f64> {
+        self.layers
+            .iter()
+            .skip(2)
+            .fold(hidden.to_vec(), |input, layer| self.apply_layer(&input, layer))
+    }
+    
+    /// GRU по последовательности: вход режется на шаги размера входа слоя
+    fn apply_gru(input: &[f64], layer: &Layer, mut hidden: Vec<f64>) -> (Vec<f64>, Vec<GruStep>) {
+        let input_dim = layer.inputs() - hidden.len();
+        let mut steps = Vec::new();
+        for x in input.chunks(input_dim.max(1)) {
+            let step = Self::gru_step(layer, x, &hidden);
+            hidden = step.output();
+            steps.push(step);
+        }
+        (hidden, steps)
+    }
+    
+    /// Один шаг GRU: h' = (1 - z) * n + z * h
+    fn gru_step(layer: &Layer, x: &[f64], hidden: &[f64]) -> GruStep {
+        let size = hidden.len();
+        let input_dim = layer.inputs() - size;
+        let sigmoid = |v: f64| 1.0 / (1.0 + (-v).exp());
         
-        let mut output = vec![0.0; output_size];
+        let mut from_input = DVector::from_column_slice(&layer.biases);
+        from_input.gemv(1.0, &layer.weights.columns(0, input_dim), &DVector::from_column_slice(x), 1.0);
+        let from_hidden = layer.weights.columns(input_dim, size) * DVector::from_column_slice(hidden);
         
-        for i in 0..output_size {
-            let mut sum = layer.biases[i];
-            for j in 0..input.len().min(layer.weights.len()) {
-                if i < layer.weights[j].len() {
-                    sum += input[j] * layer.weights[j][i];
-                }
+        let z: Vec<f64> = (0..size).map(|i| sigmoid(from_input[i] + from_hidden[i])).collect();
+        let r: Vec<f64> = (0..size).map(|i| sigmoid(from_input[size + i] + from_hidden[size + i])).collect();
+        let candidate_hidden = from_hidden.as_slice()[2 * size..].to_vec();
+        let n: Vec<f64> = (0..size)
+            .map(|i| (from_input[2 * size + i] + r[i] * candidate_hidden[i]).tanh())
+            .collect();
+        
+        GruStep { x: x.to_vec(), hidden: hidden.to_vec(), z, r, n, candidate_hidden }
+    }
+    
+    /// BPTT через GRU: градиент слоя и градиент по входу (склеенные шаги)
+    fn gru_backward(layer: &Layer, steps: &[GruStep], delta: Vec<f64>) -> (LayerGradient, Vec<f64>) {
+        let size = delta.len();
+        let input_dim = layer.inputs() - size;
+        let mut weight_grads = DMatrix::zeros(3 * size, layer.inputs());
+        let mut bias_grads = vec![0.0; 3 * size];
+        let mut input_delta = vec![0.0; steps.len() * input_dim];
+        let mut dh = delta;
+        
+        for (t, step) in steps.iter().enumerate().rev() {
+            // Градиенты по предактивациям гейтов [z | r | n] и по U_n * h
+            let mut gates = vec![0.0; 3 * size];
+            let mut d_candidate_hidden = vec![0.0; size];
+            for i in 0..size {
+                let (z, r, n) = (step.z[i], step.r[i], step.n[i]);
+                let dn = dh[i] * (1.0 - z) * (1.0 - n * n);
+                let dz = dh[i] * (step.hidden[i] - n) * z * (1.0 - z);
+                let dr = dn * step.candidate_hidden[i] * r * (1.0 - r);
+                gates[i] = dz;
+                gates[size + i] = dr;
+                gates[2 * size + i] = dn;
+                d_candidate_hidden[i] = dn * r;
+            }
+            let hidden_gates = DVector::from_iterator(
+                3 * size,
+                gates[..2 * size].iter().chain(&d_candidate_hidden).copied(),
+            );
+            
+            bias_grads.iter_mut().zip(&gates).for_each(|(g, d)| *g += d);
+            let gates = DVector::from_vec(gates);
+            let x = DVector::from_column_slice(&step.x);
+            let hidden = DVector::from_column_slice(&step.hidden);
+            
+            weight_grads.columns_mut(0, input_dim).ger(1.0, &gates, &x, 1.0);
+            weight_grads.columns_mut(input_dim, size).ger(1.0, &hidden_gates, &hidden, 1.0);
+            
+            let x_delta = layer.weights.columns(0, input_dim).tr_mul(&gates);
+            input_delta[t * input_dim..(t + 1) * input_dim].copy_from_slice(x_delta.as_slice());
+            
+            let mut dh_prev = layer.weights.columns(input_dim, size).tr_mul(&hidden_gates);
+            dh_prev.iter_mut().zip(dh.iter().zip(&step.z)).for_each(|(p, (d, z))| *p += d * z);
+            dh = dh_prev.as_slice().to_vec();
+        }
+        
+        let columns = (0..weight_grads.ncols())
+            .map(|j| (j, weight_grads.column(j).iter().copied().collect()))
+            .collect();
+        (LayerGradient { columns, biases: bias_grads }, input_delta)
+    }
+    
+    fn apply_layer(&self, input: &[f64], layer: &Layer) -> Vec<f64> {
+        self.apply_dense(input, layer).activated
+    }
+    
+    /// Полносвязный слой: линейная часть, layer norm и активация
+    fn apply_dense(&self, input: &[f64], layer: &Layer) -> LayerTrace {
+        // Выходной softmax-слой целиком считается на GPU
+        if matches!(layer.activation, ActivationType::Softmax) && !layer.layer_norm {
+            if let Some(activated) = self.gpu_affine(&layer.weights, input, &layer.biases, false, true) {
+                return LayerTrace { activated, ..Default::default() };
             }
-            output[i] = sum;
         }
         
+        let mut output = self
+            .gpu_affine(&layer.weights, input, &layer.biases, false, false)
+            .or_else(|| Self::affine_f32(layer, input))
+            .unwrap_or_else(|| {
+                let mut output = DVector::from_column_slice(&layer.biases);
+                output.gemv(1.0, &layer.weights, &DVector::from_column_slice(input), 1.0);
+                output.as_slice().to_vec()
+            });
+        
+        let mut normalized = None;
+        if layer.layer_norm && !output.is_empty() {
+            let n = output.len() as f64;
+            let mean = output.iter().sum::<f64>() / n;
+            let variance = output.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+            let std = (variance + 1e-5).sqrt();
+            output.iter_mut().for_each(|x| *x = (*x - mean) / std);
+            normalized = Some((output.clone(), std));
+        }
+        
+        LayerTrace {
+            activated: Self::activate(&layer.activation, output),
+            dropout_mask: None,
+            normalized,
+        }
+    }
+    
+    /// `W·x + b` в f32 по копии весов (`None`, если копии нет)
+    fn affine_f32(layer: &Layer, input: &[f64]) -> Option<Vec<f64>> {
+        let weights = layer.compute_weights()?;
+        let input = DVector::from_iterator(input.len(), input.iter().map(|&x| x as f32));
+        let mut output = DVector::from_iterator(layer.biases.len(), layer.biases.iter().map(|&b| b as f32));
+        output.gemv(1.0, weights, &input, 1.0);
+        Some(output.iter().map(|&x| x as f64).collect())
+    }
+    
+    /// Смена точности вычислений; f32-копии весов создаются заново
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.precision = precision;
+        self.sync_compute_weights();
+    }
+    
+    /// Пересоздание f32-копий весов полносвязных слоёв по текущей точности
+    ///
+    /// Нужно после загрузки модели и изменений архитектуры (копии не сохраняются).
+    pub fn sync_compute_weights(&mut self) {
+        let precision = self.precision;
+        for layer in self.layers.iter_mut().skip(1) {
+            layer.compute_weights = match (precision, layer.kind) {
+                (Precision::F32, LayerKind::Dense) => Some(layer.weights.map(|w| w as f32)),
+                _ => None,
+            };
+        }
+    }
+    
+    /// `init + W·x` (или `Wᵀ·x`) на GPU, если он включён и матрица достаточно велика
+    ///
+    /// `None` означает, что вычисление нужно выполнить на CPU.
+    fn gpu_affine(&self, weights: &DMatrix<f64>, input: &[f64], init: &[f64], transpose: bool, softmax: bool) -> Option<Vec<f64>> {
+        let gpu = self.gpu.as_ref()?;
+        if weights.len() < GPU_MIN_ELEMENTS {
+            return None;
+        }
+        gpu.affine(weights, input, init, transpose, softmax)
+    }
+    
+    /// Включение GPU-бэкенда; возвращает имя адаптера или `None` (остаётся CPU)
+    pub fn enable_gpu(&mut self) -> Option<String> {
+        if self.gpu.is_none() {
+            self.gpu = GpuCompute::new().map(Arc::new);
+        }
+        self.gpu.as_ref().map(|gpu| gpu.adapter_name().to_string())
+    }
+    
+    /// Возврат к вычислениям на CPU
+    pub fn disable_gpu(&mut self) {
+        self.gpu = None;
+    }
+    
+    /// Маска inverted dropout: каждый элемент обнуляется с вероятностью `p`
+    fn dropout_mask(&self, len: usize, p: f64) -> Vec<f64> {
+        let keep = 1.0 - p.clamp(0.0, 0.95);
+        self.rng.with(|rng| {
+            (0..len)
+                .map(|_| if rng.gen_bool(keep) { 1.0 / keep } else { 0.0 })
+                .collect()
+        })
+    }
+    
+    fn activate(activation: &ActivationType, output: Vec<f64>) -> Vec<f64> {
         // Применение функции активации
-        match layer.activation {
+        match activation {
             ActivationType::ReLU => output.iter().map(|&x| x.max(0.0)).collect(),
             ActivationType::Tanh => output.iter().map(|&x| x.tanh()).collect(),
             ActivationType::Sigmoid => output.iter().map(|&x| 1.0 / (1.0 + (-x).exp())).collect(),
@@ -545,67 +1859,315 @@ impl AIModel {
     }
     
     /// Генерация ответа
-    pub fn generate(&self, input_text: &str, max_length: usize) -> String {
-        let tokens = self.tokenize(input_text);
-        let mut generated_tokens = tokens.clone();
+    pub fn generate(&self, input_text: &str, config: &GenerationConfig) -> String {
+        self.generate_streaming(input_text, config, |_| ControlFlow::Continue(()))
+    }
+    
+    /// Эмбеддинг текста: усреднённое по токенам состояние последнего скрытого слоя
+    ///
+    /// Для feed-forward сети состояние берётся по скользящему окну контекста,
+    /// для GRU — скрытое состояние после каждого токена.
+    pub fn embed(&self, text: &str) -> Vec<f64> {
+        let tokens = self.tokenize(text);
+        let last_hidden = self.layers.len().saturating_sub(2);
+        let mut sum = vec![0.0; self.layers.get(last_hidden).map_or(0, Layer::outputs)];
+        if tokens.is_empty() || self.layers.len() < 3 {
+            return sum;
+        }
         
-        for _ in 0..max_length {
-            let context: Vec<usize> = generated_tokens
-                .iter()
-                .rev()
-                .take(self.context_length)
-                .rev()
-                .cloned()
-                .collect();
-            
-            let probs = self.forward(&context);
-            let next_token = self.sample_token(&probs);
+        let mut hidden = vec![0.0; self.hidden_dim];
+        for end in 1..=tokens.len() {
+            let state = if self.recurrent {
+                hidden = Self::gru_step(&self.layers[1], &self.embedding(tokens[end - 1]), &hidden).output();
+                self.layers[2..=last_hidden]
+                    .iter()
+                    .fold(hidden.clone(), |input, layer| self.apply_layer(&input, layer))
+            } else {
+                let window = &tokens[end.saturating_sub(self.context_length)..end];
+                self.forward_trace(window, false).activations.swap_remove(last_hidden)
+            };
+            sum.iter_mut().zip(&state).for_each(|(s, x)| *s += x);
+        }
+        
+        let n = tokens.len() as f64;
+        sum.iter_mut().for_each(|s| *s /= n);
+        sum
+    }
+    
+    /// Генерация с извлечением контекста (RAG)
+    ///
+    /// Запрос эмбеддится, из памяти берутся до `limit` близких фрагментов,
+    /// которые добавляются перед запросом.
+    pub fn generate_with_memory(
+        &self,
+        input_text: &str,
+        memory: &dyn SemanticMemory,
+        limit: usize,
+        config: &GenerationConfig,
+    ) -> String {
+        let snippets = memory.find_similar(&self.embed(input_text), limit);
+        if snippets.is_empty() {
+            return self.generate(input_text, config);
+        }
+        
+        let prompt = format!("{} {}", snippets.join(" "), input_text);
+        self.generate(&prompt, config)
+    }
+    
+    /// Потоковая генерация: `on_token` получает каждый новый фрагмент текста
+    ///
+    /// `ControlFlow::Break(())` из колбэка прерывает генерацию. Возвращается
+    /// весь текст, сгенерированный к моменту остановки. С GRU скрытое
+    /// состояние переносится между токенами, поэтому контекст не обрезается
+    /// до `context_length`.
+    pub fn generate_streaming(
+        &self,
+        input_text: &str,
+        config: &GenerationConfig,
+        mut on_token: impl FnMut(&str) -> ControlFlow<()>,
+    ) -> String {
+        let prompt = self.tokenize(input_text);
+        let mut hidden = self.recurrent.then(|| {
+            prompt.iter().fold(vec![0.0; self.hidden_dim], |hidden, &token| {
+                Self::gru_step(&self.layers[1], &self.embedding(token), &hidden).output()
+            })
+        });
+        
+        let end_tokens: Vec<usize> = self
+            .reverse_vocab
+            .iter()
+            .filter(|(_, token)| token.as_str() == "<END>")
+            .map(|(&idx, _)| idx)
+            .collect();
+        
+        let mut generated_tokens = Vec::new();
+        let mut text = String::new();
+        let mut emitted = 0;
+        for step in 0..config.max_tokens {
+            let mut probs = match &hidden {
+                Some(hidden) => self.forward_from_hidden(hidden),
+                None => {
+                    let history: Vec<usize> = prompt.iter().chain(&generated_tokens).cloned().collect();
+                    self.forward(&history[history.len().saturating_sub(self.context_length)..])
+                }
+            };
+            if step < config.min_tokens {
+                for &end in &end_tokens {
+                    if let Some(p) = probs.get_mut(end) {
+                        *p = 0.0;
+                    }
+                }
+            }
+            let next_token = self.sample_token(&probs, config);
             
             // Проверка на конец генерации
-            if let Some(token_str) = self.reverse_vocab.get(&next_token) {
-                if token_str == "<END>" {
-                    break;
-                }
+            if end_tokens.contains(&next_token) {
+                break;
             }
             
             generated_tokens.push(next_token);
+            if let Some(hidden) = &mut hidden {
+                *hidden = Self::gru_step(&self.layers[1], &self.embedding(next_token), hidden).output();
+            }
+            
+            // Новый фрагмент — прирост декодированного текста до стоп-последовательности
+            text = self.decode(&generated_tokens);
+            let stop = config.find_stop(&text);
+            if let Some(pos) = stop {
+                text.truncate(pos);
+            }
+            let ready = stop.unwrap_or_else(|| config.safe_len(&text)).max(emitted);
+            let piece = text.get(emitted..ready).unwrap_or_default().to_string();
+            emitted = ready;
+            if !piece.is_empty() && on_token(&piece).is_break() {
+                return text;
+            }
+            if stop.is_some() {
+                break;
+            }
         }
         
-        self.decode(&generated_tokens[tokens.len()..])
+        // Придержанный хвост, так и не ставший стоп-последовательностью
+        if let Some(rest) = text.get(emitted..).filter(|rest| !rest.is_empty()) {
+            let _ = on_token(rest);
+        }
+        text
     }
     
-    /// Обучение на данных
-    pub fn train(&mut self, texts: &[String], epochs: usize, progress_callback: impl Fn(usize, usize, f64)) {
+    /// Обучение на данных, возвращает историю loss
+    pub fn train(&mut self, texts: &[String], epochs: usize, progress_callback: impl Fn(usize, usize, f64)) -> TrainingReport {
+        self.sync_compute_weights();
+        let mut report = TrainingReport::default();
         for epoch in 0..epochs {
-            let mut total_loss = 0.0;
-            let mut num_samples = 0;
+            let Some((avg_loss, validation)) = self.train_epoch(texts, epoch, epochs, &mut report.step_losses) else {
+                report.cancelled = true;
+                break;
+            };
+            report.push_epoch(avg_loss, validation);
+            progress_callback(epoch + 1, epochs, avg_loss);
+        }
+        report
+    }
+    
+    /// Обучение в фоновом потоке, управляемое через `TrainingHandle`
+    pub fn train_async(model: Arc<Mutex<AIModel>>, texts: Vec<String>, epochs: usize) -> TrainingHandle {
+        TrainingHandle::spawn(model, move |model| Ok(model.train(&texts, epochs, |_, _, _| {})))
+    }
+    
+    /// Обучение с периодической записью чекпоинтов
+    ///
+    /// Старые чекпоинты в директории удаляются: новый запуск начинается с нуля.
+    pub fn train_with_checkpoints(
+        &mut self,
+        texts: &[String],
+        epochs: usize,
+        checkpoints: &CheckpointConfig,
+        progress_callback: impl Fn(usize, usize, f64),
+    ) -> Result<TrainingReport, Box<dyn std::error::Error>> {
+        checkpoints.clear()?;
+        self.train_from_epoch(texts, 0, epochs, checkpoints, progress_callback)
+    }
+    
+    /// Продолжение обучения с последнего чекпоинта
+    ///
+    /// Загружает модель и состояние оптимизатора из чекпоинта и дообучает
+    /// оставшиеся эпохи. Эпоха, с которой продолжили, — `TrainingReport::start_epoch`.
+    pub fn resume_training(
+        &mut self,
+        texts: &[String],
+        checkpoints: &CheckpointConfig,
+        progress_callback: impl Fn(usize, usize, f64),
+    ) -> Result<TrainingReport, Box<dyn std::error::Error>> {
+        let path = checkpoints.latest().ok_or("Чекпоинты не найдены")?;
+        let checkpoint = Checkpoint::load(path)?;
+        let (record_step_losses, control) = (self.record_step_losses, self.training_control.take());
+        let validation_texts = std::mem::take(&mut self.validation_texts);
+        *self = checkpoint.model;
+        self.record_step_losses = record_step_losses;
+        self.training_control = control;
+        self.validation_texts = validation_texts;
+        self.train_from_epoch(texts, checkpoint.epoch, checkpoint.total_epochs, checkpoints, progress_callback)
+    }
+    
+    fn train_from_epoch(
+        &mut self,
+        texts: &[String],
+        start_epoch: usize,
+        epochs: usize,
+        checkpoints: &CheckpointConfig,
+        progress_callback: impl Fn(usize, usize, f64),
+    ) -> Result<TrainingReport, Box<dyn std::error::Error>> {
+        self.sync_compute_weights();
+        let mut report = TrainingReport::starting_at(start_epoch);
+        for epoch in start_epoch..epochs {
+            let Some((avg_loss, validation)) = self.train_epoch(texts, epoch, epochs, &mut report.step_losses) else {
+                report.cancelled = true;
+                break;
+            };
+            report.push_epoch(avg_loss, validation);
+            progress_callback(epoch + 1, epochs, avg_loss);
             
-            for text in texts {
-                let tokens = self.tokenize(text);
-                
-                // Создаем обучающие пары (контекст -> следующее слово)
-                for i in 0..(tokens.len().saturating_sub(1)) {
-                    let context_end = (i + 1).min(tokens.len());
-                    let context_start = context_end.saturating_sub(self.context_length);
-                    let context = &tokens[context_start..context_end];
-                    let target = tokens[context_end.min(tokens.len() - 1)];
-                    
-                    // Forward pass
-                    let output = self.forward(context);
-                    
-                    // Вычисление loss
-                    let loss = self.compute_loss(&output, target);
-                    total_loss += loss;
-                    num_samples += 1;
-                    
-                    // Backward pass (упрощенный градиентный спуск)
-                    self.update_weights(context, target, &output);
+            if (epoch + 1) % checkpoints.every_epochs.max(1) == 0 || epoch + 1 == epochs {
+                checkpoints.save(self, epoch + 1, epochs)?;
+            }
+        }
+        Ok(report)
+    }
+    
+    /// Одна эпоха обучения, возвращает средний loss и loss на `validation_texts`
+    /// (`None`, если обучение остановлено)
+    ///
+    /// При `record_step_losses` loss каждого шага дописывается в `step_losses`.
+    fn train_epoch(
+        &mut self,
+        texts: &[String],
+        epoch: usize,
+        total_epochs: usize,
+        step_losses: &mut Vec<f64>,
+    ) -> Option<(f64, Option<f64>)> {
+        let mut total_loss = 0.0;
+        let mut num_samples = 0;
+        let sampling_ratio = self
+            .scheduled_sampling
+            .map_or(0.0, |sampling| sampling.ratio(epoch, total_epochs));
+        let mut batch = GradientBatch::default();
+        
+        for text in texts {
+            if let Some(control) = &self.training_control {
+                if !control.wait_while_paused() {
+                    return None;
                 }
             }
             
-            let avg_loss = if num_samples > 0 { total_loss / num_samples as f64 } else { 0.0 };
-            progress_callback(epoch + 1, epochs, avg_loss);
+            let tokens = self.tokenize(text);
+            // Последовательность, подаваемая на вход: истинные токены или предсказания
+            let mut inputs = tokens.iter().take(1).copied().collect::<Vec<_>>();
+            
+            // Создаем обучающие пары (контекст -> следующее слово)
+            for i in 0..(tokens.len().saturating_sub(1)) {
+                let context_end = i + 1;
+                let context_start = context_end.saturating_sub(self.context_length);
+                let context = &inputs[context_start..context_end];
+                let target = tokens[context_end];
+                
+                // Forward + backward pass
+                let (output, gradients) = self.backward(context, target);
+                
+                // Вычисление loss
+                let loss = self.compute_loss(&output, target);
+                total_loss += loss;
+                num_samples += 1;
+                if self.record_step_losses {
+                    step_losses.push(loss);
+                }
+                
+                // Шаг оптимизатора: сразу или по заполнении мини-батча
+                if self.batch_size <= 1 {
+                    self.apply_gradients(&gradients);
+                } else {
+                    batch.add(gradients);
+                    if batch.steps >= self.batch_size {
+                        self.apply_gradients(&batch.take_mean());
+                    }
+                }
+                
+                let fed = if sampling_ratio > 0.0 && self.rng.with(|rng| rng.gen_bool(sampling_ratio)) {
+                    output
+                        .iter()
+                        .enumerate()
+                        .max_by(|a, b| a.1.total_cmp(b.1))
+                        .map_or(target, |(idx, _)| idx)
+                } else {
+                    target
+                };
+                inputs.push(fed);
+            }
+        }
+        if batch.steps > 0 {
+            self.apply_gradients(&batch.take_mean());
         }
+        
+        let avg_loss = if num_samples > 0 { total_loss / num_samples as f64 } else { 0.0 };
+        let validation = (!self.validation_texts.is_empty()).then(|| self.evaluate(&self.validation_texts));
+        if let Some(control) = &self.training_control {
+            control.record_epoch(epoch + 1, total_epochs, avg_loss, validation);
+        }
+        Some((avg_loss, validation))
+    }
+    
+    /// Средний loss на текстах без обучения (например, на валидационной выборке)
+    pub fn evaluate(&self, texts: &[String]) -> f64 {
+        let mut total_loss = 0.0;
+        let mut num_samples = 0;
+        for text in texts {
+            let tokens = self.tokenize(text);
+            for end in 1..tokens.len() {
+                let context = &tokens[end.saturating_sub(self.context_length)..end];
+                total_loss += self.compute_loss(&self.forward(context), tokens[end]);
+                num_samples += 1;
+            }
+        }
+        if num_samples > 0 { total_loss / num_samples as f64 } else { 0.0 }
     }
     
     fn compute_loss(&self, output: &[f64], target: usize) -> f64 {
@@ -613,85 +2175,486 @@ impl AIModel {
             return 1.0;
         }
         // Cross-entropy loss
-        -output[target].ln()
+        -output[target].max(f64::MIN_POSITIVE).ln()
     }
     
-    fn update_weights(&mut self, context: &[usize], target: usize, output: &[f64]) {
-        // Упрощенный градиентный спуск
-        // В реальной реализации здесь был бы полный backpropagation
-        let lr = self.learning_rate;
+    /// Обратное распространение ошибки (softmax + cross-entropy)
+    ///
+    /// Возвращает выход сети и градиенты для каждого слоя. Для embedding слоя
+    /// градиенты содержат только строки токенов из контекста.
+    fn backward(&self, context: &[usize], target: usize) -> (Vec<f64>, Vec<LayerGradient>) {
+        let trace = self.forward_trace(context, true);
+        let output = trace.activations.last().cloned().unwrap_or_default();
         
         if target >= output.len() || self.layers.is_empty() {
-            return;
+            return (output, Vec::new());
         }
         
-        // Обновление весов выходного слоя
-        let output_layer_idx = self.layers.len() - 1;
-        if output_layer_idx < self.layers.len() {
-            let error = output[target] - 1.0; // gradient
+        // Градиент softmax + cross-entropy: p - onehot(target)
+        let mut delta = output.clone();
+        delta[target] -= 1.0;
+        
+        let mut gradients = Vec::with_capacity(self.layers.len());
+        for (idx, layer) in self.layers.iter().enumerate().skip(1).rev() {
+            let state = &trace.layers[idx];
+            if let Some(mask) = &state.dropout_mask {
+                delta.iter_mut().zip(mask).for_each(|(d, m)| *d *= m);
+            }
+            
+            if layer.kind == LayerKind::Gru {
+                let (gradient, input_delta) = Self::gru_backward(layer, &trace.gru_steps, delta);
+                gradients.push(gradient);
+                delta = input_delta;
+                continue;
+            }
+            
+            let input = &trace.activations[idx - 1];
+            delta = Self::activation_backward(&layer.activation, &state.activated, delta);
+            if let Some((normalized, std)) = &state.normalized {
+                delta = Self::layer_norm_backward(normalized, *std, delta);
+            }
+            
+            // dW = delta * input^T, только для ненулевых входов
+            let columns = input
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| **x != 0.0)
+                .map(|(j, x)| (j, delta.iter().map(|d| x * d).collect()))
+                .collect();
+            let input_delta = self
+                .gpu_affine(&layer.weights, &delta, &vec![0.0; layer.inputs()], true, false)
+                .or_else(|| {
+                    let weights = layer.compute_weights()?;
+                    let delta = DVector::from_iterator(delta.len(), delta.iter().map(|&d| d as f32));
+                    Some(weights.tr_mul(&delta).iter().map(|&d| d as f64).collect())
+                })
+                .unwrap_or_else(|| layer.weights.tr_mul(&DVector::from_column_slice(&delta)).as_slice().to_vec());
             
-            // Простое обновление bias
-            if target < self.layers[output_layer_idx].biases.len() {
-                self.layers[output_layer_idx].biases[target] -= lr * error;
+            gradients.push(LayerGradient { columns, biases: delta });
+            delta = input_delta;
+        }
+        
+        // Градиенты embedding: по столбцу на каждый токен контекста
+        let mut columns: Vec<(usize, Vec<f64>)> = Vec::new();
+        for (pos, &token) in context.iter().take(self.context_length).enumerate() {
+            if token >= self.layers[0].inputs() {
+                continue;
+            }
+            let slice = &delta[pos * self.embedding_dim..(pos + 1) * self.embedding_dim];
+            match columns.iter_mut().find(|(column, _)| *column == token) {
+                Some((_, grad)) => grad.iter_mut().zip(slice).for_each(|(g, d)| *g += d),
+                None => columns.push((token, slice.to_vec())),
+            }
+        }
+        gradients.push(LayerGradient { columns, biases: Vec::new() });
+        gradients.reverse();
+        
+        (output, gradients)
+    }
+    
+    /// Backward для layer norm без обучаемых параметров
+    fn layer_norm_backward(normalized: &[f64], std: f64, delta: Vec<f64>) -> Vec<f64> {
+        let n = delta.len() as f64;
+        let mean_delta = delta.iter().sum::<f64>() / n;
+        let mean_delta_y = delta.iter().zip(normalized).map(|(d, y)| d * y).sum::<f64>() / n;
+        delta
+            .iter()
+            .zip(normalized)
+            .map(|(d, y)| (d - mean_delta - y * mean_delta_y) / std)
+            .collect()
+    }
+    
+    /// Смена зерна; влияет на последующие dropout, сэмплирование и новые веса
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+        self.rng = ModelRng::new(seed);
+    }
+    
+    /// Dropout и layer norm для всех скрытых слоёв
+    pub fn set_regularization(&mut self, dropout: f64, layer_norm: bool) {
+        let hidden = self.layers.len().saturating_sub(1);
+        for layer in self.layers.iter_mut().take(hidden).skip(1) {
+            layer.dropout = dropout.clamp(0.0, 0.95);
+            layer.layer_norm = layer_norm && layer.kind == LayerKind::Dense;
+        }
+    }
+    
+    /// Производная функции активации по выходу слоя
+    fn activation_backward(activation: &ActivationType, output: &[f64], delta: Vec<f64>) -> Vec<f64> {
+        match activation {
+            ActivationType::ReLU => delta.iter().zip(output).map(|(d, &y)| if y > 0.0 { *d } else { 0.0 }).collect(),
+            ActivationType::Tanh => delta.iter().zip(output).map(|(d, y)| d * (1.0 - y * y)).collect(),
+            ActivationType::Sigmoid => delta.iter().zip(output).map(|(d, y)| d * y * (1.0 - y)).collect(),
+            // Softmax всегда идёт вместе с cross-entropy, градиент уже посчитан
+            ActivationType::Softmax => delta,
+        }
+    }
+    
+    /// Применение градиентов выбранным оптимизатором
+    fn apply_gradients(&mut self, gradients: &[LayerGradient]) {
+        if gradients.is_empty() {
+            return;
+        }
+        
+        let lr = self.learning_rate;
+        let optimizer = self.optimizer;
+        let state = &mut self.optimizer_state;
+        state.step += 1;
+        state.ensure_layers(self.layers.len());
+        let t = state.step;
+        
+        for (idx, (layer, gradient)) in self.layers.iter_mut().zip(gradients).enumerate() {
+            for (column, grad) in &gradient.columns {
+                let (m, v) = state.moments(idx, Some(*column), grad.len());
+                optimizer.step(layer.column_mut(*column), grad, m, v, lr, t);
+                layer.sync_compute_column(*column);
             }
+            if !gradient.biases.is_empty() {
+                let (m, v) = state.moments(idx, None, gradient.biases.len());
+                optimizer.step(&mut layer.biases, &gradient.biases, m, v, lr, t);
+            }
+        }
+    }
+    
+    /// Смена оптимизатора (сбрасывает накопленное состояние)
+    pub fn set_optimizer(&mut self, optimizer: Optimizer) {
+        if self.optimizer != optimizer {
+            self.optimizer = optimizer;
+            self.optimizer_state = OptimizerState::default();
         }
     }
     
-    fn sample_token(&self, probs: &[f64]) -> usize {
-        let mut rng = rand::thread_rng();
-        let random_val: f64 = rng.gen();
+    fn sample_token(&self, probs: &[f64], config: &GenerationConfig) -> usize {
+        if probs.is_empty() {
+            return 0;
+        }
+        
+        // Температура 0 — жадный выбор
+        if config.temperature <= 0.0 {
+            return probs
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(idx, _)| idx)
+                .unwrap_or(0);
+        }
+        
+        // Перевзвешивание температурой: p^(1/T)
+        let mut candidates: Vec<(usize, f64)> = probs
+            .iter()
+            .enumerate()
+            .map(|(idx, &p)| (idx, p.max(0.0).powf(1.0 / config.temperature)))
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        
+        if config.top_k > 0 {
+            candidates.truncate(config.top_k);
+        }
+        
+        let total: f64 = candidates.iter().map(|(_, p)| p).sum();
+        if total <= 0.0 {
+            return candidates[0].0;
+        }
+        
+        // Nucleus: оставляем минимальный префикс с суммой >= top_p
+        if config.top_p < 1.0 {
+            let mut cumsum = 0.0;
+            let mut keep = candidates.len();
+            for (i, (_, p)) in candidates.iter().enumerate() {
+                cumsum += p / total;
+                if cumsum >= config.top_p {
+                    keep = i + 1;
+                    break;
+                }
+            }
+            candidates.truncate(keep);
+        }
+        
+        let total: f64 = candidates.iter().map(|(_, p)| p).sum();
+        let random_val: f64 = self.rng.with(|rng| rng.gen::<f64>()) * total;
         let mut cumsum = 0.0;
         
-        for (idx, &prob) in probs.iter().enumerate() {
-            cumsum += prob;
-            if random_val < cumsum {
-                return idx;
+        for &(idx, p) in &candidates {
+            cumsum += p;
+            if random_val < cumsum {
+                return idx;
+            }
+        }
+        
+        candidates.last().map(|(idx, _)| *idx).unwrap_or(0)
+    }
+    
+    /// Обучение BPE токенизатора на корпусе
+    ///
+    /// Заменяет словарь слов на словарь подслов и переинициализирует слои,
+    /// поэтому после вызова модель нужно обучить заново.
+    pub fn train_tokenizer(&mut self, texts: &[String], vocab_size: usize) {
+        let texts: Vec<String> = texts.iter().map(|text| self.normalize(text).into_owned()).collect();
+        let tokenizer = BpeTokenizer::train(&texts, vocab_size);
+        self.replace_vocab(tokenizer.tokens.clone());
+        self.tokenizer = Some(tokenizer);
+    }
+    
+    /// Словарь слов из корпуса вместо встроенного списка
+    ///
+    /// Остаются не более `max_size` токенов (включая служебные), встретившихся
+    /// хотя бы `min_freq` раз. Embedding и выходной слой пересоздаются под
+    /// новый словарь. Возвращает размер словаря.
+    pub fn build_vocab_from_corpus(&mut self, texts: &[String], max_size: usize, min_freq: usize) -> usize {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for text in texts {
+            for word in self.normalize(text).split_whitespace() {
+                // Нормализатор сам решает про регистр и оставляет `<NUM>` как есть
+                let word = if self.normalizer.is_some() { word.to_string() } else { word.to_lowercase() };
+                *counts.entry(word).or_insert(0) += 1;
+            }
+        }
+        
+        let mut tokens: Vec<String> = SPECIAL_TOKENS.iter().map(|t| t.to_string()).collect();
+        let mut words: Vec<(String, usize)> = counts
+            .into_iter()
+            .filter(|(word, count)| *count >= min_freq && !tokens.contains(word))
+            .collect();
+        words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tokens.extend(
+            words
+                .into_iter()
+                .take(max_size.saturating_sub(tokens.len()))
+                .map(|(word, _)| word),
+        );
+        
+        self.replace_vocab(tokens);
+        self.tokenizer = None;
+        self.vocab.len()
+    }
+    
+    /// Замена словаря (индекс = позиция токена) с пересозданием слоёв
+    fn replace_vocab(&mut self, tokens: Vec<String>) {
+        self.vocab.clear();
+        self.reverse_vocab.clear();
+        for (idx, token) in tokens.into_iter().enumerate() {
+            self.vocab.insert(token.clone(), idx);
+            self.reverse_vocab.insert(idx, token);
+        }
+        
+        self.layers.clear();
+        self.optimizer_state = OptimizerState::default();
+        self.init_layers();
+    }
+    
+    /// Нормализация текста, общая для обучения и генерации
+    pub fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match &self.normalizer {
+            Some(normalizer) => Cow::Owned(normalizer.normalize(text)),
+            None => Cow::Borrowed(text),
+        }
+    }
+    
+    /// Токенизация текста
+    pub fn tokenize(&self, text: &str) -> Vec<usize> {
+        let text = self.normalize(text);
+        if let Some(tokenizer) = &self.tokenizer {
+            return tokenizer
+                .encode(&text)
+                .iter()
+                .map(|piece| *self.vocab.get(piece).unwrap_or(&self.get_unk_token()))
+                .collect();
+        }
+        
+        text.split_whitespace()
+            .map(|word| {
+                self.vocab
+                    .get(word)
+                    .or_else(|| self.vocab.get(&word.to_lowercase()))
+                    .copied()
+                    .unwrap_or_else(|| self.get_unk_token())
+            })
+            .collect()
+    }
+    
+    pub fn get_unk_token(&self) -> usize {
+        *self.vocab.get("<UNK>").unwrap_or(&0)
+    }
+    
+    /// Декодирование токенов в текст
+    pub fn decode(&self, tokens: &[usize]) -> String {
+        if let Some(tokenizer) = &self.tokenizer {
+            let pieces: Vec<String> = tokens
+                .iter()
+                .filter_map(|token| self.reverse_vocab.get(token))
+                .cloned()
+                .collect();
+            return tokenizer.decode(&pieces);
+        }
+        
+        tokens
+            .iter()
+            .filter_map(|&token| self.reverse_vocab.get(&token))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    
+    /// Добавление нового слова в словарь
+    ///
+    /// Embedding и выходной слой расширяются случайными весами, поэтому
+    /// новое слово сразу можно и подать на вход, и сгенерировать.
+    pub fn add_to_vocab(&mut self, word: String) {
+        if self.vocab.contains_key(&word) {
+            return;
+        }
+        
+        // Следующий свободный индекс (в старых моделях индексы могли идти с пропусками)
+        let idx = self.reverse_vocab.keys().max().map_or(0, |max| max + 1);
+        self.vocab.insert(word.clone(), idx);
+        self.reverse_vocab.insert(idx, word);
+        
+        if self.layers.len() < 2 {
+            return;
+        }
+        let size = idx + 1;
+        let rng = &self.rng;
+        
+        // Embedding: новый столбец
+        let embedding = &mut self.layers[0];
+        let columns = embedding.inputs();
+        if columns < size {
+            embedding.weights.resize_horizontally_mut(size, 0.0);
+            rng.with(|rng| {
+                for column in columns..size {
+                    embedding
+                        .column_mut(column)
+                        .iter_mut()
+                        .for_each(|w| *w = rng.gen_range(-0.1..0.1));
+                }
+            });
+        }
+        
+        // Выходной слой: новая строка весов и смещение
+        let output = self.layers.last_mut().unwrap();
+        let rows = output.weights.nrows();
+        if rows < size {
+            output.weights.resize_vertically_mut(size, 0.0);
+            rng.with(|rng| {
+                for row in rows..size {
+                    output
+                        .weights
+                        .row_mut(row)
+                        .iter_mut()
+                        .for_each(|w| *w = rng.gen_range(-0.1..0.1));
+                }
+            });
+            output.biases.resize(size, 0.0);
+            if output.compute_weights.is_some() {
+                output.compute_weights = Some(output.weights.map(|w| w as f32));
+            }
+        }
+    }
+    
+    /// Слияние с другой моделью той же архитектуры: `(1 - alpha) * self + alpha * other`
+    ///
+    /// Словари объединяются: у общих токенов усредняются эмбеддинги и строки
+    /// выходного слоя, токены только из `other` переносятся с его весами.
+    /// Состояние оптимизатора сбрасывается.
+    pub fn merge(&self, other: &AIModel, alpha: f64) -> Result<AIModel, String> {
+        let compatible = self.embedding_dim == other.embedding_dim
+            && self.context_length == other.context_length
+            && self.recurrent == other.recurrent
+            && self.layers.len() == other.layers.len()
+            && self.layers.len() >= 3
+            && self.layers[1..self.layers.len() - 1]
+                .iter()
+                .zip(&other.layers[1..other.layers.len() - 1])
+                .all(|(a, b)| a.kind == b.kind && a.weights.shape() == b.weights.shape())
+            && self.layers.last().unwrap().inputs() == other.layers.last().unwrap().inputs();
+        if !compatible {
+            return Err("✗ Архитектуры моделей не совпадают".to_string());
+        }
+        
+        let alpha = alpha.clamp(0.0, 1.0);
+        let mix = |a: f64, b: f64| (1.0 - alpha) * a + alpha * b;
+        let mut merged = self.clone();
+        merged.optimizer_state = OptimizerState::default();
+        
+        // Скрытые слои
+        let last = merged.layers.len() - 1;
+        for (layer, source) in merged.layers[1..last].iter_mut().zip(&other.layers[1..last]) {
+            layer.weights.zip_apply(&source.weights, |a, b| *a = mix(*a, b));
+            layer.biases.iter_mut().zip(&source.biases).for_each(|(a, &b)| *a = mix(*a, b));
+        }
+        
+        // Словарь: общие токены усредняются, новые копируются из `other`
+        let source_embedding = &other.layers[0];
+        let source_output = other.layers.last().unwrap();
+        for (token, &source_idx) in &other.vocab {
+            if source_idx >= source_embedding.inputs() || source_idx >= source_output.biases.len() {
+                continue;
+            }
+            let weight = if merged.vocab.contains_key(token) { alpha } else { 1.0 };
+            merged.add_to_vocab(token.clone());
+            let idx = merged.vocab[token];
+            
+            let embedding = &mut merged.layers[0];
+            if idx < embedding.inputs() {
+                let source = source_embedding.weights.column(source_idx);
+                embedding
+                    .column_mut(idx)
+                    .iter_mut()
+                    .zip(source.iter())
+                    .for_each(|(a, &b)| *a = (1.0 - weight) * *a + weight * b);
+            }
+            let output = merged.layers.last_mut().unwrap();
+            if idx < output.biases.len() {
+                let source = source_output.weights.row(source_idx);
+                output
+                    .weights
+                    .row_mut(idx)
+                    .iter_mut()
+                    .zip(source.iter())
+                    .for_each(|(a, &b)| *a = (1.0 - weight) * *a + weight * b);
+                output.biases[idx] = (1.0 - weight) * output.biases[idx] + weight * source_output.biases[source_idx];
             }
         }
         
-        probs.len().saturating_sub(1)
+        merged.sync_compute_weights();
+        Ok(merged)
     }
     
-    /// Токенизация текста
-    pub fn tokenize(&self, text: &str) -> Vec<usize> {
-        text.split_whitespace()
-            .map(|word| {
-                let word_lower = word.to_lowercase();
-                *self.vocab.get(&word_lower).unwrap_or(&self.get_unk_token())
-            })
-            .collect()
+    /// Текущий словарь модели (токены в порядке индексов)
+    pub fn vocabulary(&self) -> Vocabulary {
+        let mut entries: Vec<(&String, &usize)> = self.vocab.iter().collect();
+        entries.sort_by_key(|(_, &idx)| idx);
+        Vocabulary {
+            tokens: entries.into_iter().map(|(token, _)| token.clone()).collect(),
+            tokenizer: self.tokenizer.clone(),
+        }
     }
     
-    fn get_unk_token(&self) -> usize {
-        *self.vocab.get("<UNK>").unwrap_or(&0)
+    /// Сохранение словаря без весов
+    pub fn save_vocab(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        self.vocabulary().save(path)
     }
     
-    /// Декодирование токенов в текст
-    pub fn decode(&self, tokens: &[usize]) -> String {
-        tokens
-            .iter()
-            .filter_map(|&token| self.reverse_vocab.get(&token))
-            .cloned()
-            .collect::<Vec<_>>()
-            .join(" ")
+    /// Замена словаря загруженным из файла
+    ///
+    /// Индексы токенов меняются, поэтому слои пересоздаются (как при обучении токенизатора).
+    pub fn load_vocab(&mut self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let vocabulary = Vocabulary::load(path)?;
+        self.replace_vocab(vocabulary.tokens);
+        self.tokenizer = vocabulary.tokenizer;
+        Ok(())
     }
     
-    /// Добавление нового слова в словарь
-    pub fn add_to_vocab(&mut self, word: String) {
-        if !self.vocab.contains_key(&word) {
-            let idx = self.vocab.len();
-            self.vocab.insert(word.clone(), idx);
-            self.reverse_vocab.insert(idx, word);
-            
-            // Расширяем embedding layer
-            let mut rng = rand::thread_rng();
-            if !self.layers.is_empty() {
-                let new_embedding: Vec<f64> = (0..self.embedding_dim)
-                    .map(|_| rng.gen_range(-0.1..0.1))
-                    .collect();
-                self.layers[0].weights.push(new_embedding);
-            }
+    /// Добавление в словарь отсутствующих токенов из файла; веса сохраняются
+    ///
+    /// Возвращает количество добавленных токенов.
+    pub fn merge_vocab(&mut self, path: impl AsRef<Path>) -> Result<usize, Box<dyn std::error::Error>> {
+        let vocabulary = Vocabulary::load(path)?;
+        let before = self.vocab.len();
+        for token in vocabulary.tokens {
+            self.add_to_vocab(token);
         }
+        Ok(self.vocab.len() - before)
     }
     
     /// Сохранение модели
@@ -704,30 +2667,209 @@ impl AIModel {
     /// Загрузка модели
     pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
         let data = std::fs::read_to_string(path)?;
-        let model = serde_json::from_str(&data)?;
+        let mut model: AIModel = serde_json::from_str(&data)?;
+        model.rng = ModelRng::new(model.seed);
         Ok(model)
     }
     
+    /// Число обучаемых параметров (веса и смещения всех слоёв)
+    pub fn parameter_count(&self) -> usize {
+        self.layers.iter().map(|layer| layer.weights.len() + layer.biases.len()).sum()
+    }
+    
     /// Получение информации о модели
     pub fn info(&self) -> String {
         format!(
-            "Модель AI (fp64)\n\
-             Словарь: {} слов\n\
+            "Модель AI ({})\n\
+             Словарь: {} токенов ({})\n\
              Embedding dimension: {}\n\
              Hidden dimension: {}\n\
              Context length: {}\n\
-             Слои: {}\n\
-             Learning rate: {}",
+             Слои: {} ({}: {})\n\
+             Learning rate: {}\n\
+             Batch size: {}\n\
+             Оптимизатор: {}\n\
+             Вычисления: {}",
+            self.precision.name(),
             self.vocab.len(),
+            match &self.tokenizer {
+                Some(tokenizer) => format!("BPE, {} слияний", tokenizer.merges.len()),
+                None => "слова".to_string(),
+            },
             self.embedding_dim,
             self.hidden_dim,
             self.context_length,
             self.layers.len(),
-            self.learning_rate
+            if self.recurrent { "GRU" } else { "feed-forward" },
+            self.hidden_specs()
+                .iter()
+                .map(|spec| format!("{} {:?}", spec.size, spec.activation))
+                .collect::<Vec<_>>()
+                .join(" → "),
+            self.learning_rate,
+            self.batch_size,
+            self.optimizer.name(),
+            match &self.gpu {
+                Some(gpu) => format!("GPU ({})", gpu.adapter_name()),
+                None => "CPU".to_string(),
+            }
+        )
+    }
+}
+
+/// Замеры одной генерации ответа
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationMetrics {
+    /// Сгенерированных токенов
+    pub tokens: usize,
+    pub elapsed_secs: f64,
+    /// Название источника ответа (`InferenceBackend::name`)
+    pub backend: String,
+}
+
+impl GenerationMetrics {
+    pub fn tokens_per_sec(&self) -> f64 {
+        if self.elapsed_secs > 0.0 {
+            self.tokens as f64 / self.elapsed_secs
+        } else {
+            0.0
+        }
+    }
+    
+    /// Строка для подписи под сообщением
+    pub fn summary(&self) -> String {
+        format!(
+            "{} ток. · {:.2} с · {:.1} ток/с · {}",
+            self.tokens,
+            self.elapsed_secs,
+            self.tokens_per_sec(),
+            self.backend
         )
     }
 }
 
+/// Источник ответов для чата: локальная сеть или внешний сервис
+pub trait InferenceBackend {
+    /// Название для UI
+    fn name(&self) -> String;
+    
+    fn generate(&self, prompt: &str, config: &GenerationConfig) -> Result<String, Box<dyn std::error::Error>>;
+}
+
+impl InferenceBackend for AIModel {
+    fn name(&self) -> String {
+        "Локальная модель".to_string()
+    }
+    
+    fn generate(&self, prompt: &str, config: &GenerationConfig) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(AIModel::generate(self, prompt, config))
+    }
+}
+
+/// Клиент OpenAI-совместимого API (`POST {base_url}/chat/completions`)
+///
+/// Подходит и для локальных серверов (llama.cpp, Ollama, LM Studio) по
+/// HTTP, и для облачных сервисов по HTTPS (rustls).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenAIBackend {
+    /// Например `http://localhost:11434/v1`
+    pub base_url: String,
+    pub api_key: String,
+    pub model: String,
+    pub timeout_secs: u64,
+}
+
+/// Сколько ждать соединения с сервером API: недоступный хост не должен
+/// держать запрос все `timeout_secs`
+const OPENAI_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+impl Default for OpenAIBackend {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:11434/v1".to_string(),
+            api_key: String::new(),
+            model: "llama3".to_string(),
+            timeout_secs: 60,
+        }
+    }
+}
+
+impl OpenAIBackend {
+    /// Тело запроса chat/completions
+    fn request_body(&self, prompt: &str, config: &GenerationConfig) -> serde_json::Value {
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "messages": [{ "role": "user", "content": prompt }],
+            "temperature": config.temperature,
+            "top_p": config.top_p,
+            "max_tokens": config.max_tokens,
+        });
+        if !config.stop_sequences.is_empty() {
+            body["stop"] = serde_json::json!(config.stop_sequences);
+        }
+        body
+    }
+    
+    /// POST JSON и разбор JSON-ответа; ответ не 2xx возвращается ошибкой с телом
+    fn post_json(&self, path: &str, body: &serde_json::Value) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let timeout = std::time::Duration::from_secs(self.timeout_secs.max(1));
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(timeout.min(OPENAI_CONNECT_TIMEOUT))
+            .timeout_read(timeout)
+            .timeout_write(timeout)
+            .build();
+        let url = format!("{}/{}", self.base_url.trim_end_matches('/'), path);
+        let mut request = agent.post(&url);
+        if !self.api_key.is_empty() {
+            request = request.set("Authorization", &format!("Bearer {}", self.api_key));
+        }
+        match request.send_json(body) {
+            Ok(response) => Ok(response.into_json()?),
+            Err(ureq::Error::Status(status, response)) => {
+                Err(format!("HTTP {}: {}", status, response.into_string().unwrap_or_default().trim()).into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl InferenceBackend for OpenAIBackend {
+    fn name(&self) -> String {
+        format!("API: {}", self.model)
+    }
+    
+    fn generate(&self, prompt: &str, config: &GenerationConfig) -> Result<String, Box<dyn std::error::Error>> {
+        self.generate_with_usage(prompt, config).map(|(text, _)| text)
+    }
+}
+
+impl OpenAIBackend {
+    /// Ответ и число токенов из `usage.completion_tokens`, если сервис его сообщил
+    pub fn generate_with_usage(
+        &self,
+        prompt: &str,
+        config: &GenerationConfig,
+    ) -> Result<(String, Option<usize>), Box<dyn std::error::Error>> {
+        let response = self.post_json("chat/completions", &self.request_body(prompt, config))?;
+        let content = response["choices"][0]["message"]["content"]
+            .as_str()
+            .ok_or("В ответе API нет choices[0].message.content")?;
+        let tokens = response["usage"]["completion_tokens"].as_u64().map(|n| n as usize);
+        Ok((content.trim().to_string(), tokens))
+    }
+}
+
+/// Косинусная близость векторов (0 для нулевого вектора)
+pub fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
 impl Default for AIModel {
     fn default() -> Self {
         Self::new(128, 256, 8)
@@ -756,7 +2898,692 @@ mod tests {
     #[test]
     fn test_generation() {
         let model = AIModel::default();
-        let response = model.generate("привет", 5);
+        let config = GenerationConfig { max_tokens: 5, ..Default::default() };
+        let response = model.generate("привет", &config);
         assert!(!response.is_empty());
     }
+    
+    #[test]
+    fn test_sampling_controls() {
+        let model = AIModel::new(8, 16, 2);
+        let probs = vec![0.1, 0.6, 0.3];
+        
+        let greedy = GenerationConfig { temperature: 0.0, ..Default::default() };
+        assert_eq!(model.sample_token(&probs, &greedy), 1);
+        
+        let top_k = GenerationConfig { top_k: 1, ..Default::default() };
+        assert_eq!(model.sample_token(&probs, &top_k), 1);
+        
+        let top_p = GenerationConfig { top_k: 0, top_p: 0.8, ..Default::default() };
+        for _ in 0..20 {
+            assert_ne!(model.sample_token(&probs, &top_p), 0);
+        }
+    }
+    
+    #[test]
+    fn test_optimizers_reduce_loss() {
+        let texts = vec!["привет как дела у тебя".to_string()];
+        for optimizer in [Optimizer::Sgd, Optimizer::momentum(), Optimizer::adam()] {
+            let mut model = AIModel::new(16, 32, 4);
+            model.learning_rate = 0.01;
+            model.set_optimizer(optimizer);
+            
+            let report = model.train(&texts, 5, |_, _, _| {});
+            assert!(report.improved(), "{}: {:?}", optimizer.name(), report.epoch_losses);
+        }
+    }
+    
+    #[test]
+    fn test_train_async_pause_and_cancel() {
+        let model = Arc::new(Mutex::new(AIModel::new(8, 16, 2)));
+        let texts = vec!["привет как дела у тебя".to_string(); 4];
+        
+        let handle = AIModel::train_async(Arc::clone(&model), texts.clone(), 2);
+        let report = handle.join().unwrap();
+        assert_eq!(report.epoch_losses.len(), 2);
+        assert!(!report.cancelled);
+        assert!(model.lock().unwrap().training_control.is_none());
+        
+        let handle = AIModel::train_async(Arc::clone(&model), texts, 1000);
+        handle.pause();
+        std::thread::sleep(Duration::from_millis(100));
+        let (paused_epoch, _) = handle.control().progress();
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(handle.control().progress().0, paused_epoch);
+        
+        handle.resume();
+        handle.cancel();
+        let report = handle.join().unwrap();
+        assert!(report.cancelled);
+        assert!(report.epoch_losses.len() < 1000);
+    }
+    
+    #[test]
+    fn test_seed_makes_runs_reproducible() {
+        let build = || AIModelBuilder::new().embedding_dim(8).hidden_layer(16, ActivationType::Tanh).seed(7).build().unwrap();
+        let texts = vec!["привет как дела у тебя".to_string()];
+        let config = GenerationConfig { max_tokens: 10, ..Default::default() };
+        
+        let (mut a, mut b) = (build(), build());
+        assert_eq!(a.layers[1].weights, b.layers[1].weights);
+        a.set_regularization(0.2, false);
+        b.set_regularization(0.2, false);
+        assert_eq!(a.train(&texts, 2, |_, _, _| {}), b.train(&texts, 2, |_, _, _| {}));
+        assert_eq!(a.generate("привет", &config), b.generate("привет", &config));
+        
+        a.set_seed(Some(1));
+        let first = a.generate("привет", &config);
+        a.set_seed(Some(1));
+        assert_eq!(a.generate("привет", &config), first);
+    }
+    
+    #[test]
+    fn test_evaluate_matches_training_loss_scale() {
+        let texts = vec!["привет как дела у тебя".to_string()];
+        let mut model = AIModel::new(8, 16, 2);
+        model.learning_rate = 0.01;
+        
+        let before = model.evaluate(&texts);
+        model.train(&texts, 5, |_, _, _| {});
+        assert!(model.evaluate(&texts) < before);
+        assert_eq!(model.evaluate(&[]), 0.0);
+        
+        // Валидация после каждой эпохи
+        model.validation_texts = texts.clone();
+        let report = model.train(&texts, 2, |_, _, _| {});
+        assert_eq!(report.validation_losses.len(), 2);
+        assert_eq!(report.validation_loss, report.validation_losses.last().copied());
+        let history = report.loss_history();
+        assert_eq!(history.iter().map(|loss| loss.epoch).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(history[1].validation, Some(report.validation_losses[1]));
+    }
+    
+    #[test]
+    fn test_training_report_records_steps() {
+        let texts = vec!["привет как дела у тебя".to_string()];
+        let mut model = AIModel::new(8, 16, 2);
+        model.learning_rate = 0.01;
+        
+        let report = model.train(&texts, 3, |_, _, _| {});
+        assert_eq!(report.epoch_losses.len(), 3);
+        assert!(report.step_losses.is_empty());
+        
+        model.record_step_losses = true;
+        let report = model.train(&texts, 2, |_, _, _| {});
+        let steps_per_epoch = model.tokenize(&texts[0]).len() - 1;
+        assert_eq!(report.step_losses.len(), 2 * steps_per_epoch);
+        let first_epoch = &report.step_losses[..steps_per_epoch];
+        let mean = first_epoch.iter().sum::<f64>() / steps_per_epoch as f64;
+        assert!((mean - report.epoch_losses[0]).abs() < 1e-12);
+        assert_eq!(report.best_epoch().map(|(epoch, _)| epoch <= 2), Some(true));
+    }
+    
+    #[test]
+    fn test_train_tokenizer_replaces_vocab() {
+        let mut model = AIModel::new(8, 16, 2);
+        let texts = vec!["приветствую приветливых соседей".to_string(); 4];
+        model.train_tokenizer(&texts, 80);
+        
+        assert!(model.tokenizer.is_some());
+        assert_eq!(model.vocab.len(), model.layers.last().unwrap().biases.len());
+        let unk = model.get_unk_token();
+        let tokens = model.tokenize("приветливые соседи");
+        assert!(tokens.iter().all(|&t| t != unk));
+        assert_eq!(model.decode(&tokens), "приветливые соседи");
+    }
+    
+    #[test]
+    fn test_gru_training_and_generation() {
+        let mut model = AIModel::new(8, 16, 4);
+        model.set_recurrent(true);
+        assert_eq!(model.layers[1].kind, LayerKind::Gru);
+        assert_eq!(model.layers[1].inputs(), 8 + 16);
+        
+        let texts = vec!["привет как дела у тебя".to_string()];
+        model.learning_rate = 0.01;
+        let losses = std::cell::RefCell::new(Vec::new());
+        model.train(&texts, 5, |_, _, loss| losses.borrow_mut().push(loss));
+        let losses = losses.into_inner();
+        assert!(losses.last().unwrap() < losses.first().unwrap(), "{:?}", losses);
+        
+        let config = GenerationConfig { max_tokens: 20, ..Default::default() };
+        let _ = model.generate("привет как дела у тебя сегодня вечером", &config);
+    }
+    
+    #[test]
+    fn test_gru_gradient_matches_numeric() {
+        let mut model = AIModel::new(3, 4, 3);
+        model.set_recurrent(true);
+        let context = [5, 9, 12];
+        let target = 7;
+        
+        let (_, gradients) = model.backward(&context, target);
+        let analytic = gradients[1].columns.iter().find(|(column, _)| *column == 4).unwrap().1[5];
+        
+        let eps = 1e-6;
+        let mut plus = model.clone();
+        plus.layers[1].weights[(5, 4)] += eps;
+        let mut minus = model.clone();
+        minus.layers[1].weights[(5, 4)] -= eps;
+        let loss = |m: &AIModel| m.compute_loss(&m.forward(&context), target);
+        let numeric = (loss(&plus) - loss(&minus)) / (2.0 * eps);
+        
+        assert!((analytic - numeric).abs() < 1e-6, "{} vs {}", analytic, numeric);
+    }
+    
+    #[test]
+    fn test_layer_norm_gradient_matches_numeric() {
+        let mut model = AIModel::new(3, 5, 2);
+        model.set_regularization(0.0, true);
+        let context = [4, 11];
+        let target = 2;
+        
+        let (_, gradients) = model.backward(&context, target);
+        let analytic = gradients[2].columns.iter().find(|(column, _)| *column == 1).unwrap().1[3];
+        
+        let eps = 1e-6;
+        let mut plus = model.clone();
+        plus.layers[2].weights[(3, 1)] += eps;
+        let mut minus = model.clone();
+        minus.layers[2].weights[(3, 1)] -= eps;
+        let loss = |m: &AIModel| m.compute_loss(&m.forward(&context), target);
+        let numeric = (loss(&plus) - loss(&minus)) / (2.0 * eps);
+        
+        assert!((analytic - numeric).abs() < 1e-6, "{} vs {}", analytic, numeric);
+    }
+    
+    #[test]
+    fn test_dropout_only_during_training() {
+        let mut model = AIModel::new(8, 16, 2);
+        model.set_regularization(0.5, false);
+        assert!(model.layers.last().unwrap().dropout == 0.0);
+        
+        let context = [3, 7];
+        assert_eq!(model.forward(&context), model.forward(&context));
+        
+        let trace = model.forward_trace(&context, true);
+        let mask = trace.layers[1].dropout_mask.as_ref().unwrap();
+        assert!(mask.iter().all(|&m| m == 0.0 || m == 2.0));
+    }
+    
+    #[test]
+    fn test_checkpoints_and_resume() {
+        let dir = std::env::temp_dir().join(format!("aimodel_ckpt_{}", std::process::id()));
+        let checkpoints = CheckpointConfig { dir: dir.clone(), every_epochs: 1, keep_last: 2 };
+        let texts = vec!["привет как дела".to_string()];
+        
+        let mut model = AIModel::new(8, 16, 2);
+        model.train_with_checkpoints(&texts, 3, &checkpoints, |_, _, _| {}).unwrap();
+        assert_eq!(checkpoints.list().len(), 2);
+        
+        // Имитируем падение после первой эпохи
+        checkpoints.clear().unwrap();
+        checkpoints.save(&model, 1, 3).unwrap();
+        
+        let epochs = std::cell::RefCell::new(Vec::new());
+        let mut resumed = AIModel::new(8, 16, 2);
+        let report = resumed
+            .resume_training(&texts, &checkpoints, |epoch, _, _| epochs.borrow_mut().push(epoch))
+            .unwrap();
+        assert_eq!(report.start_epoch, 1);
+        assert_eq!(report.epoch_losses.len(), 2);
+        assert_eq!(epochs.into_inner(), vec![2, 3]);
+        assert!(resumed.optimizer_state.step > model.optimizer_state.step);
+        
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+    
+    #[test]
+    fn test_auto_save_prunes_old_models() {
+        let dir = std::env::temp_dir().join(format!("aimodel_autosave_{}", std::process::id()));
+        let auto_save = AutoSaveConfig::new(&dir, 2);
+        let model = AIModel::new(8, 16, 2);
+        
+        for stamp in [100, 200, 300] {
+            auto_save.save(&model, stamp).unwrap();
+        }
+        let files = auto_save.list();
+        let names: Vec<_> = files.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names, ["model_000000000200.json", "model_000000000300.json"]);
+        assert!(!dir.join("model_000000000100.vocab.json").exists());
+        
+        let vocabulary = Vocabulary::load(AutoSaveConfig::vocab_path(&files[1])).unwrap();
+        assert_eq!(vocabulary.tokens, model.vocabulary().tokens);
+        assert_eq!(AIModel::load(&files[1]).unwrap().vocab.len(), model.vocab.len());
+        
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+    
+    #[test]
+    fn test_weights_serialize_in_column_format() {
+        let model = AIModel::new(4, 6, 2);
+        let json = serde_json::to_value(&model.layers[2]).unwrap();
+        let columns = json["weights"].as_array().unwrap();
+        assert_eq!(columns.len(), 6);
+        assert_eq!(columns[1][3].as_f64().unwrap(), model.layers[2].weights[(3, 1)]);
+        
+        let restored: Layer = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.weights, model.layers[2].weights);
+    }
+    
+    #[test]
+    fn test_set_optimizer_resets_state() {
+        let mut model = AIModel::new(8, 16, 2);
+        model.train(&["привет мир".to_string()], 1, |_, _, _| {});
+        assert!(model.optimizer_state.step > 0);
+        
+        model.set_optimizer(Optimizer::Sgd);
+        assert_eq!(model.optimizer_state.step, 0);
+    }
+    
+    #[test]
+    fn test_forward_with_gpu_or_fallback() {
+        let mut model = AIModel::new(8, 16, 2);
+        let cpu = model.forward(&[4, 5]);
+        
+        // Без адаптера модель остаётся на CPU и результат не меняется
+        match model.enable_gpu() {
+            Some(_) => assert!(model.gpu.is_some()),
+            None => assert!(model.gpu.is_none()),
+        }
+        let probs = model.forward(&[4, 5]);
+        assert_eq!(probs.len(), cpu.len());
+        assert!(probs.iter().zip(&cpu).all(|(a, b)| (a - b).abs() < 1e-4));
+        
+        model.disable_gpu();
+        assert!(model.info().contains("Вычисления: CPU"));
+    }
+    
+    #[test]
+    fn test_generate_streaming_pieces_and_cancel() {
+        let model = AIModel::new(8, 16, 2);
+        let config = GenerationConfig { temperature: 0.0, max_tokens: 10, ..Default::default() };
+        
+        let mut pieces = Vec::new();
+        let text = model.generate_streaming("привет", &config, |piece| {
+            pieces.push(piece.to_string());
+            ControlFlow::Continue(())
+        });
+        assert_eq!(pieces.concat(), text);
+        assert_eq!(text, model.generate("привет", &config));
+        
+        let mut calls = 0;
+        let stopped = model.generate_streaming("привет", &config, |_| {
+            calls += 1;
+            ControlFlow::Break(())
+        });
+        assert!(calls <= 1);
+        assert_eq!(pieces.first().cloned().unwrap_or_default(), stopped);
+    }
+    
+    #[test]
+    fn test_vocab_save_load_merge() {
+        let dir = std::env::temp_dir().join(format!("vocab_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("vocab.json");
+        
+        let mut source = AIModel::new(4, 8, 2);
+        source.add_to_vocab("крымский".to_string());
+        source.save_vocab(&path).unwrap();
+        
+        let mut target = AIModel::new(4, 8, 2);
+        let added = target.merge_vocab(&path).unwrap();
+        assert_eq!(added, 1);
+        assert!(target.vocab.contains_key("крымский"));
+        assert_eq!(target.merge_vocab(&path).unwrap(), 0);
+        
+        let mut loaded = AIModel::new(4, 8, 2);
+        loaded.load_vocab(&path).unwrap();
+        assert_eq!(loaded.vocab.len(), source.vocab.len());
+        assert_eq!(loaded.layers[0].inputs(), loaded.vocab.len());
+        assert_eq!(loaded.decode(&loaded.tokenize("крымский")), "крымский");
+        
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+    
+    #[test]
+    fn test_embed_and_cosine_similarity() {
+        let mut model = AIModel::new(8, 16, 3);
+        let embedding = model.embed("привет мир");
+        assert_eq!(embedding.len(), 16);
+        assert!((cosine_similarity(&embedding, &model.embed("привет мир")) - 1.0).abs() < 1e-9);
+        assert_eq!(model.embed(""), vec![0.0; 16]);
+        
+        model.set_recurrent(true);
+        assert_eq!(model.embed("привет мир").len(), 16);
+        
+        assert!((cosine_similarity(&[1.0, 0.0], &[0.0, 2.0])).abs() < 1e-12);
+        assert!((cosine_similarity(&[1.0, 1.0], &[2.0, 2.0]) - 1.0).abs() < 1e-12);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+    
+    #[test]
+    fn test_generate_with_memory_uses_snippets() {
+        use crate::memory::SnippetMemory;
+        
+        let model = AIModel::new(8, 16, 3);
+        let config = GenerationConfig { temperature: 0.0, max_tokens: 5, ..Default::default() };
+        
+        // Пустая память — обычная генерация
+        let empty = SnippetMemory::new();
+        assert_eq!(model.generate_with_memory("привет", &empty, 2, &config), model.generate("привет", &config));
+        
+        let mut memory = SnippetMemory { min_similarity: -1.0, ..Default::default() };
+        memory.remember(&model, "модель учится на данных");
+        assert_eq!(memory.len(), 1);
+        assert_eq!(
+            model.generate_with_memory("привет", &memory, 2, &config),
+            model.generate("модель учится на данных привет", &config)
+        );
+    }
+    
+    #[test]
+    fn test_openai_backend_against_local_server() {
+        use std::io::{Read, Write};
+        
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Читаем до конца тела (по Content-Length)
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length: usize = head
+                        .lines()
+                        .find_map(|line| line.split_once(':').filter(|(name, _)| name.eq_ignore_ascii_case("content-length")))
+                        .unwrap()
+                        .1
+                        .trim()
+                        .parse()
+                        .unwrap();
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+            }
+            let body = r#"{"choices":[{"message":{"role":"assistant","content":" Привет! "}}],"usage":{"completion_tokens":3}}"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        
+        let backend = OpenAIBackend {
+            base_url: format!("http://127.0.0.1:{}/v1", port),
+            api_key: "secret".to_string(),
+            ..Default::default()
+        };
+        let answer = backend.generate_with_usage("привет", &GenerationConfig::default()).unwrap();
+        assert_eq!(answer, ("Привет!".to_string(), Some(3)));
+        
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /v1/chat/completions HTTP/1.1"));
+        assert!(request.contains("Authorization: Bearer secret"));
+        assert!(request.contains("\"content\":\"привет\""));
+        
+        // Закрытый порт: ошибка соединения, а не зависание
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let closed_port = closed.local_addr().unwrap().port();
+        drop(closed);
+        let unreachable = OpenAIBackend {
+            base_url: format!("http://127.0.0.1:{}/v1", closed_port),
+            timeout_secs: 1,
+            ..Default::default()
+        };
+        assert!(unreachable.generate("привет", &GenerationConfig::default()).is_err());
+    }
+    
+    #[test]
+    fn test_generation_metrics() {
+        let metrics = GenerationMetrics {
+            tokens: 30,
+            elapsed_secs: 1.5,
+            backend: "Локальная модель".to_string(),
+        };
+        assert_eq!(metrics.tokens_per_sec(), 20.0);
+        assert_eq!(metrics.summary(), "30 ток. · 1.50 с · 20.0 ток/с · Локальная модель");
+        assert_eq!(GenerationMetrics::default().tokens_per_sec(), 0.0);
+    }
+    
+    #[test]
+    fn test_build_vocab_from_corpus() {
+        let mut model = AIModel::new(4, 8, 2);
+        let texts = vec![
+            "кот ест рыбу".to_string(),
+            "Кот спит".to_string(),
+            "кот ест".to_string(),
+        ];
+        
+        let size = model.build_vocab_from_corpus(&texts, 6, 2);
+        assert_eq!(size, 6);
+        assert_eq!(model.reverse_vocab[&4], "кот");
+        assert_eq!(model.reverse_vocab[&5], "ест");
+        assert!(!model.vocab.contains_key("рыбу"));
+        assert_eq!(model.layers[0].inputs(), 6);
+        assert_eq!(model.layers.last().unwrap().biases.len(), 6);
+        assert_eq!(model.tokenize("спит"), vec![model.get_unk_token()]);
+    }
+    
+    #[test]
+    fn test_tokenize_normalizes_punctuation() {
+        let model = AIModel::new(4, 8, 2);
+        assert_eq!(model.tokenize("Привет!"), vec![model.vocab["привет"], model.vocab["!"]]);
+        assert_eq!(model.tokenize("привет"), model.tokenize("ПРИВЕТ"));
+        assert_eq!(model.tokenize("2024"), vec![model.vocab["<NUM>"]]);
+    }
+    
+    #[test]
+    fn test_stop_sequences_and_min_tokens() {
+        let config = GenerationConfig {
+            stop_sequences: vec!["\nUser:".to_string(), "стоп".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(config.find_stop("ответ\nUser: ещё"), Some(10));
+        assert_eq!(config.find_stop("ответ"), None);
+        assert_eq!(config.safe_len("ответ\nUs"), "ответ".len());
+        assert_eq!(config.safe_len("ответ ст"), "ответ ".len());
+        assert_eq!(config.safe_len("ответ"), "ответ".len());
+        
+        // Модель, которая всегда выбирает <END>
+        let mut model = AIModel::new(4, 8, 2);
+        model.build_vocab_from_corpus(&["привет мир как дела".to_string()], 100, 1);
+        let end = model.vocab["<END>"];
+        model.layers.last_mut().unwrap().biases[end] = 100.0;
+        let greedy = GenerationConfig { temperature: 0.0, max_tokens: 10, ..Default::default() };
+        assert_eq!(model.generate("привет", &greedy), "");
+        
+        let forced = GenerationConfig { min_tokens: 3, ..greedy.clone() };
+        let text = model.generate("привет", &forced);
+        assert_eq!(text.split_whitespace().count(), 3);
+        
+        // Стоп на первом слове: ответ пустой, фрагменты не выдаются
+        let first = text.split_whitespace().next().unwrap().to_string();
+        let stopped = GenerationConfig { stop_sequences: vec![first], ..forced };
+        let mut pieces = Vec::new();
+        let result = model.generate_streaming("привет", &stopped, |piece| {
+            pieces.push(piece.to_string());
+            ControlFlow::Continue(())
+        });
+        assert_eq!(result, "");
+        assert!(pieces.is_empty());
+    }
+    
+    #[test]
+    fn test_builder_custom_stack() {
+        let specs = HiddenLayerSpec::parse_list("32 relu, 16, 8 sigmoid").unwrap();
+        assert_eq!(specs[1], HiddenLayerSpec::new(16, ActivationType::Tanh));
+        assert!(HiddenLayerSpec::parse_list("32 softplus").is_err());
+        assert!(HiddenLayerSpec::parse_list("много").is_err());
+        
+        let texts = vec!["кот ест рыбу".to_string()];
+        let mut source = AIModel::new(4, 8, 2);
+        source.build_vocab_from_corpus(&texts, 100, 1);
+        
+        let mut model = AIModelBuilder::new()
+            .embedding_dim(6)
+            .context_length(3)
+            .hidden_layers(specs)
+            .vocabulary(source.vocabulary())
+            .build()
+            .unwrap();
+        assert_eq!(model.layers.len(), 5);
+        assert_eq!(model.hidden_dim, 32);
+        assert_eq!(model.layers[1].inputs(), 18);
+        assert_eq!(model.layers[3].biases.len(), 8);
+        assert_eq!(model.layers[4].inputs(), 8);
+        assert_eq!(model.layers[4].biases.len(), model.vocab.len());
+        assert_eq!(model.embed("кот").len(), 8);
+        
+        let losses = std::cell::RefCell::new(Vec::new());
+        model.train(&texts, 5, |_, _, loss| losses.borrow_mut().push(loss));
+        let losses = losses.into_inner();
+        assert!(losses.last().unwrap() < losses.first().unwrap());
+        
+        // GRU заменяет первый скрытый слой, остальной стек сохраняется
+        model.set_recurrent(true);
+        assert_eq!(model.layers[1].outputs(), 32);
+        model.train(&texts, 1, |_, _, _| {});
+        model.generate("кот", &GenerationConfig::default());
+        
+        assert!(AIModelBuilder::new().build().is_err());
+        assert!(AIModelBuilder::new().hidden_layer(0, ActivationType::Tanh).build().is_err());
+        assert!(AIModelBuilder::new().hidden_layer(8, ActivationType::Softmax).build().is_err());
+        let valid = AIModelBuilder::new().hidden_layer(8, ActivationType::Tanh);
+        assert!(valid.clone().learning_rate(0.0).build().is_err());
+        assert!(valid.clone().learning_rate(f64::NAN).build().is_err());
+        assert!(valid.clone().batch_size(0).build().is_err());
+        assert_eq!(valid.batch_size(4).build().unwrap().batch_size, 4);
+    }
+    
+    #[test]
+    fn test_mini_batch_training() {
+        let texts = vec!["привет как дела у тебя".to_string(), "кот ест рыбу".to_string()];
+        let mut model = AIModel::new(16, 32, 4);
+        model.learning_rate = 0.01;
+        model.set_optimizer(Optimizer::adam());
+        model.batch_size = 3;
+        
+        let report = model.train(&texts, 5, |_, _, _| {});
+        assert!(report.improved(), "{:?}", report.epoch_losses);
+        // Шаг оптимизатора — один на батч, включая неполный последний
+        let steps: usize = texts.iter().map(|text| model.tokenize(text).len().saturating_sub(1)).sum();
+        assert_eq!(model.optimizer_state.step, 5 * (steps as u64).div_ceil(3));
+    }
+    
+    #[test]
+    fn test_scheduled_sampling_schedule() {
+        let sampling = ScheduledSampling { max_ratio: 0.5, warmup_epochs: 2 };
+        assert_eq!(sampling.ratio(0, 6), 0.0);
+        assert_eq!(sampling.ratio(1, 6), 0.0);
+        assert!((sampling.ratio(2, 6) - 0.125).abs() < 1e-12);
+        assert!((sampling.ratio(5, 6) - 0.5).abs() < 1e-12);
+        assert_eq!(ScheduledSampling::new(0.3).ratio(0, 1), 0.3);
+        
+        // Обучение с полной подменой входов не ломается
+        let mut model = AIModel::new(4, 8, 2);
+        model.scheduled_sampling = Some(ScheduledSampling::new(1.0));
+        let loss = std::cell::Cell::new(f64::NAN);
+        model.train(&["привет как дела у тебя".to_string()], 2, |_, _, l| loss.set(l));
+        assert!(loss.get().is_finite());
+    }
+    
+    #[test]
+    fn test_f32_precision_keeps_f64_master_weights() {
+        let texts = vec!["привет как дела".to_string()];
+        let mut model = AIModel::new(4, 8, 2);
+        let reference = model.forward(&[1, 2]);
+        
+        model.set_precision(Precision::F32);
+        assert!(model.layers[1].compute_weights().is_some());
+        assert!(model.layers[0].compute_weights.is_none());
+        let probs = model.forward(&[1, 2]);
+        assert!(probs.iter().zip(&reference).all(|(a, b)| (a - b).abs() < 1e-5));
+        
+        let losses = std::cell::RefCell::new(Vec::new());
+        model.train(&texts, 5, |_, _, loss| losses.borrow_mut().push(loss));
+        let losses = losses.into_inner();
+        assert!(losses.last().unwrap() < losses.first().unwrap());
+        
+        // f32-копии следуют за обновлёнными мастер-весами
+        let layer = &model.layers[2];
+        let copy = layer.compute_weights().unwrap();
+        assert!(layer.weights.iter().zip(copy.iter()).all(|(w, c)| (*w as f32) == *c));
+        
+        // Копии не сохраняются и восстанавливаются перед обучением
+        let json = serde_json::to_string(&model).unwrap();
+        let mut restored: AIModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.precision, Precision::F32);
+        assert!(restored.layers[1].compute_weights.is_none());
+        restored.train(&texts, 1, |_, _, _| {});
+        assert!(restored.layers[1].compute_weights().is_some());
+    }
+    
+    #[test]
+    fn test_default_vocab_indices_are_contiguous() {
+        let model = AIModel::default();
+        assert_eq!(model.vocab.len(), model.reverse_vocab.len());
+        assert!(model.vocab.values().all(|&idx| idx < model.vocab.len()));
+        assert_eq!(model.layers.last().unwrap().biases.len(), model.vocab.len());
+    }
+    
+    #[test]
+    fn test_add_to_vocab_grows_output_layer() {
+        let mut model = AIModel::new(4, 8, 2);
+        let size = model.vocab.len();
+        
+        model.add_to_vocab("нейросеть".to_string());
+        model.add_to_vocab("нейросеть".to_string());
+        let idx = model.vocab["нейросеть"];
+        assert_eq!(idx, size);
+        assert_eq!(model.reverse_vocab[&idx], "нейросеть");
+        assert_eq!(model.layers[0].inputs(), size + 1);
+        
+        let output = model.layers.last().unwrap();
+        assert_eq!(output.weights.nrows(), size + 1);
+        assert_eq!(output.biases.len(), size + 1);
+        assert!(output.weights.row(size).iter().any(|&w| w != 0.0));
+        
+        // Новое слово можно выучить и сгенерировать
+        let probs = model.forward(&[idx]);
+        assert_eq!(probs.len(), size + 1);
+        assert!(probs[idx] > 0.0);
+        let texts = vec!["нейросеть нейросеть нейросеть".to_string()];
+        model.train(&texts, 30, |_, _, _| {});
+        let greedy = GenerationConfig { temperature: 0.0, max_tokens: 1, ..Default::default() };
+        assert_eq!(model.generate("нейросеть", &greedy), "нейросеть");
+    }
+    
+    #[test]
+    fn test_merge_interpolates_and_unions_vocab() {
+        let mut a = AIModel::new(4, 8, 2);
+        let mut b = AIModel::new(4, 8, 2);
+        a.build_vocab_from_corpus(&["кот ест".to_string()], 100, 1);
+        b.build_vocab_from_corpus(&["кот спит".to_string()], 100, 1);
+        
+        let merged = a.merge(&b, 0.5).unwrap();
+        let expected = (&a.layers[2].weights + &b.layers[2].weights) * 0.5;
+        assert!((merged.layers[2].weights.clone() - expected).abs().max() < 1e-12);
+        
+        // Общий токен — среднее, токен только из b — веса b
+        let (ka, kb, km) = (a.vocab["кот"], b.vocab["кот"], merged.vocab["кот"]);
+        let mean = (a.layers[0].weights[(0, ka)] + b.layers[0].weights[(0, kb)]) / 2.0;
+        assert!((merged.layers[0].weights[(0, km)] - mean).abs() < 1e-12);
+        let (sb, sm) = (b.vocab["спит"], merged.vocab["спит"]);
+        assert_eq!(merged.layers[0].weights.column(sm), b.layers[0].weights.column(sb));
+        assert_eq!(merged.layers.last().unwrap().weights.row(sm), b.layers.last().unwrap().weights.row(sb));
+        assert!(merged.vocab.contains_key("ест"));
+        assert_eq!(merged.layers.last().unwrap().biases.len(), merged.vocab.len());
+        
+        assert_eq!(a.merge(&b, 0.0).unwrap().layers[1].weights, a.layers[1].weights);
+        assert!(a.merge(&AIModel::new(4, 16, 2), 0.5).is_err());
+    }
 }
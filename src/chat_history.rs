@@ -0,0 +1,199 @@
+use crate::chat_ui::ChatMessage;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Файл истории чата по умолчанию
+pub const DEFAULT_HISTORY_PATH: &str = "chat_history.json";
+
+/// Папка снимков моделей, привязанных к чатам
+pub const SESSION_MODELS_DIR: &str = "sessions";
+
+/// История чата, сохраняемая между запусками
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ChatHistory {
+    /// Название вкладки (пусто — по первому сообщению пользователя)
+    #[serde(default)]
+    pub title: String,
+    /// Время начала переписки (секунды Unix)
+    pub created_at: u64,
+    /// Время последнего сохранения (секунды Unix)
+    pub updated_at: u64,
+    pub messages: Vec<ChatMessage>,
+    /// Снимок модели этого чата (`None` — общая текущая модель)
+    #[serde(default)]
+    pub model_snapshot: Option<PathBuf>,
+}
+
+impl ChatHistory {
+    pub fn new(messages: Vec<ChatMessage>) -> Self {
+        let now = unix_time();
+        Self {
+            title: String::new(),
+            created_at: now,
+            updated_at: now,
+            messages,
+            model_snapshot: None,
+        }
+    }
+
+    /// Загрузка истории; `Ok(None)`, если файла ещё нет
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("✗ Ошибка чтения истории {:?}: {}", path, e)),
+        };
+        serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("✗ Повреждённый файл истории {:?}: {}", path, e))
+    }
+
+    /// Сохранение с обновлением `updated_at`
+    pub fn save(&mut self, path: &Path) -> Result<(), String> {
+        self.updated_at = unix_time();
+        write_json(path, self)
+    }
+
+    /// Название для вкладки
+    pub fn display_title(&self) -> String {
+        if !self.title.trim().is_empty() {
+            return self.title.trim().to_string();
+        }
+        match self.messages.iter().find(|msg| msg.is_user) {
+            Some(msg) => {
+                let first_line = msg.text.lines().next().unwrap_or_default().trim();
+                let mut title: String = first_line.chars().take(24).collect();
+                if first_line.chars().count() > 24 {
+                    title.push('…');
+                }
+                title
+            }
+            None => "Новый чат".to_string(),
+        }
+    }
+}
+
+/// Несколько независимых чатов, хранящихся в одном файле
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ChatSessions {
+    /// Индекс открытого чата
+    #[serde(default)]
+    pub active: usize,
+    pub sessions: Vec<ChatHistory>,
+}
+
+impl ChatSessions {
+    /// Загрузка чатов; файл со старой одиночной историей становится одним чатом
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("✗ Ошибка чтения истории {:?}: {}", path, e)),
+        };
+        let mut sessions = match serde_json::from_str::<Self>(&json) {
+            Ok(sessions) => sessions,
+            Err(e) => match serde_json::from_str::<ChatHistory>(&json) {
+                Ok(history) => Self { active: 0, sessions: vec![history] },
+                Err(_) => return Err(format!("✗ Повреждённый файл истории {:?}: {}", path, e)),
+            },
+        };
+        if sessions.sessions.is_empty() {
+            return Ok(None);
+        }
+        sessions.active = sessions.active.min(sessions.sessions.len() - 1);
+        Ok(Some(sessions))
+    }
+
+    /// Сохранение с обновлением `updated_at` открытого чата
+    pub fn save(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(session) = self.sessions.get_mut(self.active) {
+            session.updated_at = unix_time();
+        }
+        write_json(path, self)
+    }
+}
+
+/// Запись JSON через временный файл, чтобы сбой посреди записи не испортил историю
+fn write_json(path: &Path, value: &impl Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| format!("✗ Ошибка сериализации истории: {}", e))?;
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("✗ Ошибка создания папки истории: {}", e))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json)
+        .and_then(|_| fs::rename(&tmp, path))
+        .map_err(|e| format!("✗ Ошибка сохранения истории {:?}: {}", path, e))
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_roundtrip() {
+        let path = std::env::temp_dir()
+            .join(format!("chat_history_test_{}", std::process::id()))
+            .join("history.json");
+        assert!(ChatHistory::load(&path).unwrap().is_none());
+
+        let mut history = ChatHistory::new(vec![ChatMessage {
+            text: "Привет".to_string(),
+            is_user: true,
+            timestamp: "12:00".to_string(),
+            metrics: None,
+        }]);
+        history.save(&path).unwrap();
+
+        let loaded = ChatHistory::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.created_at, history.created_at);
+        assert_eq!(loaded.messages.len(), 1);
+        assert_eq!(loaded.messages[0].text, "Привет");
+        assert!(loaded.messages[0].is_user);
+
+        fs::write(&path, "{").unwrap();
+        assert!(ChatHistory::load(&path).is_err());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_sessions_roundtrip_and_legacy() {
+        let path = std::env::temp_dir()
+            .join(format!("chat_sessions_test_{}", std::process::id()))
+            .join("history.json");
+        let message = |text: &str| ChatMessage {
+            text: text.to_string(),
+            is_user: true,
+            timestamp: "12:00".to_string(),
+            metrics: None,
+        };
+
+        // Старый формат — одна история
+        let mut legacy = ChatHistory::new(vec![message("Старый чат")]);
+        legacy.save(&path).unwrap();
+        let loaded = ChatSessions::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.sessions.len(), 1);
+        assert_eq!(loaded.sessions[0].display_title(), "Старый чат");
+
+        let mut second = ChatHistory::new(vec![message("Очень длинный первый вопрос про погоду")]);
+        second.model_snapshot = Some(PathBuf::from("sessions/2.model.json"));
+        let mut sessions = ChatSessions {
+            active: 1,
+            sessions: vec![loaded.sessions[0].clone(), second],
+        };
+        sessions.save(&path).unwrap();
+
+        let loaded = ChatSessions::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.active, 1);
+        assert_eq!(loaded.sessions[1].display_title(), "Очень длинный первый воп…");
+        assert_eq!(loaded.sessions[1].model_snapshot, Some(PathBuf::from("sessions/2.model.json")));
+        assert_eq!(ChatHistory::new(Vec::new()).display_title(), "Новый чат");
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -1,24 +1,265 @@
-use crate::ai_model::AIModel;
-use crate::file_processor::{FileProcessor, FileStats};
+use crate::ai_model::{
+    AIModel, AIModelBuilder, AutoSaveConfig, CheckpointConfig, EpochLoss, GenerationConfig, GenerationMetrics, HiddenLayerSpec, InferenceBackend, OpenAIBackend, Optimizer,
+    Precision, ScheduledSampling, TrainingHandle,
+};
+use crate::file_processor::{CorpusReport, ExtractionMode, FileProcessor, FileStats};
+use crate::chat_history::{ChatHistory, ChatSessions, DEFAULT_HISTORY_PATH, SESSION_MODELS_DIR};
+use crate::folder_watcher::FolderWatcher;
+use crate::language::Language;
+use crate::loss_chart::LossChart;
+use crate::data_preview::DataPreview;
+use crate::ecosystem::{EcosystemView, MAX_SPAWN};
+use crate::commands::ChatCommand;
+use crate::kaif::KaifEngine;
+use crate::local_time;
+use crate::markdown;
+use crate::notify;
+use crate::shortcuts::{Shortcut, ShortcutAction};
+use crate::memory::{SemanticMemory, SnippetMemory};
+use crate::speech::Speaker;
+use crate::system_monitor::{MetricHistory, SystemMonitor};
+use crate::ui_settings::{UiSettings, DEFAULT_UI_SETTINGS_PATH};
+use crate::virtual_list::VirtualList;
+use crate::voice::{VoiceInput, DEFAULT_WHISPER_MODEL};
 use eframe::egui;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Файлы больше этого размера загружаются потоково
+const STREAMING_THRESHOLD: u64 = 64 * 1024 * 1024;
+/// Размер куска при потоковой загрузке
+const STREAMING_CHUNK_SIZE: usize = 1024 * 1024;
+/// Зерно разбиения на обучающую и валидационную выборки
+const VALIDATION_SEED: u64 = 42;
+/// Сколько файл в наблюдаемой папке должен не меняться, прежде чем его прочитать
+const WATCH_DEBOUNCE: Duration = Duration::from_secs(1);
+/// Папка кэша текста, извлечённого из PDF
+const PARSED_CACHE_DIR: &str = "cache/parsed";
+/// Период обновления монитора ресурсов
+const MONITOR_INTERVAL: Duration = Duration::from_secs(1);
+/// Замеров в истории монитора (спарклайны)
+const MONITOR_HISTORY: usize = 60;
+/// Прикреплённый файл короче этого числа слов передаётся целиком
+const ATTACHMENT_INLINE_WORDS: usize = 150;
+/// Размер фрагмента длинного прикреплённого файла в словах
+const ATTACHMENT_PASSAGE_WORDS: usize = 60;
+/// Фрагментов прикреплённого файла в одном запросе
+const ATTACHMENT_SNIPPETS: usize = 3;
+
+/// Событие фоновой загрузки файла
+enum LoadEvent {
+    /// Доля прочитанного файла
+    Progress(f32),
+    Done(Result<LoadedFile, String>),
+}
+
+/// Событие фоновой генерации ответа
+enum GenerationEvent {
+    /// Очередной фрагмент текста
+    Token(String),
+    Done(Result<GenerationMetrics, String>),
+}
+
+/// Действие над сообщением чата, выбранное в его контекстном меню
+enum MessageAction {
+    Copy(usize),
+    Edit(usize),
+    Delete(usize),
+    /// Отправка изменённого сообщения; всё после него удаляется
+    Resend(usize),
+    CancelEdit,
+    Speak(usize),
+    StopSpeaking,
+}
+
+/// Настройки генерации только для следующего сообщения поверх общих
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct NextMessageSettings {
+    max_tokens: Option<usize>,
+    temperature: Option<f64>,
+    /// `true` — OpenAI-совместимый API, `false` — локальная модель
+    remote: Option<bool>,
+}
+
+impl NextMessageSettings {
+    fn is_set(&self) -> bool {
+        *self != Self::default()
+    }
+    
+    fn apply(&self, config: &mut GenerationConfig, remote: &mut bool) {
+        if let Some(max_tokens) = self.max_tokens {
+            config.max_tokens = max_tokens;
+            config.min_tokens = config.min_tokens.min(max_tokens);
+        }
+        if let Some(temperature) = self.temperature {
+            config.temperature = temperature;
+        }
+        if let Some(use_remote) = self.remote {
+            *remote = use_remote;
+        }
+    }
+}
+
+/// Действие с потерей данных, которое выполняется только после подтверждения
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DestructiveAction {
+    ClearChat,
+    ResetModel,
+    RemoveFiles,
+}
+
+impl DestructiveAction {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::ClearChat => "очистка чата",
+            Self::ResetModel => "пересоздание модели",
+            Self::RemoveFiles => "удаление загруженных файлов",
+        }
+    }
+    
+    fn question(&self) -> &'static str {
+        match self {
+            Self::ClearChat => "Удалить все сообщения текущего чата?",
+            Self::ResetModel => "Пересоздать модель? Обученные веса будут сброшены.",
+            Self::RemoveFiles => "Убрать все загруженные файлы и обучающие примеры?",
+        }
+    }
+}
+
+/// Состояние до последнего разрушительного действия (одна ступень отмены)
+enum UndoState {
+    Chat {
+        messages: Vec<ChatMessage>,
+        created_at: u64,
+        title: String,
+    },
+    Model(Box<AIModel>),
+    Files {
+        loaded_files: Vec<(PathBuf, String)>,
+        training_data: Vec<String>,
+        file_stats: Option<FileStats>,
+    },
+}
+
+/// Генерация ответа в фоновом потоке; фрагменты дописываются в последнее сообщение
+pub struct ResponseGeneration {
+    receiver: Receiver<GenerationEvent>,
+    stop: Arc<AtomicBool>,
+}
+
+impl ResponseGeneration {
+    /// Просьба прервать генерацию после текущего токена
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Результат фоновой загрузки
+struct LoadedFile {
+    /// Текст файла (`None` для больших файлов, разобранных по кускам)
+    content: Option<String>,
+    stats: FileStats,
+    examples: Vec<String>,
+    /// Примеры, отброшенные фильтром языка, по языкам
+    discarded: BTreeMap<Language, usize>,
+}
+
+/// Загрузка файла, выполняющаяся в фоновом потоке
+pub struct FileLoading {
+    pub path: PathBuf,
+    receiver: Receiver<LoadEvent>,
+    pub progress: f32,
+}
+
+/// Файл, прикреплённый к разговору как контекст запросов (не обучающие данные)
+///
+/// Короткий файл добавляется перед каждым запросом целиком, длинный режется
+/// на фрагменты, из которых по эмбеддингу запроса выбираются ближайшие.
+pub struct ChatAttachment {
+    pub path: PathBuf,
+    /// Весь текст, если файл короткий
+    inline: Option<String>,
+    memory: SnippetMemory,
+}
+
+impl ChatAttachment {
+    /// Чтение файла и эмбеддинг его фрагментов (вызывается в фоновом потоке)
+    fn load(processor: &FileProcessor, model: &Mutex<AIModel>, path: PathBuf) -> Result<Self, String> {
+        let text = processor.read_file(&path)?;
+        let words = text.split_whitespace().count();
+        if words == 0 {
+            return Err(format!("✗ В файле {:?} нет текста", path));
+        }
+        
+        let mut memory = SnippetMemory::new();
+        let inline = if words <= ATTACHMENT_INLINE_WORDS {
+            Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+        } else {
+            let model = model.lock().map_err(|_| "✗ Модель заблокирована упавшим потоком".to_string())?;
+            memory.remember_document(&model, &text, ATTACHMENT_PASSAGE_WORDS);
+            None
+        };
+        Ok(Self { path, inline, memory })
+    }
+    
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+    
+    pub fn summary(&self) -> String {
+        match &self.inline {
+            Some(_) => format!("📎 {} (целиком)", self.name()),
+            None => format!("📎 {} ({} фрагм.)", self.name(), self.memory.len()),
+        }
+    }
+    
+    /// Запрос с контекстом из файла; без модели берутся первые фрагменты
+    fn prompt(&self, model: Option<&AIModel>, input: &str) -> String {
+        let context = match &self.inline {
+            Some(text) => text.clone(),
+            None => {
+                let mut snippets = model
+                    .map(|model| self.memory.find_similar(&model.embed(input), ATTACHMENT_SNIPPETS))
+                    .unwrap_or_default();
+                if snippets.is_empty() {
+                    snippets = self.memory.entries.iter().take(ATTACHMENT_SNIPPETS).map(|entry| entry.text.clone()).collect();
+                }
+                snippets.join("\n")
+            }
+        };
+        format!("{}\n\n{}", context, input)
+    }
+}
 
 /// Режим работы приложения
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AppMode {
     Chat,
     Training,
+    /// Воксельный мир
+    Ecosystem,
 }
 
 /// Сообщение в чате
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub text: String,
     pub is_user: bool,
     pub timestamp: String,
+    /// Замеры генерации (только для ответов модели)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<GenerationMetrics>,
 }
 
 /// Статус обучения
@@ -45,16 +286,135 @@ pub struct ChatUI {
     // Чат
     pub messages: Vec<ChatMessage>,
     pub input_text: String,
+    /// Ответ, генерируемый прямо сейчас
+    pub generation: Option<ResponseGeneration>,
+    /// Редактируемое сообщение пользователя: индекс и новый текст
+    pub editing_message: Option<(usize, String)>,
+    /// Голосовой ввод; создаётся при первом нажатии на микрофон
+    pub voice: Option<VoiceInput>,
+    pub voice_model_path: String,
+    /// Язык распознавания: `ru`, `en` или `auto`
+    pub voice_language: String,
+    /// Канал распознавания: во время записи по нему приходят ошибки
+    /// микрофона, после неё — распознанный текст
+    transcription: Option<Receiver<Result<String, String>>>,
+    /// Отправитель канала распознавания, пока идёт запись
+    transcription_sender: Option<Sender<Result<String, String>>>,
+    /// Файл, прикреплённый к разговору как контекст
+    pub attachment: Option<ChatAttachment>,
+    attachment_loading: Option<Receiver<Result<ChatAttachment, String>>>,
+    /// Синтезатор речи и озвучиваемое сообщение
+    speaker: Speaker,
+    speaking_message: Option<usize>,
+    pub generation_config: GenerationConfig,
+    /// Стоп-последовательности по одной на строку (`\n` — перевод строки)
+    pub stop_sequences_input: String,
+    pub use_gpu: bool,
+    /// Отвечать через внешний OpenAI-совместимый API вместо локальной модели
+    pub use_remote_backend: bool,
+    pub remote_backend: OpenAIBackend,
     
     // Обучение
     pub training_status: TrainingStatus,
+    /// Начало текущего обучения (для длительности в итоговом сообщении)
+    training_started: Option<Instant>,
+    pub training_handle: Option<TrainingHandle>,
+    /// Открыт диалог подтверждения остановки обучения
+    pub confirm_stop_training: bool,
+    /// Loss по эпохам текущего или последнего обучения
+    pub loss_history: Vec<EpochLoss>,
+    pub loss_chart: LossChart,
+    /// Файл экспорта loss в CSV
+    pub loss_csv_path: String,
     pub training_data: Vec<String>,
     pub epochs: usize,
+    pub learning_rate: f64,
+    /// Шагов на одно обновление весов
+    pub batch_size: usize,
+    pub optimizer: Optimizer,
+    pub precision: Precision,
+    pub bpe_vocab_size: usize,
+    pub vocab_min_freq: usize,
+    /// Скрытые слои для пересоздания модели, например `256 tanh, 128 relu`
+    pub architecture: String,
+    pub embedding_dim: usize,
+    pub context_length: usize,
+    pub recurrent: bool,
+    pub dropout: f64,
+    pub layer_norm: bool,
+    /// Максимальная доля подмены входов предсказаниями (0 — teacher forcing)
+    pub scheduled_sampling: f64,
+    /// Доля примеров, откладываемых для валидации (0 — без валидации)
+    pub validation_split: f64,
+    pub use_checkpoints: bool,
+    pub checkpoint_dir: String,
+    /// Сохранять модель в `auto_save_dir` после каждого успешного обучения
+    pub auto_save: bool,
+    pub auto_save_dir: String,
+    /// Сколько автосохранений хранить (0 — все)
+    pub auto_save_keep: usize,
     pub loaded_files: Vec<(PathBuf, String)>,
+    pub file_loading: Option<FileLoading>,
+    /// Колонки CSV с текстом через запятую (имена или номера с 1; пусто — все)
+    pub csv_columns: String,
+    /// Склеивать колонки вопроса и ответа CSV в диалоговые примеры
+    pub csv_dialogue: bool,
+    pub csv_question_column: String,
+    pub csv_answer_column: String,
+    /// Регулярные выражения для отбрасывания строк, по одному на строку
+    pub cleaner_patterns: String,
     pub file_stats: Option<FileStats>,
+    /// Отчёт по корпусу; сбрасывается при изменении данных или словаря
+    pub corpus_report: Option<CorpusReport>,
+    /// Окно просмотра и правки обучающих примеров
+    pub show_data_preview: bool,
+    pub data_preview: DataPreview,
+    /// Симуляция режима «Экосистема»
+    pub ecosystem: EcosystemView,
+    /// «Эмоции» ассистента по эмбеддингам разговора; задают аватар
+    pub kaif: KaifEngine,
+    /// Папка, новые файлы из которой автоматически добавляются в обучающие данные
+    pub watch_dir: String,
+    pub folder_watcher: Option<FolderWatcher>,
+    /// Файлы, ожидающие загрузки: из наблюдаемой папки, выбранные в окне или перетащенные
+    pub file_queue: VecDeque<PathBuf>,
+    /// Папка, в которой откроется следующее окно выбора файла
+    dialog_dir: Option<PathBuf>,
+    
+    // История чата
+    /// Редактируемый путь к файлу истории; применяется к `ui_settings`
+    /// при потере фокуса
+    pub history_path: String,
+    /// Все чаты; сообщения открытого чата живут в `messages` и переносятся сюда при сохранении
+    pub sessions: Vec<ChatHistory>,
+    pub active_session: usize,
+    /// Сколько сообщений было в последней сохранённой истории
+    saved_messages: usize,
     
     // UI состояние
+    pub ui_settings: UiSettings,
+    /// Высоты сообщений для отрисовки только видимых
+    message_list: VirtualList,
+    /// Смещение прокрутки чата, применяемое в следующем кадре
+    chat_scroll_to: Option<f32>,
+    /// Действие, ожидающее подтверждения в диалоге
+    confirm_action: Option<DestructiveAction>,
+    /// Последнее разрушительное действие и состояние для его отмены
+    undo: Option<(DestructiveAction, UndoState)>,
+    /// Действие, которому назначается следующее нажатое сочетание
+    recording_shortcut: Option<ShortcutAction>,
+    /// Настройки оформления, записанные в файл последними
+    saved_ui_settings: UiSettings,
     pub show_model_info: bool,
+    pub show_settings: bool,
+    /// Окно настроек следующего сообщения (⚙ у кнопки отправки)
+    show_next_message: bool,
+    next_message: NextMessageSettings,
+    /// Боковая панель ресурсов (RAM/CPU/VRAM/FPS)
+    pub show_system_monitor: bool,
+    pub system_monitor: SystemMonitor,
+    pub monitor_history: MetricHistory,
+    last_monitor_update: Option<Instant>,
     pub auto_scroll: bool,
     pub file_path_input: String,
 }
@@ -63,19 +423,45 @@ impl ChatUI {
     pub fn new() -> Self {
         let model = AIModel::default();
         
-        // Приветственное сообщение
-        let welcome_msg = ChatMessage {
-            text: "Привет! Я AI ассистент с возможностью дообучения 🤖\n\nВыберите режим:\n• 💬 Разговор - общение со мной\n• 📚 Обучение - загрузка файлов и дообучение\n\nЯ здесь, чтобы помочь!".to_string(),
-            is_user: false,
-            timestamp: Self::get_timestamp(),
+        // Настройки первыми: в них путь к файлу истории
+        let (ui_settings, settings_error) = match UiSettings::load(Path::new(DEFAULT_UI_SETTINGS_PATH)) {
+            Ok(settings) => (settings.unwrap_or_default(), None),
+            Err(e) => (UiSettings::default(), Some(e)),
+        };
+        // Чаты прошлых запусков или один чат с приветственным сообщением
+        let (sessions, load_error) = match ChatSessions::load(Path::new(&ui_settings.history_path)) {
+            Ok(Some(sessions)) => (sessions, None),
+            Ok(None) => (ChatSessions { active: 0, sessions: vec![ChatHistory::new(Vec::new())] }, None),
+            Err(e) => (ChatSessions { active: 0, sessions: vec![ChatHistory::new(Vec::new())] }, Some(e)),
         };
+        let saved_messages = sessions.sessions[sessions.active].messages.len();
         
-        Self {
+        let mut app = Self {
             model: Arc::new(Mutex::new(model)),
-            file_processor: FileProcessor::new(),
+            file_processor: FileProcessor {
+                cache_dir: Some(PathBuf::from(PARSED_CACHE_DIR)),
+                ..FileProcessor::new()
+            },
             mode: AppMode::Chat,
-            messages: vec![welcome_msg],
+            messages: Vec::new(),
             input_text: String::new(),
+            generation: None,
+            editing_message: None,
+            voice: None,
+            voice_model_path: DEFAULT_WHISPER_MODEL.to_string(),
+            voice_language: "ru".to_string(),
+            transcription: None,
+            transcription_sender: None,
+            attachment: None,
+            attachment_loading: None,
+            speaker: Speaker::new(),
+            speaking_message: None,
+            generation_config: GenerationConfig::default(),
+            stop_sequences_input: String::new(),
+            use_gpu: false,
+            use_remote_backend: false,
+            remote_backend: OpenAIBackend::default(),
+            training_started: None,
             training_status: TrainingStatus {
                 is_training: false,
                 current_epoch: 0,
@@ -83,148 +469,1300 @@ impl ChatUI {
                 loss: 0.0,
                 progress: 0.0,
             },
+            training_handle: None,
+            confirm_stop_training: false,
+            loss_history: Vec::new(),
+            loss_chart: LossChart::new(),
+            loss_csv_path: "loss.csv".to_string(),
             training_data: Vec::new(),
             epochs: 10,
+            learning_rate: 0.001,
+            batch_size: 1,
+            optimizer: Optimizer::default(),
+            precision: Precision::default(),
+            bpe_vocab_size: 2000,
+            vocab_min_freq: 2,
+            architecture: "256 tanh, 256 tanh".to_string(),
+            embedding_dim: 128,
+            context_length: 8,
+            recurrent: false,
+            dropout: 0.0,
+            layer_norm: false,
+            scheduled_sampling: 0.0,
+            validation_split: 0.0,
+            use_checkpoints: false,
+            checkpoint_dir: "checkpoints".to_string(),
+            auto_save: false,
+            auto_save_dir: "models".to_string(),
+            auto_save_keep: 5,
             loaded_files: Vec::new(),
+            file_loading: None,
+            csv_columns: String::new(),
+            csv_dialogue: false,
+            csv_question_column: "question".to_string(),
+            csv_answer_column: "answer".to_string(),
+            cleaner_patterns: String::new(),
+            watch_dir: String::new(),
+            folder_watcher: None,
+            file_queue: VecDeque::new(),
+            dialog_dir: None,
+            history_path: ui_settings.history_path.clone(),
+            sessions: sessions.sessions,
+            active_session: sessions.active,
+            saved_messages,
             file_stats: None,
+            corpus_report: None,
+            show_data_preview: false,
+            data_preview: DataPreview::new(),
+            ecosystem: EcosystemView::new(),
+            kaif: KaifEngine::new(),
+            ui_settings: ui_settings.clone(),
+            saved_ui_settings: ui_settings,
+            message_list: VirtualList::new(80.0),
+            chat_scroll_to: None,
+            confirm_action: None,
+            undo: None,
+            recording_shortcut: None,
             show_model_info: false,
+            show_settings: false,
+            show_next_message: false,
+            next_message: NextMessageSettings::default(),
+            show_system_monitor: false,
+            system_monitor: SystemMonitor::new(),
+            monitor_history: MetricHistory::new(MONITOR_HISTORY),
+            last_monitor_update: None,
             auto_scroll: true,
             file_path_input: String::new(),
+        };
+        app.open_session(sessions.active);
+        for e in load_error.into_iter().chain(settings_error) {
+            app.messages.push(ChatMessage {
+                text: e,
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+        }
+        app
+    }
+    
+    fn welcome_message() -> ChatMessage {
+        ChatMessage {
+            text: "Привет! Я AI ассистент с возможностью дообучения 🤖\n\nВыберите режим:\n• 💬 Разговор - общение со мной\n• 📚 Обучение - загрузка файлов и дообучение\n\nЯ здесь, чтобы помочь!".to_string(),
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        }
+    }
+    
+    /// Сохранение истории, если с прошлого сохранения появились сообщения
+    fn save_history_if_changed(&mut self) {
+        // Недописанный ответ сохраняется после окончания генерации
+        if self.messages.len() == self.saved_messages || self.generation.is_some() {
+            return;
+        }
+        
+        self.sessions[self.active_session].messages = self.messages.clone();
+        let mut sessions = ChatSessions {
+            active: self.active_session,
+            sessions: std::mem::take(&mut self.sessions),
+        };
+        let result = sessions.save(Path::new(&self.ui_settings.history_path));
+        self.sessions = sessions.sessions;
+        if let Err(e) = result {
+            // Сообщение об ошибке не сохраняется повторно, чтобы не зациклиться
+            self.messages.push(ChatMessage {
+                text: e,
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+        }
+        self.saved_messages = self.messages.len();
+    }
+    
+    /// Запись настроек оформления после их изменения
+    fn save_ui_settings_if_changed(&mut self) {
+        if self.ui_settings == self.saved_ui_settings {
+            return;
+        }
+        self.saved_ui_settings = self.ui_settings.clone();
+        if let Err(e) = self.ui_settings.save(Path::new(DEFAULT_UI_SETTINGS_PATH)) {
+            self.messages.push(ChatMessage {
+                text: e,
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+        }
+    }
+    
+    /// Новая история открытого чата с одним приветствием
+    fn clear_history(&mut self) {
+        if let Some(generation) = self.generation.take() {
+            generation.stop();
+        }
+        self.messages = vec![Self::welcome_message()];
+        self.message_list.reset(self.messages.len());
+        self.editing_message = None;
+        self.stop_speaking();
+        self.kaif.reset();
+        let session = &mut self.sessions[self.active_session];
+        session.created_at = ChatHistory::new(Vec::new()).created_at;
+        session.title.clear();
+        self.saved_messages = usize::MAX;
+        self.save_history_if_changed();
+    }
+    
+    /// Показ сообщений чата `index` и загрузка его снимка модели
+    fn open_session(&mut self, index: usize) {
+        // Отмена очистки относится к прежнему чату
+        if matches!(self.undo, Some((DestructiveAction::ClearChat, _))) {
+            self.undo = None;
+        }
+        self.active_session = index;
+        self.messages = self.sessions[index].messages.clone();
+        self.editing_message = None;
+        self.stop_speaking();
+        self.kaif.reset();
+        if self.messages.is_empty() {
+            self.messages.push(Self::welcome_message());
+        }
+        self.message_list.reset(self.messages.len());
+        
+        let Some(path) = self.sessions[index].model_snapshot.clone() else {
+            return;
+        };
+        if let Err(e) = self.replace_model(&path) {
+            self.messages.push(ChatMessage {
+                text: format!("✗ Снимок модели этого чата не загружен: {}", e),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+        }
+    }
+    
+    /// Замена текущей модели сохранённой в файле
+    fn replace_model(&mut self, path: &Path) -> Result<(), String> {
+        let mut model = self.model.try_lock().map_err(|_| "⏳ модель занята обучением или генерацией".to_string())?;
+        *model = AIModel::load(path).map_err(|e| format!("ошибка загрузки {:?}: {}", path, e))?;
+        if matches!(self.undo, Some((DestructiveAction::ResetModel, _))) {
+            self.undo = None;
+        }
+        if self.use_gpu && model.enable_gpu().is_none() {
+            self.use_gpu = false;
+        }
+        drop(model);
+        self.corpus_report = None;
+        Ok(())
+    }
+    
+    /// Переключение на другой чат; во время генерации ответа недоступно
+    fn switch_session(&mut self, index: usize) {
+        if self.generation.is_some() || index == self.active_session || index >= self.sessions.len() {
+            return;
+        }
+        self.sessions[self.active_session].messages = self.messages.clone();
+        self.open_session(index);
+        self.saved_messages = usize::MAX;
+    }
+    
+    fn new_session(&mut self) {
+        self.sessions.push(ChatHistory::new(Vec::new()));
+        self.switch_session(self.sessions.len() - 1);
+    }
+    
+    /// Закрытие чата вместе с его снимком модели; последний чат только очищается
+    fn close_session(&mut self, index: usize) {
+        if self.generation.is_some() || index >= self.sessions.len() {
+            return;
+        }
+        if self.sessions.len() == 1 {
+            self.confirm_action = Some(DestructiveAction::ClearChat);
+            return;
+        }
+        
+        self.sessions[self.active_session].messages = self.messages.clone();
+        let removed = self.sessions.remove(index);
+        if let Some(path) = removed.model_snapshot {
+            let _ = fs::remove_file(path);
+        }
+        if index < self.active_session {
+            self.active_session -= 1;
+        } else if index == self.active_session {
+            self.open_session(index.min(self.sessions.len() - 1));
+        }
+        self.saved_messages = usize::MAX;
+    }
+    
+    /// Сохранение текущей модели как снимка открытого чата
+    fn snapshot_session_model(&mut self) {
+        let session = &self.sessions[self.active_session];
+        let path = Path::new(SESSION_MODELS_DIR).join(format!("{}.model.json", session.created_at));
+        let result = match self.model.try_lock() {
+            Ok(model) => fs::create_dir_all(SESSION_MODELS_DIR)
+                .map_err(|e| e.to_string())
+                .and_then(|_| model.save(&path).map_err(|e| e.to_string())),
+            Err(_) => Err("модель занята обучением".to_string()),
+        };
+        let text = match result {
+            Ok(()) => {
+                let session = &mut self.sessions[self.active_session];
+                session.model_snapshot = Some(path);
+                self.saved_messages = usize::MAX;
+                format!("📸 Снимок модели привязан к чату «{}»", session.display_title())
+            }
+            Err(e) => format!("✗ Ошибка сохранения снимка модели: {}", e),
+        };
+        self.messages.push(ChatMessage {
+            text,
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+    }
+    
+    /// Отвязка снимка: чат снова использует текущую модель
+    fn forget_session_model(&mut self) {
+        if let Some(path) = self.sessions[self.active_session].model_snapshot.take() {
+            let _ = fs::remove_file(path);
+            self.saved_messages = usize::MAX;
+        }
+    }
+    
+    /// Местные дата и время для метки нового сообщения
+    fn get_timestamp() -> String {
+        local_time::timestamp(&chrono::Local::now())
+    }
+    
+    fn send_message(&mut self) {
+        if self.input_text.trim().is_empty() {
+            return;
+        }
+        if let Some(command) = ChatCommand::parse(&self.input_text) {
+            let input = std::mem::take(&mut self.input_text);
+            self.messages.push(ChatMessage {
+                text: input.trim().to_string(),
+                is_user: true,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            self.run_command(command);
+            return;
+        }
+        if self.generation.is_some() {
+            return;
+        }
+        
+        // Добавляем сообщение пользователя
+        let user_msg = ChatMessage {
+            text: self.input_text.clone(),
+            is_user: true,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        };
+        self.messages.push(user_msg);
+        
+        // Генерируем ответ в фоне, дописывая его в пустое сообщение
+        let mut input = self.input_text.clone();
+        self.input_text.clear();
+        self.feel(&input);
+        if let Some(attachment) = &self.attachment {
+            // Пока модель занята обучением, фрагменты выбираются без эмбеддинга
+            let model = self.model.try_lock().ok();
+            input = attachment.prompt(model.as_deref(), &input);
+        }
+        self.messages.push(ChatMessage {
+            text: String::new(),
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+        
+        let (sender, receiver) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        // Разовые настройки действуют только на этот ответ
+        let mut config = self.generation_config.clone();
+        let mut use_remote = self.use_remote_backend;
+        std::mem::take(&mut self.next_message).apply(&mut config, &mut use_remote);
+        let remote = use_remote.then(|| self.remote_backend.clone());
+        let model = Arc::clone(&self.model);
+        thread::spawn(move || {
+            let result = match remote {
+                // API отвечает целиком, поэтому приходит одним фрагментом
+                Some(backend) => {
+                    let started = Instant::now();
+                    backend
+                        .generate_with_usage(&input, &config)
+                        .map(|(text, tokens)| {
+                            // Без usage в ответе считаем слова
+                            let tokens = tokens.unwrap_or_else(|| text.split_whitespace().count());
+                            let _ = sender.send(GenerationEvent::Token(text));
+                            GenerationMetrics {
+                                tokens,
+                                elapsed_secs: started.elapsed().as_secs_f64(),
+                                backend: backend.name(),
+                            }
+                        })
+                        .map_err(|e| format!("✗ Ошибка API: {}", e))
+                }
+                None => match model.lock() {
+                    Ok(model) => {
+                        let started = Instant::now();
+                        let text = model.generate_streaming(&input, &config, |piece| {
+                            let sent = sender.send(GenerationEvent::Token(piece.to_string())).is_ok();
+                            if sent && !thread_stop.load(Ordering::Relaxed) {
+                                ControlFlow::Continue(())
+                            } else {
+                                ControlFlow::Break(())
+                            }
+                        });
+                        Ok(GenerationMetrics {
+                            tokens: model.tokenize(&text).len(),
+                            elapsed_secs: started.elapsed().as_secs_f64(),
+                            backend: InferenceBackend::name(&*model),
+                        })
+                    }
+                    Err(_) => Err("✗ Модель заблокирована упавшим потоком".to_string()),
+                },
+            };
+            let _ = sender.send(GenerationEvent::Done(result));
+        });
+        self.generation = Some(ResponseGeneration { receiver, stop });
+    }
+    
+    /// Повторная генерация последнего ответа: он удаляется, запрос отправляется заново
+    fn regenerate_last_reply(&mut self) {
+        if self.generation.is_some() {
+            return;
+        }
+        let Some(index) = self.messages.iter().rposition(|msg| msg.is_user) else {
+            return;
+        };
+        let text = self.messages[index].text.clone();
+        let draft = std::mem::replace(&mut self.input_text, text);
+        self.messages.truncate(index);
+        self.editing_message = None;
+        self.stop_speaking();
+        self.send_message();
+        self.input_text = draft;
+        self.saved_messages = usize::MAX;
+    }
+    
+    /// Глобальные сочетания клавиш; при назначении нажатие записывается в настройки
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if let Some(action) = self.recording_shortcut {
+            let pressed = ctx.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+            });
+            if let Some((key, modifiers)) = pressed {
+                // Нажатие не должно попасть в поле ввода или сработать как сочетание
+                ctx.input_mut(|i| i.events.clear());
+                if key != egui::Key::Escape {
+                    if let Some(shortcut) = Shortcut::from_event(key, modifiers) {
+                        self.ui_settings.shortcuts.set(action, shortcut);
+                    }
+                }
+                self.recording_shortcut = None;
+            }
+            return;
+        }
+        
+        let shortcuts = self.ui_settings.shortcuts;
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts.switch_mode.0)) {
+            self.mode = match self.mode {
+                AppMode::Chat => AppMode::Training,
+                AppMode::Training => AppMode::Ecosystem,
+                AppMode::Ecosystem => AppMode::Chat,
+            };
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts.regenerate.0)) {
+            self.regenerate_last_reply();
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcuts.focus_input.0)) {
+            ctx.memory_mut(|m| m.request_focus(egui::Id::new("chat_input")));
+        }
+    }
+    
+    /// Перевод строки в позиции курсора поля ввода
+    fn insert_newline(&mut self, ctx: &egui::Context, input_id: egui::Id) {
+        let mut state = egui::TextEdit::load_state(ctx, input_id).unwrap_or_default();
+        let chars = self.input_text.chars().count();
+        let index = state.ccursor_range().map_or(chars, |range| range.primary.index.min(chars));
+        let byte = self.input_text.char_indices().nth(index).map_or(self.input_text.len(), |(byte, _)| byte);
+        self.input_text.insert(byte, '\n');
+        state.set_ccursor_range(Some(egui::text::CCursorRange::one(egui::text::CCursor::new(index + 1))));
+        state.store(ctx, input_id);
+    }
+    
+    /// Прикрепление файла к разговору: чтение и эмбеддинг в фоне
+    fn attach_file(&mut self, path: PathBuf) {
+        if !path.is_file() {
+            self.messages.push(ChatMessage {
+                text: format!("✗ Прикрепить можно только файл: {:?}", path),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let processor = self.file_processor.clone();
+        let model = Arc::clone(&self.model);
+        thread::spawn(move || {
+            let _ = sender.send(ChatAttachment::load(&processor, &model, path));
+        });
+        self.attachment_loading = Some(receiver);
+    }
+    
+    fn poll_attachment(&mut self) {
+        let Some(receiver) = &self.attachment_loading else {
+            return;
+        };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err("✗ Поток чтения файла завершился аварийно".to_string()),
+        };
+        self.attachment_loading = None;
+        match result {
+            Ok(attachment) => self.attachment = Some(attachment),
+            Err(e) => self.messages.push(ChatMessage {
+                text: e,
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            }),
+        }
+    }
+    
+    /// Начало записи голоса (кнопка микрофона нажата)
+    fn start_voice_input(&mut self) {
+        if self.voice.is_none() {
+            match VoiceInput::new(self.voice_model_path.trim()) {
+                Ok(voice) => self.voice = Some(voice),
+                Err(e) => {
+                    self.messages.push(ChatMessage {
+                        text: e,
+                        is_user: false,
+                        timestamp: Self::get_timestamp(),
+                        metrics: None,
+                    });
+                    return;
+                }
+            }
+        }
+        let Some(voice) = &mut self.voice else {
+            return;
+        };
+        let (sender, receiver) = mpsc::channel();
+        match voice.start(sender.clone()) {
+            Ok(()) => {
+                self.transcription = Some(receiver);
+                self.transcription_sender = Some(sender);
+            }
+            Err(e) => self.messages.push(ChatMessage {
+                text: e,
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            }),
+        }
+    }
+    
+    /// Остановка записи (кнопка отпущена) и распознавание в фоне
+    fn finish_voice_input(&mut self) {
+        let Some(voice) = &mut self.voice else {
+            return;
+        };
+        let recording = voice.stop();
+        let transcriber = voice.transcriber();
+        // Микрофон закрыт: его последние ошибки показываются раньше результата
+        self.poll_transcription();
+        let (recording, sender) = match (recording, self.transcription_sender.take()) {
+            (Ok(recording), Some(sender)) => (recording, sender),
+            (result, _) => {
+                self.transcription = None;
+                if let Err(e) = result {
+                    self.messages.push(ChatMessage {
+                        text: e,
+                        is_user: false,
+                        timestamp: Self::get_timestamp(),
+                        metrics: None,
+                    });
+                }
+                return;
+            }
+        };
+        
+        let language = self.voice_language.trim().to_string();
+        thread::spawn(move || {
+            let _ = sender.send(transcriber.transcribe(&recording, &language));
+        });
+    }
+    
+    /// Ошибки микрофона выводятся в чат, распознанный текст дописывается
+    /// в поле ввода
+    fn poll_transcription(&mut self) {
+        while let Some(receiver) = &self.transcription {
+            let result = match receiver.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => Err("✗ Поток распознавания завершился аварийно".to_string()),
+            };
+            // Пока идёт запись, сообщения канала — только ошибки микрофона
+            if self.transcription_sender.is_none() {
+                self.transcription = None;
+            }
+            match result {
+                Ok(text) if text.is_empty() => {}
+                Ok(text) => {
+                    if !self.input_text.trim().is_empty() {
+                        self.input_text.push(' ');
+                    }
+                    self.input_text.push_str(&text);
+                }
+                Err(e) => self.messages.push(ChatMessage {
+                    text: e,
+                    is_user: false,
+                    timestamp: Self::get_timestamp(),
+                    metrics: None,
+                }),
+            }
+        }
+    }
+    
+    /// Выполнение команды чата; результат выводится сообщением ассистента
+    fn run_command(&mut self, command: Result<ChatCommand, String>) {
+        let text = match command {
+            Err(e) => e,
+            Ok(ChatCommand::Train(_)) if self.training_status.is_training => "⏳ Обучение уже идёт".to_string(),
+            Ok(ChatCommand::Train(epochs)) => {
+                if let Some(epochs) = epochs {
+                    self.epochs = epochs;
+                }
+                // Сообщения о запуске или ошибке добавляет start_training
+                self.start_training(false);
+                return;
+            }
+            Ok(ChatCommand::Save(path)) => match self.model.try_lock() {
+                Ok(model) => match model.save(&path) {
+                    Ok(()) => format!("💾 Модель сохранена в {:?}", path),
+                    Err(e) => format!("✗ Ошибка сохранения модели {:?}: {}", path, e),
+                },
+                Err(_) => "⏳ Модель занята обучением или генерацией".to_string(),
+            },
+            Ok(ChatCommand::Load(path)) => match self.replace_model(&path) {
+                Ok(()) => format!("📂 Модель загружена из {:?}", path),
+                Err(e) => format!("✗ Модель не загружена: {}", e),
+            },
+            Ok(ChatCommand::Info) => match self.model.try_lock() {
+                Ok(model) => model.info(),
+                Err(_) => "⏳ Модель занята обучением или генерацией".to_string(),
+            },
+            Ok(ChatCommand::Clear) => {
+                // Команда набрана явно, подтверждение не нужно; отмена остаётся
+                self.perform_destructive(DestructiveAction::ClearChat);
+                return;
+            }
+            Ok(ChatCommand::Spawn(requested)) => {
+                let count = self.ecosystem.spawn(requested);
+                let created = format!(
+                    "🌍 Создано вокселей: {} (всего в мире: {}) — смотрите режим «Экосистема»",
+                    count,
+                    self.ecosystem.world.voxels.len()
+                );
+                if count < requested {
+                    format!(
+                        "⚠ Запрошено {}, но за раз создаётся не больше {}, а в мире — не больше {} вокселей\n{}",
+                        requested,
+                        MAX_SPAWN,
+                        self.ecosystem.world.reproduction.max_population,
+                        created
+                    )
+                } else {
+                    created
+                }
+            }
+            Ok(ChatCommand::Help) => ChatCommand::help(),
+        };
+        self.messages.push(ChatMessage {
+            text,
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+    }
+    
+    /// Дописывание пришедших фрагментов в последнее сообщение
+    fn poll_generation(&mut self) {
+        let Some(generation) = &self.generation else {
+            return;
+        };
+        
+        let mut result = None;
+        loop {
+            match generation.receiver.try_recv() {
+                Ok(GenerationEvent::Token(piece)) => {
+                    if let Some(msg) = self.messages.last_mut() {
+                        msg.text.push_str(&piece);
+                    }
+                }
+                Ok(GenerationEvent::Done(done)) => result = Some(done),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    result.get_or_insert_with(|| Err("✗ Поток генерации завершился аварийно".to_string()));
+                    break;
+                }
+            }
+        }
+        
+        let Some(result) = result else {
+            return;
+        };
+        let stopped = generation.stop.load(Ordering::Relaxed);
+        self.generation = None;
+        if let Some(msg) = self.messages.last_mut() {
+            match result {
+                Err(e) => msg.text = e,
+                // Если ответ пустой, даем стандартный ответ
+                Ok(_) if msg.text.trim().is_empty() && !stopped => {
+                    msg.text = "Я пока не знаю, как на это ответить. Попробуйте дообучить меня на ваших данных! 📚".to_string();
+                }
+                Ok(metrics) => {
+                    if stopped {
+                        msg.text.push_str(" ⏹");
+                    }
+                    msg.metrics = Some(metrics);
+                }
+            }
+        }
+        if let Some(reply) = self.messages.last().map(|msg| msg.text.clone()) {
+            self.feel(&reply);
+        }
+        if self.ui_settings.auto_speak && !stopped {
+            self.speak_message(self.messages.len().saturating_sub(1));
+        }
+        // Сообщение изменилось без смены их числа — сохраняем историю заново
+        self.saved_messages = usize::MAX;
+    }
+    
+    /// Эмбеддинг реплики в подсистему кайфа; пропускается, пока модель занята обучением
+    fn feel(&mut self, text: &str) {
+        if let Ok(model) = self.model.try_lock() {
+            let embedding = model.embed(text);
+            drop(model);
+            self.kaif.feed(&embedding);
+        }
+    }
+    
+    /// Аватар ассистента по текущему настроению
+    fn avatar(&self, size: f32) -> egui::RichText {
+        let mood = self.kaif.state().mood();
+        let [r, g, b] = mood.color();
+        egui::RichText::new(mood.emoji()).size(size).color(egui::Color32::from_rgb(r, g, b))
+    }
+    
+    fn mood_hover_text(&self) -> String {
+        let state = self.kaif.state();
+        format!(
+            "Настроение: {}\nКайф: {:.2}\nНовизна: {:.2}\nРезонанс: {:.2}",
+            state.mood().name(),
+            state.kaif,
+            state.novelty,
+            state.resonance
+        )
+    }
+    
+    /// Озвучивание сообщения `index`; ошибка синтезатора выводится в чат
+    fn speak_message(&mut self, index: usize) {
+        let Some(msg) = self.messages.get(index) else {
+            return;
+        };
+        match self.speaker.speak(&msg.text) {
+            Ok(()) => self.speaking_message = Some(index),
+            Err(e) => {
+                self.speaking_message = None;
+                self.messages.push(ChatMessage {
+                    text: e,
+                    is_user: false,
+                    timestamp: Self::get_timestamp(),
+                    metrics: None,
+                });
+            }
+        }
+    }
+    
+    fn stop_speaking(&mut self) {
+        self.speaker.stop();
+        self.speaking_message = None;
+    }
+    
+    fn load_file(&mut self) {
+        if self.file_path_input.trim().is_empty() {
+            self.messages.push(ChatMessage {
+                text: "✗ Ошибка: введите путь к файлу".to_string(),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            return;
+        }
+        
+        if self.file_loading.is_some() {
+            self.messages.push(ChatMessage {
+                text: "⏳ Дождитесь окончания загрузки предыдущего файла".to_string(),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            return;
+        }
+        
+        self.load_path(PathBuf::from(self.file_path_input.trim()));
+    }
+    
+    /// Фоновая загрузка файла с текущими настройками вкладки обучения
+    fn load_path(&mut self, path: PathBuf) {
+        self.apply_csv_options();
+        let patterns: Vec<String> = self.cleaner_patterns.lines().map(str::to_string).collect();
+        if let Err(e) = self.file_processor.cleaner.set_drop_patterns(&patterns) {
+            self.messages.push(ChatMessage {
+                text: e,
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            return;
+        }
+        
+        // Проверяем существование файла
+        if !path.exists() {
+            self.messages.push(ChatMessage {
+                text: format!("✗ Файл не найден: {:?}\n\n💡 Попробуйте:\n• examples/training_data_ru.txt\n• examples\\training_data_ru.txt\n• Полный путь к файлу", path),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            return;
+        }
+        
+        // Чтение и извлечение примеров в фоновом потоке, чтобы не блокировать кадр
+        let (sender, receiver) = mpsc::channel();
+        let processor = self.file_processor.clone();
+        let worker_path = path.clone();
+        thread::spawn(move || Self::read_file_worker(&processor, &worker_path, &sender));
+        self.file_loading = Some(FileLoading {
+            path,
+            receiver,
+            progress: 0.0,
+        });
+    }
+    
+    /// Включение и выключение наблюдения за папкой `watch_dir`
+    fn toggle_folder_watcher(&mut self) {
+        let text = if let Some(watcher) = self.folder_watcher.take() {
+            format!("👁 Наблюдение за папкой {:?} выключено", watcher.dir())
+        } else {
+            let processor = self.file_processor.clone();
+            match FolderWatcher::start(self.watch_dir.trim(), WATCH_DEBOUNCE, move |path| processor.is_supported(path)) {
+                Ok(watcher) => {
+                    let text = format!("👁 Наблюдение за папкой {:?} включено\n\n💡 Новые файлы будут загружены автоматически", watcher.dir());
+                    self.folder_watcher = Some(watcher);
+                    text
+                }
+                Err(e) => e,
+            }
+        };
+        self.messages.push(ChatMessage {
+            text,
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+    }
+    
+    /// Постановка новых файлов наблюдаемой папки в очередь загрузки
+    fn poll_folder_watcher(&mut self) {
+        let Some(watcher) = &self.folder_watcher else {
+            return;
+        };
+        
+        for path in watcher.new_files() {
+            self.messages.push(ChatMessage {
+                text: format!("📥 Новый файл в папке: {:?}", path.file_name().unwrap_or_default()),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            self.file_queue.push_back(path);
+        }
+    }
+    
+    /// Загрузка файлов из очереди по одному
+    fn poll_file_queue(&mut self) {
+        if self.file_loading.is_none() {
+            if let Some(path) = self.file_queue.pop_front() {
+                self.load_path(path);
+            }
+        }
+    }
+    
+    /// Постановка в очередь файла или всех поддерживаемых файлов папки
+    /// Системное окно выбора файла (или папки) с поддерживаемыми расширениями
+    fn pick_path(&mut self, folder: bool) -> Option<PathBuf> {
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(dir) = &self.dialog_dir {
+            dialog = dialog.set_directory(dir);
+        }
+        let path = if folder {
+            dialog.pick_folder()?
+        } else {
+            dialog
+                .add_filter("Поддерживаемые файлы", &self.file_processor.supported_extensions)
+                .pick_file()?
+        };
+        self.dialog_dir = if folder { Some(path.clone()) } else { path.parent().map(Path::to_path_buf) };
+        Some(path)
+    }
+    
+    fn enqueue_path(&mut self, path: PathBuf) {
+        let text = if path.is_dir() {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(&path)
+                .map(|entries| entries.filter_map(Result::ok).map(|entry| entry.path()).collect())
+                .unwrap_or_default();
+            files.retain(|file| file.is_file() && self.file_processor.is_supported(file));
+            files.sort();
+            let count = files.len();
+            self.file_queue.extend(files);
+            format!("📂 Папка {:?}: в очередь добавлено файлов: {}", path.file_name().unwrap_or_default(), count)
+        } else if self.file_processor.is_supported(&path) {
+            let text = format!("📥 В очереди на загрузку: {:?}", path.file_name().unwrap_or_default());
+            self.file_queue.push_back(path);
+            text
+        } else {
+            format!("✗ Неподдерживаемый формат файла: {:?}", path.file_name().unwrap_or_default())
+        };
+        self.messages.push(ChatMessage {
+            text,
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+    }
+    
+    /// Файлы, перетащенные на окно, и подсказка во время перетаскивания
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let (hovering, dropped) = ctx.input(|i| {
+            let dropped: Vec<PathBuf> = i.raw.dropped_files.iter().filter_map(|file| file.path.clone()).collect();
+            (!i.raw.hovered_files.is_empty(), dropped)
+        });
+        
+        if hovering {
+            let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("drop_overlay")));
+            let screen = ctx.screen_rect();
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(120));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "📥 Отпустите файлы для загрузки",
+                egui::FontId::proportional(22.0),
+                egui::Color32::WHITE,
+            );
+        }
+        
+        for path in dropped {
+            self.enqueue_path(path);
         }
     }
     
-    fn get_timestamp() -> String {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        let hours = (now / 3600) % 24;
-        let minutes = (now / 60) % 60;
-        format!("{:02}:{:02}", hours, minutes)
+    /// Чтение файла кусками в фоновом потоке с отправкой прогресса и результата
+    ///
+    /// Содержимое файлов до `STREAMING_THRESHOLD` сохраняется целиком, большие
+    /// файлы разбираются по кускам без хранения текста.
+    fn read_file_worker(processor: &FileProcessor, path: &Path, sender: &Sender<LoadEvent>) {
+        let total = std::fs::metadata(path).map_or(0, |meta| meta.len());
+        let read = || -> Result<LoadedFile, String> {
+            let mut chunks = processor.read_chunks(path, STREAMING_CHUNK_SIZE)?;
+            let mut content = (total <= STREAMING_THRESHOLD).then(String::new);
+            let mut stats = FileStats { lines: 0, words: 0, chars: 0, bytes: 0 };
+            let mut examples = Vec::new();
+            let mut discarded = BTreeMap::new();
+            
+            while let Some(chunk) = chunks.next() {
+                let chunk = chunk?;
+                match &mut content {
+                    Some(content) => content.push_str(&chunk),
+                    None => {
+                        stats.add(&processor.get_file_stats(&chunk));
+                        examples.extend(processor.filter_language(processor.prepare_training_data(&chunk), &mut discarded));
+                    }
+                }
+                let _ = sender.send(LoadEvent::Progress(chunks.bytes_read() as f32 / total.max(1) as f32));
+            }
+            
+            if let Some(content) = &content {
+                stats = processor.get_file_stats(content);
+                examples = processor.filter_language(processor.prepare_training_data(content), &mut discarded);
+            }
+            Ok(LoadedFile { content, stats, examples, discarded })
+        };
+        let _ = sender.send(LoadEvent::Done(read()));
     }
     
-    fn send_message(&mut self) {
-        if self.input_text.trim().is_empty() {
+    /// Приём событий фоновой загрузки файла
+    fn poll_file_loading(&mut self) {
+        let Some(loading) = &mut self.file_loading else {
             return;
+        };
+        
+        let mut result = None;
+        loop {
+            match loading.receiver.try_recv() {
+                Ok(LoadEvent::Progress(progress)) => loading.progress = progress.min(1.0),
+                Ok(LoadEvent::Done(done)) => result = Some(done),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    result.get_or_insert_with(|| Err("Поток загрузки завершился аварийно".to_string()));
+                    break;
+                }
+            }
         }
         
-        // Добавляем сообщение пользователя
-        let user_msg = ChatMessage {
-            text: self.input_text.clone(),
-            is_user: true,
-            timestamp: Self::get_timestamp(),
+        if let Some(result) = result {
+            if let Some(loading) = self.file_loading.take() {
+                self.finish_file_loading(loading.path, result);
+            }
+        }
+    }
+    
+    fn finish_file_loading(&mut self, path: PathBuf, result: Result<LoadedFile, String>) {
+        let file = match result {
+            Ok(file) => file,
+            Err(e) => {
+                self.messages.push(ChatMessage {
+                    text: format!("❌ Ошибка загрузки файла!\n\n{}\n\n💡 Проверьте:\n• Путь к файлу правильный?\n• Файл существует?\n• Формат поддерживается?", e),
+                    is_user: false,
+                    timestamp: Self::get_timestamp(),
+                    metrics: None,
+                });
+                return;
+            }
         };
-        self.messages.push(user_msg);
         
-        // Генерируем ответ
-        let input = self.input_text.clone();
-        self.input_text.clear();
+        if file.stats.words == 0 {
+            self.messages.push(ChatMessage {
+                text: format!("⚠️ Файл пустой!\n\n📁 Файл: {:?}\n\n💡 Убедитесь, что файл содержит текст.", 
+                    path.file_name().unwrap_or_default()
+                ),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            return;
+        }
         
-        let model = self.model.clone();
-        let response = {
-            let model = model.lock().unwrap();
-            model.generate(&input, 50)
-        };
+        let file_name = path.file_name().unwrap_or_default().to_os_string();
+        let stats_text = file.stats.format();
+        self.file_stats = Some(file.stats);
+        // Содержимое больших файлов не сохраняется, чтобы не держать их в памяти
+        self.loaded_files.push((path, file.content.unwrap_or_default()));
         
-        // Если ответ пустой, даем стандартный ответ
-        let response_text = if response.trim().is_empty() {
-            "Я пока не знаю, как на это ответить. Попробуйте дообучить меня на ваших данных! 📚".to_string()
+        let discarded_text = if file.discarded.is_empty() {
+            String::new()
         } else {
-            response
+            let counts: Vec<String> = file
+                .discarded
+                .iter()
+                .map(|(language, count)| format!("{} — {}", language.name(), count))
+                .collect();
+            format!("\n🌐 Отброшено по языку: {}", counts.join(", "))
         };
         
-        let ai_msg = ChatMessage {
-            text: response_text,
+        let examples_count = file.examples.len();
+        if examples_count == 0 {
+            self.messages.push(ChatMessage {
+                text: format!("⚠️ Не удалось извлечь данные для обучения!\n\n📁 Файл: {:?}\n{}{}\n\n💡 Файл загружен, но текст слишком короткий.\nДобавьте больше содержимого (минимум 5 символов).", 
+                    file_name,
+                    stats_text,
+                    discarded_text
+                ),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            return;
+        }
+        
+        self.training_data.extend(file.examples);
+        self.corpus_report = None;
+        
+        self.messages.push(ChatMessage {
+            text: format!("✅ Файл успешно загружен!\n\n📁 Файл: {:?}\n{}\n📊 Извлечено примеров: {}{}\n\n💡 Теперь нажмите \"Начать обучение\"!", 
+                file_name,
+                stats_text,
+                examples_count,
+                discarded_text
+            ),
             is_user: false,
             timestamp: Self::get_timestamp(),
-        };
-        self.messages.push(ai_msg);
+            metrics: None,
+        });
+        
+        self.file_path_input.clear();
     }
     
-    fn load_file(&mut self) {
-        if self.file_path_input.trim().is_empty() {
+    /// Перенос настроек CSV из полей вкладки обучения в обработчик файлов
+    fn apply_csv_options(&mut self) {
+        let csv = &mut self.file_processor.csv;
+        csv.text_columns = self
+            .csv_columns
+            .split(',')
+            .map(str::trim)
+            .filter(|column| !column.is_empty())
+            .map(str::to_string)
+            .collect();
+        csv.dialogue_columns = self
+            .csv_dialogue
+            .then(|| (self.csv_question_column.trim().to_string(), self.csv_answer_column.trim().to_string()));
+    }
+    
+    /// Переключение GPU-бэкенда модели (при отсутствии адаптера — CPU)
+    fn apply_gpu(&mut self) {
+        // Обучение и генерация держат модель; переключатель возвращается назад
+        let Ok(mut model) = self.model.try_lock() else {
+            self.use_gpu = !self.use_gpu;
             self.messages.push(ChatMessage {
-                text: "✗ Ошибка: введите путь к файлу".to_string(),
+                text: "⏳ Модель занята обучением или генерацией, GPU можно переключить позже".to_string(),
                 is_user: false,
                 timestamp: Self::get_timestamp(),
+                metrics: None,
             });
             return;
-        }
-        
-        let path = PathBuf::from(self.file_path_input.trim());
+        };
+        let text = if !self.use_gpu {
+            model.disable_gpu();
+            "🖥 Вычисления переключены на CPU".to_string()
+        } else if let Some(adapter) = model.enable_gpu() {
+            format!("⚡ Вычисления на GPU: {}", adapter)
+        } else {
+            self.use_gpu = false;
+            "⚠️ GPU недоступен, вычисления остаются на CPU".to_string()
+        };
+        drop(model);
         
-        // Проверяем существование файла
-        if !path.exists() {
+        self.messages.push(ChatMessage {
+            text,
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+    }
+    
+    fn train_tokenizer(&mut self) {
+        if self.training_data.is_empty() {
             self.messages.push(ChatMessage {
-                text: format!("✗ Файл не найден: {:?}\n\n💡 Попробуйте:\n• examples/training_data_ru.txt\n• examples\\training_data_ru.txt\n• Полный путь к файлу", path),
+                text: "✗ Нет данных для токенизатора. Загрузите файлы! 📁".to_string(),
                 is_user: false,
                 timestamp: Self::get_timestamp(),
+                metrics: None,
             });
             return;
         }
         
-        match self.file_processor.read_file(&path) {
-            Ok(content) => {
-                if content.trim().is_empty() {
+        let info = {
+            let mut model = self.model.lock().unwrap();
+            model.train_tokenizer(&self.training_data, self.bpe_vocab_size);
+            model.info()
+        };
+        self.corpus_report = None;
+        
+        self.messages.push(ChatMessage {
+            text: format!("🔤 BPE токенизатор обучен!\n\n{}\n\n⚠️ Веса сброшены — запустите обучение модели.", info),
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+    }
+    
+    /// Новая модель с заданной архитектурой; словарь текущей модели сохраняется
+    ///
+    /// Возвращает `true`, если модель пересоздана.
+    fn rebuild_model(&mut self) -> bool {
+        let result = HiddenLayerSpec::parse_list(&self.architecture).and_then(|layers| {
+            let mut model = self.model.lock().unwrap();
+            let rebuilt = AIModelBuilder::new()
+                .embedding_dim(self.embedding_dim)
+                .context_length(self.context_length)
+                .hidden_layers(layers)
+                .recurrent(self.recurrent)
+                .learning_rate(self.learning_rate)
+                .batch_size(self.batch_size)
+                .optimizer(self.optimizer)
+                .vocabulary(model.vocabulary())
+                .build()?;
+            *model = rebuilt;
+            Ok(model.info())
+        });
+        
+        let rebuilt = result.is_ok();
+        let text = match result {
+            Ok(info) => format!("🏗 Модель пересоздана!\n\n{}\n\n⚠️ Веса сброшены — запустите обучение модели.", info),
+            Err(e) => e,
+        };
+        self.messages.push(ChatMessage {
+            text,
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+        rebuilt
+    }
+    
+    /// Выполнение подтверждённого действия с запоминанием состояния для отмены
+    fn perform_destructive(&mut self, action: DestructiveAction) {
+        let state = match action {
+            DestructiveAction::ClearChat => {
+                let session = &self.sessions[self.active_session];
+                let state = UndoState::Chat {
+                    messages: self.messages.clone(),
+                    created_at: session.created_at,
+                    title: session.title.clone(),
+                };
+                self.clear_history();
+                state
+            }
+            DestructiveAction::ResetModel => {
+                let Ok(model) = self.model.try_lock() else {
                     self.messages.push(ChatMessage {
-                        text: format!("⚠️ Файл пустой!\n\n📁 Файл: {:?}\n\n💡 Убедитесь, что файл содержит текст.", 
-                            path.file_name().unwrap_or_default()
-                        ),
+                        text: "⏳ Модель занята обучением или генерацией".to_string(),
                         is_user: false,
                         timestamp: Self::get_timestamp(),
+                        metrics: None,
                     });
                     return;
-                }
-                
-                self.file_stats = Some(self.file_processor.get_file_stats(&content));
-                self.loaded_files.push((path.clone(), content.clone()));
-                
-                let training_examples = self.file_processor.extract_training_data(&content);
-                let examples_count = training_examples.len();
-                
-                if training_examples.is_empty() {
-                    self.messages.push(ChatMessage {
-                        text: format!("⚠️ Не удалось извлечь данные для обучения!\n\n📁 Файл: {:?}\n{}\n\n💡 Файл загружен, но текст слишком короткий.\nДобавьте больше содержимого (минимум 5 символов).", 
-                            path.file_name().unwrap_or_default(),
-                            self.file_stats.as_ref().unwrap().format()
-                        ),
-                        is_user: false,
-                        timestamp: Self::get_timestamp(),
-                    });
+                };
+                let previous = Box::new(model.clone());
+                drop(model);
+                if !self.rebuild_model() {
                     return;
                 }
-                
-                self.training_data.extend(training_examples);
-                
-                self.messages.push(ChatMessage {
-                    text: format!("✅ Файл успешно загружен!\n\n📁 Файл: {:?}\n{}\n📊 Извлечено примеров: {}\n\n💡 Теперь нажмите \"Начать обучение\"!", 
-                        path.file_name().unwrap_or_default(),
-                        self.file_stats.as_ref().unwrap().format(),
-                        examples_count
-                    ),
-                    is_user: false,
-                    timestamp: Self::get_timestamp(),
-                });
-                
-                self.file_path_input.clear();
+                UndoState::Model(previous)
             }
-            Err(e) => {
+            DestructiveAction::RemoveFiles => {
+                let state = UndoState::Files {
+                    loaded_files: std::mem::take(&mut self.loaded_files),
+                    training_data: std::mem::take(&mut self.training_data),
+                    file_stats: self.file_stats.take(),
+                };
+                self.corpus_report = None;
                 self.messages.push(ChatMessage {
-                    text: format!("❌ Ошибка загрузки файла!\n\n{}\n\n💡 Проверьте:\n• Путь к файлу правильный?\n• Файл существует?\n• Формат поддерживается?", e),
+                    text: "🗑 Загруженные файлы и обучающие примеры убраны".to_string(),
                     is_user: false,
                     timestamp: Self::get_timestamp(),
+                    metrics: None,
                 });
+                state
             }
-        }
+        };
+        self.undo = Some((action, state));
+    }
+    
+    /// Возврат состояния до последнего разрушительного действия
+    fn undo_last(&mut self) {
+        let Some((action, state)) = self.undo.take() else {
+            return;
+        };
+        let text = match state {
+            UndoState::Chat { messages, created_at, title } => {
+                self.stop_speaking();
+                self.messages = messages;
+                self.message_list.reset(self.messages.len());
+                let session = &mut self.sessions[self.active_session];
+                session.created_at = created_at;
+                session.title = title;
+                self.saved_messages = usize::MAX;
+                return;
+            }
+            UndoState::Model(previous) => {
+                let Ok(mut model) = self.model.try_lock() else {
+                    self.undo = Some((action, UndoState::Model(previous)));
+                    return;
+                };
+                *model = *previous;
+                if self.use_gpu && model.enable_gpu().is_none() {
+                    self.use_gpu = false;
+                }
+                drop(model);
+                self.corpus_report = None;
+                "↶ Прежняя модель возвращена".to_string()
+            }
+            UndoState::Files { mut loaded_files, mut training_data, file_stats } => {
+                // Файлы, загруженные после удаления, остаются в конце списка
+                loaded_files.append(&mut self.loaded_files);
+                training_data.append(&mut self.training_data);
+                self.loaded_files = loaded_files;
+                self.training_data = training_data;
+                self.file_stats = self.file_stats.take().or(file_stats);
+                self.corpus_report = None;
+                format!("↶ Файлы возвращены: {}, примеров: {}", self.loaded_files.len(), self.training_data.len())
+            }
+        };
+        self.messages.push(ChatMessage {
+            text,
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+    }
+    
+    /// Словарь слов по частотам в загруженных данных
+    fn build_vocab(&mut self) {
+        let info = {
+            let mut model = self.model.lock().unwrap();
+            model.build_vocab_from_corpus(&self.training_data, self.bpe_vocab_size, self.vocab_min_freq);
+            model.info()
+        };
+        self.corpus_report = None;
+        
+        self.messages.push(ChatMessage {
+            text: format!("📖 Словарь построен по корпусу!\n\n{}\n\n⚠️ Веса сброшены — запустите обучение модели.", info),
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
+        });
+    }
+    
+    /// Отчёт по всем загруженным примерам относительно текущего словаря
+    fn build_corpus_report(&mut self) {
+        // Во время обучения модель занята фоновым потоком
+        let Ok(model) = self.model.try_lock() else {
+            self.messages.push(ChatMessage {
+                text: "⏳ Модель занята обучением, отчёт будет доступен после него".to_string(),
+                is_user: false,
+                timestamp: Self::get_timestamp(),
+                metrics: None,
+            });
+            return;
+        };
+        let report = CorpusReport::build(self.loaded_files.len(), &self.training_data, &model);
+        drop(model);
+        self.corpus_report = Some(report);
     }
     
-    fn start_training(&mut self) {
+    /// Запуск обучения; `resume` — продолжить с последнего чекпоинта
+    fn start_training(&mut self, resume: bool) {
         if self.training_data.is_empty() {
             self.messages.push(ChatMessage {
                 text: "✗ Нет данных для обучения. Загрузите файлы! 📁".to_string(),
                 is_user: false,
                 timestamp: Self::get_timestamp(),
+                metrics: None,
             });
             return;
         }
@@ -234,37 +1772,252 @@ impl ChatUI {
                 text: format!("✗ Ошибка валидации: {}", e),
                 is_user: false,
                 timestamp: Self::get_timestamp(),
+                metrics: None,
             });
             return;
         }
         
+        let architecture_note = match self.validate_hyperparameters() {
+            Ok(note) => note,
+            Err(e) => {
+                self.messages.push(ChatMessage {
+                    text: e,
+                    is_user: false,
+                    timestamp: Self::get_timestamp(),
+                    metrics: None,
+                });
+                return;
+            }
+        };
+        
         self.training_status.is_training = true;
         self.training_status.total_epochs = self.epochs;
         self.training_status.current_epoch = 0;
+        self.loss_history.clear();
+        self.loss_chart.reset_view();
         
         self.messages.push(ChatMessage {
-            text: format!("🚀 Начинаю обучение!\n\n📊 Примеров: {}\n🔄 Эпох: {}\n\nПодождите...", 
-                self.training_data.len(), self.epochs),
+            text: format!("🚀 Начинаю обучение!\n\n📊 Примеров: {}\n🔄 Эпох: {}\n⚡ Оптимизатор: {}\n📐 Скорость: {}, батч: {}{}\n\nПодождите...", 
+                self.training_data.len(), self.epochs, self.optimizer.name(), self.learning_rate, self.batch_size,
+                architecture_note.map(|note| format!("\n\n{}", note)).unwrap_or_default()),
             is_user: false,
             timestamp: Self::get_timestamp(),
+            metrics: None,
         });
         
         // Запускаем обучение в отдельном потоке
         let model = self.model.clone();
-        let data = self.training_data.clone();
+        let (data, validation) = self.split_training_data();
         let epochs = self.epochs;
+        let optimizer = self.optimizer;
+        let (learning_rate, batch_size) = (self.learning_rate, self.batch_size);
+        let precision = self.precision;
+        let recurrent = self.recurrent;
+        let (dropout, layer_norm) = (self.dropout, self.layer_norm);
+        let sampling = (self.scheduled_sampling > 0.0).then(|| ScheduledSampling::new(self.scheduled_sampling));
+        let checkpoints = self.use_checkpoints.then(|| CheckpointConfig::new(self.checkpoint_dir.trim()));
         
-        thread::spawn(move || {
-            let mut model = model.lock().unwrap();
-            model.train(&data, epochs, |epoch, total, loss| {
-                println!("Эпоха {}/{}, Loss: {:.4}", epoch, total, loss);
-            });
+        self.training_started = Some(Instant::now());
+        // После нового обучения возврат старых весов уже не ожидается
+        if matches!(self.undo, Some((DestructiveAction::ResetModel, _))) {
+            self.undo = None;
+        }
+        self.training_handle = Some(TrainingHandle::spawn(model, move |model| {
+            // Loss по эпохам публикуется через TrainingControl и рисуется на графике
+            let progress = |_, _, _| {};
+            model.validation_texts = validation;
+            
+            let result = match &checkpoints {
+                Some(checkpoints) if resume => model.resume_training(&data, checkpoints, progress),
+                _ => {
+                    model.set_optimizer(optimizer);
+                    model.learning_rate = learning_rate;
+                    model.batch_size = batch_size;
+                    model.set_precision(precision);
+                    model.set_recurrent(recurrent);
+                    model.set_regularization(dropout, layer_norm);
+                    model.scheduled_sampling = sampling;
+                    match &checkpoints {
+                        Some(checkpoints) => model.train_with_checkpoints(&data, epochs, checkpoints, progress),
+                        None => Ok(model.train(&data, epochs, progress)),
+                    }
+                }
+            };
+            
+            result.map_err(|e| e.to_string())
+        }));
+    }
+    
+    /// Проверка гиперпараметров перед обучением
+    ///
+    /// Скорость и батч применяются к модели при запуске; размеры слоёв требуют
+    /// пересоздания модели, поэтому их расхождение с текущей моделью
+    /// возвращается как предупреждение.
+    fn validate_hyperparameters(&self) -> Result<Option<String>, String> {
+        if !(self.learning_rate.is_finite() && self.learning_rate > 0.0) {
+            return Err("✗ Скорость обучения должна быть положительным числом".to_string());
+        }
+        if self.batch_size == 0 {
+            return Err("✗ Размер батча должен быть больше нуля".to_string());
+        }
+        if self.embedding_dim == 0 {
+            return Err("✗ Размер эмбеддинга должен быть больше нуля".to_string());
+        }
+        if self.context_length == 0 {
+            return Err("✗ Длина контекста должна быть больше нуля".to_string());
+        }
+        let layers = HiddenLayerSpec::parse_list(&self.architecture)?;
+        
+        let model = self.model.try_lock().map_err(|_| "⏳ Модель занята обучением или генерацией".to_string())?;
+        let unchanged = model.embedding_dim == self.embedding_dim
+            && model.context_length == self.context_length
+            && model.hidden_specs() == layers;
+        Ok((!unchanged).then(|| {
+            "⚠️ Размеры слоёв отличаются от текущей модели — нажмите «Пересоздать модель», чтобы применить их".to_string()
+        }))
+    }
+    
+    /// Обучающая и валидационная выборки (разбиение фиксировано зерном)
+    fn split_training_data(&self) -> (Vec<String>, Vec<String>) {
+        if self.validation_split <= 0.0 {
+            return (self.training_data.clone(), Vec::new());
+        }
+        self.file_processor
+            .split_dataset(&self.training_data, 1.0 - self.validation_split, VALIDATION_SEED)
+    }
+    
+    /// Автосохранение модели после обучения; возвращает строку для отчёта
+    fn auto_save_model(&self) -> String {
+        let auto_save = AutoSaveConfig::new(self.auto_save_dir.trim(), self.auto_save_keep);
+        let stamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+        let result = match self.model.try_lock() {
+            Ok(model) => auto_save.save(&model, stamp).map_err(|e| e.to_string()),
+            Err(_) => Err("модель занята".to_string()),
+        };
+        match result {
+            Ok(path) => format!("💾 Модель сохранена: {}", path.display()),
+            Err(e) => format!("✗ Ошибка автосохранения модели: {}", e),
+        }
+    }
+    
+    /// Прогресс фонового обучения и итоговое сообщение по завершении
+    fn poll_training(&mut self) {
+        let Some(handle) = &self.training_handle else {
+            return;
+        };
+        let (epoch, total) = handle.control().progress();
+        self.training_status.current_epoch = epoch;
+        if total > 0 {
+            self.training_status.total_epochs = total;
+            self.training_status.progress = epoch as f32 / total as f32;
+        }
+        if let Some(loss) = handle.control().last_loss() {
+            self.training_status.loss = loss;
+        }
+        self.loss_history = handle.control().loss_history();
+        if !handle.is_finished() {
+            return;
+        }
+        
+        let mut cancelled = false;
+        let mut succeeded = false;
+        let mut text = match self.training_handle.take().map(TrainingHandle::join) {
+            Some(Ok(report)) => {
+                cancelled = report.cancelled;
+                succeeded = !report.cancelled;
+                self.loss_history = report.loss_history();
+                let mut text = if report.cancelled {
+                    format!("⏹ Обучение остановлено после {} эпох", report.start_epoch + report.epoch_losses.len())
+                } else {
+                    "✓ Обучение завершено!".to_string()
+                };
+                if report.start_epoch > 0 {
+                    text.push_str(&format!("\n\n⏯ Продолжено с эпохи {}", report.start_epoch));
+                }
+                if let Some(loss) = report.final_loss() {
+                    text.push_str(&format!("\n📉 Loss: {:.4}", loss));
+                }
+                if let Some((epoch, loss)) = report.best_epoch() {
+                    text.push_str(&format!("\n🏆 Лучший loss {:.4} на эпохе {}", loss, epoch));
+                }
+                if let Some(loss) = report.validation_loss {
+                    text.push_str(&format!("\n🧪 Loss на валидации: {:.4}", loss));
+                }
+                text
+            }
+            Some(Err(e)) => format!("✗ Ошибка обучения: {}", e),
+            None => return,
+        };
+        if let Some(started) = self.training_started.take() {
+            let secs = started.elapsed().as_secs();
+            text.push_str(&format!("\n⏱ Длительность: {}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60));
+        }
+        self.training_status.is_training = false;
+        if succeeded && self.auto_save {
+            text.push_str(&format!("\n{}", self.auto_save_model()));
+        }
+        
+        // Остановленное пользователем обучение он и так видит
+        if !cancelled {
+            let mut errors = Vec::new();
+            if self.ui_settings.notify_training {
+                let title = text.lines().next().unwrap_or_default();
+                let body = text.lines().skip(1).filter(|line| !line.trim().is_empty()).collect::<Vec<_>>().join("\n");
+                errors.extend(notify::desktop_notification(title, &body).err());
+            }
+            if self.ui_settings.training_sound {
+                errors.extend(notify::play_completion_sound().err());
+            }
+            for error in errors {
+                text.push_str(&format!("\n{}", error));
+            }
+        }
+        self.messages.push(ChatMessage {
+            text,
+            is_user: false,
+            timestamp: Self::get_timestamp(),
+            metrics: None,
         });
     }
 }
 
 impl eframe::App for ChatUI {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.poll_training();
+        self.poll_generation();
+        self.poll_transcription();
+        self.poll_file_loading();
+        self.poll_attachment();
+        self.poll_folder_watcher();
+        self.handle_dropped_files(ctx);
+        self.poll_file_queue();
+        self.save_history_if_changed();
+        self.save_ui_settings_if_changed();
+        self.update_system_monitor(ctx);
+        if self.speaking_message.is_some() {
+            match self.speaker.poll() {
+                Ok(true) => {}
+                Ok(false) => self.speaking_message = None,
+                Err(e) => {
+                    self.speaking_message = None;
+                    self.messages.push(ChatMessage {
+                        text: e,
+                        is_user: false,
+                        timestamp: Self::get_timestamp(),
+                        metrics: None,
+                    });
+                }
+            }
+        }
+        self.handle_shortcuts(ctx);
+        self.ecosystem.step(ctx.input(|i| i.stable_dt));
+        
+        // Масштаб интерфейса относительно системного
+        let pixels_per_point = frame.info().native_pixels_per_point.unwrap_or(1.0) * self.ui_settings.ui_scale;
+        if (ctx.pixels_per_point() - pixels_per_point).abs() > 1e-3 {
+            ctx.set_pixels_per_point(pixels_per_point);
+        }
+        
         // Устанавливаем стиль DeepSeek - голубые оттенки
         let mut style = (*ctx.style()).clone();
         style.visuals = egui::Visuals::light();
@@ -278,6 +2031,12 @@ impl eframe::App for ChatUI {
         style.visuals.window_rounding = egui::Rounding::same(8.0);
         style.visuals.menu_rounding = egui::Rounding::same(6.0);
         
+        style.animation_time = if self.ui_settings.reduced_motion {
+            0.0
+        } else {
+            egui::Style::default().animation_time
+        };
+        
         ctx.set_style(style);
         
         // Верхняя панель с режимами (компактная)
@@ -289,7 +2048,8 @@ impl eframe::App for ChatUI {
                 ui.add_space(10.0);
                 
                 // Заголовок
-                ui.label(egui::RichText::new("🤖 AI Ассистент").size(18.0).strong());
+                ui.label(self.avatar(18.0)).on_hover_text(self.mood_hover_text());
+                ui.label(egui::RichText::new("AI Ассистент").size(18.0).strong());
                 
                 ui.add_space(20.0);
                 
@@ -309,11 +2069,33 @@ impl eframe::App for ChatUI {
                     self.mode = AppMode::Training;
                 }
                 
+                if ui.selectable_label(self.mode == AppMode::Ecosystem, 
+                    egui::RichText::new("🌍 Экосистема").size(14.0))
+                    .clicked() {
+                    self.mode = AppMode::Ecosystem;
+                }
+                
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.add_space(10.0);
                     if ui.button(egui::RichText::new("ℹ️").size(16.0)).clicked() {
                         self.show_model_info = !self.show_model_info;
                     }
+                    if let Some((action, _)) = &self.undo {
+                        let hover = format!("Отменить: {}", action.label());
+                        let busy = matches!(action, DestructiveAction::ResetModel) && self.training_status.is_training;
+                        if ui.add_enabled(!busy, egui::Button::new("↶ Отменить")).on_hover_text(hover).clicked() {
+                            self.undo_last();
+                        }
+                    }
+                    if ui.button(egui::RichText::new("⚙️").size(16.0)).clicked() {
+                        self.show_settings = !self.show_settings;
+                    }
+                    if ui.selectable_label(self.show_system_monitor, egui::RichText::new("📊").size(16.0))
+                        .on_hover_text("Монитор ресурсов")
+                        .clicked()
+                    {
+                        self.show_system_monitor = !self.show_system_monitor;
+                    }
                 });
             });
             ui.add_space(5.0);
@@ -334,31 +2116,144 @@ impl eframe::App for ChatUI {
                 .show(ui, |ui| {
                     ui.set_min_width(ui.available_width() - 20.0);
                     
+                    // Подсказки команд; Tab дополняет первую
+                    let input_id = egui::Id::new("chat_input");
+                    let suggestions = ChatCommand::suggestions(&self.input_text);
+                    let mut completion = None;
+                    if !suggestions.is_empty() {
+                        if ui.memory(|m| m.has_focus(input_id))
+                            && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab))
+                        {
+                            completion = Some(suggestions[0].0);
+                        }
+                        ui.horizontal_wrapped(|ui| {
+                            for (name, args, description) in &suggestions {
+                                let label = if args.is_empty() { name.to_string() } else { format!("{} {}", name, args) };
+                                if ui.small_button(label).on_hover_text(*description).clicked() {
+                                    completion = Some(name);
+                                }
+                            }
+                            ui.label(egui::RichText::new("Tab — дополнить").size(11.0).weak());
+                        });
+                    }
+                    // Прикреплённый файл
+                    if self.attachment_loading.is_some() {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label(egui::RichText::new("Чтение прикреплённого файла...").size(11.0).weak());
+                        });
+                    } else if let Some(attachment) = &self.attachment {
+                        let mut detach = false;
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(attachment.summary()).size(12.0))
+                                .on_hover_text(attachment.path.display().to_string());
+                            detach = ui.small_button("✕").on_hover_text("Открепить файл").clicked();
+                        });
+                        if detach {
+                            self.attachment = None;
+                        }
+                    }
+                    
+                    if let Some(name) = completion {
+                        self.input_text = format!("{} ", name);
+                        let mut state = egui::TextEdit::load_state(ui.ctx(), input_id).unwrap_or_default();
+                        let end = egui::text::CCursor::new(self.input_text.chars().count());
+                        state.set_ccursor_range(Some(egui::text::CCursorRange::one(end)));
+                        state.store(ui.ctx(), input_id);
+                        ui.memory_mut(|m| m.request_focus(input_id));
+                    }
+                    
+                    // Отправка и перевод строки по настроенным сочетаниям, до того как клавишу обработает поле
+                    let shortcuts = self.ui_settings.shortcuts;
+                    let mut send = false;
+                    if ui.memory(|m| m.has_focus(input_id)) {
+                        send = ui.input_mut(|i| i.consume_shortcut(&shortcuts.send.0));
+                        // Enter, не занятый отправкой, поле ввода само превращает в перевод строки
+                        if shortcuts.newline.0.key != egui::Key::Enter
+                            && ui.input_mut(|i| i.consume_shortcut(&shortcuts.newline.0))
+                        {
+                            self.insert_newline(ui.ctx(), input_id);
+                        }
+                    }
+                    
                     ui.horizontal(|ui| {
                         // Поле ввода
                         let text_edit = egui::TextEdit::multiline(&mut self.input_text)
-                            .hint_text("Напишите сообщение...")
-                            .desired_width(ui.available_width() - 60.0)
+                            .id(input_id)
+                            .hint_text("Напишите сообщение или /команду...")
+                            .font(egui::FontId::proportional(self.ui_settings.chat_font_size))
+                            .desired_width(ui.available_width() - 140.0)
                             .desired_rows(1)
                             .frame(false);
                         
                         let response = ui.add(text_edit);
                         
-                        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                            if !ui.input(|i| i.modifiers.shift) {
-                                self.send_message();
-                                response.request_focus();
-                            }
+                        if send {
+                            self.send_message();
+                            response.request_focus();
                         }
                         
                         ui.add_space(5.0);
                         
-                        // Кнопка отправки (голубая)
-                        let send_button = egui::Button::new(egui::RichText::new("📤").size(20.0))
-                            .fill(egui::Color32::from_rgb(100, 150, 255));
+                        let attach = ui
+                            .add_enabled(
+                                self.attachment_loading.is_none(),
+                                egui::Button::new(egui::RichText::new("📎").size(20.0)),
+                            )
+                            .on_hover_text("Прикрепить текст или PDF как контекст разговора");
+                        if attach.clicked() {
+                            if let Some(path) = self.pick_path(false) {
+                                self.attach_file(path);
+                            }
+                        }
                         
-                        if ui.add(send_button).clicked() {
-                            self.send_message();
+                        // Микрофон: запись, пока кнопка удерживается
+                        let recording = self.voice.as_ref().is_some_and(VoiceInput::is_recording);
+                        let transcribing = !recording && self.transcription.is_some();
+                        let mic_icon = if recording { "🔴" } else if transcribing { "⏳" } else { "🎤" };
+                        let mic = ui
+                            .add_enabled(
+                                VoiceInput::available() && !transcribing,
+                                egui::Button::new(egui::RichText::new(mic_icon).size(20.0)),
+                            )
+                            .on_hover_text("Удерживайте, чтобы говорить")
+                            .on_disabled_hover_text(if transcribing {
+                                "Распознавание..."
+                            } else {
+                                "Голосовой ввод не собран: пересоберите с --features voice"
+                            });
+                        if !recording && mic.hovered() && ui.input(|i| i.pointer.primary_pressed()) {
+                            self.start_voice_input();
+                        } else if recording && !mic.is_pointer_button_down_on() {
+                            self.finish_voice_input();
+                        }
+                        
+                        if let Some(generation) = &self.generation {
+                            // Остановка генерации; уже полученный текст остаётся в чате
+                            let stop_button = egui::Button::new(egui::RichText::new("⏹").size(20.0))
+                                .fill(egui::Color32::from_rgb(230, 120, 120));
+                            if ui.add(stop_button).on_hover_text("Остановить генерацию").clicked() {
+                                generation.stop();
+                            }
+                        } else {
+                            let next_message = self.next_message;
+                            let gear = egui::Button::new(egui::RichText::new("⚙").size(20.0)).selected(next_message.is_set());
+                            let hover = if next_message.is_set() {
+                                "Для следующего сообщения заданы свои настройки"
+                            } else {
+                                "Настройки только для следующего сообщения"
+                            };
+                            if ui.add(gear).on_hover_text(hover).clicked() {
+                                self.show_next_message = !self.show_next_message;
+                            }
+                            
+                            // Кнопка отправки (голубая)
+                            let send_button = egui::Button::new(egui::RichText::new("📤").size(20.0))
+                                .fill(egui::Color32::from_rgb(100, 150, 255));
+                            
+                            if ui.add(send_button).on_hover_text(format!("Отправить ({})", shortcuts.send.format())).clicked() {
+                                self.send_message();
+                            }
                         }
                     });
                 });
@@ -366,13 +2261,52 @@ impl eframe::App for ChatUI {
             ui.add_space(10.0);
         });
         
+        // Монитор ресурсов (до центральной панели, чтобы она заняла остаток)
+        egui::SidePanel::right("system_monitor")
+            .resizable(false)
+            .default_width(220.0)
+            .show_animated(ctx, self.show_system_monitor, |ui| self.render_system_monitor(ui));
+        
         // Центральная панель с контентом
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.mode {
                 AppMode::Chat => self.render_chat_mode(ui),
                 AppMode::Training => self.render_training_mode(ui),
+                AppMode::Ecosystem => self.ecosystem.show(ui),
+            }
+        });
+        
+        // Подтверждение остановки обучения
+        if self.confirm_stop_training {
+            match &self.training_handle {
+                Some(handle) if !handle.is_finished() => {
+                    egui::Window::new("⏹ Остановить обучение?")
+                        .collapsible(false)
+                        .resizable(false)
+                        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                        .show(ctx, |ui| {
+                            let (epoch, total) = handle.control().progress();
+                            ui.label(format!(
+                                "Обучение прервётся после текущего примера (эпоха {}/{}).\n\
+                                 Уже пройденные эпохи сохранятся в модели.",
+                                epoch, total
+                            ));
+                            ui.add_space(8.0);
+                            ui.horizontal(|ui| {
+                                if ui.button("⏹ Остановить").clicked() {
+                                    handle.cancel();
+                                    self.confirm_stop_training = false;
+                                }
+                                if ui.button("Продолжить обучение").clicked() {
+                                    self.confirm_stop_training = false;
+                                }
+                            });
+                        });
+                }
+                // Обучение уже закончилось само
+                _ => self.confirm_stop_training = false,
             }
-        });
+        }
         
         // Окно информации о модели
         if self.show_model_info {
@@ -392,22 +2326,338 @@ impl eframe::App for ChatUI {
                 });
         }
         
+        // Окно обучающих примеров
+        if self.show_data_preview {
+            let mut changed = false;
+            egui::Window::new("📝 Обучающие примеры")
+                .open(&mut self.show_data_preview)
+                .default_size([700.0, 450.0])
+                .show(ctx, |ui| {
+                    // Во время обучения модель занята, токены не считаются
+                    let model = self.model.try_lock().ok();
+                    let count = |text: &str| model.as_ref().map_or(0, |model| model.tokenize(text).len());
+                    let count_tokens: Option<&dyn Fn(&str) -> usize> = model.is_some().then_some(&count);
+                    changed = self.data_preview.show(ui, &mut self.training_data, count_tokens);
+                });
+            if changed {
+                self.corpus_report = None;
+            }
+        }
+        
+        // Окно разовых настроек следующего сообщения
+        if self.show_next_message {
+            let defaults = &self.generation_config;
+            let next = &mut self.next_message;
+            egui::Window::new("⚙ Следующее сообщение")
+                .open(&mut self.show_next_message)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(egui::RichText::new("Отмеченное применяется к одному ответу, общие настройки не меняются").size(11.0).weak());
+                    ui.add_space(4.0);
+                    egui::Grid::new("next_message").num_columns(2).show(ui, |ui| {
+                        let mut enabled = next.max_tokens.is_some();
+                        if ui.checkbox(&mut enabled, "Макс. токенов").changed() {
+                            next.max_tokens = enabled.then_some(defaults.max_tokens);
+                        }
+                        match &mut next.max_tokens {
+                            Some(max_tokens) => ui.add(egui::Slider::new(max_tokens, 1..=500)),
+                            None => ui.label(defaults.max_tokens.to_string()),
+                        };
+                        ui.end_row();
+                        
+                        let mut enabled = next.temperature.is_some();
+                        if ui.checkbox(&mut enabled, "Температура").changed() {
+                            next.temperature = enabled.then_some(defaults.temperature);
+                        }
+                        match &mut next.temperature {
+                            Some(temperature) => ui.add(egui::Slider::new(temperature, 0.0..=2.0)),
+                            None => ui.label(format!("{:.2}", defaults.temperature)),
+                        };
+                        ui.end_row();
+                        
+                        let mut enabled = next.remote.is_some();
+                        if ui.checkbox(&mut enabled, "Источник").changed() {
+                            next.remote = enabled.then_some(self.use_remote_backend);
+                        }
+                        match &mut next.remote {
+                            Some(remote) => {
+                                ui.horizontal(|ui| {
+                                    ui.radio_value(remote, false, "Локальная модель");
+                                    ui.radio_value(remote, true, "API");
+                                });
+                            }
+                            None => {
+                                ui.label(if self.use_remote_backend { "API" } else { "Локальная модель" });
+                            }
+                        }
+                        ui.end_row();
+                    });
+                    ui.add_space(4.0);
+                    if ui.add_enabled(next.is_set(), egui::Button::new("↺ Сбросить")).clicked() {
+                        *next = NextMessageSettings::default();
+                    }
+                });
+        }
+        
+        // Окно настроек генерации
+        if self.show_settings {
+            let mut gpu_toggled = false;
+            let mut clear_history = false;
+            egui::Window::new("⚙️ Настройки генерации")
+                .open(&mut self.show_settings)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let config = &mut self.generation_config;
+                    
+                    ui.add(egui::Slider::new(&mut config.temperature, 0.0..=2.0).text("Температура"));
+                    ui.add(egui::Slider::new(&mut config.top_k, 0..=200).text("Top-k (0 = выкл.)"));
+                    ui.add(egui::Slider::new(&mut config.top_p, 0.05..=1.0).text("Top-p"));
+                    ui.add(egui::Slider::new(&mut config.max_tokens, 1..=500).text("Макс. токенов"));
+                    ui.add(egui::Slider::new(&mut config.min_tokens, 0..=config.max_tokens).text("Мин. токенов"));
+                    
+                    ui.label("Стоп-последовательности (по одной на строку, \\n — перевод строки):");
+                    let stops = ui.add(egui::TextEdit::multiline(&mut self.stop_sequences_input).desired_rows(2));
+                    if stops.changed() {
+                        config.stop_sequences = self
+                            .stop_sequences_input
+                            .lines()
+                            .filter(|line| !line.is_empty())
+                            .map(|line| line.replace("\\n", "\n"))
+                            .collect();
+                    }
+                    
+                    ui.add_space(5.0);
+                    if ui.button("↺ По умолчанию").clicked() {
+                        *config = GenerationConfig::default();
+                        self.stop_sequences_input.clear();
+                    }
+                    
+                    ui.separator();
+                    ui.label("♿ Оформление:");
+                    let settings = &mut self.ui_settings;
+                    ui.add(egui::Slider::new(&mut settings.ui_scale, UiSettings::SCALE_RANGE).text("Масштаб интерфейса"));
+                    ui.add(egui::Slider::new(&mut settings.chat_font_size, UiSettings::FONT_SIZE_RANGE).text("Шрифт чата"));
+                    ui.checkbox(&mut settings.reduced_motion, "Без анимаций")
+                        .on_hover_text("Отключает анимации интерфейса и мигание курсора");
+                    ui.checkbox(&mut settings.auto_speak, "🔊 Озвучивать ответы")
+                        .on_hover_text("Синтезатор речи ОС: espeak-ng, say или System.Speech");
+                    if ui.button("↺ Оформление по умолчанию").clicked() {
+                        *settings = UiSettings {
+                            auto_speak: settings.auto_speak,
+                            shortcuts: settings.shortcuts,
+                            notify_training: settings.notify_training,
+                            training_sound: settings.training_sound,
+                            ..UiSettings::default()
+                        };
+                    }
+                    
+                    ui.separator();
+                    ui.label("⌨ Горячие клавиши:");
+                    egui::Grid::new("shortcut_settings").num_columns(2).show(ui, |ui| {
+                        for action in ShortcutAction::ALL {
+                            ui.label(action.label());
+                            let text = if self.recording_shortcut == Some(action) {
+                                "Нажмите сочетание… (Esc — отмена)".to_string()
+                            } else {
+                                self.ui_settings.shortcuts.get(action).format()
+                            };
+                            if ui.button(text).on_hover_text("Нажмите, затем нажмите новое сочетание").clicked() {
+                                self.recording_shortcut = Some(action);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    for (a, b) in self.ui_settings.shortcuts.conflicts() {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(200, 120, 0),
+                            format!("⚠ Одно сочетание у «{}» и «{}»", a.label(), b.label()),
+                        );
+                    }
+                    if ui.button("↺ Сочетания по умолчанию").clicked() {
+                        self.ui_settings.shortcuts = Default::default();
+                        self.recording_shortcut = None;
+                    }
+                    
+                    ui.separator();
+                    ui.label("🎤 Голосовой ввод (whisper.cpp):");
+                    egui::Grid::new("voice_settings").num_columns(2).show(ui, |ui| {
+                        ui.label("Модель:");
+                        if ui.text_edit_singleline(&mut self.voice_model_path).changed() {
+                            // Новая модель загрузится при следующем нажатии на микрофон
+                            self.voice = None;
+                        }
+                        ui.end_row();
+                        ui.label("Язык:");
+                        ui.text_edit_singleline(&mut self.voice_language)
+                            .on_hover_text("Код языка (ru, en) или auto");
+                        ui.end_row();
+                    });
+                    
+                    ui.separator();
+                    ui.label("💾 Файл истории чата:");
+                    ui.horizontal(|ui| {
+                        let path = ui.text_edit_singleline(&mut self.history_path);
+                        if path.lost_focus() {
+                            let history_path = match self.history_path.trim() {
+                                "" => DEFAULT_HISTORY_PATH.to_string(),
+                                path => path.to_string(),
+                            };
+                            if history_path != self.ui_settings.history_path {
+                                // Путь запоминается в настройках, текущая переписка сразу записывается в новое место
+                                self.ui_settings.history_path = history_path.clone();
+                                self.saved_messages = usize::MAX;
+                            }
+                            self.history_path = history_path;
+                        }
+                        if ui.button("🗑 Очистить историю").clicked() {
+                            clear_history = true;
+                        }
+                    });
+                    
+                    ui.separator();
+                    gpu_toggled = ui
+                        .add_enabled(!self.training_status.is_training, egui::Checkbox::new(&mut self.use_gpu, "⚡ GPU-вычисления"))
+                        .on_disabled_hover_text("Недоступно во время обучения")
+                        .changed();
+                    
+                    ui.separator();
+                    ui.label("🌐 Источник ответов:");
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut self.use_remote_backend, false, "Локальная модель");
+                        ui.radio_value(&mut self.use_remote_backend, true, "OpenAI-совместимый API");
+                    });
+                    if self.use_remote_backend {
+                        let backend = &mut self.remote_backend;
+                        egui::Grid::new("remote_backend").num_columns(2).show(ui, |ui| {
+                            ui.label("URL:");
+                            ui.text_edit_singleline(&mut backend.base_url);
+                            ui.end_row();
+                            ui.label("Модель:");
+                            ui.text_edit_singleline(&mut backend.model);
+                            ui.end_row();
+                            ui.label("API-ключ:");
+                            ui.add(egui::TextEdit::singleline(&mut backend.api_key).password(true));
+                            ui.end_row();
+                        });
+                    }
+                });
+            if gpu_toggled {
+                self.apply_gpu();
+            }
+            if clear_history {
+                self.confirm_action = Some(DestructiveAction::ClearChat);
+            }
+        }
+        
+        // Подтверждение разрушительного действия
+        if let Some(action) = self.confirm_action {
+            let mut confirmed = None;
+            egui::Window::new("⚠ Подтверждение")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(action.question());
+                    ui.label(egui::RichText::new("Действие можно отменить кнопкой «↶ Отменить» вверху").size(11.0).weak());
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("✓ Да").clicked() {
+                            confirmed = Some(true);
+                        }
+                        if ui.button("Отмена").clicked() {
+                            confirmed = Some(false);
+                        }
+                    });
+                });
+            if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+                confirmed = Some(false);
+            }
+            if let Some(confirmed) = confirmed {
+                self.confirm_action = None;
+                if confirmed {
+                    self.perform_destructive(action);
+                }
+            }
+        }
+        
         ctx.request_repaint();
     }
 }
 
 impl ChatUI {
     fn render_chat_mode(&mut self, ui: &mut egui::Ui) {
-        // Область сообщений с auto-scroll
-        egui::ScrollArea::vertical()
+        self.render_session_tabs(ui);
+        
+        // Область сообщений с auto-scroll; раскладываются только видимые сообщения
+        let mut scroll_area = egui::ScrollArea::vertical()
             .id_source("chat_scroll")
             .auto_shrink([false, false])
-            .stick_to_bottom(self.auto_scroll)
-            .show(ui, |ui| {
+            .stick_to_bottom(self.auto_scroll);
+        if let Some(offset) = self.chat_scroll_to.take() {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        scroll_area
+            .show_viewport(ui, |ui, viewport| {
                 ui.set_min_width(ui.available_width());
+                let origin = ui.max_rect().top();
                 ui.add_space(10.0);
                 
-                for msg in &self.messages {
+                let idle = self.generation.is_none();
+                let mut action = None;
+                let generating = (!idle).then(|| self.messages.len().saturating_sub(1));
+                // Курсор мигает дважды в секунду
+                let cursor_visible = self.ui_settings.reduced_motion || ui.input(|i| i.time).fract() < 0.5;
+                let font_size = self.ui_settings.chat_font_size;
+                let timestamp_size = (font_size * 0.7).max(10.0);
+                let speaking = self.speaking_message;
+                // Настроение показывает только последний ответ, у прошлых — обычный аватар
+                let last_reply = self.messages.iter().rposition(|msg| !msg.is_user);
+                let avatar = self.avatar(font_size + 2.0);
+                let mood_hover = self.mood_hover_text();
+                let today = chrono::Local::now().date_naive();
+                
+                // Ранние сообщения подгружаются страницами при прокрутке к началу
+                self.message_list.prepare(self.messages.len(), ui.available_width(), font_size);
+                let first_loaded = self.message_list.first_loaded();
+                if first_loaded > 0 {
+                    let scrolling_up = viewport.min.y < 100.0 && ui.input(|i| i.scroll_delta.y > 0.0);
+                    let clicked = ui
+                        .vertical_centered(|ui| ui.button(format!("⬆ Ранние сообщения: {}", first_loaded)).clicked())
+                        .inner;
+                    if clicked || scrolling_up {
+                        self.chat_scroll_to = Some(viewport.min.y + self.message_list.load_previous_page());
+                    }
+                    ui.add_space(8.0);
+                }
+                
+                // Полоса с запасом в полэкрана, чтобы при прокрутке не мелькали пустоты
+                let base = ui.cursor().top() - origin;
+                let margin = viewport.height() * 0.5;
+                let rows = self.message_list.visible_range(viewport.min.y - base - margin, viewport.max.y - base + margin);
+                ui.add_space(self.message_list.offset(first_loaded..rows.start));
+                let mut last_date = rows
+                    .start
+                    .checked_sub(1)
+                    .and_then(|previous| local_time::message_date(&self.messages[previous].timestamp));
+                
+                for index in rows.clone() {
+                    let msg = &self.messages[index];
+                    let row_top = ui.cursor().top();
+                    // Разделитель перед первым сообщением нового дня
+                    let date = local_time::message_date(&msg.timestamp);
+                    if let Some(day) = date.filter(|_| date != last_date && self.messages.len() > 1) {
+                        ui.vertical_centered(|ui| {
+                            ui.label(
+                                egui::RichText::new(local_time::date_label(day, today))
+                                    .size(timestamp_size)
+                                    .color(egui::Color32::GRAY)
+                            );
+                        });
+                        ui.add_space(8.0);
+                        last_date = date;
+                    }
+                    
+
                     let available_width = ui.available_width();
                     let max_width = available_width * 0.75;  // 75% ширины экрана
                     
@@ -416,7 +2666,7 @@ impl ChatUI {
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
                             ui.add_space(10.0);
                             
-                            egui::Frame::none()
+                            let bubble = egui::Frame::none()
                                 .fill(egui::Color32::from_rgb(220, 235, 255))  // Голубой фон
                                 .rounding(egui::Rounding::same(12.0))
                                 .inner_margin(egui::Margin::same(12.0))
@@ -425,21 +2675,39 @@ impl ChatUI {
                                     ui.set_max_width(max_width);
                                     
                                     ui.label(
-                                        egui::RichText::new(&msg.timestamp)
-                                            .size(10.0)
+                                        egui::RichText::new(local_time::message_time(&msg.timestamp))
+                                            .size(timestamp_size)
                                             .color(egui::Color32::DARK_GRAY)
-                                    );
+                                    ).on_hover_text(&msg.timestamp);
                                     
                                     ui.add_space(4.0);
-                                    ui.label(egui::RichText::new(&msg.text).size(14.0));
+                                    match &mut self.editing_message {
+                                        Some((editing, text)) if *editing == index => {
+                                            ui.add(egui::TextEdit::multiline(text).desired_width(max_width).desired_rows(2));
+                                            ui.horizontal(|ui| {
+                                                if ui.add_enabled(idle, egui::Button::new("✓ Отправить")).clicked() {
+                                                    action = Some(MessageAction::Resend(index));
+                                                }
+                                                if ui.button("Отмена").clicked() {
+                                                    action = Some(MessageAction::CancelEdit);
+                                                }
+                                            });
+                                        }
+                                        _ => {
+                                            ui.label(egui::RichText::new(&msg.text).size(font_size));
+                                        }
+                                    }
                                 });
+                            bubble.response.interact(egui::Sense::click()).context_menu(|ui| {
+                                Self::message_context_menu(ui, index, true, idle, &mut action);
+                            });
                         });
                     } else {
                         // Сообщение AI слева с белым фоном
                         ui.with_layout(egui::Layout::left_to_right(egui::Align::Min), |ui| {
                             ui.add_space(10.0);
                             
-                            egui::Frame::none()
+                            let bubble = egui::Frame::none()
                                 .fill(egui::Color32::WHITE)
                                 .rounding(egui::Rounding::same(12.0))
                                 .inner_margin(egui::Margin::same(12.0))
@@ -448,27 +2716,248 @@ impl ChatUI {
                                     ui.set_max_width(max_width);
                                     
                                     ui.horizontal(|ui| {
-                                        ui.label(egui::RichText::new("🤖").size(16.0));
+                                        if last_reply == Some(index) {
+                                            ui.label(avatar.clone()).on_hover_text(&mood_hover);
+                                        } else {
+                                            ui.label(egui::RichText::new("🤖").size(font_size + 2.0));
+                                        }
                                         ui.label(
-                                            egui::RichText::new(&msg.timestamp)
-                                                .size(10.0)
+                                            egui::RichText::new(local_time::message_time(&msg.timestamp))
+                                                .size(timestamp_size)
                                                 .color(egui::Color32::DARK_GRAY)
-                                        );
+                                        ).on_hover_text(&msg.timestamp);
+                                        if generating != Some(index) {
+                                            if speaking == Some(index) {
+                                                if ui.small_button("⏹").on_hover_text("Остановить озвучивание").clicked() {
+                                                    action = Some(MessageAction::StopSpeaking);
+                                                }
+                                            } else if ui.small_button("🔊").on_hover_text("Озвучить").clicked() {
+                                                action = Some(MessageAction::Speak(index));
+                                            }
+                                        }
                                     });
                                     
                                     ui.add_space(4.0);
-                                    ui.label(egui::RichText::new(&msg.text).size(14.0));
+                                    if generating == Some(index) {
+                                        let cursor = if cursor_visible { "▌" } else { " " };
+                                        markdown::show(ui, &format!("{}{}", msg.text, cursor), font_size);
+                                    } else {
+                                        markdown::show(ui, &msg.text, font_size);
+                                    }
+                                    if let Some(metrics) = &msg.metrics {
+                                        ui.add_space(4.0);
+                                        ui.label(
+                                            egui::RichText::new(format!("⏱ {}", metrics.summary()))
+                                                .size(timestamp_size)
+                                                .color(egui::Color32::GRAY)
+                                        );
+                                    }
                                 });
+                            bubble.response.interact(egui::Sense::click()).context_menu(|ui| {
+                                Self::message_context_menu(ui, index, false, idle && generating != Some(index), &mut action);
+                            });
                         });
                     }
                     
                     ui.add_space(12.0);
+                    self.message_list.set_height(index, ui.cursor().top() - row_top);
                 }
+                ui.add_space(self.message_list.offset(rows.end..self.messages.len()));
                 
                 ui.add_space(20.0);  // Отступ снизу
+                
+                if let Some(action) = action {
+                    self.apply_message_action(ui, action);
+                }
             });
     }
     
+    /// Контекстное меню сообщения; изменение и удаление недоступны во время генерации
+    fn message_context_menu(ui: &mut egui::Ui, index: usize, is_user: bool, idle: bool, action: &mut Option<MessageAction>) {
+        if ui.button("📋 Копировать").clicked() {
+            *action = Some(MessageAction::Copy(index));
+            ui.close_menu();
+        }
+        if is_user && ui.add_enabled(idle, egui::Button::new("✏ Изменить и отправить заново")).clicked() {
+            *action = Some(MessageAction::Edit(index));
+            ui.close_menu();
+        }
+        if ui.add_enabled(idle, egui::Button::new("🗑 Удалить")).clicked() {
+            *action = Some(MessageAction::Delete(index));
+            ui.close_menu();
+        }
+    }
+    
+    fn apply_message_action(&mut self, ui: &mut egui::Ui, action: MessageAction) {
+        match action {
+            MessageAction::Copy(index) => {
+                if let Some(msg) = self.messages.get(index) {
+                    let text = msg.text.clone();
+                    ui.output_mut(|o| o.copied_text = text);
+                }
+            }
+            MessageAction::Edit(index) => {
+                self.editing_message = self.messages.get(index).map(|msg| (index, msg.text.clone()));
+            }
+            MessageAction::Delete(index) => {
+                if index < self.messages.len() {
+                    self.messages.remove(index);
+                    self.editing_message = None;
+                    self.stop_speaking();
+                    self.saved_messages = usize::MAX;
+                }
+            }
+            MessageAction::Resend(index) => {
+                let Some((_, text)) = self.editing_message.take() else {
+                    return;
+                };
+                if text.trim().is_empty() || self.generation.is_some() {
+                    self.editing_message = Some((index, text));
+                    return;
+                }
+                self.messages.truncate(index);
+                self.stop_speaking();
+                self.input_text = text;
+                self.send_message();
+                self.saved_messages = usize::MAX;
+            }
+            MessageAction::CancelEdit => self.editing_message = None,
+            MessageAction::Speak(index) => self.speak_message(index),
+            MessageAction::StopSpeaking => self.stop_speaking(),
+        }
+    }
+    
+    /// Замер FPS каждый кадр и остальных метрик раз в `MONITOR_INTERVAL`
+    fn update_system_monitor(&mut self, ctx: &egui::Context) {
+        let dt = ctx.input(|i| i.unstable_dt);
+        if dt > 0.0 {
+            // Сглаживание, чтобы число не прыгало каждый кадр
+            let fps = 1.0 / dt;
+            let previous = self.system_monitor.get_fps();
+            self.system_monitor.update_fps(if previous > 0.0 { previous * 0.9 + fps * 0.1 } else { fps });
+        }
+        if self.last_monitor_update.is_some_and(|last| last.elapsed() < MONITOR_INTERVAL) {
+            return;
+        }
+        self.last_monitor_update = Some(Instant::now());
+        
+        self.system_monitor.update_ram();
+        self.system_monitor.update_cpu();
+        // Объём видеопамяти адаптер не сообщает: оцениваем по весам модели на GPU
+        if let Ok(model) = self.model.try_lock() {
+            let used_mb = if model.gpu.is_some() { (model.parameter_count() * 8 / (1024 * 1024)) as u64 } else { 0 };
+            self.system_monitor.set_vram(used_mb, 0);
+        }
+        self.monitor_history.record(&self.system_monitor);
+    }
+    
+    fn render_system_monitor(&self, ui: &mut egui::Ui) {
+        ui.add_space(8.0);
+        ui.label(egui::RichText::new("📊 Ресурсы").size(16.0).strong());
+        ui.add_space(6.0);
+        
+        let monitor = &self.system_monitor;
+        let history = &self.monitor_history;
+        let ram_used = monitor.ram_used.load(Ordering::Relaxed);
+        let ram_total = monitor.ram_total.load(Ordering::Relaxed);
+        let vram_used = monitor.vram_used.load(Ordering::Relaxed);
+        let gpu = self.use_gpu;
+        let rows = [
+            (
+                format!("RAM: {} / {}", SystemMonitor::format_bytes(ram_used << 20), SystemMonitor::format_bytes(ram_total << 20)),
+                &history.ram_percent,
+                100.0,
+                egui::Color32::from_rgb(60, 120, 230),
+            ),
+            (
+                format!("CPU: {:.0}%", monitor.get_cpu_percent()),
+                &history.cpu_percent,
+                100.0,
+                egui::Color32::from_rgb(230, 120, 40),
+            ),
+            (
+                if gpu { format!("VRAM (модель): {}", SystemMonitor::format_bytes(vram_used << 20)) } else { "VRAM: — (CPU)".to_string() },
+                &history.vram_mb,
+                history.vram_mb.iter().copied().fold(1.0, f32::max),
+                egui::Color32::from_rgb(140, 80, 200),
+            ),
+            (
+                format!("FPS: {:.0}", monitor.get_fps()),
+                &history.fps,
+                history.fps.iter().copied().fold(60.0, f32::max),
+                egui::Color32::from_rgb(60, 170, 90),
+            ),
+        ];
+        for (label, values, max, color) in rows {
+            ui.label(egui::RichText::new(label).size(12.0));
+            sparkline(ui, values, max, color);
+            ui.add_space(6.0);
+        }
+    }
+    
+    /// Вкладки чатов; ПКМ по вкладке — название, снимок модели, закрытие
+    fn render_session_tabs(&mut self, ui: &mut egui::Ui) {
+        let idle = self.generation.is_none();
+        let mut switch_to = None;
+        let mut close = None;
+        let (mut new_chat, mut snapshot, mut forget, mut renamed) = (false, false, false, false);
+        
+        ui.horizontal_wrapped(|ui| {
+            for (index, session) in self.sessions.iter_mut().enumerate() {
+                let active = index == self.active_session;
+                let mut label = session.display_title();
+                if session.model_snapshot.is_some() {
+                    label.push_str(" 📸");
+                }
+                let response = ui
+                    .add_enabled(idle || active, egui::SelectableLabel::new(active, label))
+                    .on_hover_text("ПКМ — название, снимок модели, закрытие");
+                if response.clicked() {
+                    switch_to = Some(index);
+                }
+                response.context_menu(|ui| {
+                    ui.label("Название:");
+                    renamed |= ui.text_edit_singleline(&mut session.title).changed();
+                    if active {
+                        if ui.button("📸 Сохранить снимок модели").clicked() {
+                            snapshot = true;
+                            ui.close_menu();
+                        }
+                        if session.model_snapshot.is_some() && ui.button("✖ Отвязать снимок").clicked() {
+                            forget = true;
+                            ui.close_menu();
+                        }
+                    }
+                    if ui.add_enabled(idle, egui::Button::new("🗑 Закрыть чат")).clicked() {
+                        close = Some(index);
+                        ui.close_menu();
+                    }
+                });
+            }
+            new_chat = ui.add_enabled(idle, egui::Button::new("➕")).on_hover_text("Новый чат").clicked();
+        });
+        ui.separator();
+        
+        if renamed {
+            self.saved_messages = usize::MAX;
+        }
+        if snapshot {
+            self.snapshot_session_model();
+        }
+        if forget {
+            self.forget_session_model();
+        }
+        if let Some(index) = switch_to {
+            self.switch_session(index);
+        }
+        if let Some(index) = close {
+            self.close_session(index);
+        }
+        if new_chat {
+            self.new_session();
+        }
+    }
+    
     fn render_training_mode(&mut self, ui: &mut egui::Ui) {
         egui::ScrollArea::vertical()
             .auto_shrink([false, false])
@@ -493,15 +2982,156 @@ impl ChatUI {
                             
                             let text_edit = egui::TextEdit::singleline(&mut self.file_path_input)
                                 .hint_text("examples/training_data_ru.txt")
-                                .desired_width(ui.available_width() - 120.0);
+                                .desired_width(ui.available_width() - 300.0);
                             ui.add(text_edit);
                             
                             let load_button = egui::Button::new("📂 Загрузить")
                                 .fill(egui::Color32::from_rgb(100, 150, 255));
                             
-                            if ui.add(load_button).clicked() {
+                            if ui.add_enabled(self.file_loading.is_none(), load_button).clicked() {
                                 self.load_file();
                             }
+                            if ui.button("🗂 Обзор…").on_hover_text("Файлы также можно перетащить на окно").clicked() {
+                                if let Some(path) = self.pick_path(false) {
+                                    self.enqueue_path(path);
+                                }
+                            }
+                            if ui.button("📁 Папка…").on_hover_text("Загрузить все подходящие файлы папки").clicked() {
+                                if let Some(path) = self.pick_path(true) {
+                                    self.enqueue_path(path);
+                                }
+                            }
+                        });
+                        
+                        if let Some(loading) = &self.file_loading {
+                            ui.add(egui::ProgressBar::new(loading.progress).text(format!(
+                                "⏳ Загрузка {:?}...",
+                                loading.path.file_name().unwrap_or_default()
+                            )));
+                        }
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("CSV колонки:");
+                            ui.add_enabled(
+                                !self.csv_dialogue,
+                                egui::TextEdit::singleline(&mut self.csv_columns)
+                                    .hint_text("все")
+                                    .desired_width(150.0),
+                            )
+                            .on_hover_text("Имена из заголовка или номера с 1 через запятую");
+                            ui.checkbox(&mut self.csv_dialogue, "Диалог:");
+                            ui.add_enabled(
+                                self.csv_dialogue,
+                                egui::TextEdit::singleline(&mut self.csv_question_column).desired_width(90.0),
+                            )
+                            .on_hover_text("Колонка вопроса");
+                            ui.label("→");
+                            ui.add_enabled(
+                                self.csv_dialogue,
+                                egui::TextEdit::singleline(&mut self.csv_answer_column).desired_width(90.0),
+                            )
+                            .on_hover_text("Колонка ответа");
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("👁 Папка:");
+                            ui.add_enabled(
+                                self.folder_watcher.is_none(),
+                                egui::TextEdit::singleline(&mut self.watch_dir)
+                                    .hint_text("inbox/")
+                                    .desired_width(200.0),
+                            );
+                            let mut watching = self.folder_watcher.is_some();
+                            if ui
+                                .checkbox(&mut watching, "Следить")
+                                .on_hover_text("Файлы, добавленные в папку, загружаются автоматически")
+                                .changed()
+                            {
+                                self.toggle_folder_watcher();
+                            }
+                            if !self.file_queue.is_empty() {
+                                ui.label(format!("в очереди: {}", self.file_queue.len()));
+                            }
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("🌐 Язык примеров:");
+                            let language = &mut self.file_processor.language;
+                            egui::ComboBox::from_id_source("language_filter")
+                                .selected_text(language.map_or("Все", |language| language.name()))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(language, None, "Все");
+                                    for option in [Language::Russian, Language::English] {
+                                        ui.selectable_value(language, Some(option), option.name());
+                                    }
+                                });
+                            
+                            let mut dialogue = self.file_processor.extraction_mode == ExtractionMode::Dialogue;
+                            if ui
+                                .checkbox(&mut dialogue, "💬 Пары диалога")
+                                .on_hover_text("Q:/A:, Вопрос:/Ответ:, User:/Assistant: → пары «вопрос — ответ»")
+                                .changed()
+                            {
+                                self.file_processor.extraction_mode =
+                                    if dialogue { ExtractionMode::Dialogue } else { ExtractionMode::Text };
+                            }
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            let chunking = &mut self.file_processor.chunking;
+                            ui.label("✂ Длина примеров: от");
+                            ui.add(egui::DragValue::new(&mut chunking.min_length).clamp_range(1..=1000));
+                            ui.label("до");
+                            ui.add(egui::DragValue::new(&mut chunking.max_length).clamp_range(0..=100_000))
+                                .on_hover_text("Длинные абзацы режутся на окна; 0 — без ограничения");
+                            ui.label("перекрытие");
+                            let max_overlap = chunking.max_length.saturating_sub(1);
+                            chunking.overlap = chunking.overlap.min(max_overlap);
+                            ui.add_enabled(
+                                chunking.max_length > 0,
+                                egui::DragValue::new(&mut chunking.overlap).clamp_range(0..=max_overlap),
+                            );
+                            ui.label("символов");
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            let mut use_cache = self.file_processor.cache_dir.is_some();
+                            if ui
+                                .checkbox(&mut use_cache, "💾 Кэш текста PDF")
+                                .on_hover_text(format!("Извлечённый текст сохраняется в {}", PARSED_CACHE_DIR))
+                                .changed()
+                            {
+                                self.file_processor.cache_dir = use_cache.then(|| PathBuf::from(PARSED_CACHE_DIR));
+                            }
+                            if ui.add_enabled(use_cache, egui::Button::new("🗑 Очистить кэш")).clicked() {
+                                let text = match self.file_processor.clear_cache() {
+                                    Ok(removed) => format!("🗑 Кэш очищен, удалено файлов: {}", removed),
+                                    Err(e) => e,
+                                };
+                                self.messages.push(ChatMessage {
+                                    text,
+                                    is_user: false,
+                                    timestamp: Self::get_timestamp(),
+                                    metrics: None,
+                                });
+                            }
+                        });
+                        
+                        ui.collapsing("🧹 Очистка текста", |ui| {
+                            let cleaner = &mut self.file_processor.cleaner;
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut cleaner.strip_markup, "Удалять разметку");
+                                ui.checkbox(&mut cleaner.remove_boilerplate, "Удалять колонтитулы")
+                                    .on_hover_text("Повторяющиеся короткие строки и номера страниц");
+                                ui.checkbox(&mut cleaner.collapse_whitespace, "Схлопывать пробелы");
+                            });
+                            ui.label("Отбрасывать строки по регулярным выражениям (по одному на строку):");
+                            ui.add(
+                                egui::TextEdit::multiline(&mut self.cleaner_patterns)
+                                    .hint_text("^Copyright\n^Глава \\d+$")
+                                    .desired_rows(2)
+                                    .desired_width(f32::INFINITY),
+                            );
                         });
                         
                         ui.add_space(8.0);
@@ -514,7 +3144,15 @@ impl ChatUI {
                         
                         if !self.loaded_files.is_empty() {
                             ui.add_space(10.0);
-                            ui.label(format!("✓ Загружено: {} файлов", self.loaded_files.len()));
+                            ui.horizontal(|ui| {
+                                ui.label(format!("✓ Загружено: {} файлов", self.loaded_files.len()));
+                                if ui
+                                    .add_enabled(!self.training_status.is_training, egui::Button::new("🗑 Убрать все"))
+                                    .clicked()
+                                {
+                                    self.confirm_action = Some(DestructiveAction::RemoveFiles);
+                                }
+                            });
                         }
                     });
                 
@@ -537,25 +3175,230 @@ impl ChatUI {
                             ui.add(egui::Slider::new(&mut self.epochs, 1..=100).text("эпох"));
                         });
                         
+                        ui.label(egui::RichText::new("⚙ Гиперпараметры").strong());
+                        let architecture_valid = HiddenLayerSpec::parse_list(&self.architecture).is_ok();
+                        egui::Grid::new("hyperparameters").num_columns(2).spacing([10.0, 4.0]).show(ui, |ui| {
+                            ui.label("Скорость обучения:");
+                            ui.add(egui::DragValue::new(&mut self.learning_rate)
+                                .speed(0.0001)
+                                .clamp_range(0.000001..=1.0)
+                                .max_decimals(6));
+                            ui.end_row();
+                            
+                            ui.label("Размер батча:");
+                            ui.add(egui::DragValue::new(&mut self.batch_size).clamp_range(1..=256))
+                                .on_hover_text("Шагов, градиенты которых усредняются перед обновлением весов");
+                            ui.end_row();
+                            
+                            ui.label("Оптимизатор:");
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_source("optimizer_select")
+                                    .selected_text(self.optimizer.name())
+                                    .show_ui(ui, |ui| {
+                                        for optimizer in [Optimizer::Sgd, Optimizer::momentum(), Optimizer::adam()] {
+                                            ui.selectable_value(&mut self.optimizer, optimizer, optimizer.name());
+                                        }
+                                    });
+                                
+                                ui.label("Точность:");
+                                egui::ComboBox::from_id_source("precision_select")
+                                    .selected_text(self.precision.name())
+                                    .show_ui(ui, |ui| {
+                                        for precision in [Precision::F64, Precision::F32] {
+                                            ui.selectable_value(&mut self.precision, precision, precision.name());
+                                        }
+                                    });
+                            });
+                            ui.end_row();
+                            
+                            ui.label("Эмбеддинг:");
+                            ui.add(egui::DragValue::new(&mut self.embedding_dim).clamp_range(1..=1024));
+                            ui.end_row();
+                            
+                            ui.label("🏗 Скрытые слои:");
+                            let mut edit = egui::TextEdit::singleline(&mut self.architecture).desired_width(180.0);
+                            if !architecture_valid {
+                                edit = edit.text_color(egui::Color32::from_rgb(200, 60, 60));
+                            }
+                            ui.add(edit).on_hover_text("Размеры и активации через запятую: 256 tanh, 128 relu");
+                            ui.end_row();
+                            
+                            ui.label("Контекст:");
+                            ui.add(egui::DragValue::new(&mut self.context_length).clamp_range(1..=64));
+                            ui.end_row();
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.recurrent, "🔁 GRU слой (длинный контекст)")
+                                .on_hover_text("Переключение пересоздаёт первый скрытый слой");
+                            
+                            let enabled = !self.training_status.is_training && architecture_valid;
+                            if ui.add_enabled(enabled, egui::Button::new("Пересоздать модель"))
+                                .on_hover_text("Эмбеддинг, скрытые слои и контекст применяются только к новой модели")
+                                .clicked()
+                            {
+                                self.confirm_action = Some(DestructiveAction::ResetModel);
+                            }
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.ui_settings.notify_training, "🔔 Уведомить по окончании")
+                                .on_hover_text("Системное уведомление, когда обучение завершится или упадёт");
+                            ui.checkbox(&mut self.ui_settings.training_sound, "🔊 Звук");
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.auto_save, "💾 Автосохранение в")
+                                .on_hover_text("После успешного обучения модель и словарь пишутся в файлы с меткой времени");
+                            ui.add_enabled(
+                                self.auto_save,
+                                egui::TextEdit::singleline(&mut self.auto_save_dir).desired_width(120.0),
+                            );
+                            ui.label("хранить:");
+                            ui.add_enabled(self.auto_save, egui::DragValue::new(&mut self.auto_save_keep).clamp_range(0..=100))
+                                .on_hover_text("Сколько последних сохранений оставлять (0 — все)");
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.use_checkpoints, "💾 Чекпоинты в");
+                            ui.add_enabled(
+                                self.use_checkpoints,
+                                egui::TextEdit::singleline(&mut self.checkpoint_dir).desired_width(200.0),
+                            );
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("Dropout:");
+                            ui.add(egui::Slider::new(&mut self.dropout, 0.0..=0.5));
+                            ui.checkbox(&mut self.layer_norm, "Layer norm");
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("Scheduled sampling:");
+                            ui.add(egui::Slider::new(&mut self.scheduled_sampling, 0.0..=0.5))
+                                .on_hover_text("Доля шагов, на которых модели подаётся её собственное предсказание (растёт к концу обучения)");
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("Размер словаря:");
+                            ui.add(egui::Slider::new(&mut self.bpe_vocab_size, 100..=20000).text("токенов"));
+                            
+                            let enabled = !self.training_status.is_training && !self.training_data.is_empty();
+                            if ui.add_enabled(enabled, egui::Button::new("🔤 Обучить токенизатор")).clicked() {
+                                self.train_tokenizer();
+                            }
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("Мин. частота слова:");
+                            ui.add(egui::DragValue::new(&mut self.vocab_min_freq).clamp_range(1..=100));
+                            
+                            let enabled = !self.training_status.is_training && !self.training_data.is_empty();
+                            if ui.add_enabled(enabled, egui::Button::new("📖 Словарь из корпуса")).clicked() {
+                                self.build_vocab();
+                            }
+                        });
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("Валидация:");
+                            ui.add(egui::Slider::new(&mut self.validation_split, 0.0..=0.5))
+                                .on_hover_text("Доля примеров, не участвующих в обучении; по ним считается loss на валидации");
+                        });
+                        
                         ui.add_space(5.0);
-                        ui.label(format!("📊 Примеров: {}", self.training_data.len()));
+                        if self.validation_split > 0.0 {
+                            let (train, validation) =
+                                FileProcessor::split_sizes(self.training_data.len(), 1.0 - self.validation_split);
+                            ui.label(format!(
+                                "📊 Примеров: {} (обучение: {}, валидация: {})",
+                                self.training_data.len(),
+                                train,
+                                validation
+                            ));
+                        } else {
+                            ui.label(format!("📊 Примеров: {}", self.training_data.len()));
+                        }
+                        
+                        let report_enabled = !self.training_status.is_training && !self.training_data.is_empty();
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(report_enabled, egui::Button::new("📈 Отчёт по корпусу")).clicked() {
+                                self.build_corpus_report();
+                            }
+                            if ui
+                                .add_enabled(!self.training_data.is_empty(), egui::Button::new("📝 Просмотр и правка"))
+                                .on_hover_text("Таблица примеров: правка, удаление мусора, объединение, число токенов")
+                                .clicked()
+                            {
+                                self.show_data_preview = true;
+                            }
+                        });
+                        if let Some(report) = &self.corpus_report {
+                            ui.label(egui::RichText::new(report.format()).monospace().size(11.0));
+                        }
                         
                         ui.add_space(10.0);
                         
                         if self.training_status.is_training {
                             ui.label("🔄 Обучение в процессе...");
                             ui.add(egui::ProgressBar::new(self.training_status.progress)
-                                .text(format!("Эпоха {}/{}", 
+                                .text(format!("Эпоха {}/{}, Loss: {:.4}", 
                                     self.training_status.current_epoch,
-                                    self.training_status.total_epochs)));
+                                    self.training_status.total_epochs,
+                                    self.training_status.loss)));
+                            
+                            if let Some(handle) = &self.training_handle {
+                                ui.horizontal(|ui| {
+                                    if handle.is_paused() {
+                                        if ui.button("▶ Продолжить").clicked() {
+                                            handle.resume();
+                                        }
+                                    } else if ui.button("⏸ Пауза").clicked() {
+                                        handle.pause();
+                                    }
+                                    if ui.button("⏹ Остановить").clicked() {
+                                        self.confirm_stop_training = true;
+                                    }
+                                });
+                            }
                         } else {
                             let train_button = egui::Button::new(
                                 egui::RichText::new("🚀 Начать обучение").size(14.0))
                                 .fill(egui::Color32::from_rgb(100, 180, 100));
                             
-                            if ui.add(train_button).clicked() {
-                                self.start_training();
-                            }
+                            ui.horizontal(|ui| {
+                                if ui.add(train_button).clicked() {
+                                    self.start_training(false);
+                                }
+                                
+                                if self.use_checkpoints && ui.button("⏯ Продолжить с чекпоинта").clicked() {
+                                    self.start_training(true);
+                                }
+                            });
+                        }
+                        
+                        if !self.loss_history.is_empty() {
+                            ui.add_space(10.0);
+                            ui.label(egui::RichText::new("📉 График loss").strong());
+                            self.loss_chart.show(ui, &self.loss_history, 180.0);
+                            ui.label(egui::RichText::new("Ctrl + колесо — масштаб, перетаскивание — сдвиг, двойной щелчок — сброс")
+                                .size(11.0)
+                                .weak());
+                            ui.horizontal(|ui| {
+                                ui.label("CSV:");
+                                ui.add(egui::TextEdit::singleline(&mut self.loss_csv_path).desired_width(180.0));
+                                if ui.button("💾 Экспорт CSV").clicked() {
+                                    let text = match LossChart::export_csv(&self.loss_history, Path::new(self.loss_csv_path.trim())) {
+                                        Ok(()) => format!("✓ Loss по {} эпохам сохранён в {}", self.loss_history.len(), self.loss_csv_path.trim()),
+                                        Err(e) => e,
+                                    };
+                                    self.messages.push(ChatMessage {
+                                        text,
+                                        is_user: false,
+                                        timestamp: Self::get_timestamp(),
+                                        metrics: None,
+                                    });
+                                }
+                            });
                         }
                     });
                 
@@ -578,7 +3421,7 @@ impl ChatUI {
                             .show(ui, |ui| {
                                 for msg in self.messages.iter().rev().take(10).rev() {
                                     if !msg.is_user {
-                                        ui.label(format!("[{}] {}", msg.timestamp, msg.text));
+                                        ui.label(format!("[{}] {}", local_time::message_time(&msg.timestamp), msg.text));
                                         ui.add_space(5.0);
                                     }
                                 }
@@ -595,3 +3438,22 @@ impl Default for ChatUI {
         Self::new()
     }
 }
+
+/// Мини-график последних значений метрики (0..`max`)
+fn sparkline(ui: &mut egui::Ui, values: &VecDeque<f32>, max: f32, color: egui::Color32) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(ui.available_width(), 28.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 3.0, ui.visuals().extreme_bg_color);
+    if values.len() < 2 || max <= 0.0 {
+        return;
+    }
+    
+    let step = rect.width() / (MONITOR_HISTORY - 1) as f32;
+    let start = rect.right() - step * (values.len() - 1) as f32;
+    let points: Vec<egui::Pos2> = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| egui::pos2(start + step * i as f32, rect.bottom() - (value / max).clamp(0.0, 1.0) * (rect.height() - 2.0) - 1.0))
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
@@ -0,0 +1,208 @@
+use crate::voxel::VoxelRecord;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Default chunk edge length in cells
+pub const CHUNK_SIZE: i32 = 64;
+
+/// Chunk containing `position`
+pub fn chunk_of(position: [i32; 3], size: i32) -> [i32; 3] {
+    position.map(|c| c.div_euclid(size.max(1)))
+}
+
+/// A frozen voxel with the entity it had (`Entity::to_bits`), so memories
+/// about it can be re-tagged when it thaws
+pub type FrozenVoxel = (u64, VoxelRecord);
+
+/// When chunks of the world freeze and thaw, see `VoxelWorld::stream`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamingRules {
+    pub enabled: bool,
+    pub chunk_size: i32,
+    /// Camera focus; chunks within `focus_radius` cells of it stay hot
+    pub focus: Option<[i32; 3]>,
+    pub focus_radius: i32,
+    /// Chunks with at least this many moving voxels stay hot (0 keeps every chunk hot)
+    pub min_activity: usize,
+    /// Ticks a chunk has to stay out of focus and quiet before it freezes
+    pub cool_down: u64,
+    /// Frozen chunks kept in memory; further ones are written to `spill_dir`
+    pub max_resident_chunks: usize,
+    pub spill_dir: Option<PathBuf>,
+}
+
+impl StreamingRules {
+    /// Whether chunk `key` is within `focus_radius` of the focus
+    pub fn in_focus(&self, key: [i32; 3]) -> bool {
+        let Some(focus) = self.focus else {
+            return false;
+        };
+        let size = i64::from(self.chunk_size.max(1));
+        let distance_sq: i64 = (0..3)
+            .map(|axis| {
+                let min = i64::from(key[axis]) * size;
+                let nearest = i64::from(focus[axis]).clamp(min, min + size - 1);
+                (nearest - i64::from(focus[axis])).pow(2)
+            })
+            .sum();
+        distance_sq <= i64::from(self.focus_radius).pow(2)
+    }
+}
+
+impl Default for StreamingRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chunk_size: CHUNK_SIZE,
+            focus: None,
+            focus_radius: 2 * CHUNK_SIZE,
+            min_activity: 1,
+            cool_down: 50,
+            max_resident_chunks: 256,
+            spill_dir: None,
+        }
+    }
+}
+
+/// Frozen chunks: in memory up to `max_resident_chunks`, on disk beyond that
+///
+/// Spilled chunks are JSON files in `spill_dir`, removed when they thaw. A
+/// chunk that fails to spill stays in memory.
+#[derive(Default)]
+pub struct ChunkStore {
+    resident: HashMap<[i32; 3], Vec<FrozenVoxel>>,
+    spilled: HashMap<[i32; 3], PathBuf>,
+    counts: HashMap<[i32; 3], usize>,
+}
+
+impl ChunkStore {
+    /// Freeze `voxels` into chunk `key`, appending to it when it is already frozen
+    pub fn freeze(&mut self, key: [i32; 3], mut voxels: Vec<FrozenVoxel>, rules: &StreamingRules) {
+        if self.spilled.contains_key(&key) || self.resident.contains_key(&key) {
+            let mut frozen = self.thaw(key);
+            frozen.append(&mut voxels);
+            voxels = frozen;
+        }
+        *self.counts.entry(key).or_default() = voxels.len();
+        
+        if self.resident.len() >= rules.max_resident_chunks {
+            if let Some(dir) = &rules.spill_dir {
+                let path = dir.join(format!("chunk_{}_{}_{}.json", key[0], key[1], key[2]));
+                let written = std::fs::create_dir_all(dir)
+                    .map_err(|e| e.to_string())
+                    .and_then(|_| serde_json::to_vec(&voxels).map_err(|e| e.to_string()))
+                    .and_then(|data| std::fs::write(&path, data).map_err(|e| e.to_string()));
+                if written.is_ok() {
+                    self.spilled.insert(key, path);
+                    return;
+                }
+            }
+        }
+        self.resident.insert(key, voxels);
+    }
+    
+    /// Take every voxel of chunk `key` out of the store
+    pub fn thaw(&mut self, key: [i32; 3]) -> Vec<FrozenVoxel> {
+        self.counts.remove(&key);
+        if let Some(voxels) = self.resident.remove(&key) {
+            return voxels;
+        }
+        let Some(path) = self.spilled.remove(&key) else {
+            return Vec::new();
+        };
+        let voxels = std::fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        let _ = std::fs::remove_file(&path);
+        voxels
+    }
+    
+    /// Copy of every frozen voxel, for saving the world
+    pub fn snapshot(&self) -> Result<Vec<FrozenVoxel>, Box<dyn std::error::Error>> {
+        let mut voxels: Vec<FrozenVoxel> = self.resident.values().flatten().cloned().collect();
+        for path in self.spilled.values() {
+            let chunk: Vec<FrozenVoxel> = serde_json::from_slice(&std::fs::read(path)?)?;
+            voxels.extend(chunk);
+        }
+        Ok(voxels)
+    }
+    
+    pub fn keys(&self) -> impl Iterator<Item = [i32; 3]> + '_ {
+        self.counts.keys().copied()
+    }
+    
+    pub fn contains(&self, key: [i32; 3]) -> bool {
+        self.counts.contains_key(&key)
+    }
+    
+    /// Number of frozen chunks
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+    
+    /// Number of frozen voxels
+    pub fn voxel_count(&self) -> usize {
+        self.counts.values().sum()
+    }
+    
+    /// Chunks currently written to disk
+    pub fn spilled(&self) -> HashSet<[i32; 3]> {
+        self.spilled.keys().copied().collect()
+    }
+    
+    /// Drop every frozen chunk, deleting spilled files
+    pub fn clear(&mut self) {
+        for path in self.spilled.values() {
+            let _ = std::fs::remove_file(path);
+        }
+        self.resident.clear();
+        self.spilled.clear();
+        self.counts.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::Voxel;
+    
+    #[test]
+    fn test_chunk_store_spills_and_thaws() {
+        assert_eq!(chunk_of([-1, 0, 64], 64), [-1, 0, 1]);
+        let dir = std::env::temp_dir().join(format!("voxel_chunks_{}", std::process::id()));
+        let rules = StreamingRules {
+            focus: Some([0, 0, 0]),
+            focus_radius: 10,
+            max_resident_chunks: 1,
+            spill_dir: Some(dir.clone()),
+            ..StreamingRules::default()
+        };
+        assert!(rules.in_focus([0, 0, 0]) && rules.in_focus([-1, -1, -1]));
+        assert!(!rules.in_focus([1, 0, 0]));
+        
+        let mut store = ChunkStore::default();
+        let frozen = |entity, position| (entity, VoxelRecord::new(Voxel::new(), position));
+        store.freeze([1, 0, 0], vec![frozen(1, [64, 0, 0])], &rules);
+        store.freeze([2, 0, 0], vec![frozen(2, [128, 0, 0])], &rules);
+        store.freeze([2, 0, 0], vec![frozen(3, [129, 0, 0])], &rules);
+        assert_eq!((store.len(), store.voxel_count()), (2, 3));
+        assert_eq!(store.spilled(), HashSet::from([[2, 0, 0]]));
+        assert_eq!(store.snapshot().unwrap().len(), 3);
+        
+        // Spilled to disk and back, positions included
+        let thawed: Vec<(u64, [i32; 3])> = store.thaw([2, 0, 0]).into_iter().map(|(entity, record)| (entity, record.position.0)).collect();
+        assert_eq!(thawed, [(2, [128, 0, 0]), (3, [129, 0, 0])]);
+        assert!(store.spilled().is_empty());
+        assert!(store.thaw([5, 0, 0]).is_empty());
+        store.clear();
+        assert!(store.is_empty());
+        std::fs::remove_dir(dir).unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+/// Команда, введённая в чат через `/`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatCommand {
+    /// Обучение на загруженных данных (число эпох — необязательно)
+    Train(Option<usize>),
+    Save(PathBuf),
+    Load(PathBuf),
+    Info,
+    Clear,
+    /// Создание сущностей в воксельном мире
+    Spawn(usize),
+    Help,
+}
+
+/// Команды с аргументами и описанием для подсказок и `/help`
+pub const COMMANDS: &[(&str, &str, &str)] = &[
+    ("/train", "[эпох]", "начать обучение на загруженных данных"),
+    ("/save", "<файл>", "сохранить модель"),
+    ("/load", "<файл>", "загрузить модель"),
+    ("/info", "", "информация о модели"),
+    ("/clear", "", "очистить текущий чат"),
+    ("/spawn", "<число>", "создать воксели в режиме «Экосистема»"),
+    ("/help", "", "список команд"),
+];
+
+impl ChatCommand {
+    /// Разбор ввода; `None`, если это обычное сообщение, а не команда
+    pub fn parse(input: &str) -> Option<Result<Self, String>> {
+        let input = input.trim();
+        let rest = input.strip_prefix('/')?;
+        let (name, arg) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let arg = arg.trim();
+
+        let number = |what: &str| {
+            arg.parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| format!("✗ /{}: {} — положительное целое число, получено «{}»", name, what, arg))
+        };
+        let path = || {
+            (!arg.is_empty())
+                .then(|| PathBuf::from(arg))
+                .ok_or_else(|| format!("✗ /{}: укажите путь к файлу", name))
+        };
+
+        Some(match name.to_lowercase().as_str() {
+            "train" if arg.is_empty() => Ok(Self::Train(None)),
+            "train" => number("число эпох").map(|epochs| Self::Train(Some(epochs))),
+            "save" => path().map(Self::Save),
+            "load" => path().map(Self::Load),
+            "info" => Ok(Self::Info),
+            "clear" => Ok(Self::Clear),
+            "spawn" => number("число сущностей").map(Self::Spawn),
+            "help" | "?" => Ok(Self::Help),
+            _ => Err(format!("✗ Неизвестная команда /{} — список команд: /help", name)),
+        })
+    }
+
+    /// Подсказки для набираемого имени команды (пока в вводе нет пробела)
+    pub fn suggestions(input: &str) -> Vec<(&'static str, &'static str, &'static str)> {
+        let input = input.trim_start();
+        if !input.starts_with('/') || input.contains(char::is_whitespace) {
+            return Vec::new();
+        }
+        let typed = input.to_lowercase();
+        COMMANDS.iter().copied().filter(|(name, _, _)| name.starts_with(&typed)).collect()
+    }
+
+    /// Текст для `/help`
+    pub fn help() -> String {
+        let mut text = "⌨ Команды чата:".to_string();
+        for (name, args, description) in COMMANDS {
+            let usage = if args.is_empty() { name.to_string() } else { format!("{} {}", name, args) };
+            text.push_str(&format!("\n• `{}` — {}", usage, description));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(ChatCommand::parse("привет"), None);
+        assert_eq!(ChatCommand::parse("/train"), Some(Ok(ChatCommand::Train(None))));
+        assert_eq!(ChatCommand::parse(" /train 20 "), Some(Ok(ChatCommand::Train(Some(20)))));
+        assert!(matches!(ChatCommand::parse("/train много"), Some(Err(_))));
+        assert_eq!(ChatCommand::parse("/save model.bin"), Some(Ok(ChatCommand::Save(PathBuf::from("model.bin")))));
+        assert!(matches!(ChatCommand::parse("/save"), Some(Err(_))));
+        assert_eq!(ChatCommand::parse("/INFO"), Some(Ok(ChatCommand::Info)));
+        assert_eq!(ChatCommand::parse("/spawn 100"), Some(Ok(ChatCommand::Spawn(100))));
+        assert!(matches!(ChatCommand::parse("/spawn 0"), Some(Err(_))));
+        assert!(matches!(ChatCommand::parse("/fly"), Some(Err(_))));
+
+        let names: Vec<&str> = ChatCommand::suggestions("/s").iter().map(|(name, _, _)| *name).collect();
+        assert_eq!(names, ["/save", "/spawn"]);
+        assert!(ChatCommand::suggestions("/save x").is_empty());
+        assert!(ChatCommand::suggestions("текст").is_empty());
+        assert_eq!(ChatCommand::suggestions("/").len(), COMMANDS.len());
+    }
+}
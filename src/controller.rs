@@ -0,0 +1,106 @@
+//! Evolvable neural controllers: a voxel's senses mapped to a steering force
+//!
+//! A controller is a single-layer network stored in the genome. Its inputs
+//! are the ten perception channels, the three emotion axes, the velocity
+//! (relative to `MAX_SPEED`) and a bias; its three tanh outputs push the
+//! velocity one step along each axis once they pass ±0.5. Crossover and
+//! mutation live in `EvolutionEngine`.
+
+use crate::voxel::{Voxel, MAX_SPEED};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Sensor inputs, bias included
+pub const CONTROLLER_INPUTS: usize = 17;
+/// Force outputs, one per axis
+pub const CONTROLLER_OUTPUTS: usize = 3;
+/// Weights of a full controller
+pub const CONTROLLER_WEIGHTS: usize = CONTROLLER_INPUTS * CONTROLLER_OUTPUTS;
+
+/// Sensor → force weights, row-major by output; empty means no controller
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NeuralController {
+    pub weights: Vec<f64>,
+}
+
+impl NeuralController {
+    /// Controller with weights uniform in -`scale`..`scale`
+    pub fn random(rng: &mut impl Rng, scale: f64) -> Self {
+        let scale = scale.abs().max(f64::EPSILON);
+        Self {
+            weights: (0..CONTROLLER_WEIGHTS).map(|_| rng.gen_range(-scale..scale)).collect(),
+        }
+    }
+    
+    /// Whether the controller has a full set of weights
+    pub fn is_active(&self) -> bool {
+        self.weights.len() == CONTROLLER_WEIGHTS
+    }
+    
+    /// What the controller sees of `voxel` moving at `velocity`
+    pub fn inputs(voxel: &Voxel, velocity: [i8; 3]) -> [f64; CONTROLLER_INPUTS] {
+        let senses = [
+            voxel.perception_visual,
+            voxel.perception_auditory,
+            voxel.perception_tactile,
+            voxel.perception_thermal,
+            voxel.perception_chemical,
+            voxel.perception_pressure,
+            voxel.perception_time,
+            voxel.perception_space,
+            voxel.perception_self,
+            voxel.perception_other,
+        ];
+        let velocity = velocity.map(|v| f64::from(v) / f64::from(MAX_SPEED));
+        let mut inputs = [1.0; CONTROLLER_INPUTS];
+        for (input, sense) in inputs.iter_mut().zip(senses) {
+            *input = f64::from(sense.to_f32());
+        }
+        inputs[10..13].copy_from_slice(&[voxel.emotion_valence, voxel.emotion_arousal, voxel.emotion_dominance]);
+        inputs[13..16].copy_from_slice(&velocity);
+        inputs
+    }
+    
+    /// Network outputs in -1..1; zero for an inactive controller
+    pub fn forward(&self, inputs: &[f64; CONTROLLER_INPUTS]) -> [f64; CONTROLLER_OUTPUTS] {
+        if !self.is_active() {
+            return [0.0; CONTROLLER_OUTPUTS];
+        }
+        let mut outputs = [0.0; CONTROLLER_OUTPUTS];
+        for (output, row) in outputs.iter_mut().zip(self.weights.chunks(CONTROLLER_INPUTS)) {
+            *output = row.iter().zip(inputs).map(|(w, x)| w * x).sum::<f64>().tanh();
+        }
+        outputs
+    }
+    
+    /// Velocity step per axis the controller asks for in `voxel`'s situation
+    pub fn steer(&self, voxel: &Voxel, velocity: [i8; 3]) -> [i8; 3] {
+        self.forward(&Self::inputs(voxel, velocity)).map(|output| output.round() as i8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use half::f16;
+    
+    #[test]
+    fn test_controller_steers_from_senses() {
+        let mut voxel = Voxel::new();
+        assert_eq!(NeuralController::default().steer(&voxel, [0; 3]), [0, 0, 0]);
+        
+        // Flee along x from crowding, drift up on the bias alone
+        let mut controller = NeuralController { weights: vec![0.0; CONTROLLER_WEIGHTS] };
+        controller.weights[9] = -4.0;
+        controller.weights[2 * CONTROLLER_INPUTS + 16] = 2.0;
+        assert_eq!(controller.steer(&voxel, [0; 3]), [0, 0, 1]);
+        voxel.perception_other = f16::from_f32(1.0);
+        assert_eq!(controller.steer(&voxel, [0; 3]), [-1, 0, 1]);
+        
+        let inputs = NeuralController::inputs(&voxel, [MAX_SPEED, 0, 0]);
+        assert_eq!((inputs[9], inputs[13], inputs[16]), (1.0, 1.0, 1.0));
+        let random = NeuralController::random(&mut rand::thread_rng(), 0.5);
+        assert!(random.is_active());
+        assert!(random.weights.iter().all(|w| w.abs() < 0.5));
+    }
+}
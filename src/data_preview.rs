@@ -0,0 +1,141 @@
+use eframe::egui;
+use std::collections::BTreeSet;
+
+/// Таблица обучающих примеров перед обучением
+///
+/// Примеры можно править прямо в ячейках, удалять по одному или выбранными,
+/// объединять выбранные в один. Показываются только видимые строки, поэтому
+/// таблица не тормозит на больших корпусах.
+#[derive(Default)]
+pub struct DataPreview {
+    /// Индексы выбранных примеров
+    selected: BTreeSet<usize>,
+    /// Показываются только примеры, содержащие эту подстроку
+    filter: String,
+}
+
+impl DataPreview {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Отрисовка таблицы; `true`, если примеры изменились
+    ///
+    /// `count_tokens` отсутствует, пока модель занята обучением.
+    pub fn show(&mut self, ui: &mut egui::Ui, examples: &mut Vec<String>, count_tokens: Option<&dyn Fn(&str) -> usize>) -> bool {
+        let mut changed = false;
+        self.selected.retain(|&index| index < examples.len());
+
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.add(egui::TextEdit::singleline(&mut self.filter).hint_text("Фильтр").desired_width(160.0));
+            ui.label(format!("Выбрано: {}", self.selected.len()));
+            if ui
+                .add_enabled(self.selected.len() >= 2, egui::Button::new("🔗 Объединить"))
+                .on_hover_text("Выбранные примеры склеиваются в первый из них")
+                .clicked()
+            {
+                merge_examples(examples, &self.selected);
+                self.selected.clear();
+                changed = true;
+            }
+            if ui.add_enabled(!self.selected.is_empty(), egui::Button::new("🗑 Удалить выбранные")).clicked() {
+                remove_examples(examples, &self.selected);
+                self.selected.clear();
+                changed = true;
+            }
+            if ui.add_enabled(!self.selected.is_empty(), egui::Button::new("Снять выбор")).clicked() {
+                self.selected.clear();
+            }
+        });
+        ui.separator();
+
+        let filter = self.filter.to_lowercase();
+        let visible: Vec<usize> = (0..examples.len())
+            .filter(|&index| filter.is_empty() || examples[index].to_lowercase().contains(&filter))
+            .collect();
+        if visible.is_empty() {
+            ui.label(egui::RichText::new("Нет примеров").weak());
+            return changed;
+        }
+
+        let row_height = ui.spacing().interact_size.y + 4.0;
+        let mut removed = None;
+        egui::ScrollArea::vertical()
+            .id_source("data_preview_rows")
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, visible.len(), |ui, rows| {
+                for &index in &visible[rows] {
+                    ui.horizontal(|ui| {
+                        let mut selected = self.selected.contains(&index);
+                        if ui.checkbox(&mut selected, "").changed() {
+                            if selected {
+                                self.selected.insert(index);
+                            } else {
+                                self.selected.remove(&index);
+                            }
+                        }
+                        ui.label(egui::RichText::new(format!("{:>5}", index + 1)).monospace().weak());
+
+                        let tokens = count_tokens.map_or_else(|| "—".to_string(), |count| count(&examples[index]).to_string());
+                        ui.label(egui::RichText::new(format!("{:>4} ток.", tokens)).monospace().size(11.0))
+                            .on_hover_text("Токенов в примере");
+                        if ui.small_button("🗑").on_hover_text("Удалить пример").clicked() {
+                            removed = Some(index);
+                        }
+                        let edit = egui::TextEdit::singleline(&mut examples[index]).desired_width(ui.available_width());
+                        changed |= ui.add(edit).changed();
+                    });
+                }
+            });
+
+        if let Some(index) = removed {
+            examples.remove(index);
+            self.selected.clear();
+            changed = true;
+        }
+        changed
+    }
+}
+
+/// Склейка выбранных примеров в первый из них; возвращает его индекс
+pub fn merge_examples(examples: &mut Vec<String>, indices: &BTreeSet<usize>) -> Option<usize> {
+    let indices: Vec<usize> = indices.iter().copied().filter(|&index| index < examples.len()).collect();
+    let (&first, rest) = indices.split_first()?;
+    let merged: Vec<String> = indices.iter().map(|&index| examples[index].trim().to_string()).collect();
+    examples[first] = merged.join(" ");
+    for &index in rest.iter().rev() {
+        examples.remove(index);
+    }
+    Some(first)
+}
+
+/// Удаление выбранных примеров; возвращает число удалённых
+pub fn remove_examples(examples: &mut Vec<String>, indices: &BTreeSet<usize>) -> usize {
+    let before = examples.len();
+    let mut index = 0;
+    examples.retain(|_| {
+        let keep = !indices.contains(&index);
+        index += 1;
+        keep
+    });
+    before - examples.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_and_remove_examples() {
+        let mut examples: Vec<String> = ["раз", "два ", "мусор", "три", "четыре"].iter().map(|s| s.to_string()).collect();
+
+        assert_eq!(merge_examples(&mut examples, &BTreeSet::from([1, 3])), Some(1));
+        assert_eq!(examples, ["раз", "два три", "мусор", "четыре"]);
+
+        assert_eq!(remove_examples(&mut examples, &BTreeSet::from([2, 10])), 1);
+        assert_eq!(examples, ["раз", "два три", "четыре"]);
+
+        assert_eq!(merge_examples(&mut examples, &BTreeSet::new()), None);
+    }
+}
@@ -0,0 +1,596 @@
+use crate::events::WorldEvent;
+use crate::evolution::SelectionStrategy;
+use crate::islands::IslandModel;
+use crate::lighting::{LightPattern, LightingSystem};
+use crate::octree::{Aabb, Frustum};
+use crate::sound::SoundKind;
+use crate::voxel::{Boundary, Role, SpatialIndex, Voxel, VoxelWorld, GRID_CELL_SIZE, OCTREE_LEAF_SIZE, WORLD_EXTENT};
+use eframe::egui;
+use half::f16;
+use rand::Rng;
+use std::time::Instant;
+
+/// Точек облака, рисуемых за кадр
+const MAX_DRAWN_POINTS: usize = 20_000;
+/// Файл мира по умолчанию
+pub const DEFAULT_WORLD_PATH: &str = "ecosystem_world.json";
+/// Половина ребра куба, в котором появляются новые воксели
+const SPAWN_RADIUS: i32 = 50;
+/// Вокселей, создаваемых за один раз
+pub const MAX_SPAWN: usize = 100_000;
+/// Событий, показываемых в журнале мира
+const JOURNAL_LINES: usize = 8;
+/// Островов, на которые делится популяция при включении островной модели
+const ISLANDS: usize = 4;
+
+/// Сводка по вокселям мира
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EcosystemStats {
+    pub voxels: usize,
+    pub predators: usize,
+    pub total_energy: f64,
+    pub mean_energy: f64,
+    pub max_energy: f64,
+    pub mean_valence: f64,
+    pub mean_arousal: f64,
+}
+
+/// Режим «Экосистема»: воксельный мир внутри чат-приложения
+///
+/// Заменяет отдельный бинарник с `EngineUI`: симуляция идёт с заданной
+/// частотой тиков, облако точек вращается перетаскиванием и масштабируется
+/// колесом мыши.
+pub struct EcosystemView {
+    pub world: VoxelWorld,
+    pub lighting: LightingSystem,
+    pub running: bool,
+    pub ticks_per_second: f32,
+    pub spawn_count: usize,
+    /// Ёмкость листа октодерева (когда оно выбрано вместо сетки)
+    pub leaf_size: usize,
+    /// Файл для сохранения и загрузки мира
+    pub world_path: String,
+    /// Итог последнего сохранения или загрузки
+    status: Option<String>,
+    /// Накопленное время до следующего тика
+    pending: f32,
+    pub ticks: u64,
+    started: Instant,
+    /// Поворот облака вокруг вертикальной оси, радианы
+    yaw: f32,
+    zoom: f32,
+}
+
+impl EcosystemView {
+    pub fn new() -> Self {
+        // В самом мире эти правила выключены по умолчанию, экосистема их включает
+        let mut world = VoxelWorld::new();
+        world.reproduction.enabled = true;
+        world.predation.enabled = true;
+        world.collisions = true;
+        world.bounds = Some(Aabb { min: [-WORLD_EXTENT; 3], max: [WORLD_EXTENT; 3] });
+        Self {
+            world,
+            lighting: LightingSystem::new(),
+            running: true,
+            ticks_per_second: 10.0,
+            spawn_count: 100,
+            leaf_size: OCTREE_LEAF_SIZE,
+            world_path: DEFAULT_WORLD_PATH.to_string(),
+            status: None,
+            pending: 0.0,
+            ticks: 0,
+            started: Instant::now(),
+            yaw: 0.6,
+            zoom: 1.0,
+        }
+    }
+
+    /// Продвижение симуляции на `dt` секунд фиксированными тиками
+    pub fn step(&mut self, dt: f32) {
+        if !self.running {
+            return;
+        }
+        let tick = 1.0 / self.ticks_per_second.max(1.0);
+        // После долгой паузы кадра не догоняем больше секунды симуляции
+        self.pending = (self.pending + dt).min(1.0);
+        while self.pending >= tick {
+            self.pending -= tick;
+            self.world.update(tick);
+            self.ticks += 1;
+        }
+        self.lighting.update_lighting(self.started.elapsed().as_secs_f32());
+    }
+
+    /// Создание `count` вокселей со случайными позицией, скоростью и резонансом;
+    /// доля `predator_share` из них — хищники
+    ///
+    /// Число ограничено `1..=MAX_SPAWN` и местом, оставшимся до
+    /// `reproduction.max_population`. Возвращает число созданных вокселей.
+    pub fn spawn(&mut self, count: usize) -> usize {
+        let headroom = self.world.reproduction.max_population.saturating_sub(self.world.voxels.len());
+        let count = count.clamp(1, MAX_SPAWN).min(headroom);
+        let mut rng = rand::thread_rng();
+        let predator_share = self.world.predation.predator_share.clamp(0.0, 1.0);
+        for _ in 0..count {
+            let position = [
+                rng.gen_range(-SPAWN_RADIUS..=SPAWN_RADIUS),
+                rng.gen_range(-SPAWN_RADIUS..=SPAWN_RADIUS),
+                rng.gen_range(-SPAWN_RADIUS..=SPAWN_RADIUS),
+            ];
+            let entity = self.world.add_voxel(position);
+            self.world.set_velocity(entity, [0; 3].map(|_: i8| rng.gen_range(-1..=1)));
+            if let Some(mut voxel) = self.world.world.get_mut::<Voxel>(entity) {
+                voxel.energy = rng.gen_range(0.0..1.0);
+                voxel.resonance = f16::from_f32(rng.gen_range(-0.1..0.2));
+                voxel.emotion_valence = rng.gen_range(-1.0..1.0);
+                voxel.emotion_arousal = rng.gen_range(0.0..1.0);
+                if rng.gen_bool(predator_share) {
+                    voxel.set_role(Role::Predator);
+                }
+            }
+        }
+        count
+    }
+
+    pub fn clear(&mut self) {
+        self.world.clear();
+        self.ticks = 0;
+    }
+    
+    /// Сохранение мира в `world_path`
+    pub fn save(&self) -> Result<(), String> {
+        let path = self.world_path.trim();
+        self.world
+            .save(path)
+            .map_err(|e| format!("✗ Мир не сохранён в {}: {}", path, e))
+    }
+    
+    /// Экспорт истории статистики популяции в CSV рядом с файлом мира
+    pub fn export_stats(&self) -> Result<String, String> {
+        let path = std::path::Path::new(self.world_path.trim()).with_extension("csv");
+        std::fs::write(&path, self.world.stats_history().to_csv())
+            .map_err(|e| format!("✗ Статистика не сохранена в {}: {}", path.display(), e))?;
+        Ok(path.display().to_string())
+    }
+    
+    /// Загрузка мира из `world_path`; при ошибке текущий мир остаётся
+    pub fn load(&mut self) -> Result<(), String> {
+        let path = self.world_path.trim();
+        self.world = VoxelWorld::load(path).map_err(|e| format!("✗ Мир не загружен из {}: {}", path, e))?;
+        if let SpatialIndex::Octree(octree) = &self.world.spatial {
+            self.leaf_size = octree.leaf_size;
+        }
+        self.pending = 0.0;
+        Ok(())
+    }
+    
+    pub fn uses_octree(&self) -> bool {
+        matches!(self.world.spatial, SpatialIndex::Octree(_))
+    }
+
+    /// Одно поколение эволюции геномов всех вокселей
+    pub fn evolve(&mut self) {
+        self.world.evolve();
+    }
+
+    pub fn stats(&self) -> EcosystemStats {
+        let mut stats = EcosystemStats::default();
+        for voxel in self.world.voxels.iter().filter_map(|&entity| self.world.world.get::<Voxel>(entity)) {
+            stats.voxels += 1;
+            if voxel.role() == Role::Predator {
+                stats.predators += 1;
+            }
+            stats.total_energy += voxel.energy;
+            stats.max_energy = stats.max_energy.max(voxel.energy);
+            stats.mean_valence += voxel.emotion_valence;
+            stats.mean_arousal += voxel.emotion_arousal;
+        }
+        if stats.voxels > 0 {
+            let n = stats.voxels as f64;
+            stats.mean_energy = stats.total_energy / n;
+            stats.mean_valence /= n;
+            stats.mean_arousal /= n;
+        }
+        stats
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = if self.running { "⏸ Пауза" } else { "▶ Запуск" };
+            if ui.button(label).clicked() {
+                self.running = !self.running;
+            }
+            ui.add(egui::Slider::new(&mut self.ticks_per_second, 1.0..=60.0).text("тиков/с"));
+            ui.checkbox(&mut self.world.trauma_mode, "Trauma mode")
+                .on_hover_text("Каждый тик энергия ×1.5, возбуждение ×1.3");
+            ui.add(egui::Slider::new(&mut self.world.interaction_strength, 0.0..=5.0).text("Притяжение"))
+                .on_hover_text("Сила взаимодействия соседей: близкие по валентности притягиваются, противоположные отталкиваются");
+            let rules = &self.world.reproduction;
+            let hover = format!(
+                "Воксель с энергией от {:.1} и кайфом от {:.1} рождает потомка с мутировавшим геномом",
+                rules.energy_threshold, rules.kaif_threshold
+            );
+            ui.checkbox(&mut self.world.reproduction.enabled, "Размножение").on_hover_text(hover);
+            ui.checkbox(&mut self.world.collisions, "Столкновения")
+                .on_hover_text("Воксели отскакивают друг от друга с учётом упругости и трения");
+            ui.checkbox(&mut self.world.policies, "Поведение")
+                .on_hover_text("Испуганные убегают от соседей, радостные собираются в стаи, голодные ищут пищу");
+            ui.checkbox(&mut self.world.neural_control, "Нейроуправление")
+                .on_hover_text("Воксели рулят унаследованной нейросетью от чувств к силе; её веса эволюционируют");
+            ui.checkbox(&mut self.world.predation.enabled, "Хищники")
+                .on_hover_text("Хищники высасывают энергию у жертв при касании; жертвы питаются пищей среды");
+            let rules = &self.world.lifecycle;
+            let hover = format!(
+                "Молодые (до {} тиков) быстро тратят энергию и плохо видят, старые (с {} тиков) видят хуже и дают больше мутаций; \
+                 без энергии и после {} тиков воксели умирают",
+                rules.adult_at, rules.elder_at, rules.lifespan
+            );
+            ui.checkbox(&mut self.world.lifecycle.enabled, "Возраст").on_hover_text(hover);
+            ui.checkbox(&mut self.world.environment.enabled, "Среда")
+                .on_hover_text("Поля температуры, света и пищи: воксели чувствуют их и едят пищу; химические сигналы растекаются и выветриваются");
+            let mut language = self.world.language.is_some();
+            if ui
+                .checkbox(&mut language, "Язык")
+                .on_hover_text("Воксели иногда говорят о своих чувствах и воспоминаниях; соседи слышат тон слов")
+                .changed()
+            {
+                if language {
+                    self.world.enable_language();
+                } else {
+                    self.world.disable_language();
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut self.spawn_count).clamp_range(1..=MAX_SPAWN));
+            if ui.button("➕ Создать воксели").clicked() {
+                self.spawn(self.spawn_count);
+            }
+            if ui.button("🧬 Поколение эволюции").clicked() {
+                self.evolve();
+            }
+            if ui.button("📢 Пинг").on_hover_text("Громкий звук в центре мира; воксели слышат его слухом").clicked() {
+                self.world.emit_sound(SoundKind::Ping, [0, 0, 0]);
+            }
+            if ui.button("💡 Световой паттерн").clicked() {
+                self.lighting.add_pattern(LightPattern::new());
+            }
+            if ui.button("🗑 Очистить мир").clicked() {
+                self.clear();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Файл мира:");
+            ui.add(egui::TextEdit::singleline(&mut self.world_path).desired_width(200.0));
+            if ui.button("💾 Сохранить").clicked() {
+                let voxels = self.world.voxels.len();
+                self.status = Some(match self.save() {
+                    Ok(()) => format!("✓ Сохранено вокселей: {}", voxels),
+                    Err(e) => e,
+                });
+            }
+            if ui.button("📂 Загрузить").clicked() {
+                self.status = Some(match self.load() {
+                    Ok(()) => format!("✓ Загружено вокселей: {}", self.world.voxels.len()),
+                    Err(e) => e,
+                });
+            }
+            if ui.button("📈 Статистика CSV").on_hover_text("Численность, рождения, смерти, средние энергия и кайф по тикам").clicked() {
+                let ticks = self.world.stats_history().len();
+                self.status = Some(match self.export_stats() {
+                    Ok(path) => format!("✓ {} тиков статистики в {}", ticks, path),
+                    Err(e) => e,
+                });
+            }
+            if let Some(status) = &self.status {
+                ui.label(egui::RichText::new(status).size(11.0).weak());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Индекс соседей:");
+            let mut octree = self.uses_octree();
+            let switched = ui.radio_value(&mut octree, false, "Сетка").changed()
+                | ui.radio_value(&mut octree, true, "Октодерево")
+                    .on_hover_text("Для больших разреженных миров: запросы и отсечение невидимого по узлам")
+                    .changed();
+            let resized = ui
+                .add_enabled(octree, egui::DragValue::new(&mut self.leaf_size).clamp_range(1..=1024).prefix("лист: "))
+                .changed();
+            if switched || (octree && resized) {
+                if octree {
+                    self.world.use_octree(self.leaf_size);
+                } else {
+                    self.world.use_grid(GRID_CELL_SIZE);
+                }
+            }
+            let mut gpu = self.world.gpu.is_some();
+            let hover = format!("Движение и энергия считаются на GPU при {} вокселях и больше", self.world.gpu_min_voxels);
+            if ui.checkbox(&mut gpu, "GPU").on_hover_text(hover).changed() {
+                if gpu {
+                    self.status = Some(match self.world.enable_gpu() {
+                        Some(name) => format!("✓ GPU: {}", name),
+                        None => "✗ GPU недоступен, расчёт остаётся на CPU".to_string(),
+                    });
+                } else {
+                    self.world.disable_gpu();
+                }
+            }
+            ui.checkbox(&mut self.world.streaming.enabled, "Стриминг").on_hover_text(
+                "Мир делится на чанки: считаются только видимые и активные, тихие чанки за кадром замораживаются",
+            );
+            if self.world.streaming.enabled {
+                ui.label(format!(
+                    "Заморожено: {} чанков, {} вокселей",
+                    self.world.chunks.len(),
+                    self.world.chunks.voxel_count()
+                ));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Границы мира:");
+            let mut bounded = self.world.bounds.is_some();
+            let mut extent = self.world.bounds.map_or(WORLD_EXTENT, |bounds| bounds.max[0]);
+            let toggled = ui.checkbox(&mut bounded, "").changed();
+            let resized = ui
+                .add_enabled(bounded, egui::DragValue::new(&mut extent).clamp_range(8..=1_000_000).prefix("± "))
+                .changed();
+            if toggled || resized {
+                self.world.bounds = bounded.then_some(Aabb { min: [-extent; 3], max: [extent; 3] });
+            }
+            ui.add_enabled_ui(bounded, |ui| {
+                ui.radio_value(&mut self.world.boundary, Boundary::Clamp, "Стена");
+                ui.radio_value(&mut self.world.boundary, Boundary::Bounce, "Отскок");
+                ui.radio_value(&mut self.world.boundary, Boundary::Wrap, "Тор")
+                    .on_hover_text("Вышедший за край воксель появляется с противоположной стороны");
+            });
+        });
+        ui.separator();
+
+        let stats = self.stats();
+        egui::Grid::new("ecosystem_stats").num_columns(4).spacing([24.0, 4.0]).show(ui, |ui| {
+            ui.label(format!("Вокселей: {} · хищников: {}", stats.voxels, stats.predators));
+            ui.label(format!("Тиков: {} · рождений: {}", self.ticks, self.world.births));
+            ui.label(format!("Энергия: Σ {:.1}", stats.total_energy));
+            ui.label(format!("ср. {:.3} / макс. {:.3}", stats.mean_energy, stats.max_energy));
+            ui.end_row();
+            ui.label(format!("Валентность: {:+.2}", stats.mean_valence));
+            ui.label(format!("Возбуждение: {:.2}", stats.mean_arousal));
+            if self.world.evolution.self_adaptive {
+                let rates = self.world.mutation_rates();
+                ui.label(format!("Мутации: ср. {:.3} ({:.3}–{:.3})", rates.mean, rates.min, rates.max))
+                    .on_hover_text(format!("Собственные скорости мутаций геномов: <0.001 / <0.01 / <0.1 / ≥0.1 — {:?}", rates.histogram));
+            } else {
+                ui.label(format!("Мутации: {:.2}", self.world.evolution.mutation_rate));
+            }
+            ui.label(format!("Световых паттернов: {}", self.lighting.patterns.len()));
+            ui.end_row();
+            ui.label(format!("Пища: Σ {:.1}", self.world.environment.nutrients.sum()));
+            ui.label(format!("Сигналы: Σ {:+.2}", self.world.environment.signals.sum()));
+            let selection = match self.world.evolution.selection {
+                SelectionStrategy::Truncation => "усечение".to_string(),
+                SelectionStrategy::Tournament { size } => format!("турнир ({})", size),
+                SelectionStrategy::Roulette => "рулетка".to_string(),
+            };
+            ui.label(format!("Отбор: {} · элита {}", selection, self.world.evolution.elitism));
+            let speciation = &mut self.world.evolution.speciation;
+            ui.checkbox(&mut speciation.enabled, format!("Виды: {}", speciation.species.len()))
+                .on_hover_text("Группировать геномы по сходству и делить приспособленность внутри вида");
+            let novelty = &mut self.world.evolution.novelty;
+            ui.checkbox(&mut novelty.enabled, format!("Новизна: архив {}", novelty.archive.len()))
+                .on_hover_text("Отбирать по новизне поведения вместо приспособленности");
+            ui.checkbox(&mut self.world.evolution.self_adaptive, "Самоадаптация")
+                .on_hover_text("Каждый геном хранит свою скорость мутаций, которая тоже мутирует (логнормально)");
+            let pareto = &mut self.world.evolution.pareto;
+            ui.checkbox(&mut pareto.enabled, format!("Парето: фронт {}", pareto.front.len()))
+                .on_hover_text("Отбирать по энергии, кайфу и долголетию сразу (NSGA-II); новизна важнее");
+            let mut islands = !self.world.islands.is_empty();
+            let toggled = ui.checkbox(&mut islands, format!("Острова: {}", self.world.islands.islands.len()))
+                .on_hover_text("Эволюционировать подпопуляции с разными мутациями в отдельных потоках и обмениваться лучшими геномами")
+                .changed();
+            if toggled {
+                self.world.islands = if islands { IslandModel::spread(ISLANDS) } else { IslandModel::default() };
+            }
+            ui.end_row();
+        });
+        if !self.world.islands.is_empty() {
+            egui::CollapsingHeader::new(format!("🏝 Острова (поколение {})", self.world.islands.generation))
+                .id_source("ecosystem_islands")
+                .show(ui, |ui| {
+                    for (i, island) in self.world.islands.islands.iter().enumerate() {
+                        ui.label(egui::RichText::new(format!(
+                            "#{} · мутации {:.2} (свои ср. {:.3}) · скрещивание {:.2} · вокселей {} · лучший {:.3} · ср. {:.3} · мигрантов {}",
+                            i,
+                            island.engine.mutation_rate,
+                            island.stats.mutation_rates.mean,
+                            island.engine.crossover_rate,
+                            island.stats.members,
+                            island.stats.best_fitness,
+                            island.stats.mean_fitness,
+                            island.stats.immigrants
+                        )).size(11.0));
+                    }
+                });
+        }
+        egui::CollapsingHeader::new(format!("📜 Журнал мира ({})", self.world.events.len()))
+            .id_source("ecosystem_journal")
+            .show(ui, |ui| {
+                for record in self.world.events.iter().rev().take(JOURNAL_LINES) {
+                    ui.label(egui::RichText::new(format!("[{}] {}", record.tick, describe(&record.event))).size(11.0));
+                }
+            });
+        if self.world.language.is_some() {
+            egui::CollapsingHeader::new(format!("💬 Речь вокселей ({})", self.world.utterances.len()))
+                .id_source("ecosystem_utterances")
+                .show(ui, |ui| {
+                    for utterance in self.world.utterances.iter().rev().take(JOURNAL_LINES) {
+                        ui.label(egui::RichText::new(format!(
+                            "[{}] {:?} в {:?}: «{}»",
+                            utterance.tick, utterance.speaker, utterance.position, utterance.text
+                        )).size(11.0));
+                    }
+                });
+        }
+        let strongest = self.world.strongest(1).next().and_then(|(entity, voxel)| Some((entity, voxel, self.world.inspect(entity)?)));
+        if let Some((entity, voxel, snapshot)) = strongest {
+            egui::CollapsingHeader::new("🔍 Сильнейший воксель")
+                .id_source("ecosystem_inspector")
+                .show(ui, |ui| {
+                    ui.label(format!(
+                        "{:?} ({:?}) · поколение {} · в {:?} · скорость {:?}",
+                        entity, snapshot.role, snapshot.generation, snapshot.position, snapshot.velocity
+                    ));
+                    ui.label(format!(
+                        "Возраст {} тиков ({:?})",
+                        snapshot.age_ticks,
+                        voxel.life_stage(&self.world.lifecycle)
+                    ));
+                    ui.label(format!(
+                        "Энергия {:.3} · кайф {:.2} · эмоция {:?} · температура {}",
+                        snapshot.energy, snapshot.kaif, snapshot.dominant_emotion, snapshot.temperature
+                    ));
+                    let senses: Vec<String> = snapshot.senses.iter().map(|(name, value)| format!("{} {:.2}", name, value)).collect();
+                    ui.label(egui::RichText::new(senses.join(" · ")).size(11.0).weak());
+                    ui.label(format!("Геном: {}", snapshot.genome.join(", ")));
+                    let traits = voxel.genome.traits();
+                    ui.label(format!(
+                        "Гены: чуткость ×{:.2} · общительность ×{:.2} · обмен веществ ×{:.2}",
+                        traits.acuity, traits.sociability, traits.metabolism
+                    ));
+                    ui.label(format!(
+                        "Память: {} недавних · {} долговременных · валентность {:.2} · чаще всего {:?}",
+                        snapshot.memory.recent, snapshot.memory.long_term, snapshot.memory.mean_valence, snapshot.memory.dominant_kind
+                    ));
+                });
+        }
+        ui.separator();
+
+        self.show_point_cloud(ui);
+    }
+
+    /// Облако точек: ортографическая проекция с поворотом вокруг оси Y
+    fn show_point_cloud(&mut self, ui: &mut egui::Ui) {
+        let size = egui::vec2(ui.available_width(), ui.available_height().max(200.0));
+        let (rect, response) = ui.allocate_exact_size(size, egui::Sense::drag());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 6.0, egui::Color32::from_rgb(20, 24, 32));
+
+        if response.dragged() {
+            self.yaw += response.drag_delta().x * 0.01;
+        }
+        if response.hovered() {
+            let scroll = ui.input(|i| i.scroll_delta.y);
+            self.zoom = (self.zoom * (1.0 + scroll * 0.002)).clamp(0.1, 20.0);
+        }
+
+        let Some(bounds) = self.world.spatial.bounds() else {
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "Мир пуст — создайте воксели или введите /spawn в чате",
+                egui::FontId::proportional(14.0),
+                egui::Color32::GRAY,
+            );
+            return;
+        };
+
+        // Масштаб по углам границ индекса, без обхода всех точек
+        let (sin, cos) = self.yaw.sin_cos();
+        let project = |p: &[f32; 3]| (p[0] * cos + p[2] * sin, p[1]);
+        let extent = (0..8)
+            .map(|corner: usize| {
+                let p = [0, 1, 2].map(|axis| {
+                    (if corner & (1 << axis) != 0 { bounds.max[axis] } else { bounds.min[axis] }) as f32
+                });
+                let (x, y) = project(&p);
+                x.abs().max(y.abs())
+            })
+            .fold(1.0f32, f32::max);
+        let scale = 0.45 * rect.width().min(rect.height()) / extent * self.zoom;
+        // Видимая область остаётся «горячей» при стриминге чанков
+        self.world.streaming.focus = Some([0, 0, 0]);
+        self.world.streaming.focus_radius = (rect.size().length() / 2.0 / scale).ceil() as i32;
+
+        // Рисуются только точки, попадающие в окно
+        let frustum = Frustum::orthographic(self.yaw, rect.width() / 2.0 / scale, rect.height() / 2.0 / scale);
+        let points = self.world.visible_points(&frustum);
+        for (position, color) in points.iter().take(MAX_DRAWN_POINTS) {
+            let (x, y) = project(position);
+            let point = rect.center() + egui::vec2(x * scale, -y * scale);
+            if rect.contains(point) {
+                let [r, g, b] = color.map(|c| (c.clamp(0.0, 1.0) * 200.0 + 55.0) as u8);
+                painter.circle_filled(point, 1.5, egui::Color32::from_rgb(r, g, b));
+            }
+        }
+        if points.len() > MAX_DRAWN_POINTS {
+            painter.text(
+                rect.left_top() + egui::vec2(8.0, 8.0),
+                egui::Align2::LEFT_TOP,
+                format!("Показано {} из {} точек", MAX_DRAWN_POINTS, points.len()),
+                egui::FontId::proportional(12.0),
+                egui::Color32::GRAY,
+            );
+        }
+    }
+}
+
+/// Строка журнала для события мира
+fn describe(event: &WorldEvent) -> String {
+    let at = |p: &[i32; 3]| format!("({}, {}, {})", p[0], p[1], p[2]);
+    match event {
+        WorldEvent::Spawned { entity, position } => format!("Появился {:?} в {}", entity, at(position)),
+        WorldEvent::Died { entity, position } => format!("Погиб {:?} в {}", entity, at(position)),
+        WorldEvent::Reproduced { parent, child, position } => {
+            format!("{:?} родил {:?} в {}", parent, child, at(position))
+        }
+        WorldEvent::Collided { first, second, position } => {
+            format!("{:?} и {:?} столкнулись в {}", first, second, at(position))
+        }
+    }
+}
+
+impl Default for EcosystemView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ecosystem_spawn_step_and_stats() {
+        let mut ecosystem = EcosystemView::new();
+        assert!(ecosystem.world.reproduction.enabled && ecosystem.world.predation.enabled);
+        assert!(ecosystem.world.collisions && ecosystem.world.bounds.is_some());
+        // Без рождений и смертей численность за три тика не меняется
+        ecosystem.world.reproduction.enabled = false;
+        ecosystem.world.predation.enabled = false;
+        assert_eq!(ecosystem.stats(), EcosystemStats::default());
+
+        assert_eq!(ecosystem.spawn(20), 20);
+        let stats = ecosystem.stats();
+        assert_eq!(stats.voxels, 20);
+        assert!(stats.max_energy <= 1.0 && stats.mean_energy >= 0.0);
+
+        // 10 тиков в секунду: за 0.35 с — три тика, остаток копится
+        ecosystem.step(0.35);
+        assert_eq!(ecosystem.ticks, 3);
+        ecosystem.running = false;
+        ecosystem.step(1.0);
+        assert_eq!(ecosystem.ticks, 3);
+
+        ecosystem.evolve();
+        assert_eq!(ecosystem.stats().voxels, 20);
+        ecosystem.clear();
+        assert_eq!(ecosystem.stats().voxels, 0);
+
+        // Не больше, чем осталось места до предела популяции
+        ecosystem.world.reproduction.max_population = 25;
+        assert_eq!(ecosystem.spawn(20), 20);
+        assert_eq!(ecosystem.spawn(20), 5);
+        assert_eq!(ecosystem.spawn(1), 0);
+        assert_eq!(ecosystem.stats().voxels, 25);
+    }
+}
@@ -5,21 +5,22 @@ pub use bevy_ecs::prelude::*;
 pub mod systems {
     use bevy_ecs::prelude::*;
     use crate::voxel::Voxel;
+    use crate::voxel_soa::{Position, Velocity};
     
     /// System to update voxel physics
-    pub fn update_voxel_physics(mut query: Query<&mut Voxel>) {
-        for mut voxel in query.iter_mut() {
+    pub fn update_voxel_physics(mut query: Query<(&mut Position, &mut Velocity, &Voxel)>) {
+        for (mut position, mut velocity, voxel) in query.iter_mut() {
             // Update position based on velocity
-            voxel.position[0] += voxel.velocity_x as i32;
-            voxel.position[1] += voxel.velocity_y as i32;
-            voxel.position[2] += voxel.velocity_z as i32;
+            position.0[0] += velocity.0[0] as i32;
+            position.0[1] += velocity.0[1] as i32;
+            position.0[2] += velocity.0[2] as i32;
             
             // Update velocity based on acceleration
-            voxel.velocity_x = (voxel.velocity_x as i16 + voxel.acceleration_x as i16)
+            velocity.0[0] = (velocity.0[0] as i16 + voxel.acceleration_x as i16)
                 .max(-128).min(127) as i8;
-            voxel.velocity_y = (voxel.velocity_y as i16 + voxel.acceleration_y as i16)
+            velocity.0[1] = (velocity.0[1] as i16 + voxel.acceleration_y as i16)
                 .max(-128).min(127) as i8;
-            voxel.velocity_z = (voxel.velocity_z as i16 + voxel.acceleration_z as i16)
+            velocity.0[2] = (velocity.0[2] as i16 + voxel.acceleration_z as i16)
                 .max(-128).min(127) as i8;
         }
     }
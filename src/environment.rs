@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+
+/// Scalar field sampled on a regular 3D lattice
+///
+/// Lattice point `(i, j, k)` sits at `origin + (i, j, k) * spacing`; samples
+/// between points are trilinearly interpolated and positions outside the
+/// lattice are clamped to its border.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScalarField {
+    pub origin: [i32; 3],
+    pub dims: [usize; 3],
+    pub spacing: i32,
+    values: Vec<f32>,
+}
+
+impl ScalarField {
+    pub fn new(origin: [i32; 3], dims: [usize; 3], spacing: i32, fill: f32) -> Self {
+        let dims = dims.map(|d| d.max(1));
+        Self {
+            origin,
+            dims,
+            spacing: spacing.max(1),
+            values: vec![fill; dims[0] * dims[1] * dims[2]],
+        }
+    }
+    
+    /// Field covering the cube `[-half_extent, half_extent]^3`
+    pub fn centered(half_extent: i32, spacing: i32, fill: f32) -> Self {
+        let spacing = spacing.max(1);
+        let points = (2 * half_extent.max(0) / spacing) as usize + 1;
+        Self::new([-half_extent.max(0); 3], [points; 3], spacing, fill)
+    }
+    
+    fn index(&self, cell: [usize; 3]) -> usize {
+        (cell[2] * self.dims[1] + cell[1]) * self.dims[0] + cell[0]
+    }
+    
+    /// Lattice coordinates of a position (fractional, clamped to the lattice)
+    fn lattice(&self, position: [f32; 3]) -> [f32; 3] {
+        [0, 1, 2].map(|axis| {
+            let t = (position[axis] - self.origin[axis] as f32) / self.spacing as f32;
+            t.clamp(0.0, (self.dims[axis] - 1) as f32)
+        })
+    }
+    
+    /// Nearest lattice point
+    fn nearest(&self, position: [i32; 3]) -> [usize; 3] {
+        self.lattice(position.map(|c| c as f32)).map(|t| t.round() as usize)
+    }
+    
+    pub fn position_of(&self, cell: [usize; 3]) -> [i32; 3] {
+        [0, 1, 2].map(|axis| self.origin[axis] + cell[axis] as i32 * self.spacing)
+    }
+    
+    pub fn get(&self, cell: [usize; 3]) -> f32 {
+        self.values[self.index(cell)]
+    }
+    
+    pub fn sample(&self, position: [f32; 3]) -> f32 {
+        let t = self.lattice(position);
+        let low = t.map(|t| t.floor() as usize);
+        let high = [0, 1, 2].map(|axis| (low[axis] + 1).min(self.dims[axis] - 1));
+        let frac = [0, 1, 2].map(|axis| t[axis] - low[axis] as f32);
+        
+        let mut value = 0.0;
+        for corner in 0..8 {
+            let mut weight = 1.0;
+            let cell = [0, 1, 2].map(|axis| {
+                if corner & (1 << axis) != 0 {
+                    weight *= frac[axis];
+                    high[axis]
+                } else {
+                    weight *= 1.0 - frac[axis];
+                    low[axis]
+                }
+            });
+            value += weight * self.get(cell);
+        }
+        value
+    }
+    
+    /// Central-difference gradient, per unit of distance
+    pub fn gradient(&self, position: [f32; 3]) -> [f32; 3] {
+        let h = self.spacing as f32;
+        [0, 1, 2].map(|axis| {
+            let mut ahead = position;
+            let mut behind = position;
+            ahead[axis] += h;
+            behind[axis] -= h;
+            (self.sample(ahead) - self.sample(behind)) / (2.0 * h)
+        })
+    }
+    
+    /// Add `amount` at the lattice point nearest to `position`
+    pub fn add(&mut self, position: [i32; 3], amount: f32) {
+        let index = self.index(self.nearest(position));
+        self.values[index] += amount;
+    }
+    
+    /// Remove up to `amount` at the nearest lattice point; returns what was taken
+    pub fn take(&mut self, position: [i32; 3], amount: f32) -> f32 {
+        let index = self.index(self.nearest(position));
+        let taken = self.values[index].clamp(0.0, amount.max(0.0));
+        self.values[index] -= taken;
+        taken
+    }
+    
+    pub fn fill_with(&mut self, value: impl Fn([i32; 3]) -> f32) {
+        for z in 0..self.dims[2] {
+            for y in 0..self.dims[1] {
+                for x in 0..self.dims[0] {
+                    let index = self.index([x, y, z]);
+                    self.values[index] = value(self.position_of([x, y, z]));
+                }
+            }
+        }
+    }
+    
+    /// Relax every point towards `target` by `rate` (0..1)
+    pub fn relax(&mut self, target: &Self, rate: f32) {
+        let rate = rate.clamp(0.0, 1.0);
+        for (value, &goal) in self.values.iter_mut().zip(&target.values) {
+            *value += (goal - *value) * rate;
+        }
+    }
+    
+    /// One explicit diffusion step: every point moves towards its six neighbors
+    /// by `rate` (clamped to the stable 0..1/6); the border reflects, so the
+    /// total is conserved
+    pub fn diffuse(&mut self, rate: f32) {
+        let rate = rate.clamp(0.0, 1.0 / 6.0);
+        if rate == 0.0 {
+            return;
+        }
+        let previous = self.values.clone();
+        for z in 0..self.dims[2] {
+            for y in 0..self.dims[1] {
+                for x in 0..self.dims[0] {
+                    let cell = [x, y, z];
+                    let index = self.index(cell);
+                    let mut flow = 0.0;
+                    for axis in 0..3 {
+                        for neighbor_at in [cell[axis].saturating_sub(1), (cell[axis] + 1).min(self.dims[axis] - 1)] {
+                            let mut neighbor = cell;
+                            neighbor[axis] = neighbor_at;
+                            flow += previous[self.index(neighbor)] - previous[index];
+                        }
+                    }
+                    self.values[index] = previous[index] + rate * flow;
+                }
+            }
+        }
+    }
+    
+    pub fn scale(&mut self, factor: f32) {
+        for value in &mut self.values {
+            *value *= factor;
+        }
+    }
+    
+    pub fn sum(&self) -> f64 {
+        self.values.iter().map(|&v| f64::from(v)).sum()
+    }
+}
+
+/// World-level temperature, light and nutrient fields
+///
+/// Temperature rises towards +Y (warm "surface", cold "depths"), light fades
+/// with depth, nutrients form patches that voxels eat and that slowly regrow
+/// towards their initial distribution. Voxels also leave chemical signals
+/// (pheromones) that spread through their own field and fade.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Environment {
+    pub enabled: bool,
+    pub temperature: ScalarField,
+    pub light: ScalarField,
+    pub nutrients: ScalarField,
+    /// Nutrient distribution the field regrows towards
+    pub nutrient_capacity: ScalarField,
+    /// Fraction of the missing nutrients restored per second
+    pub regrowth_rate: f32,
+    /// Nutrients a voxel can eat per second
+    pub consumption_rate: f32,
+    /// Energy gained per unit of nutrients eaten
+    pub food_energy: f64,
+    /// Chemical signals emitted by the voxels
+    pub signals: ScalarField,
+    /// Signal a voxel emits per second, signed by its valence
+    pub emission_rate: f32,
+    /// Share exchanged with each neighboring lattice point per second
+    pub diffusion_rate: f32,
+    /// Fraction of the signal that fades per second
+    pub signal_decay: f32,
+}
+
+impl Environment {
+    pub fn new(half_extent: i32, spacing: i32) -> Self {
+        let extent = half_extent.max(1) as f32;
+        let mut temperature = ScalarField::centered(half_extent, spacing, 0.0);
+        temperature.fill_with(|p| (p[1] as f32 / extent).clamp(-1.0, 1.0));
+        let mut light = ScalarField::centered(half_extent, spacing, 0.0);
+        light.fill_with(|p| ((p[1] as f32 / extent + 1.0) / 2.0).clamp(0.0, 1.0));
+        let mut nutrients = ScalarField::centered(half_extent, spacing, 0.0);
+        let patch = extent / 3.0;
+        nutrients.fill_with(|p| {
+            let [x, y, z] = p.map(|c| c as f32 / patch);
+            (x.sin() * y.cos() * z.sin()).max(0.0)
+        });
+        
+        Self {
+            enabled: true,
+            temperature,
+            light,
+            nutrient_capacity: nutrients.clone(),
+            nutrients,
+            regrowth_rate: 0.02,
+            consumption_rate: 0.5,
+            food_energy: 1.0,
+            signals: ScalarField::centered(half_extent, spacing, 0.0),
+            emission_rate: 0.1,
+            diffusion_rate: 0.5,
+            signal_decay: 0.1,
+        }
+    }
+    
+    /// Nutrients regrow towards capacity, signals spread and fade
+    pub fn update(&mut self, delta_time: f32) {
+        self.nutrients.relax(&self.nutrient_capacity, self.regrowth_rate * delta_time);
+        self.signals.diffuse(self.diffusion_rate * delta_time);
+        self.signals.scale((1.0 - self.signal_decay * delta_time).clamp(0.0, 1.0));
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new(64, 4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_scalar_field_sampling() {
+        let mut field = ScalarField::centered(8, 4, 0.0);
+        assert_eq!(field.dims, [5, 5, 5]);
+        field.fill_with(|p| p[0] as f32);
+        
+        // Linear fields are reproduced exactly between lattice points, clamped outside
+        assert!((field.sample([1.0, 3.0, -2.0]) - 1.0).abs() < 1e-5);
+        assert_eq!(field.sample([100.0, 0.0, 0.0]), 8.0);
+        assert!((field.gradient([0.0, 0.0, 0.0])[0] - 1.0).abs() < 1e-5);
+        assert_eq!(field.gradient([0.0, 0.0, 0.0])[1], 0.0);
+        
+        assert_eq!(field.take([4, 0, 0], 3.0), 3.0);
+        assert_eq!(field.take([4, 0, 0], 3.0), 1.0);
+        assert_eq!(field.take([-8, 0, 0], 1.0), 0.0);
+        field.add([5, 1, 0], 2.0);
+        assert_eq!(field.get([3, 2, 2]), 2.0);
+        
+        let target = ScalarField::centered(8, 4, 1.0);
+        let mut empty = ScalarField::centered(8, 4, 0.0);
+        empty.relax(&target, 0.25);
+        assert_eq!(empty.sum(), 125.0 * 0.25);
+    }
+    
+    #[test]
+    fn test_signal_diffusion_and_decay() {
+        let mut field = ScalarField::centered(8, 4, 0.0);
+        field.add([0, 0, 0], 6.0);
+        field.add([8, 8, 8], 1.0);
+        field.diffuse(0.1);
+        
+        // Each of the six neighbors receives a tenth, the border corner loses only
+        // what flows to its three neighbors, and nothing leaks out
+        assert!((field.get([2, 2, 2]) - 2.4).abs() < 1e-5);
+        assert!((field.get([3, 2, 2]) - 0.6).abs() < 1e-5);
+        assert!((field.get([4, 4, 4]) - 0.7).abs() < 1e-5);
+        assert!((field.sum() - 7.0).abs() < 1e-4);
+        
+        field.scale(0.5);
+        assert!((field.sum() - 3.5).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,112 @@
+use bevy_ecs::entity::Entity;
+use std::collections::VecDeque;
+
+/// Default number of events kept in `EventLog`
+pub const EVENT_HISTORY: usize = 1024;
+
+/// Something that happened in the voxel world
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorldEvent {
+    Spawned { entity: Entity, position: [i32; 3] },
+    Died { entity: Entity, position: [i32; 3] },
+    Reproduced { parent: Entity, child: Entity, position: [i32; 3] },
+    /// Two voxels bumped into each other
+    Collided { first: Entity, second: Entity, position: [i32; 3] },
+}
+
+/// Event with its sequence number and the world tick it happened on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EventRecord {
+    pub seq: u64,
+    pub tick: u64,
+    pub event: WorldEvent,
+}
+
+/// Ring buffer of the latest world events
+///
+/// Every event gets an increasing sequence number, so any number of consumers
+/// (the UI journal, tests) can each keep a cursor and read only what is new
+/// with `since`. Events older than `capacity` are dropped.
+#[derive(Clone, Debug)]
+pub struct EventLog {
+    pub capacity: usize,
+    history: VecDeque<EventRecord>,
+    next_seq: u64,
+}
+
+impl EventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            history: VecDeque::new(),
+            next_seq: 0,
+        }
+    }
+    
+    pub fn push(&mut self, tick: u64, event: WorldEvent) {
+        while self.history.len() >= self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(EventRecord { seq: self.next_seq, tick, event });
+        self.next_seq += 1;
+    }
+    
+    /// Sequence number the next event will get; a cursor for `since`
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+    
+    /// Retained events with sequence numbers from `seq` on, oldest first
+    pub fn since(&self, seq: u64) -> impl Iterator<Item = &EventRecord> {
+        let skip = self.history.front().map_or(0, |first| seq.saturating_sub(first.seq) as usize);
+        self.history.iter().skip(skip)
+    }
+    
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &EventRecord> {
+        self.history.iter()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+    
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(EVENT_HISTORY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_event_log_ring_buffer() {
+        let mut log = EventLog::new(3);
+        let entity = Entity::from_raw(7);
+        for tick in 0..5 {
+            log.push(tick, WorldEvent::Spawned { entity, position: [tick as i32, 0, 0] });
+        }
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.next_seq(), 5);
+        let ticks: Vec<u64> = log.iter().map(|record| record.tick).collect();
+        assert_eq!(ticks, [2, 3, 4]);
+        
+        // A cursor reads only what is new; dropped events are skipped
+        let cursor = log.next_seq();
+        log.push(5, WorldEvent::Died { entity, position: [0, 0, 0] });
+        let new: Vec<u64> = log.since(cursor).map(|record| record.seq).collect();
+        assert_eq!(new, [5]);
+        assert_eq!(log.since(0).count(), 3);
+        assert_eq!(log.since(100).count(), 0);
+    }
+}
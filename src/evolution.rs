@@ -1,13 +1,108 @@
-use crate::voxel::{Genome, Voxel};
+use crate::controller::NeuralController;
+use crate::novelty::{self, NoveltySearch};
+use crate::pareto::{self, ParetoEvolution};
+use crate::speciation::Speciation;
+use crate::voxel::{Genome, Voxel, VoxelRecord, GENE_COUNT};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+/// How `EvolutionEngine::breed` picks parents and who gets replaced
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SelectionStrategy {
+    /// Parents drawn uniformly from the fitter half; the weaker half is replaced
+    #[default]
+    Truncation,
+    /// Each parent is the fittest of `size` random members
+    Tournament { size: usize },
+    /// Parents drawn with probability proportional to fitness
+    Roulette,
+}
+
+/// Bounds of a self-adapted mutation rate
+pub const MUTATION_RATE_RANGE: (f64, f64) = (1e-4, 1.0);
+
+/// Distribution of the genomes' own mutation rates, see
+/// `EvolutionEngine::self_adaptive`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MutationRateStats {
+    /// Genomes carrying their own rate
+    pub count: usize,
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+    /// Rates below 0.001, below 0.01, below 0.1 and from 0.1 on
+    pub histogram: [usize; 4],
+}
+
+impl MutationRateStats {
+    pub fn of<'a>(genomes: impl IntoIterator<Item = &'a Genome>) -> Self {
+        let rates: Vec<f64> = genomes.into_iter().filter_map(|genome| genome.mutation_rate).collect();
+        if rates.is_empty() {
+            return Self::default();
+        }
+        let mut histogram = [0; 4];
+        for &rate in &rates {
+            histogram[[0.001, 0.01, 0.1].iter().filter(|&&bound| rate >= bound).count()] += 1;
+        }
+        Self {
+            count: rates.len(),
+            min: rates.iter().copied().fold(f64::INFINITY, f64::min),
+            mean: rates.iter().sum::<f64>() / rates.len() as f64,
+            max: rates.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            histogram,
+        }
+    }
+}
+
 /// NextGen Evolution: combine + mutate + fitness
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct EvolutionEngine {
     pub mutation_rate: f64,
     pub crossover_rate: f64,
     pub fitness_threshold: f64,
+    #[serde(default)]
+    pub selection: SelectionStrategy,
+    /// The fittest members that always survive a generation unchanged
+    #[serde(default)]
+    pub elitism: usize,
+    #[serde(default)]
+    pub speciation: Speciation,
+    #[serde(default)]
+    pub novelty: NoveltySearch,
+    #[serde(default)]
+    pub pareto: ParetoEvolution,
+    /// Standard deviation of the Gaussian mutation of `Genome::genes`
+    #[serde(default = "default_gene_sigma")]
+    pub gene_sigma: f64,
+    /// How far blend crossover may reach beyond the parents' genes (BLX-α)
+    #[serde(default = "default_blend_alpha")]
+    pub blend_alpha: f64,
+    /// Genomes carry their own mutation rate, which mutates too; starts
+    /// from `mutation_rate`
+    #[serde(default)]
+    pub self_adaptive: bool,
+    /// Learning rate τ of the log-normal rate mutation `rate · exp(τ·N(0, 1))`
+    #[serde(default = "default_adaptation_rate")]
+    pub adaptation_rate: f64,
+}
+
+fn default_adaptation_rate() -> f64 {
+    0.2
+}
+
+fn default_gene_sigma() -> f64 {
+    0.1
+}
+
+fn default_blend_alpha() -> f64 {
+    0.5
+}
+
+/// Standard normal sample (Box-Muller)
+fn gaussian(rng: &mut impl Rng) -> f64 {
+    let u: f64 = 1.0 - rng.gen::<f64>();
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
 }
 
 impl EvolutionEngine {
@@ -16,6 +111,15 @@ impl EvolutionEngine {
             mutation_rate: 0.1,
             crossover_rate: 0.7,
             fitness_threshold: 0.5,
+            selection: SelectionStrategy::default(),
+            elitism: 0,
+            speciation: Speciation::default(),
+            novelty: NoveltySearch::default(),
+            pareto: ParetoEvolution::default(),
+            gene_sigma: default_gene_sigma(),
+            blend_alpha: default_blend_alpha(),
+            self_adaptive: false,
+            adaptation_rate: default_adaptation_rate(),
         }
     }
     
@@ -37,15 +141,72 @@ impl EvolutionEngine {
                 child.add_concept(concept.clone());
             }
         }
+        child.genes = self.blend(&parent1.genes, &parent2.genes);
+        child.controller = self.cross_controllers(&parent1.controller, &parent2.controller);
+        // Geometric mean, the natural middle of log-normal rates
+        child.mutation_rate = match (parent1.mutation_rate, parent2.mutation_rate) {
+            (Some(a), Some(b)) => Some((a * b).sqrt()),
+            (a, b) => a.or(b),
+        };
         
         child
     }
     
+    /// Uniform crossover of two controllers: each weight from either parent;
+    /// a parent without a controller passes on the other's
+    pub fn cross_controllers(&self, controller1: &NeuralController, controller2: &NeuralController) -> NeuralController {
+        match (controller1.is_active(), controller2.is_active()) {
+            (true, true) => {
+                let mut rng = rand::thread_rng();
+                NeuralController {
+                    weights: controller1.weights
+                        .iter()
+                        .zip(&controller2.weights)
+                        .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
+                        .collect(),
+                }
+            }
+            (true, false) => controller1.clone(),
+            (false, _) => controller2.clone(),
+        }
+    }
+    
+    /// Blend crossover of two gene vectors: each child gene is uniform in the
+    /// parents' range widened by `blend_alpha` of its width on both sides;
+    /// missing genes count as neutral
+    pub fn blend(&self, genes1: &[f64], genes2: &[f64]) -> Vec<f64> {
+        let mut rng = rand::thread_rng();
+        (0..GENE_COUNT.max(genes1.len()).max(genes2.len()))
+            .map(|i| {
+                let (a, b) = (genes1.get(i).copied().unwrap_or(0.0), genes2.get(i).copied().unwrap_or(0.0));
+                let spread = (a - b).abs() * self.blend_alpha.max(0.0);
+                let (low, high) = (a.min(b) - spread, a.max(b) + spread);
+                if high > low {
+                    rng.gen_range(low..high)
+                } else {
+                    a
+                }
+            })
+            .collect()
+    }
+    
     /// Mutate genome
+    ///
+    /// With `self_adaptive`, the genome's own rate mutates first (log-normal)
+    /// and is then used instead of `mutation_rate`.
     pub fn mutate(&self, genome: &mut Genome) {
         let mut rng = rand::thread_rng();
+        let (lowest, highest) = MUTATION_RATE_RANGE;
+        let rate = if self.self_adaptive {
+            let rate = genome.mutation_rate.unwrap_or(self.mutation_rate).max(lowest);
+            let rate = (rate * (self.adaptation_rate * gaussian(&mut rng)).exp()).clamp(lowest, highest);
+            genome.mutation_rate = Some(rate);
+            rate
+        } else {
+            self.mutation_rate
+        };
         
-        if rng.gen_bool(self.mutation_rate) {
+        if rng.gen_bool(rate) {
             // Add random concept
             if genome.concepts.len() < genome.max_concepts {
                 let new_concept = format!("mutated_{}", rng.gen::<u32>());
@@ -53,7 +214,7 @@ impl EvolutionEngine {
             }
         }
         
-        if rng.gen_bool(self.mutation_rate) {
+        if rng.gen_bool(rate) {
             // Remove random concept
             if !genome.concepts.is_empty() {
                 let idx = rng.gen_range(0..genome.concepts.len());
@@ -61,13 +222,30 @@ impl EvolutionEngine {
             }
         }
         
-        if rng.gen_bool(self.mutation_rate) {
+        if rng.gen_bool(rate) {
             // Modify random concept
             if !genome.concepts.is_empty() {
                 let idx = rng.gen_range(0..genome.concepts.len());
                 genome.concepts[idx] = format!("{}_mut", genome.concepts[idx]);
             }
         }
+        
+        // Gaussian nudge of every gene, each with the mutation rate
+        if genome.genes.len() < GENE_COUNT {
+            genome.genes.resize(GENE_COUNT, 0.0);
+        }
+        for gene in &mut genome.genes {
+            if rng.gen_bool(rate) {
+                *gene += gaussian(&mut rng) * self.gene_sigma;
+            }
+        }
+        
+        // Controller weights mutate the same way
+        for weight in &mut genome.controller.weights {
+            if rng.gen_bool(rate) {
+                *weight += gaussian(&mut rng) * self.gene_sigma;
+            }
+        }
     }
     
     /// Calculate fitness based on voxel properties
@@ -77,6 +255,9 @@ impl EvolutionEngine {
         // Energy contributes to fitness
         fitness += voxel.energy * 0.3;
         
+        // Feeding success: food for prey, drained energy for predators
+        fitness += voxel.intake() * 0.2;
+        
         // Genome complexity
         fitness += voxel.genome.concepts.len() as f64 * 0.1;
         
@@ -98,41 +279,132 @@ impl EvolutionEngine {
         fitness
     }
     
-    /// Evolve a population of voxels
-    pub fn evolve(&self, voxels: &mut [Voxel]) {
-        // Calculate fitness for all
-        let mut fitness_scores: Vec<(usize, f64)> = voxels.iter()
-            .enumerate()
-            .map(|(i, v)| (i, self.fitness(v)))
-            .collect();
-        
-        // Sort by fitness
-        fitness_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    /// Selection scores for `voxels` with their positions: novelty of their
+    /// behavior when novelty search is enabled (which also grows its archive),
+    /// else Pareto rank when multi-objective evolution is enabled, else `fitness`
+    pub fn scores(&mut self, voxels: &[(&Voxel, [i32; 3])]) -> Vec<f64> {
+        if self.novelty.enabled {
+            let behaviors: Vec<_> = voxels.iter().map(|&(voxel, position)| novelty::behavior(voxel, position)).collect();
+            self.novelty.score(&behaviors)
+        } else if self.pareto.enabled {
+            let points: Vec<_> = voxels.iter().map(|&(voxel, _)| pareto::objectives(voxel)).collect();
+            self.pareto.score(&points)
+        } else {
+            voxels.iter().map(|&(voxel, _)| self.fitness(voxel)).collect()
+        }
+    }
+    
+    /// Offspring genomes for the members that do not survive a generation
+    ///
+    /// `population` holds each member's genome and fitness. With truncation
+    /// the fitter half survives and parents come from it; tournament and
+    /// roulette replace everyone but the `elitism` fittest and draw parents
+    /// from the whole population. The `elitism` fittest always survive.
+    ///
+    /// With speciation enabled, members rank by fitness shared within their
+    /// species, mate only within it, and the champion of a young species
+    /// always survives.
+    ///
+    /// Returns `(index, genome)` for every replaced member. Populations
+    /// smaller than two are left alone.
+    pub fn breed(&mut self, population: &[(&Genome, f64)]) -> Vec<(usize, Genome)> {
+        if population.len() < 2 {
+            return Vec::new();
+        }
+        let mut scored = population.to_vec();
+        let mut protected = vec![false; population.len()];
+        let mut species_members: Vec<Vec<usize>> = Vec::new();
+        let mut species = Vec::new();
+        if self.speciation.enabled {
+            species = self.speciation.speciate(population);
+            species_members = vec![Vec::new(); self.speciation.species.len()];
+            for (i, &index) in species.iter().enumerate() {
+                species_members[index].push(i);
+            }
+            // Shifted to be non-negative before it is shared
+            let least = population.iter().map(|(_, fitness)| *fitness).fold(f64::INFINITY, f64::min);
+            for (member, &index) in scored.iter_mut().zip(&species) {
+                member.1 = (member.1 - least) / species_members[index].len() as f64;
+            }
+            for (index, members) in species_members.iter().enumerate() {
+                if !self.speciation.is_young(index) {
+                    continue;
+                }
+                let champion = members.iter().max_by(|&&a, &&b| population[a].1.total_cmp(&population[b].1));
+                if let Some(&champion) = champion {
+                    protected[champion] = true;
+                }
+            }
+        }
+        let mut ranked: Vec<usize> = (0..scored.len()).collect();
+        ranked.sort_by(|&a, &b| scored[b].1.total_cmp(&scored[a].1));
         
-        // Select top performers
-        let top_count = (voxels.len() / 2).max(1);
+        let survivors = match self.selection {
+            SelectionStrategy::Truncation => population.len() / 2,
+            _ => 0,
+        };
+        let survivors = survivors.max(self.elitism).min(population.len());
         let mut rng = rand::thread_rng();
-        
-        // Create new generation
-        for i in top_count..voxels.len() {
-            let parent1_idx = fitness_scores[rng.gen_range(0..top_count)].0;
-            let parent2_idx = fitness_scores[rng.gen_range(0..top_count)].0;
-            
-            if rng.gen_bool(self.crossover_rate) {
-                // Crossover
-                let mut new_genome = self.combine(
-                    &voxels[parent1_idx].genome,
-                    &voxels[parent2_idx].genome,
-                );
-                self.mutate(&mut new_genome);
-                voxels[i].genome = new_genome;
-            } else {
-                // Mutation only
-                voxels[i].genome = voxels[parent1_idx].genome.clone();
-                self.mutate(&mut voxels[i].genome);
+        ranked[survivors..]
+            .iter()
+            .filter(|&&i| !protected[i])
+            .map(|&i| {
+                let first = self.select(&scored, &ranked, &mut rng);
+                let second = match species.get(first) {
+                    Some(&index) => {
+                        let mates = &species_members[index];
+                        mates[rng.gen_range(0..mates.len())]
+                    }
+                    None => self.select(&scored, &ranked, &mut rng),
+                };
+                let (parent1, parent2) = (population[first].0, population[second].0);
+                let mut genome = if rng.gen_bool(self.crossover_rate) {
+                    self.combine(parent1, parent2)
+                } else {
+                    parent1.clone()
+                };
+                self.mutate(&mut genome);
+                (i, genome)
+            })
+            .collect()
+    }
+    
+    /// Index of one parent by `selection`; `ranked` is the population sorted
+    /// fittest first
+    fn select(&self, population: &[(&Genome, f64)], ranked: &[usize], rng: &mut impl Rng) -> usize {
+        match self.selection {
+            SelectionStrategy::Truncation => ranked[rng.gen_range(0..(ranked.len() / 2).max(1))],
+            SelectionStrategy::Tournament { size } => (0..size.max(1))
+                .map(|_| rng.gen_range(0..population.len()))
+                .max_by(|&a, &b| population[a].1.total_cmp(&population[b].1))
+                .unwrap_or(ranked[0]),
+            SelectionStrategy::Roulette => {
+                // Shifted so the least fit member still has a small chance
+                let least = population.iter().map(|(_, fitness)| *fitness).fold(f64::INFINITY, f64::min);
+                let weights: Vec<f64> = population.iter().map(|(_, fitness)| fitness - least + 1e-6).collect();
+                let mut pick = rng.gen_range(0.0..weights.iter().sum::<f64>());
+                for (i, weight) in weights.iter().enumerate() {
+                    if pick < *weight {
+                        return i;
+                    }
+                    pick -= weight;
+                }
+                ranked[0]
             }
         }
     }
+    
+    /// Evolve a population of voxels; returns the number of replaced genomes
+    pub fn evolve(&mut self, voxels: &mut [VoxelRecord]) -> usize {
+        let scores = self.scores(&voxels.iter().map(|record| (&record.voxel, record.position.0)).collect::<Vec<_>>());
+        let population: Vec<(&Genome, f64)> = voxels.iter().map(|record| &record.voxel.genome).zip(scores).collect();
+        let offspring = self.breed(&population);
+        let replaced = offspring.len();
+        for (i, genome) in offspring {
+            voxels[i].voxel.genome = genome;
+        }
+        replaced
+    }
 }
 
 impl Default for EvolutionEngine {
@@ -140,3 +412,203 @@ impl Default for EvolutionEngine {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::CONTROLLER_WEIGHTS;
+    
+    fn genome(concept: &str) -> Genome {
+        let mut genome = Genome::new();
+        genome.add_concept(concept.to_string());
+        genome
+    }
+    
+    #[test]
+    fn test_selection_strategies_keep_elites() {
+        let genomes: Vec<Genome> = (0..6).map(|i| genome(&format!("g{}", i))).collect();
+        let population: Vec<(&Genome, f64)> = genomes.iter().zip([5.0, 0.0, 4.0, 1.0, 3.0, 2.0]).collect();
+        let mut engine = EvolutionEngine::new();
+        engine.mutation_rate = 0.0;
+        engine.crossover_rate = 0.0;
+        
+        // Truncation: the weaker half is replaced by copies of the fitter half
+        let offspring = engine.breed(&population);
+        let mut replaced: Vec<usize> = offspring.iter().map(|(i, _)| *i).collect();
+        replaced.sort();
+        assert_eq!(replaced, [1, 3, 5]);
+        for (_, child) in &offspring {
+            assert!(["g0", "g2", "g4"].contains(&child.concepts[0].as_str()));
+        }
+        
+        // Tournament and roulette replace all but the elites
+        for selection in [SelectionStrategy::Tournament { size: 6 }, SelectionStrategy::Roulette] {
+            engine.selection = selection;
+            engine.elitism = 2;
+            let offspring = engine.breed(&population);
+            assert_eq!(offspring.len(), 4);
+            assert!(offspring.iter().all(|(i, _)| *i != 0 && *i != 2));
+        }
+        
+        // A tournament over the whole population always picks the fittest
+        engine.selection = SelectionStrategy::Tournament { size: 1000 };
+        for (_, child) in engine.breed(&population) {
+            assert_eq!(child.concepts, ["g0"]);
+        }
+    }
+    
+    #[test]
+    fn test_speciation_protects_young_species() {
+        let light = genome("light");
+        let dark = genome("dark");
+        let population: Vec<(&Genome, f64)> = vec![(&light, 5.0), (&light, 4.0), (&light, 3.0), (&light, 2.0), (&dark, 1.0)];
+        let mut engine = EvolutionEngine::new();
+        engine.mutation_rate = 0.0;
+        engine.crossover_rate = 1.0;
+        engine.speciation.enabled = true;
+        
+        // The lone dark genome is the weakest, but it founded a young species
+        let offspring = engine.breed(&population);
+        assert_eq!(engine.speciation.species.len(), 2);
+        assert!(!offspring.is_empty());
+        assert!(offspring.iter().all(|(i, _)| *i != 4));
+        // Light parents only mate with light ones
+        for (_, child) in &offspring {
+            assert!(child.concepts.iter().all(|concept| concept == "light"));
+        }
+        
+        // Once the species is old, nothing protects it any more
+        engine.speciation.protect_generations = 0;
+        let population: Vec<(&Genome, f64)> = vec![(&light, 5.0), (&light, 4.0), (&dark, -10.0)];
+        let offspring = engine.breed(&population);
+        assert!(offspring.iter().any(|(i, _)| *i == 2));
+    }
+    
+    #[test]
+    fn test_novelty_selection() {
+        let mut voxels: Vec<VoxelRecord> = (0..4).map(|i| VoxelRecord::new(Voxel::new(), [i, 0, 0])).collect();
+        for (i, record) in voxels.iter_mut().enumerate() {
+            record.voxel.genome = genome(&format!("g{}", i));
+            record.voxel.energy = 10.0;
+        }
+        // The weakest voxel by fitness went where nobody else did
+        voxels[3].position.0 = [900, 900, 900];
+        voxels[3].voxel.energy = 0.0;
+        let mut engine = EvolutionEngine::new();
+        engine.mutation_rate = 0.0;
+        engine.crossover_rate = 0.0;
+        engine.novelty.enabled = true;
+        engine.novelty.k = 2;
+        
+        let refs: Vec<(&Voxel, [i32; 3])> = voxels.iter().map(|record| (&record.voxel, record.position.0)).collect();
+        let scores = engine.scores(&refs);
+        assert!(scores[3] > scores[0] && scores[3] > scores[1]);
+        assert!(!engine.novelty.archive.is_empty());
+        
+        engine.evolve(&mut voxels);
+        assert_eq!(voxels[3].voxel.genome.concepts, ["g3"]);
+    }
+    
+    #[test]
+    fn test_gene_crossover_and_mutation() {
+        let mut engine = EvolutionEngine::new();
+        let (mut mother, mut father) = (Genome::new(), Genome::new());
+        mother.genes = vec![0.0, 1.0, -1.0];
+        father.genes = vec![1.0, 1.0];
+        for _ in 0..100 {
+            let child = engine.combine(&mother, &father).genes;
+            assert_eq!(child.len(), GENE_COUNT);
+            assert!((-0.5..=1.5).contains(&child[0]));
+            assert_eq!(child[1], 1.0);
+            assert!((-1.5..=0.5).contains(&child[2]));
+        }
+        
+        engine.mutation_rate = 1.0;
+        engine.gene_sigma = 0.5;
+        let mut genome = Genome::new();
+        genome.genes.clear();
+        engine.mutate(&mut genome);
+        assert_eq!(genome.genes.len(), GENE_COUNT);
+        assert!(genome.genes.iter().any(|&gene| gene != 0.0));
+        
+        // Genes are multipliers around 1.0
+        genome.genes = vec![0.0, 10.0, (0.5f64).ln()];
+        let traits = genome.traits();
+        assert_eq!(traits.acuity, 1.0);
+        assert_eq!(traits.sociability, 2.0f64.exp());
+        assert!((traits.metabolism - 0.5).abs() < 1e-12);
+    }
+    
+    #[test]
+    fn test_controller_crossover_and_mutation() {
+        let mut engine = EvolutionEngine::new();
+        let zeros = NeuralController { weights: vec![0.0; CONTROLLER_WEIGHTS] };
+        let ones = NeuralController { weights: vec![1.0; CONTROLLER_WEIGHTS] };
+        let child = engine.cross_controllers(&zeros, &ones);
+        assert!(child.is_active());
+        assert!(child.weights.iter().all(|&w| w == 0.0 || w == 1.0));
+        assert_eq!(engine.cross_controllers(&NeuralController::default(), &ones), ones);
+        assert!(!engine.cross_controllers(&NeuralController::default(), &NeuralController::default()).is_active());
+        
+        engine.mutation_rate = 1.0;
+        let mut genome = Genome::new();
+        genome.controller = zeros;
+        engine.mutate(&mut genome);
+        assert!(genome.controller.weights.iter().any(|&w| w != 0.0));
+        assert_eq!(genome.controller.weights.len(), CONTROLLER_WEIGHTS);
+    }
+    
+    #[test]
+    fn test_self_adaptive_mutation_rate() {
+        let mut engine = EvolutionEngine::new();
+        let mut genome = Genome::new();
+        engine.mutate(&mut genome);
+        assert_eq!(genome.mutation_rate, None);
+        
+        engine.self_adaptive = true;
+        let genomes: Vec<Genome> = (0..50)
+            .map(|_| {
+                let mut genome = Genome::new();
+                engine.mutate(&mut genome);
+                genome
+            })
+            .collect();
+        let stats = MutationRateStats::of(&genomes);
+        assert_eq!(stats.count, 50);
+        assert!(stats.min < stats.mean && stats.mean < stats.max);
+        assert!(stats.min >= MUTATION_RATE_RANGE.0 && stats.max <= MUTATION_RATE_RANGE.1);
+        assert_eq!(stats.histogram.iter().sum::<usize>(), 50);
+        assert_eq!(MutationRateStats::of(&[Genome::new()]), MutationRateStats::default());
+        
+        // Children inherit the geometric mean of their parents' rates
+        let (mut mother, mut father) = (Genome::new(), Genome::new());
+        mother.mutation_rate = Some(0.01);
+        father.mutation_rate = Some(0.04);
+        assert!((engine.combine(&mother, &father).mutation_rate.unwrap() - 0.02).abs() < 1e-12);
+        assert_eq!(engine.combine(&mother, &Genome::new()).mutation_rate, Some(0.01));
+    }
+    
+    #[test]
+    fn test_pareto_selection() {
+        let mut voxels: Vec<VoxelRecord> = (0..4).map(|_| VoxelRecord::new(Voxel::new(), [0, 0, 0])).collect();
+        for (i, record) in voxels.iter_mut().enumerate() {
+            record.voxel.genome = genome(&format!("g{}", i));
+        }
+        // Rich but young, poor but old, and two dominated ones
+        voxels[0].voxel.energy = 10.0;
+        voxels[1].voxel.age_ticks = 1000;
+        voxels[2].voxel.energy = 1.0;
+        let mut engine = EvolutionEngine::new();
+        engine.mutation_rate = 0.0;
+        engine.crossover_rate = 0.0;
+        engine.pareto.enabled = true;
+        
+        engine.evolve(&mut voxels);
+        assert_eq!(engine.pareto.front.len(), 2);
+        assert_eq!(voxels[0].voxel.genome.concepts, ["g0"]);
+        assert_eq!(voxels[1].voxel.genome.concepts, ["g1"]);
+        for record in &voxels[2..] {
+            assert!(["g0", "g1"].contains(&record.voxel.genome.concepts[0].as_str()));
+        }
+    }
+}
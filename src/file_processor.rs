@@ -1,10 +1,140 @@
+use crate::ai_model::AIModel;
+use crate::language::{self, Language};
+use crate::sentences::split_sentences;
+use crate::text_cleaner::TextCleaner;
+use encoding_rs::{CoderResult, Decoder, Encoding, KOI8_R, UTF_8, WINDOWS_1251};
+use pdf::content::{Op, TextDrawAdjusted};
+use pdf::file::FileOptions;
+use pdf::font::ToUnicodeMap;
+use pdf::primitive::PdfString;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::{Cursor, Read};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
-use std::io::Read;
 
 /// Обработчик файлов для загрузки обучающих данных
+#[derive(Clone)]
 pub struct FileProcessor {
     pub supported_extensions: Vec<String>,
+    pub csv: CsvOptions,
+    pub chunking: ChunkingOptions,
+    /// Очистка текста перед извлечением примеров (`prepare_training_data`)
+    pub cleaner: TextCleaner,
+    /// Оставлять только примеры на этом языке (`None` — все примеры)
+    pub language: Option<Language>,
+    pub extraction_mode: ExtractionMode,
+    /// Папка кэша извлечённого из PDF текста (`None` — без кэша)
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// Способ нарезки текста на обучающие примеры
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtractionMode {
+    /// Абзацы или предложения
+    #[default]
+    Text,
+    /// Пары реплик «вопрос — ответ» (см. `extract_dialogue_pairs`)
+    Dialogue,
+}
+
+/// Префиксы реплик вопроса и ответа в расшифровках диалогов (без учёта регистра)
+const QUESTION_PREFIXES: [&str; 6] = ["q", "вопрос", "user", "пользователь", "human", "человек"];
+const ANSWER_PREFIXES: [&str; 6] = ["a", "ответ", "assistant", "ассистент", "bot", "бот"];
+
+/// Настройки чтения CSV
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    /// Колонки с текстом: имя из заголовка или номер с 1 (пусто — все колонки)
+    pub text_columns: Vec<String>,
+    /// Колонки вопроса и ответа: каждая строка становится диалоговым примером
+    pub dialogue_columns: Option<(String, String)>,
+    /// Первая строка — заголовок
+    pub has_header: bool,
+}
+
+/// Ограничения длины примеров в `extract_training_data` (в символах)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkingOptions {
+    /// Более короткие абзацы и предложения отбрасываются
+    pub min_length: usize,
+    /// Более длинные примеры режутся скользящим окном по границам слов (0 — не резать)
+    pub max_length: usize,
+    /// Сколько символов соседние окна делят между собой
+    pub overlap: usize,
+}
+
+impl Default for ChunkingOptions {
+    fn default() -> Self {
+        Self {
+            min_length: 4,
+            max_length: 0,
+            overlap: 0,
+        }
+    }
+}
+
+impl ChunkingOptions {
+    /// Окна не длиннее `max_length` символов с перекрытием `overlap`
+    ///
+    /// Окна начинаются и заканчиваются на границах слов; слово длиннее окна
+    /// становится отдельным окном. Каждое следующее окно сдвигается хотя бы на
+    /// одно слово, поэтому перекрытие не больше `max_length` не зацикливает разбиение.
+    pub fn windows<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        if self.max_length == 0 || text.chars().count() <= self.max_length {
+            return vec![text];
+        }
+        
+        // Слова как (начало, конец) в байтах и начало в символах
+        let mut words = Vec::new();
+        let mut word_start = None;
+        for (chars, (idx, c)) in text.char_indices().enumerate() {
+            match (word_start, c.is_whitespace()) {
+                (None, false) => word_start = Some((idx, chars)),
+                (Some((start, start_chars)), true) => {
+                    words.push((start, idx, start_chars, chars));
+                    word_start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some((start, start_chars)) = word_start {
+            words.push((start, text.len(), start_chars, text.chars().count()));
+        }
+        
+        let mut windows = Vec::new();
+        let mut first = 0;
+        while first < words.len() {
+            let (start, _, start_chars, _) = words[first];
+            let mut last = first;
+            while last + 1 < words.len() && words[last + 1].3 - start_chars <= self.max_length {
+                last += 1;
+            }
+            windows.push(&text[start..words[last].1]);
+            if last + 1 == words.len() {
+                break;
+            }
+            
+            let overlap_from = words[last].3.saturating_sub(self.overlap);
+            first = (first + 1..=last)
+                .find(|&idx| words[idx].2 >= overlap_from)
+                .unwrap_or(last + 1);
+        }
+        windows
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            text_columns: Vec::new(),
+            dialogue_columns: None,
+            has_header: true,
+        }
+    }
 }
 
 impl FileProcessor {
@@ -18,11 +148,15 @@ impl FileProcessor {
                 "csv".to_string(),
                 "log".to_string(),
                 "xml".to_string(),
+                // Субтитры
+                "srt".to_string(),
+                "vtt".to_string(),
                 // Код
                 "rs".to_string(),
                 "py".to_string(),
                 "js".to_string(),
                 "html".to_string(),
+                "htm".to_string(),
                 "css".to_string(),
                 "java".to_string(),
                 "cpp".to_string(),
@@ -32,6 +166,12 @@ impl FileProcessor {
                 "djvu".to_string(),
                 "djv".to_string(),
             ],
+            csv: CsvOptions::default(),
+            chunking: ChunkingOptions::default(),
+            cleaner: TextCleaner::default(),
+            language: None,
+            extraction_mode: ExtractionMode::Text,
+            cache_dir: None,
         }
     }
     
@@ -59,39 +199,549 @@ impl FileProcessor {
         match ext.as_str() {
             "pdf" => self.read_pdf(path),
             "djvu" | "djv" => self.read_djvu(path),
-            _ => {
-                // Обычные текстовые файлы
-                match fs::read_to_string(path) {
-                    Ok(content) => Ok(content),
-                    Err(e) => Err(format!("Ошибка чтения файла: {}", e)),
+            "html" | "htm" => self.read_text(path).map(|content| Self::html_to_text(&content)),
+            "csv" => self.read_text(path).and_then(|content| self.csv_to_text(&content)),
+            "srt" | "vtt" => self.read_text(path).map(|content| Self::subtitles_to_text(&content)),
+            // Обычные текстовые файлы
+            _ => self.read_text(path),
+        }
+    }
+    
+    /// Чтение текстового файла в любой из поддерживаемых кодировок
+    fn read_text(&self, path: &Path) -> Result<String, String> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(Self::decode_text(&bytes).0),
+            Err(e) => Err(format!("Ошибка чтения файла: {}", e)),
+        }
+    }
+    
+    /// Декодирование текста с определением кодировки (см. `detect_encoding`)
+    ///
+    /// Возвращает текст и имя кодировки.
+    pub fn decode_text(bytes: &[u8]) -> (String, &'static str) {
+        let (text, encoding, _) = Self::detect_encoding(bytes).decode(bytes);
+        (text.into_owned(), encoding.name())
+    }
+    
+    /// Определение кодировки по началу файла
+    ///
+    /// UTF-8/UTF-16 определяются по BOM или валидности, для остальных байтов
+    /// выбирается Windows-1251 или KOI8-R — та, в которой больше частых
+    /// строчных русских букв.
+    pub fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            return encoding;
+        }
+        match std::str::from_utf8(bytes) {
+            // Обрезанный в конце символ — тоже UTF-8 (образец может быть началом файла)
+            Ok(_) => return UTF_8,
+            Err(e) if e.error_len().is_none() => return UTF_8,
+            Err(_) => {}
+        }
+        
+        // В KOI8-R строчные и заглавные буквы переставлены относительно CP1251,
+        // поэтому неверная кодировка даёт почти сплошь заглавные
+        let score = |text: &str| text.chars().filter(|c| "оеаинтсрвлкмдпу".contains(*c)).count();
+        let (cp1251, _) = WINDOWS_1251.decode_without_bom_handling(bytes);
+        let (koi8, _) = KOI8_R.decode_without_bom_handling(bytes);
+        if score(&koi8) > score(&cp1251) { KOI8_R } else { WINDOWS_1251 }
+    }
+    
+    /// Потоковое чтение файла кусками около `chunk_size` байт по границам абзацев
+    ///
+    /// Текстовые файлы читаются блоками без загрузки целиком; PDF, HTML, CSV и
+    /// DJVU сначала извлекаются через `read_file`, затем делятся на куски.
+    pub fn read_chunks(&self, path: &Path, chunk_size: usize) -> Result<TextChunks<Box<dyn Read>>, String> {
+        if !self.is_supported(path) {
+            return Err(format!("Неподдерживаемый формат файла: {:?}", path.extension()));
+        }
+        
+        let ext = path.extension()
+            .and_then(|e| e.to_str())
+            .map(|s| s.to_lowercase())
+            .unwrap_or_default();
+        let reader: Box<dyn Read> = match ext.as_str() {
+            "pdf" | "djvu" | "djv" | "html" | "htm" | "csv" | "srt" | "vtt" => Box::new(Cursor::new(self.read_file(path)?.into_bytes())),
+            _ => Box::new(fs::File::open(path).map_err(|e| format!("Ошибка чтения файла: {}", e))?),
+        };
+        TextChunks::new(reader, chunk_size).map_err(|e| format!("Ошибка чтения файла: {}", e))
+    }
+    
+    /// Текст из выбранных колонок CSV: по абзацу на строку таблицы
+    ///
+    /// В режиме диалога абзац — вопрос и ответ на отдельных строках
+    /// (см. `dialogue_example`); строки с пустым вопросом или ответом пропускаются.
+    pub fn csv_to_text(&self, content: &str) -> Result<String, String> {
+        let mut rows = Self::parse_csv(content).into_iter();
+        let header = if self.csv.has_header { rows.next().unwrap_or_default() } else { Vec::new() };
+        let column = |name: &str| Self::csv_column(&header, name);
+        
+        let examples: Vec<String> = if let Some((question, answer)) = &self.csv.dialogue_columns {
+            let (question, answer) = (column(question)?, column(answer)?);
+            rows.filter_map(|row| {
+                let question = row.get(question).map(|s| s.trim()).filter(|s| !s.is_empty())?;
+                let answer = row.get(answer).map(|s| s.trim()).filter(|s| !s.is_empty())?;
+                Some(Self::dialogue_example(question, answer))
+            })
+            .collect()
+        } else {
+            let columns = self
+                .csv
+                .text_columns
+                .iter()
+                .map(|name| column(name))
+                .collect::<Result<Vec<_>, _>>()?;
+            rows.map(|row| {
+                let cells: Vec<&str> = if columns.is_empty() {
+                    row.iter().map(|cell| cell.trim()).collect()
+                } else {
+                    columns.iter().filter_map(|&idx| row.get(idx)).map(|cell| cell.trim()).collect()
+                };
+                cells.into_iter().filter(|cell| !cell.is_empty()).collect::<Vec<_>>().join(" ")
+            })
+            .filter(|text| !text.is_empty())
+            .collect()
+        };
+        
+        Ok(examples.join("\n\n"))
+    }
+    
+    /// Текст субтитров SRT/VTT: по предложению на строку
+    ///
+    /// Из каждого блока берутся строки после строки тайминга (`-->`), так что
+    /// номера и идентификаторы реплик, заголовок `WEBVTT` и блоки `NOTE`/`STYLE`
+    /// пропускаются; теги (`<i>`, `{\an8}`) удаляются. Строки реплик
+    /// склеиваются, пока предложение не закончится знаком препинания; строка,
+    /// начинающаяся с тире, начинает новую реплику.
+    pub fn subtitles_to_text(content: &str) -> String {
+        let mut sentences = Vec::new();
+        let mut current = String::new();
+        let mut in_cue = false;
+        
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() {
+                in_cue = false;
+                continue;
+            }
+            if line.contains("-->") {
+                in_cue = true;
+                continue;
+            }
+            if !in_cue {
+                continue;
+            }
+            
+            let text = Self::strip_subtitle_tags(line);
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if text.starts_with(['-', '—']) && !current.is_empty() {
+                sentences.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(text.trim_start_matches(['-', '—']).trim_start());
+            if current.ends_with(['.', '!', '?', '…', '"', '»']) {
+                sentences.push(std::mem::take(&mut current));
+            }
+        }
+        if !current.is_empty() {
+            sentences.push(current);
+        }
+        
+        Self::decode_html_entities(&sentences.join("\n"))
+    }
+    
+    /// Удаление тегов `<...>` и команд оформления `{...}` из строки субтитров
+    fn strip_subtitle_tags(line: &str) -> String {
+        let mut text = String::with_capacity(line.len());
+        let mut closing = None;
+        for c in line.chars() {
+            match (closing, c) {
+                (None, '<') => closing = Some('>'),
+                (None, '{') => closing = Some('}'),
+                (None, c) => text.push(c),
+                (Some(end), c) if c == end => closing = None,
+                _ => {}
+            }
+        }
+        text
+    }
+    
+    /// Диалоговый пример: реплика и ответ на соседних строках
+    pub fn dialogue_example(question: &str, answer: &str) -> String {
+        format!("{}\n{}", question.trim(), answer.trim())
+    }
+    
+    /// Индекс колонки по имени из заголовка (без учёта регистра) или номеру с 1
+    fn csv_column(header: &[String], name: &str) -> Result<usize, String> {
+        let name = name.trim();
+        let lower = name.to_lowercase();
+        if let Some(idx) = header.iter().position(|h| h.trim().to_lowercase() == lower) {
+            return Ok(idx);
+        }
+        match name.parse::<usize>() {
+            Ok(number) if number > 0 => Ok(number - 1),
+            _ => Err(format!(
+                "✗ Колонка «{}» не найдена в CSV. Доступные колонки: {}",
+                name,
+                if header.is_empty() { "номера с 1".to_string() } else { header.join(", ") }
+            )),
+        }
+    }
+    
+    /// Разбор CSV (RFC 4180): кавычки, `""` внутри кавычек, переводы строк в ячейках
+    ///
+    /// Разделитель — `,`, `;` или табуляция, выбирается по первой строке.
+    pub fn parse_csv(content: &str) -> Vec<Vec<String>> {
+        let first_line = content.lines().next().unwrap_or_default();
+        let delimiter = [',', ';', '\t']
+            .into_iter()
+            .max_by_key(|&d| first_line.matches(d).count())
+            .unwrap_or(',');
+        
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut cell = String::new();
+        let mut in_quotes = false;
+        let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+        
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if in_quotes && chars.peek() == Some(&'"') => {
+                    cell.push('"');
+                    chars.next();
+                }
+                '"' if in_quotes => in_quotes = false,
+                '"' if cell.is_empty() => in_quotes = true,
+                c if c == delimiter && !in_quotes => row.push(std::mem::take(&mut cell)),
+                '\r' if !in_quotes => {}
+                '\n' if !in_quotes => {
+                    row.push(std::mem::take(&mut cell));
+                    if row.iter().any(|cell| !cell.is_empty()) {
+                        rows.push(std::mem::take(&mut row));
+                    }
+                    row.clear();
                 }
+                c => cell.push(c),
+            }
+        }
+        row.push(cell);
+        if row.iter().any(|cell| !cell.is_empty()) {
+            rows.push(row);
+        }
+        rows
+    }
+    
+    /// Читаемый текст HTML-страницы без разметки
+    ///
+    /// Скрипты, стили и комментарии отбрасываются, блочные теги дают границы
+    /// абзацев (`\n\n`), `<br>` — перевод строки, сущности декодируются.
+    pub fn html_to_text(html: &str) -> String {
+        const BLOCK_TAGS: &[&str] = &[
+            "p", "div", "section", "article", "header", "footer", "main", "aside", "nav",
+            "h1", "h2", "h3", "h4", "h5", "h6", "li", "ul", "ol", "dl", "dt", "dd",
+            "table", "tr", "blockquote", "pre", "hr", "title", "figure", "figcaption", "body",
+        ];
+        const SKIPPED_TAGS: &[&str] = &["script", "style", "noscript", "template", "svg"];
+        
+        let mut paragraphs = Vec::new();
+        let mut current = String::new();
+        let mut rest = html;
+        
+        while let Some(start) = rest.find('<') {
+            current.push_str(&rest[..start]);
+            rest = &rest[start..];
+            
+            if rest.starts_with("<!--") {
+                rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+                continue;
+            }
+            let Some(end) = rest.find('>') else {
+                rest = "";
+                break;
+            };
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            
+            let closing = tag.starts_with('/');
+            let name = tag
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            
+            if SKIPPED_TAGS.contains(&name.as_str()) && !closing {
+                // to_ascii_lowercase сохраняет байтовые смещения
+                let close = format!("</{}", name);
+                rest = rest.to_ascii_lowercase().find(&close).map_or("", |pos| &rest[pos..]);
+            } else if name == "br" {
+                current.push('\n');
+            } else if BLOCK_TAGS.contains(&name.as_str()) {
+                Self::flush_html_paragraph(&mut current, &mut paragraphs);
             }
         }
+        current.push_str(rest);
+        Self::flush_html_paragraph(&mut current, &mut paragraphs);
+        
+        paragraphs.join("\n\n")
+    }
+    
+    /// Абзац без лишних пробелов (переводы строк от `<br>` сохраняются)
+    fn flush_html_paragraph(current: &mut String, paragraphs: &mut Vec<String>) {
+        let decoded = Self::decode_html_entities(current);
+        let paragraph = decoded
+            .split('\n')
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !paragraph.is_empty() {
+            paragraphs.push(paragraph);
+        }
+        current.clear();
+    }
+    
+    /// Декодирование `&amp;`, `&#1087;`, `&#x43F;` и распространённых именованных сущностей
+    pub(crate) fn decode_html_entities(text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        
+        while let Some(start) = rest.find('&') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+            
+            let decoded = rest
+                .char_indices()
+                .take(12)
+                .find(|&(_, c)| c == ';')
+                .and_then(|(end, _)| {
+                    let entity = &rest[1..end];
+                    let c = match entity {
+                        "amp" => '&',
+                        "lt" => '<',
+                        "gt" => '>',
+                        "quot" => '"',
+                        "apos" => '\'',
+                        "nbsp" => ' ',
+                        "laquo" => '«',
+                        "raquo" => '»',
+                        "mdash" => '—',
+                        "ndash" => '–',
+                        "hellip" => '…',
+                        "copy" => '©',
+                        _ => {
+                            let code = match entity.strip_prefix('#')? {
+                                hex if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16).ok()?,
+                                dec => dec.parse().ok()?,
+                            };
+                            char::from_u32(code)?
+                        }
+                    };
+                    Some((c, end))
+                });
+            
+            match decoded {
+                Some((c, end)) => {
+                    result.push(c);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    result.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        result.push_str(rest);
+        result
     }
     
     /// Чтение PDF файла
+    ///
+    /// Текст извлекается разбором документа (сжатые потоки, ToUnicode для
+    /// CID-шрифтов); если разбор не удался, используется поиск текстовых блоков.
     fn read_pdf(&self, path: &Path) -> Result<String, String> {
         match fs::read(path) {
-            Ok(bytes) => {
-                let text = Self::extract_text_from_pdf_bytes(&bytes);
-                if text.is_empty() {
-                    Ok(format!(
+            Ok(bytes) => Ok(self
+                .cached(path, &bytes, || Self::pdf_extract(&bytes))
+                .unwrap_or_else(|| Self::pdf_placeholder(path, bytes.len()))),
+            Err(e) => Err(format!("Ошибка чтения PDF файла: {}", e))
+        }
+    }
+    
+    /// Текст PDF разбором документа или поиском текстовых блоков; `None`, если текста не нашлось
+    fn pdf_extract(bytes: &[u8]) -> Option<String> {
+        if let Some(text) = Self::parse_pdf_text(bytes) {
+            return Some(text);
+        }
+        let text = Self::extract_text_from_pdf_bytes(bytes);
+        (!text.is_empty()).then(|| {
+            format!("📄 PDF текст (базовое извлечение):\n\n{}\n\n\
+                     ℹ️ Извлечено методом поиска текстовых блоков", text)
+        })
+    }
+    
+    /// Подсказка вместо текста, когда извлечь его не удалось
+    fn pdf_placeholder(path: &Path, size: usize) -> String {
+        format!(
                         "📄 PDF файл загружен ({} байт)\n\n\
                          ⚠️ Автоматическое извлечение текста из PDF может быть неполным.\n\n\
                          💡 Для лучшего качества обучения:\n\
                          1. Конвертируйте PDF → TXT онлайн\n\
                          2. Или используйте текстовый редактор для копирования\n\
                          3. Сохраните как .txt файл и загрузите снова\n\n\
-                         Файл: {:?}",
-                        bytes.len(),
-                        path.file_name().unwrap_or_default()
-                    ))
-                } else {
-                    Ok(format!("📄 PDF текст (базовое извлечение):\n\n{}\n\n\
-                               ℹ️ Извлечено методом поиска текстовых блоков", text))
+                 Файл: {:?}",
+            size,
+            path.file_name().unwrap_or_default()
+        )
+    }
+    
+    /// Извлечённый текст из кэша в `cache_dir` или результат `extract`, сохраняемый в кэш
+    ///
+    /// Ключ — хеш содержимого, время изменения и размер файла, поэтому
+    /// изменённый файл разбирается заново. Неудачное извлечение (`None`) не
+    /// кэшируется. Ошибки записи кэша не мешают загрузке.
+    fn cached(&self, path: &Path, bytes: &[u8], extract: impl FnOnce() -> Option<String>) -> Option<String> {
+        let Some(dir) = &self.cache_dir else {
+            return extract();
+        };
+        
+        let modified = fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |time| time.as_secs());
+        let cache_path = dir.join(format!("{:016x}-{:x}-{}.txt", Self::content_hash(bytes), modified, bytes.len()));
+        
+        if let Ok(text) = fs::read_to_string(&cache_path) {
+            return Some(text);
+        }
+        let text = extract()?;
+        if fs::create_dir_all(dir).is_ok() {
+            let _ = fs::write(&cache_path, &text);
+        }
+        Some(text)
+    }
+    
+    /// Хеш FNV-1a содержимого файла
+    pub fn content_hash(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+    }
+    
+    /// Удаление всех файлов кэша; возвращает число удалённых
+    pub fn clear_cache(&self) -> Result<usize, String> {
+        let Some(dir) = &self.cache_dir else {
+            return Ok(0);
+        };
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(format!("Ошибка очистки кэша: {}", e)),
+        };
+        
+        let mut removed = 0;
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "txt") && fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+    
+    /// Извлечение текста через парсер PDF; `None`, если документ не разобран или текста нет
+    fn parse_pdf_text(bytes: &[u8]) -> Option<String> {
+        // Парсер паникует на некоторых повреждённых файлах
+        let text = panic::catch_unwind(AssertUnwindSafe(|| {
+            let file = FileOptions::uncached().load(bytes.to_vec()).ok()?;
+            let mut pages = Vec::new();
+            for page in file.pages() {
+                let Ok(page) = page else { continue };
+                let Some(contents) = &page.contents else { continue };
+                let Ok(ops) = contents.operations(&file) else { continue };
+                
+                // Шрифты страницы: таблица ToUnicode и признак двухбайтовых кодов
+                let mut fonts: HashMap<String, (Option<ToUnicodeMap>, bool)> = HashMap::new();
+                if let Ok(resources) = page.resources() {
+                    for (name, font) in resources.fonts() {
+                        let to_unicode = font.to_unicode(&file).and_then(Result::ok);
+                        fonts.insert(name.to_string(), (to_unicode, font.is_cid()));
+                    }
+                }
+                
+                let text = Self::text_from_ops(&ops, &fonts);
+                if !text.trim().is_empty() {
+                    pages.push(text.trim().to_string());
                 }
             }
-            Err(e) => Err(format!("Ошибка чтения PDF файла: {}", e))
+            Some(pages.join("\n\n"))
+        }))
+        .ok()
+        .flatten()?;
+        
+        (!text.trim().is_empty()).then_some(text)
+    }
+    
+    /// Текст страницы из операторов содержимого: строки по сдвигам позиции, пробелы по отступам TJ
+    fn text_from_ops(ops: &[Op], fonts: &HashMap<String, (Option<ToUnicodeMap>, bool)>) -> String {
+        let mut text = String::new();
+        let mut font = None;
+        let mut line_y = None;
+        
+        let new_line = |text: &mut String| {
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+        };
+        
+        for op in ops {
+            match op {
+                Op::TextFont { name, .. } => font = fonts.get(name.as_str()),
+                Op::TextNewline => new_line(&mut text),
+                Op::MoveTextPosition { translation } if translation.y.abs() > f32::EPSILON => new_line(&mut text),
+                Op::SetTextMatrix { matrix } => {
+                    if line_y.is_some_and(|y: f32| (y - matrix.f).abs() > 1.0) {
+                        new_line(&mut text);
+                    }
+                    line_y = Some(matrix.f);
+                }
+                Op::TextDraw { text: string } => text.push_str(&Self::decode_pdf_string(string, font)),
+                Op::TextDrawAdjusted { array } => {
+                    for item in array {
+                        match item {
+                            TextDrawAdjusted::Text(string) => text.push_str(&Self::decode_pdf_string(string, font)),
+                            // Большой отступ внутри TJ — пробел между словами
+                            TextDrawAdjusted::Spacing(gap) if *gap < -200.0 && !text.ends_with(' ') => text.push(' '),
+                            TextDrawAdjusted::Spacing(_) => {}
+                        }
+                    }
+                }
+                Op::EndText if !text.is_empty() && !text.ends_with(char::is_whitespace) => text.push(' '),
+                _ => {}
+            }
+        }
+        
+        text
+    }
+    
+    /// Декодирование строки по ToUnicode текущего шрифта (коды по 2 байта у CID-шрифтов)
+    fn decode_pdf_string(string: &PdfString, font: Option<&(Option<ToUnicodeMap>, bool)>) -> String {
+        let bytes = string.as_bytes();
+        match font {
+            Some((Some(to_unicode), is_cid)) => {
+                let codes: Vec<u16> = if *is_cid {
+                    bytes.chunks(2).map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)])).collect()
+                } else {
+                    bytes.iter().map(|&b| b as u16).collect()
+                };
+                codes.into_iter().filter_map(|code| to_unicode.get(code)).collect()
+            }
+            // Двухбайтовые коды без таблицы не декодировать
+            Some((None, true)) => String::new(),
+            _ => string.to_string_lossy(),
         }
     }
     
@@ -152,14 +802,12 @@ impl FileProcessor {
             Err(e) => return Err(format!("Ошибка чтения директории: {}", e)),
         };
         
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_file() && self.is_supported(&path) {
-                    match self.read_file(&path) {
-                        Ok(content) => files_content.push((path, content)),
-                        Err(e) => eprintln!("Пропуск файла {:?}: {}", path, e),
-                    }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() && self.is_supported(&path) {
+                match self.read_file(&path) {
+                    Ok(content) => files_content.push((path, content)),
+                    Err(e) => eprintln!("Пропуск файла {:?}: {}", path, e),
                 }
             }
         }
@@ -168,37 +816,156 @@ impl FileProcessor {
     }
     
     /// Извлечение обучающих примеров из текста
+    ///
+    /// Границы длины и нарезка длинных примеров окнами задаются `chunking`.
     pub fn extract_training_data(&self, content: &str) -> Vec<String> {
         // Разбиваем на предложения/абзацы
         let mut examples = Vec::new();
+        let long_enough = |text: &&str| !text.is_empty() && text.chars().count() >= self.chunking.min_length;
         
         // Разбивка по абзацам
-        for paragraph in content.split("\n\n") {
-            let trimmed = paragraph.trim();
-            if !trimmed.is_empty() && trimmed.len() > 3 {  // Уменьшили с 10 до 3
-                examples.push(trimmed.to_string());
-            }
+        for paragraph in content.split("\n\n").map(str::trim).filter(long_enough) {
+            examples.extend(self.chunking.windows(paragraph).into_iter().map(str::to_string));
         }
         
         // Если абзацев мало, разбиваем по предложениям
         if examples.len() < 3 {  // Уменьшили с 5 до 3
             examples.clear();
-            for sentence in content.split(&['.', '!', '?', '\n'][..]) {
-                let trimmed = sentence.trim();
-                if !trimmed.is_empty() && trimmed.len() > 3 {  // Уменьшили с 10 до 3
-                    examples.push(trimmed.to_string());
-                }
+            for sentence in split_sentences(content).into_iter().filter(long_enough) {
+                examples.extend(self.chunking.windows(sentence).into_iter().map(str::to_string));
             }
         }
         
         // Если всё ещё мало, берём весь текст целиком
         if examples.is_empty() && !content.trim().is_empty() {
-            examples.push(content.trim().to_string());
+            examples.extend(self.chunking.windows(content.trim()).into_iter().map(str::to_string));
         }
         
         examples
     }
     
+    /// Диалоговые примеры из текста с репликами `Q:`/`A:`, `Вопрос:`/`Ответ:`
+    /// или `User:`/`Assistant:`
+    ///
+    /// Строки без префикса продолжают предыдущую реплику до пустой строки.
+    /// Подряд идущие реплики одной стороны склеиваются, ответ без вопроса
+    /// пропускается. Пары оформляются через `dialogue_example`.
+    pub fn extract_dialogue_pairs(content: &str) -> Vec<String> {
+        let mut pairs = Vec::new();
+        let mut question = String::new();
+        let mut answer = String::new();
+        // Кому принадлежат строки без префикса: true — вопросу, false — ответу
+        let mut current: Option<bool> = None;
+        
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() {
+                current = None;
+                continue;
+            }
+            
+            let (is_question, text) = match Self::dialogue_turn(line) {
+                Some((is_question, text)) => {
+                    // Новый вопрос или ответ после пустой строки начинают следующую пару
+                    if !answer.is_empty() && (is_question || current.is_none()) {
+                        pairs.push(Self::dialogue_example(&question, &answer));
+                        question.clear();
+                        answer.clear();
+                    }
+                    (is_question, text)
+                }
+                None => match current {
+                    Some(is_question) => (is_question, line),
+                    None => continue,
+                },
+            };
+            
+            if !is_question && question.is_empty() {
+                current = None;
+                continue;
+            }
+            let turn = if is_question { &mut question } else { &mut answer };
+            if !turn.is_empty() && !text.is_empty() {
+                turn.push(' ');
+            }
+            turn.push_str(text);
+            current = Some(is_question);
+        }
+        
+        if !question.is_empty() && !answer.is_empty() {
+            pairs.push(Self::dialogue_example(&question, &answer));
+        }
+        pairs
+    }
+    
+    /// Сторона реплики по префиксу `Роль:` и текст после него
+    fn dialogue_turn(line: &str) -> Option<(bool, &str)> {
+        let (prefix, text) = line.split_once(':')?;
+        let prefix = prefix.trim().to_lowercase();
+        if QUESTION_PREFIXES.contains(&prefix.as_str()) {
+            Some((true, text.trim()))
+        } else if ANSWER_PREFIXES.contains(&prefix.as_str()) {
+            Some((false, text.trim()))
+        } else {
+            None
+        }
+    }
+    
+    /// Очистка текста настроенным `cleaner` и извлечение обучающих примеров
+    /// в режиме `extraction_mode`
+    pub fn prepare_training_data(&self, content: &str) -> Vec<String> {
+        let cleaned;
+        let content = if self.cleaner.is_enabled() {
+            cleaned = self.cleaner.clean(content);
+            cleaned.as_str()
+        } else {
+            content
+        };
+        
+        match self.extraction_mode {
+            ExtractionMode::Text => self.extract_training_data(content),
+            ExtractionMode::Dialogue => Self::extract_dialogue_pairs(content),
+        }
+    }
+    
+    /// Отбор примеров языка `language`, отброшенные считаются в `discarded`
+    pub fn filter_language(&self, examples: Vec<String>, discarded: &mut BTreeMap<Language, usize>) -> Vec<String> {
+        match self.language {
+            Some(keep) => language::filter_by_language(examples, keep, discarded),
+            None => examples,
+        }
+    }
+    
+    /// Обучающие примеры из потока кусков текста (см. `read_chunks`), с очисткой
+    pub fn extract_training_data_iter<'a>(
+        &'a self,
+        chunks: impl IntoIterator<Item = String> + 'a,
+    ) -> impl Iterator<Item = String> + 'a {
+        chunks
+            .into_iter()
+            .flat_map(move |chunk| self.prepare_training_data(&chunk))
+    }
+    
+    /// Разбиение примеров на обучающую и валидационную выборки
+    ///
+    /// `ratio` — доля обучающей выборки (0.9 → 90% / 10%). Примеры
+    /// перемешиваются генератором с зерном `seed`, поэтому разбиение
+    /// воспроизводимо. Непустой набор всегда оставляет хотя бы один пример
+    /// для обучения.
+    pub fn split_dataset(&self, examples: &[String], ratio: f64, seed: u64) -> (Vec<String>, Vec<String>) {
+        let mut shuffled = examples.to_vec();
+        shuffled.shuffle(&mut StdRng::seed_from_u64(seed));
+        
+        let (train_len, _) = Self::split_sizes(shuffled.len(), ratio);
+        let validation = shuffled.split_off(train_len);
+        (shuffled, validation)
+    }
+    
+    /// Размеры выборок, которые вернёт `split_dataset`, без копирования примеров
+    pub fn split_sizes(len: usize, ratio: f64) -> (usize, usize) {
+        let train_len = ((len as f64 * ratio.clamp(0.0, 1.0)).round() as usize).clamp(len.min(1), len);
+        (train_len, len - train_len)
+    }
+    
     /// Получение статистики по файлу
     pub fn get_file_stats(&self, content: &str) -> FileStats {
         let lines = content.lines().count();
@@ -251,7 +1018,222 @@ pub struct FileStats {
     pub bytes: usize,
 }
 
+/// Верхние границы корзин гистограммы длин примеров (в токенах); последняя корзина — всё длиннее
+const LENGTH_BUCKETS: [usize; 6] = [4, 8, 16, 32, 64, 128];
+
+/// Сводка по всему загруженному корпусу относительно словаря модели
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusReport {
+    pub files: usize,
+    pub examples: usize,
+    pub words: usize,
+    pub tokens: usize,
+    /// Токены, отсутствующие в словаре (`<UNK>`)
+    pub unknown_tokens: usize,
+    pub min_tokens: usize,
+    pub max_tokens: usize,
+    /// Число примеров по корзинам `LENGTH_BUCKETS` и корзина длиннее последней границы
+    pub length_histogram: Vec<usize>,
+}
+
+impl CorpusReport {
+    /// Токенизация всех примеров текущим словарём модели
+    pub fn build(files: usize, examples: &[String], model: &AIModel) -> Self {
+        let unk = model.get_unk_token();
+        let mut report = Self {
+            files,
+            examples: examples.len(),
+            words: 0,
+            tokens: 0,
+            unknown_tokens: 0,
+            min_tokens: if examples.is_empty() { 0 } else { usize::MAX },
+            max_tokens: 0,
+            length_histogram: vec![0; LENGTH_BUCKETS.len() + 1],
+        };
+        
+        for example in examples {
+            let tokens = model.tokenize(example);
+            report.words += example.split_whitespace().count();
+            report.tokens += tokens.len();
+            report.unknown_tokens += tokens.iter().filter(|&&token| token == unk).count();
+            report.min_tokens = report.min_tokens.min(tokens.len());
+            report.max_tokens = report.max_tokens.max(tokens.len());
+            
+            let bucket = LENGTH_BUCKETS
+                .iter()
+                .position(|&limit| tokens.len() <= limit)
+                .unwrap_or(LENGTH_BUCKETS.len());
+            report.length_histogram[bucket] += 1;
+        }
+        report
+    }
+    
+    /// Доля неизвестных словарю токенов
+    pub fn oov_rate(&self) -> f64 {
+        if self.tokens == 0 {
+            0.0
+        } else {
+            self.unknown_tokens as f64 / self.tokens as f64
+        }
+    }
+    
+    pub fn mean_tokens(&self) -> f64 {
+        if self.examples == 0 {
+            0.0
+        } else {
+            self.tokens as f64 / self.examples as f64
+        }
+    }
+    
+    pub fn format(&self) -> String {
+        let mut text = format!(
+            "Файлов: {}\nПримеров: {}\nСлов: {}\nТокенов: {} (вне словаря: {}, {:.1}%)\nДлина примера: {}–{}, в среднем {:.1}\n",
+            self.files,
+            self.examples,
+            self.words,
+            self.tokens,
+            self.unknown_tokens,
+            self.oov_rate() * 100.0,
+            self.min_tokens,
+            self.max_tokens,
+            self.mean_tokens()
+        );
+        
+        let widest = self.length_histogram.iter().copied().max().unwrap_or(0).max(1);
+        for (bucket, &count) in self.length_histogram.iter().enumerate() {
+            let label = match bucket {
+                0 => format!("≤{}", LENGTH_BUCKETS[0]),
+                _ if bucket == LENGTH_BUCKETS.len() => format!(">{}", LENGTH_BUCKETS[bucket - 1]),
+                _ => format!("{}–{}", LENGTH_BUCKETS[bucket - 1] + 1, LENGTH_BUCKETS[bucket]),
+            };
+            let bar = "█".repeat((count * 20).div_ceil(widest));
+            text.push_str(&format!("\n{:>7} │{} {}", label, bar, count));
+        }
+        text
+    }
+}
+
+/// Размер блока, читаемого из файла за раз
+const READ_BLOCK_SIZE: usize = 64 * 1024;
+
+/// Итератор по кускам текста из `Read` с определением кодировки по первому блоку
+///
+/// Кусок заканчивается на последней границе абзаца (`\n\n`) в пределах
+/// `chunk_size`, иначе на переводе строки; строка длиннее `chunk_size`
+/// режется по символу.
+pub struct TextChunks<R> {
+    reader: R,
+    decoder: Decoder,
+    pending: String,
+    chunk_size: usize,
+    eof: bool,
+    block: Vec<u8>,
+    bytes_read: u64,
+}
+
+impl<R: Read> TextChunks<R> {
+    pub fn new(mut reader: R, chunk_size: usize) -> std::io::Result<Self> {
+        let mut first = vec![0; READ_BLOCK_SIZE];
+        let mut len = 0;
+        while len < first.len() {
+            match reader.read(&mut first[len..])? {
+                0 => break,
+                n => len += n,
+            }
+        }
+        
+        let mut decoder = FileProcessor::detect_encoding(&first[..len]).new_decoder_with_bom_removal();
+        let mut pending = String::new();
+        Self::decode(&mut decoder, &mut pending, &first[..len], false);
+        Ok(Self {
+            reader,
+            decoder,
+            pending,
+            chunk_size: chunk_size.max(1),
+            eof: false,
+            block: first,
+            bytes_read: len as u64,
+        })
+    }
+    
+    /// Сколько байт прочитано из источника (для индикатора прогресса)
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+    
+    fn decode(decoder: &mut Decoder, pending: &mut String, bytes: &[u8], last: bool) {
+        let mut read = 0;
+        loop {
+            let needed = decoder.max_utf8_buffer_length(bytes.len() - read).unwrap_or(READ_BLOCK_SIZE * 4);
+            pending.reserve(needed);
+            let (result, consumed, _) = decoder.decode_to_string(&bytes[read..], pending, last);
+            read += consumed;
+            if result == CoderResult::InputEmpty {
+                break;
+            }
+        }
+    }
+    
+    /// Конец очередного куска, если накоплено не меньше `chunk_size`
+    fn split_point(&self) -> Option<usize> {
+        if self.pending.len() < self.chunk_size {
+            return None;
+        }
+        let mut limit = self.chunk_size;
+        while !self.pending.is_char_boundary(limit) {
+            limit -= 1;
+        }
+        let window = &self.pending[..limit];
+        let split = window
+            .rfind("\n\n")
+            .map(|pos| pos + 2)
+            .or_else(|| window.rfind('\n').map(|pos| pos + 1))
+            .unwrap_or(limit);
+        // Символ длиннее chunk_size — отдаём его целиком
+        Some(if split == 0 { self.pending.chars().next().map_or(0, char::len_utf8) } else { split })
+    }
+}
+
+impl<R: Read> Iterator for TextChunks<R> {
+    type Item = Result<String, String>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(split) = self.split_point() {
+                return Some(Ok(self.pending.drain(..split).collect()));
+            }
+            if self.eof {
+                return (!self.pending.is_empty()).then(|| Ok(std::mem::take(&mut self.pending)));
+            }
+            
+            match self.reader.read(&mut self.block) {
+                Ok(0) => {
+                    Self::decode(&mut self.decoder, &mut self.pending, &[], true);
+                    self.eof = true;
+                }
+                Ok(n) => {
+                    self.bytes_read += n as u64;
+                    Self::decode(&mut self.decoder, &mut self.pending, &self.block[..n], false);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.eof = true;
+                    return Some(Err(format!("Ошибка чтения файла: {}", e)));
+                }
+            }
+        }
+    }
+}
+
 impl FileStats {
+    /// Сложение статистики кусков одного файла
+    pub fn add(&mut self, other: &FileStats) {
+        self.lines += other.lines;
+        self.words += other.words;
+        self.chars += other.chars;
+        self.bytes += other.bytes;
+    }
+    
     pub fn format(&self) -> String {
         format!(
             "Строк: {}\nСлов: {}\nСимволов: {}\nБайт: {}",
@@ -278,6 +1260,240 @@ mod tests {
         assert!(!data.is_empty());
     }
     
+    /// Минимальный PDF из одной страницы с заданным потоком содержимого
+    fn minimal_pdf(content: &str) -> Vec<u8> {
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>".to_string(),
+            format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+        
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend(format!("{} 0 obj\n{}\nendobj\n", idx + 1, object).bytes());
+        }
+        let xref = pdf.len();
+        pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).bytes());
+        for offset in offsets {
+            pdf.extend(format!("{:010} 00000 n \n", offset).bytes());
+        }
+        pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).bytes());
+        pdf
+    }
+    
+    #[test]
+    fn test_parse_pdf_text() {
+        let pdf = minimal_pdf("BT /F1 12 Tf 72 720 Td (Hello world) Tj 0 -14 Td [(Second) -300 (line)] TJ ET");
+        assert_eq!(FileProcessor::parse_pdf_text(&pdf).as_deref(), Some("Hello world\nSecond line"));
+        
+        // Не PDF — парсер отказывается, остаётся эвристика
+        assert_eq!(FileProcessor::parse_pdf_text(b"BT (plain) Tj ET"), None);
+    }
+    
+    #[test]
+    fn test_corrupt_pdf_does_not_panic() {
+        let pdf = minimal_pdf("BT /F1 12 Tf (Broken) Tj ET");
+        let truncated = &pdf[..pdf.len() / 2];
+        let mut corrupt = pdf.clone();
+        let middle = corrupt.len() / 3;
+        corrupt[middle..middle + 40].fill(0xFF);
+        
+        for bytes in [truncated, &corrupt[..], &b"%PDF-1.4\n%%EOF"[..]] {
+            let _ = FileProcessor::parse_pdf_text(bytes);
+            let _ = FileProcessor::pdf_extract(bytes);
+        }
+    }
+    
+    #[test]
+    fn test_failed_pdf_extraction_is_not_cached() {
+        let dir = std::env::temp_dir().join(format!("pdf_cache_fail_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let pdf_path = dir.join("broken.pdf");
+        fs::write(&pdf_path, b"%PDF-1.4\n%%EOF").unwrap();
+        
+        let mut processor = FileProcessor::new();
+        processor.cache_dir = Some(dir.join("cache"));
+        for _ in 0..2 {
+            assert!(processor.read_file(&pdf_path).unwrap().starts_with("📄 PDF файл загружен"));
+        }
+        assert_eq!(processor.clear_cache(), Ok(0));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_pdf_text_cache() {
+        let dir = std::env::temp_dir().join(format!("pdf_cache_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let pdf_path = dir.join("doc.pdf");
+        fs::write(&pdf_path, minimal_pdf("BT /F1 12 Tf (Cached text) Tj ET")).unwrap();
+        
+        let mut processor = FileProcessor::new();
+        processor.cache_dir = Some(dir.join("cache"));
+        assert_eq!(processor.read_file(&pdf_path).unwrap(), "Cached text");
+        
+        // Повторная загрузка берёт текст из кэша, не разбирая PDF
+        let entry = fs::read_dir(dir.join("cache")).unwrap().next().unwrap().unwrap().path();
+        fs::write(&entry, "из кэша").unwrap();
+        assert_eq!(processor.read_file(&pdf_path).unwrap(), "из кэша");
+        
+        assert_eq!(processor.clear_cache(), Ok(1));
+        assert_eq!(processor.read_file(&pdf_path).unwrap(), "Cached text");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    
+    #[test]
+    fn test_subtitles_to_text() {
+        let srt = "1\n00:00:01,000 --> 00:00:03,000\n<i>Привет,</i> как\nдела?\n\n2\n00:00:04,000 --> 00:00:06,000\n- Хорошо.\n- А у тебя &amp; семьи?\n";
+        assert_eq!(FileProcessor::subtitles_to_text(srt), "Привет, как дела?\nХорошо.\nА у тебя & семьи?");
+        
+        let vtt = "WEBVTT\n\nNOTE служебный\nкомментарий\n\nintro\n00:01.000 --> 00:02.000 align:start\n{\\an8}Hello there\n\n00:02.500 --> 00:04.000\nGeneral Kenobi!";
+        assert_eq!(FileProcessor::subtitles_to_text(vtt), "Hello there General Kenobi!");
+    }
+    
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><head><title>Заголовок</title><style>p { color: red; }</style></head>\
+                    <body><script>var x = '<p>';</script>\
+                    <p>Первый   <b>абзац</b> &laquo;текста&raquo;&nbsp;&amp; ещё</p>\
+                    <!-- комментарий --><div>Второй<br>строка &#1087;&#x440;и</div></body></html>";
+        assert_eq!(
+            FileProcessor::html_to_text(html),
+            "Заголовок\n\nПервый абзац «текста» & ещё\n\nВторой\nстрока при"
+        );
+        assert_eq!(FileProcessor::html_to_text("a &unknown; b & c"), "a &unknown; b & c");
+    }
+    
+    #[test]
+    fn test_decode_text_detects_encoding() {
+        let text = "Привет, как дела? Всё хорошо.";
+        for encoding in [WINDOWS_1251, KOI8_R] {
+            let (bytes, _, _) = encoding.encode(text);
+            assert_eq!(FileProcessor::decode_text(&bytes), (text.to_string(), encoding.name()));
+        }
+        
+        assert_eq!(FileProcessor::decode_text(text.as_bytes()).1, "UTF-8");
+        let with_bom = [&[0xEF, 0xBB, 0xBF][..], text.as_bytes()].concat();
+        assert_eq!(FileProcessor::decode_text(&with_bom).0, text);
+    }
+    
+    #[test]
+    fn test_text_chunks_split_on_paragraphs() {
+        let text = "первый абзац\n\nвторой абзац\nещё строка\n\nтретий";
+        let (bytes, _, _) = WINDOWS_1251.encode(text);
+        let chunks: Vec<String> = TextChunks::new(Cursor::new(bytes.into_owned()), 30)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        
+        assert_eq!(chunks.concat(), text);
+        assert_eq!(chunks[0], "первый абзац\n\n");
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 30));
+        
+        let processor = FileProcessor::new();
+        let examples: Vec<String> = processor.extract_training_data_iter(chunks).collect();
+        assert!(examples.contains(&"первый абзац".to_string()));
+        assert!(examples.contains(&"третий".to_string()));
+    }
+    
+    #[test]
+    fn test_split_dataset() {
+        let processor = FileProcessor::new();
+        let examples: Vec<String> = (0..10).map(|i| format!("пример {}", i)).collect();
+        
+        let (train, validation) = processor.split_dataset(&examples, 0.8, 42);
+        assert_eq!((train.len(), validation.len()), (8, 2));
+        assert_eq!(processor.split_dataset(&examples, 0.8, 42), (train.clone(), validation.clone()));
+        let mut all = [train, validation].concat();
+        all.sort();
+        assert_eq!(all, examples);
+        
+        let single = vec!["один".to_string()];
+        assert_eq!(processor.split_dataset(&single, 0.0, 1).0, single);
+    }
+    
+    #[test]
+    fn test_csv_columns_and_dialogue() {
+        let csv = "id;вопрос;ответ\n1;\"Как дела?\";\"Хорошо; спасибо\"\n2;\"Скажи \"\"привет\"\"\";\"Привет\nмир\"\n3;;пусто\n";
+        assert_eq!(FileProcessor::parse_csv(csv)[2], vec!["2", "Скажи \"привет\"", "Привет\nмир"]);
+        
+        let mut processor = FileProcessor::new();
+        processor.csv.text_columns = vec!["Ответ".to_string()];
+        assert_eq!(processor.csv_to_text(csv).unwrap(), "Хорошо; спасибо\n\nПривет\nмир\n\nпусто");
+        
+        processor.csv.dialogue_columns = Some(("2".to_string(), "ответ".to_string()));
+        assert_eq!(
+            processor.csv_to_text(csv).unwrap(),
+            "Как дела?\nХорошо; спасибо\n\nСкажи \"привет\"\nПривет\nмир"
+        );
+        
+        processor.csv.dialogue_columns = None;
+        processor.csv.text_columns = vec!["текст".to_string()];
+        assert!(processor.csv_to_text(csv).is_err());
+    }
+    
+    #[test]
+    fn test_chunking_windows() {
+        let chunking = ChunkingOptions { min_length: 4, max_length: 11, overlap: 5 };
+        assert_eq!(chunking.windows("один два три четыре пять"), vec!["один два", "два три", "три четыре", "пять"]);
+        assert_eq!(chunking.windows("коротко"), vec!["коротко"]);
+        assert_eq!(
+            ChunkingOptions { overlap: 0, ..chunking }.windows("один два три четыре пять"),
+            vec!["один два", "три четыре", "пять"]
+        );
+        assert_eq!(chunking.windows("сверхдлинноеслово и"), vec!["сверхдлинноеслово", "и"]);
+        
+        let mut processor = FileProcessor::new();
+        processor.chunking = ChunkingOptions { min_length: 10, max_length: 20, overlap: 0 };
+        let text = "Кратко.\n\nЭтот абзац длиннее двадцати символов.\n\nЕщё один абзац.\n\nИ третий абзац здесь.";
+        assert_eq!(
+            processor.extract_training_data(text),
+            vec!["Этот абзац длиннее", "двадцати символов.", "Ещё один абзац.", "И третий абзац", "здесь."]
+        );
+    }
+    
+    #[test]
+    fn test_extract_dialogue_pairs() {
+        let text = "Заголовок\n\nQ: Как дела?\nA: Хорошо.\nСпасибо!\n\nПримечание\nВопрос: Который час?\nОтвет: Полдень\n\n\
+                    A: ответ без вопроса\nUser: Привет\nuser: ты тут?\nAssistant: Да\nUSER: Пока";
+        assert_eq!(
+            FileProcessor::extract_dialogue_pairs(text),
+            vec![
+                "Как дела?\nХорошо. Спасибо!",
+                "Который час?\nПолдень",
+                "Привет ты тут?\nДа",
+            ]
+        );
+        
+        let mut processor = FileProcessor::new();
+        processor.extraction_mode = ExtractionMode::Dialogue;
+        assert_eq!(processor.prepare_training_data("Q: 2+2?\nA: 4"), vec!["2+2?\n4"]);
+    }
+    
+    #[test]
+    fn test_corpus_report() {
+        let mut model = AIModel::new(8, 8, 4);
+        model.build_vocab_from_corpus(&["кошка сидит".to_string()], 100, 1);
+        let examples = vec![
+            "кошка сидит".to_string(),
+            "кошка спит на окне".to_string(),
+            "раз два три четыре пять шесть".to_string(),
+        ];
+        
+        let report = CorpusReport::build(2, &examples, &model);
+        assert_eq!((report.files, report.examples, report.words, report.tokens), (2, 3, 12, 12));
+        assert_eq!(report.unknown_tokens, 9);
+        assert!((report.oov_rate() - 0.75).abs() < 1e-9);
+        assert_eq!((report.min_tokens, report.max_tokens), (2, 6));
+        assert_eq!(report.length_histogram, vec![2, 1, 0, 0, 0, 0, 0]);
+        assert!(report.format().contains("вне словаря: 9, 75.0%"));
+    }
+    
     #[test]
     fn test_file_stats() {
         let processor = FileProcessor::new();
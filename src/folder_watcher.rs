@@ -0,0 +1,103 @@
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+/// Наблюдение за папкой: сообщает о новых и изменённых файлах
+///
+/// События файловой системы приходят от `notify` (inotify, FSEvents,
+/// ReadDirectoryChangesW) и собираются дебаунсером: файл сообщается, когда
+/// по нему не было событий в течение `debounce`, чтобы не читать файл, который
+/// ещё копируется. Файлы, лежавшие в папке до запуска, не сообщаются.
+/// Наблюдение прекращается при удалении наблюдателя.
+pub struct FolderWatcher {
+    dir: PathBuf,
+    receiver: Receiver<PathBuf>,
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl FolderWatcher {
+    /// Запуск наблюдения; `filter` отбирает файлы (например, по расширению)
+    pub fn start(
+        dir: impl Into<PathBuf>,
+        debounce: Duration,
+        filter: impl Fn(&Path) -> bool + Send + 'static,
+    ) -> Result<Self, String> {
+        let dir = dir.into();
+        if !dir.is_dir() {
+            return Err(format!("✗ Папка не найдена: {:?}", dir));
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let mut debouncer = new_debouncer(debounce, move |result: DebounceEventResult| {
+            // Ошибки наблюдения пропускаются: следующее событие придёт как обычно
+            let Ok(events) = result else {
+                return;
+            };
+            for event in events {
+                // Удалённые файлы тоже дают событие
+                if event.path.is_file() && filter(&event.path) {
+                    let _ = sender.send(event.path);
+                }
+            }
+        })
+        .map_err(|e| format!("✗ Не удалось наблюдать за папкой {:?}: {}", dir, e))?;
+        debouncer
+            .watcher()
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("✗ Не удалось наблюдать за папкой {:?}: {}", dir, e))?;
+
+        Ok(Self {
+            dir,
+            receiver,
+            _debouncer: debouncer,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Файлы, появившиеся с прошлого вызова
+    pub fn new_files(&self) -> Vec<PathBuf> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::thread;
+    use std::time::Instant;
+
+    #[test]
+    fn test_folder_watcher_reports_new_files() {
+        let dir = std::env::temp_dir().join(format!("folder_watcher_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("old.txt"), "был до запуска").unwrap();
+
+        let watcher = FolderWatcher::start(&dir, Duration::from_millis(20), |path| {
+            path.extension().is_some_and(|ext| ext == "txt")
+        })
+        .unwrap();
+        fs::write(dir.join("new.txt"), "новый файл").unwrap();
+        fs::write(dir.join("skip.bin"), "не подходит").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut seen = Vec::new();
+        while seen.is_empty() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(20));
+            seen.extend(watcher.new_files());
+        }
+        thread::sleep(Duration::from_millis(100));
+        seen.extend(watcher.new_files());
+        assert_eq!(seen, vec![dir.join("new.txt")]);
+
+        assert!(FolderWatcher::start(dir.join("missing"), Duration::from_millis(20), |_| true).is_err());
+        drop(watcher);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
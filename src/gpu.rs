@@ -0,0 +1,239 @@
+//! GPU compute-бэкенд (wgpu) для AIModel
+//!
+//! Матрично-векторное умножение и softmax выполняются compute-шейдерами
+//! из `shaders/compute.wgsl` в f32. Без feature `gpu` модуль собирается
+//! как заглушка: адаптер не находится и модель считает на CPU.
+
+use nalgebra::DMatrix;
+
+/// Контекст GPU-вычислений
+pub struct GpuCompute {
+    adapter_name: String,
+    #[cfg(feature = "gpu")]
+    device: wgpu::Device,
+    #[cfg(feature = "gpu")]
+    queue: wgpu::Queue,
+    #[cfg(feature = "gpu")]
+    layout: wgpu::BindGroupLayout,
+    #[cfg(feature = "gpu")]
+    matvec: wgpu::ComputePipeline,
+    #[cfg(feature = "gpu")]
+    softmax: wgpu::ComputePipeline,
+    #[cfg(feature = "gpu")]
+    max_buffer_size: u64,
+}
+
+impl GpuCompute {
+    /// Имя используемого адаптера
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+}
+
+#[cfg(not(feature = "gpu"))]
+impl GpuCompute {
+    /// Без feature `gpu` адаптер никогда не находится
+    pub fn new() -> Option<Self> {
+        None
+    }
+
+    /// Недоступно без feature `gpu`: всегда `None` (вычисление на CPU)
+    pub fn affine(&self, _weights: &DMatrix<f64>, _input: &[f64], _init: &[f64], _transpose: bool, _softmax: bool) -> Option<Vec<f64>> {
+        None
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl GpuCompute {
+    const WORKGROUP_SIZE: usize = 64;
+
+    /// Поиск адаптера и компиляция шейдеров; `None`, если GPU недоступен
+    pub fn new() -> Option<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))?;
+
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("AIModel compute"),
+                required_features: wgpu::Features::empty(),
+                required_limits: adapter.limits(),
+            },
+            None,
+        ))
+        .ok()?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("compute.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/compute.wgsl").into()),
+        });
+
+        let buffer_entry = |binding, ty| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("AIModel compute layout"),
+            entries: &[
+                buffer_entry(0, wgpu::BufferBindingType::Uniform),
+                buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: true }),
+                buffer_entry(2, wgpu::BufferBindingType::Storage { read_only: true }),
+                buffer_entry(3, wgpu::BufferBindingType::Storage { read_only: false }),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("AIModel compute pipeline layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point,
+            })
+        };
+        let matvec = pipeline("matvec");
+        let softmax = pipeline("softmax");
+
+        let max_buffer_size = device.limits().max_storage_buffer_binding_size as u64;
+        Some(Self {
+            adapter_name: adapter.get_info().name,
+            device,
+            queue,
+            layout,
+            matvec,
+            softmax,
+            max_buffer_size,
+        })
+    }
+
+    /// `init + W·x` (или `init + Wᵀ·x` при `transpose`), опционально с softmax
+    ///
+    /// Softmax применяется только без транспонирования. Возвращает `None`,
+    /// если размеры не совпадают или буфер не помещается в лимиты устройства.
+    pub fn affine(&self, weights: &DMatrix<f64>, input: &[f64], init: &[f64], transpose: bool, softmax: bool) -> Option<Vec<f64>> {
+        use wgpu::util::DeviceExt;
+
+        let (rows, cols) = weights.shape();
+        let (in_len, out_len) = if transpose { (rows, cols) } else { (cols, rows) };
+        if weights.is_empty() || input.len() != in_len || init.len() != out_len || (softmax && transpose) {
+            return None;
+        }
+        if (weights.len() * 4) as u64 > self.max_buffer_size {
+            return None;
+        }
+
+        let storage = |label, data: &[f64], usage| {
+            self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: &to_bytes(data),
+                usage: wgpu::BufferUsages::STORAGE | usage,
+            })
+        };
+        let dims: Vec<u8> = [rows as u32, cols as u32, transpose as u32, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let dims = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("dims"),
+            contents: &dims,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let weights = storage("weights", weights.as_slice(), wgpu::BufferUsages::empty());
+        let input = storage("input", input, wgpu::BufferUsages::empty());
+        let output = storage("output", init, wgpu::BufferUsages::COPY_SRC);
+        let size = (out_len * 4) as u64;
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("AIModel compute bind group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: dims.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: weights.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: input.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 3, resource: output.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("affine") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("affine"),
+                timestamp_writes: None,
+            });
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_pipeline(&self.matvec);
+            pass.dispatch_workgroups(out_len.div_ceil(Self::WORKGROUP_SIZE) as u32, 1, 1);
+            if softmax {
+                pass.set_pipeline(&self.softmax);
+                pass.dispatch_workgroups(1, 1, 1);
+            }
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv().ok()?.ok()?;
+
+        let result = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            .collect();
+        readback.unmap();
+        Some(result)
+    }
+}
+
+#[cfg(feature = "gpu")]
+fn to_bytes(values: &[f64]) -> Vec<u8> {
+    values.iter().flat_map(|&v| (v as f32).to_le_bytes()).collect()
+}
+
+#[cfg(all(test, feature = "gpu"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_matches_cpu() {
+        // Без адаптера (CI, headless) проверять нечего
+        let Some(gpu) = GpuCompute::new() else { return };
+
+        let weights = DMatrix::from_fn(70, 5, |i, j| ((i * 5 + j) as f64 * 0.37).sin());
+        let input = vec![0.5, -1.0, 0.25, 2.0, -0.75];
+        let biases: Vec<f64> = (0..70).map(|i| i as f64 * 0.01).collect();
+
+        let cpu = &weights * nalgebra::DVector::from_column_slice(&input) + nalgebra::DVector::from_column_slice(&biases);
+        let out = gpu.affine(&weights, &input, &biases, false, false).unwrap();
+        assert!(out.iter().zip(cpu.iter()).all(|(a, b)| (a - b).abs() < 1e-4));
+
+        let back = gpu.affine(&weights, &biases, &[0.0; 5], true, false).unwrap();
+        let cpu_back = weights.tr_mul(&nalgebra::DVector::from_column_slice(&biases));
+        assert!(back.iter().zip(cpu_back.iter()).all(|(a, b)| (a - b).abs() < 1e-3));
+
+        let probs = gpu.affine(&weights, &input, &biases, false, true).unwrap();
+        assert!((probs.iter().sum::<f64>() - 1.0).abs() < 1e-4);
+    }
+}
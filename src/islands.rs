@@ -0,0 +1,187 @@
+//! Island model: sub-populations evolving apart, with occasional migrants
+//!
+//! Every island has its own `EvolutionEngine`, so islands can explore with
+//! different mutation and crossover rates. A generation evolves all islands
+//! in parallel, one thread each; every `migration_interval` generations the
+//! fittest `migrants` genomes of each island replace the weakest ones of the
+//! next island in a ring.
+
+use crate::evolution::{EvolutionEngine, MutationRateStats};
+use crate::voxel::{Genome, VoxelRecord};
+use serde::{Deserialize, Serialize};
+
+/// How an island did in its latest generation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IslandStats {
+    pub members: usize,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    /// Genomes replaced by offspring
+    pub replaced: usize,
+    /// Genomes that arrived from the previous island
+    pub immigrants: usize,
+    #[serde(default)]
+    pub mutation_rates: MutationRateStats,
+}
+
+/// One sub-population's engine and its latest stats
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Island {
+    pub engine: EvolutionEngine,
+    #[serde(default)]
+    pub stats: IslandStats,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IslandModel {
+    pub islands: Vec<Island>,
+    /// Generations between migrations; 0 never migrates
+    pub migration_interval: u64,
+    /// Genomes each island sends to the next one per migration
+    pub migrants: usize,
+    /// Generations evolved so far
+    pub generation: u64,
+}
+
+impl IslandModel {
+    /// `count` islands ranging from cautious (low mutation, high crossover)
+    /// to adventurous (high mutation, low crossover)
+    pub fn spread(count: usize) -> Self {
+        let islands = (0..count)
+            .map(|i| {
+                let t = if count > 1 { i as f64 / (count - 1) as f64 } else { 0.0 };
+                let mut engine = EvolutionEngine::new();
+                engine.mutation_rate = 0.02 + 0.28 * t;
+                engine.crossover_rate = 0.9 - 0.4 * t;
+                Island { engine, stats: IslandStats::default() }
+            })
+            .collect();
+        Self { islands, ..Self::default() }
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.islands.is_empty()
+    }
+    
+    /// One generation over `populations`, one per island (extra populations
+    /// are left alone); returns the number of replaced genomes, migrants
+    /// included
+    pub fn evolve(&mut self, populations: &mut [Vec<VoxelRecord>]) -> usize {
+        let replaced: Vec<usize> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self.islands
+                .iter_mut()
+                .zip(populations.iter_mut())
+                .map(|(island, voxels)| scope.spawn(move || island.engine.evolve(voxels)))
+                .collect();
+            handles.into_iter().map(|handle| handle.join().expect("island thread panicked")).collect()
+        });
+        self.generation += 1;
+        
+        let count = self.islands.len().min(populations.len());
+        let mut immigrants = vec![0; count];
+        if count > 1 && self.migration_interval > 0 && self.generation.is_multiple_of(self.migration_interval) {
+            let emigrants: Vec<Vec<Genome>> = (0..count)
+                .map(|i| {
+                    let ranked = self.ranked(i, &populations[i]);
+                    let leaving = self.migrants.min(populations[i].len() / 2);
+                    ranked[..leaving].iter().map(|&j| populations[i][j].voxel.genome.clone()).collect()
+                })
+                .collect();
+            for (from, genomes) in emigrants.into_iter().enumerate() {
+                let to = (from + 1) % count;
+                let ranked = self.ranked(to, &populations[to]);
+                for (&weakest, genome) in ranked.iter().rev().zip(genomes) {
+                    populations[to][weakest].voxel.genome = genome;
+                    immigrants[to] += 1;
+                }
+            }
+        }
+        
+        for (i, island) in self.islands.iter_mut().enumerate().take(count) {
+            let fitness: Vec<f64> = populations[i].iter().map(|record| island.engine.fitness(&record.voxel)).collect();
+            let (best_fitness, mean_fitness) = if fitness.is_empty() {
+                (0.0, 0.0)
+            } else {
+                (fitness.iter().copied().fold(f64::NEG_INFINITY, f64::max), fitness.iter().sum::<f64>() / fitness.len() as f64)
+            };
+            island.stats = IslandStats {
+                members: fitness.len(),
+                best_fitness,
+                mean_fitness,
+                replaced: replaced[i],
+                immigrants: immigrants[i],
+                mutation_rates: MutationRateStats::of(populations[i].iter().map(|record| &record.voxel.genome)),
+            };
+        }
+        replaced.iter().sum::<usize>() + immigrants.iter().sum::<usize>()
+    }
+    
+    /// Indices of `voxels` by island `island`'s fitness, fittest first
+    fn ranked(&self, island: usize, voxels: &[VoxelRecord]) -> Vec<usize> {
+        let engine = &self.islands[island].engine;
+        let fitness: Vec<f64> = voxels.iter().map(|record| engine.fitness(&record.voxel)).collect();
+        let mut ranked: Vec<usize> = (0..voxels.len()).collect();
+        ranked.sort_by(|&a, &b| fitness[b].total_cmp(&fitness[a]));
+        ranked
+    }
+}
+
+impl Default for IslandModel {
+    fn default() -> Self {
+        Self {
+            islands: Vec::new(),
+            migration_interval: 5,
+            migrants: 2,
+            generation: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::Voxel;
+    
+    fn population(concept: &str, energies: &[f64]) -> Vec<VoxelRecord> {
+        energies
+            .iter()
+            .map(|&energy| {
+                let mut voxel = Voxel::new();
+                voxel.energy = energy;
+                voxel.genome.add_concept(concept.to_string());
+                VoxelRecord::new(voxel, [0, 0, 0])
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn test_islands_migrate_in_a_ring() {
+        let mut model = IslandModel::spread(3);
+        assert!(model.islands[0].engine.mutation_rate < model.islands[2].engine.mutation_rate);
+        for island in &mut model.islands {
+            island.engine.mutation_rate = 0.0;
+            island.engine.crossover_rate = 0.0;
+        }
+        model.migration_interval = 2;
+        model.migrants = 1;
+        let mut populations = vec![
+            population("a", &[4.0, 3.0, 2.0, 1.0]),
+            population("b", &[4.0, 3.0, 2.0, 1.0]),
+            population("c", &[4.0, 3.0, 2.0, 1.0]),
+        ];
+        
+        // No migration yet: each island only breeds its own genomes
+        assert_eq!(model.evolve(&mut populations), 6);
+        assert!(populations[1].iter().all(|record| record.voxel.genome.concepts == ["b"]));
+        assert_eq!(model.islands[1].stats.members, 4);
+        assert_eq!(model.islands[1].stats.immigrants, 0);
+        
+        // Second generation: the best of each island moves to the next one
+        assert_eq!(model.evolve(&mut populations), 9);
+        assert_eq!(populations[1][3].voxel.genome.concepts, ["a"]);
+        assert_eq!(populations[0][3].voxel.genome.concepts, ["c"]);
+        assert_eq!(model.islands[2].stats.immigrants, 1);
+        assert!(model.islands[0].stats.best_fitness >= model.islands[0].stats.mean_fitness);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::ai_model::cosine_similarity;
+
+/// «Кайф» — эмоциональная подсистема: реакция на поток эмбеддингов
+///
+/// Каждый новый эмбеддинг сравнивается с предыдущим (резонанс) и со
+/// скользящим средним контекста (новизна). Мгновенный кайф максимален при
+/// умеренной новизне и высоком резонансе (перевёрнутая U-кривая Берлайна) и
+/// сглаживается экспоненциально.
+#[derive(Debug, Clone)]
+pub struct KaifEngine {
+    /// Вес прошлого в скользящих средних (0..1)
+    pub decay: f64,
+    /// Скользящее среднее эмбеддингов
+    context: Vec<f64>,
+    previous: Option<Vec<f64>>,
+    state: KaifState,
+}
+
+/// Текущее состояние подсистемы, все значения в 0..1
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KaifState {
+    pub kaif: f64,
+    /// Насколько последний сигнал отличается от контекста
+    pub novelty: f64,
+    /// Насколько последний сигнал созвучен предыдущему
+    pub resonance: f64,
+    pub samples: usize,
+}
+
+/// Настроение, выводимое из `KaifState`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mood {
+    Calm,
+    Curious,
+    Delighted,
+    Surprised,
+    Bored,
+}
+
+impl KaifEngine {
+    pub fn new() -> Self {
+        Self {
+            decay: 0.7,
+            context: Vec::new(),
+            previous: None,
+            state: KaifState::default(),
+        }
+    }
+
+    pub fn state(&self) -> KaifState {
+        self.state
+    }
+
+    /// Учёт нового эмбеддинга; нулевые и пустые векторы пропускаются
+    pub fn feed(&mut self, embedding: &[f64]) -> KaifState {
+        if embedding.is_empty() || embedding.iter().all(|&x| x == 0.0) {
+            return self.state;
+        }
+        // Смена размерности (новая модель) начинает контекст заново
+        if self.context.len() != embedding.len() {
+            self.context = embedding.to_vec();
+            self.previous = None;
+        }
+
+        let resonance = self
+            .previous
+            .as_ref()
+            .map_or(0.5, |previous| (cosine_similarity(previous, embedding) + 1.0) / 2.0);
+        let novelty = if self.state.samples == 0 {
+            0.5
+        } else {
+            (1.0 - cosine_similarity(&self.context, embedding)).clamp(0.0, 1.0)
+        };
+        let instant = resonance * (1.0 - (novelty - 0.5).abs() * 2.0);
+
+        let decay = self.decay.clamp(0.0, 0.99);
+        let kaif = if self.state.samples == 0 {
+            instant
+        } else {
+            decay * self.state.kaif + (1.0 - decay) * instant
+        };
+        self.context
+            .iter_mut()
+            .zip(embedding)
+            .for_each(|(mean, &x)| *mean = decay * *mean + (1.0 - decay) * x);
+        self.previous = Some(embedding.to_vec());
+
+        self.state = KaifState {
+            kaif: kaif.clamp(0.0, 1.0),
+            novelty,
+            resonance,
+            samples: self.state.samples + 1,
+        };
+        self.state
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self {
+            decay: self.decay,
+            ..Self::new()
+        };
+    }
+}
+
+impl Default for KaifEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KaifState {
+    pub fn mood(&self) -> Mood {
+        if self.samples == 0 {
+            Mood::Calm
+        } else if self.kaif >= 0.6 {
+            Mood::Delighted
+        } else if self.novelty >= 0.7 {
+            Mood::Surprised
+        } else if self.novelty <= 0.15 {
+            Mood::Bored
+        } else if self.kaif >= 0.3 {
+            Mood::Curious
+        } else {
+            Mood::Calm
+        }
+    }
+}
+
+impl Mood {
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Mood::Calm => "🤖",
+            Mood::Curious => "🤔",
+            Mood::Delighted => "😄",
+            Mood::Surprised => "😮",
+            Mood::Bored => "😐",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mood::Calm => "Спокойствие",
+            Mood::Curious => "Любопытство",
+            Mood::Delighted => "Кайф",
+            Mood::Surprised => "Удивление",
+            Mood::Bored => "Скука",
+        }
+    }
+
+    /// Цвет аватара (RGB)
+    pub fn color(&self) -> [u8; 3] {
+        match self {
+            Mood::Calm => [90, 130, 200],
+            Mood::Curious => [60, 160, 120],
+            Mood::Delighted => [230, 160, 30],
+            Mood::Surprised => [200, 90, 180],
+            Mood::Bored => [140, 140, 140],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kaif_moods() {
+        let mut engine = KaifEngine::new();
+        assert_eq!(engine.state().mood(), Mood::Calm);
+        assert_eq!(engine.feed(&[0.0, 0.0]).samples, 0);
+
+        // Повторение одного и того же — скука
+        for _ in 0..5 {
+            engine.feed(&[1.0, 0.0, 0.0]);
+        }
+        let bored = engine.state();
+        assert!(bored.novelty < 0.01 && bored.resonance > 0.99);
+        assert_eq!(bored.mood(), Mood::Bored);
+
+        // Противоположный сигнал — удивление, кайф падает
+        let surprised = engine.feed(&[-1.0, 0.0, 0.0]);
+        assert_eq!(surprised.mood(), Mood::Surprised);
+        assert!(surprised.kaif < bored.kaif + 1e-9);
+
+        // Умеренно новые, созвучные сигналы поднимают кайф
+        let mut engine = KaifEngine::new();
+        let mut angle: f64 = 0.0;
+        for _ in 0..20 {
+            angle += 0.6;
+            engine.feed(&[angle.cos(), angle.sin(), 0.0]);
+        }
+        assert!(engine.state().kaif > bored.kaif);
+
+        engine.reset();
+        assert_eq!(engine.state(), KaifState::default());
+    }
+}
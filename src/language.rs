@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Доля букв одного алфавита, начиная с которой язык считается определённым
+const DOMINANT_SHARE: f64 = 0.7;
+
+/// Язык обучающего примера
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Language {
+    Russian,
+    English,
+    /// Смешанный текст или текст без букв
+    Unknown,
+}
+
+impl Language {
+    /// Определение языка по доле кириллических и латинских букв
+    pub fn detect(text: &str) -> Self {
+        let (mut cyrillic, mut latin) = (0usize, 0usize);
+        for c in text.chars().filter(|c| c.is_alphabetic()) {
+            match c {
+                'а'..='я' | 'А'..='Я' | 'ё' | 'Ё' => cyrillic += 1,
+                'a'..='z' | 'A'..='Z' => latin += 1,
+                _ => {}
+            }
+        }
+
+        let total = (cyrillic + latin) as f64;
+        if total == 0.0 {
+            Language::Unknown
+        } else if cyrillic as f64 / total >= DOMINANT_SHARE {
+            Language::Russian
+        } else if latin as f64 / total >= DOMINANT_SHARE {
+            Language::English
+        } else {
+            Language::Unknown
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Russian => "Русский",
+            Language::English => "Английский",
+            Language::Unknown => "Не определён",
+        }
+    }
+}
+
+/// Отбор примеров нужного языка
+///
+/// Отброшенные примеры подсчитываются по языкам в `discarded`, чтобы
+/// показать, сколько текста потеряно при фильтрации смешанного корпуса.
+pub fn filter_by_language(
+    examples: Vec<String>,
+    keep: Language,
+    discarded: &mut BTreeMap<Language, usize>,
+) -> Vec<String> {
+    examples
+        .into_iter()
+        .filter(|example| {
+            let language = Language::detect(example);
+            if language != keep {
+                *discarded.entry(language).or_insert(0) += 1;
+            }
+            language == keep
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_filter_language() {
+        assert_eq!(Language::detect("Привет, как дела? (v2.0)"), Language::Russian);
+        assert_eq!(Language::detect("Hello, how are you?"), Language::English);
+        assert_eq!(Language::detect("Привет hello мир world"), Language::Unknown);
+        assert_eq!(Language::detect("12345"), Language::Unknown);
+
+        let examples = vec![
+            "Первый пример".to_string(),
+            "Second example".to_string(),
+            "Третий пример".to_string(),
+            "Fourth one".to_string(),
+            "---".to_string(),
+        ];
+        let mut discarded = BTreeMap::new();
+        let kept = filter_by_language(examples, Language::Russian, &mut discarded);
+        assert_eq!(kept, vec!["Первый пример", "Третий пример"]);
+        assert_eq!(discarded.get(&Language::English), Some(&2));
+        assert_eq!(discarded.get(&Language::Unknown), Some(&1));
+    }
+}
@@ -4,9 +4,73 @@ pub mod ai_model;
 pub mod file_processor;
 pub mod document_reader;
 pub mod chat_ui;
+pub mod tokenizer;
+pub mod gpu;
+pub mod memory;
+pub mod text_cleaner;
+pub mod language;
+pub mod sentences;
+pub mod folder_watcher;
+pub mod markdown;
+pub mod chat_history;
+pub mod loss_chart;
+pub mod ui_settings;
+pub mod system_monitor;
+pub mod commands;
+pub mod voice;
+pub mod speech;
+pub mod shortcuts;
+pub mod data_preview;
+pub mod voxel;
+pub mod voxel_gpu;
+pub mod voxel_soa;
+pub mod voxel_memory;
+pub mod voxel_language;
+pub mod controller;
+pub mod spatial_grid;
+pub mod octree;
+pub mod chunks;
+pub mod environment;
+pub mod sound;
+pub mod events;
+pub mod population;
+pub mod evolution;
+pub mod speciation;
+pub mod novelty;
+pub mod pareto;
+pub mod islands;
+pub mod lighting;
+pub mod ecosystem;
+pub mod notify;
+pub mod kaif;
+pub mod local_time;
+pub mod virtual_list;
 
 // Re-export main types
-pub use ai_model::AIModel;
-pub use file_processor::{FileProcessor, FileStats};
+pub use ai_model::{
+    cosine_similarity, AIModel, AIModelBuilder, AutoSaveConfig, CheckpointConfig, EpochLoss, GenerationConfig, GenerationMetrics, HiddenLayerSpec,
+    InferenceBackend, LayerKind, OpenAIBackend, Optimizer, Precision, ScheduledSampling, TrainingControl, TrainingHandle,
+    TrainingReport, Vocabulary,
+};
+pub use file_processor::{ChunkingOptions, CorpusReport, CsvOptions, ExtractionMode, FileProcessor, FileStats, TextChunks};
 pub use document_reader::DocumentReader;
+pub use tokenizer::{BpeTokenizer, TextNormalizer};
+pub use gpu::GpuCompute;
+pub use text_cleaner::TextCleaner;
+pub use language::Language;
+pub use sentences::split_sentences;
+pub use folder_watcher::FolderWatcher;
+pub use chat_history::{ChatHistory, ChatSessions};
+pub use loss_chart::LossChart;
+pub use ui_settings::UiSettings;
+pub use system_monitor::{MetricHistory, SystemMonitor};
+pub use commands::ChatCommand;
+pub use voice::VoiceInput;
+pub use speech::Speaker;
+pub use shortcuts::{Shortcut, ShortcutAction, Shortcuts};
+pub use data_preview::DataPreview;
+pub use ecosystem::EcosystemView;
+pub use kaif::{KaifEngine, KaifState, Mood};
+pub use virtual_list::VirtualList;
+pub use memory::{SemanticMemory, SnippetMemory};
 pub use chat_ui::{ChatUI, ChatMessage, AppMode, TrainingStatus};
@@ -1,5 +1,4 @@
 use half::f16;
-use serde::{Deserialize, Serialize};
 
 /// LightPattern: exactly 1000 bytes
 #[repr(C, packed)]
@@ -87,17 +86,6 @@ impl Default for LightPattern {
     }
 }
 
-// Verify size is exactly 1000 bytes
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[test]
-    fn test_light_pattern_size() {
-        assert_eq!(std::mem::size_of::<LightPattern>(), 1000);
-    }
-}
-
 /// Lighting System
 pub struct LightingSystem {
     pub patterns: Vec<LightPattern>,
@@ -129,3 +117,14 @@ impl Default for LightingSystem {
         Self::new()
     }
 }
+
+// Verify size is exactly 1000 bytes
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_light_pattern_size() {
+        assert_eq!(std::mem::size_of::<LightPattern>(), 1000);
+    }
+}
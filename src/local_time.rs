@@ -0,0 +1,66 @@
+//! Местное время и подписи дат в истории чата
+//!
+//! Время берётся из `chrono::Local`: часовой пояс и переход на летнее время
+//! учитываются при каждом вызове. Даты сообщений разбираются в `NaiveDate`.
+
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+
+/// Формат метки сообщения, в таком виде время хранится в истории чата
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+const MONTHS: [&str; 12] = [
+    "января", "февраля", "марта", "апреля", "мая", "июня",
+    "июля", "августа", "сентября", "октября", "ноября", "декабря",
+];
+
+/// Метка сообщения `ГГГГ-ММ-ДД ЧЧ:ММ`
+pub fn timestamp(time: &DateTime<Local>) -> String {
+    time.format(TIMESTAMP_FORMAT).to_string()
+}
+
+/// Дата из метки сообщения `ГГГГ-ММ-ДД ЧЧ:ММ`; у старых меток `ЧЧ:ММ` её нет
+pub fn message_date(timestamp: &str) -> Option<NaiveDate> {
+    let date = timestamp.split_whitespace().next()?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// Время из метки сообщения для показа в пузыре
+pub fn message_time(timestamp: &str) -> &str {
+    timestamp.rsplit(' ').next().unwrap_or(timestamp)
+}
+
+/// Подпись разделителя дат: «Сегодня», «Вчера» или «16 октября 2026»
+pub fn date_label(date: NaiveDate, today: NaiveDate) -> String {
+    let month = MONTHS[date.month0() as usize];
+    match (today - date).num_days() {
+        0 => "Сегодня".to_string(),
+        1 => "Вчера".to_string(),
+        _ if date.year() == today.year() => format!("{} {}", date.day(), month),
+        _ => format!("{} {} {}", date.day(), month, date.year()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_local_date_time() {
+        let time = Local.with_ymd_and_hms(2026, 10, 16, 21, 30, 5).unwrap();
+        assert_eq!(timestamp(&time), "2026-10-16 21:30");
+
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(message_date("2026-10-16 21:30"), Some(today));
+        assert_eq!(message_date("2026-02-30 21:30"), None);
+        assert_eq!(message_date("21:30"), None);
+        assert_eq!(message_time("2026-10-16 21:30"), "21:30");
+        assert_eq!(message_time("21:30"), "21:30");
+
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        assert_eq!(date_label(today, today), "Сегодня");
+        assert_eq!(date_label(date(2026, 10, 15), today), "Вчера");
+        assert_eq!(date_label(date(2026, 3, 8), today), "8 марта");
+        assert_eq!(date_label(date(2025, 12, 31), today), "31 декабря 2025");
+    }
+}
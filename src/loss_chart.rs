@@ -0,0 +1,93 @@
+use crate::ai_model::EpochLoss;
+use eframe::egui;
+use egui_plot::{Corner, Legend, Line, Plot, PlotPoints, Points};
+use std::fs;
+use std::path::Path;
+
+const TRAIN_COLOR: egui::Color32 = egui::Color32::from_rgb(60, 120, 230);
+const VALIDATION_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 120, 40);
+
+/// График loss по эпохам: обучение и валидация
+///
+/// Рисуется `egui_plot`: Ctrl + колесо мыши масштабирует, перетаскивание сдвигает,
+/// двойной щелчок возвращает полный масштаб. При наведении показываются
+/// эпоха и значение под курсором.
+#[derive(Default)]
+pub struct LossChart {
+    /// Вернуть полный масштаб при следующем показе
+    reset: bool,
+}
+
+impl LossChart {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset_view(&mut self) {
+        self.reset = true;
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, history: &[EpochLoss], height: f32) {
+        let train: Vec<[f64; 2]> = history.iter().map(|loss| [loss.epoch as f64, loss.train]).collect();
+        let validation: Vec<[f64; 2]> = history
+            .iter()
+            .filter_map(|loss| loss.validation.map(|value| [loss.epoch as f64, value]))
+            .collect();
+
+        let mut plot = Plot::new("loss_chart")
+            .height(height)
+            .legend(Legend::default().position(Corner::RightTop))
+            .label_formatter(|name, value| {
+                let epoch = value.x.round().max(0.0) as usize;
+                if name.is_empty() {
+                    format!("Эпоха {}\nLoss: {:.4}", epoch, value.y)
+                } else {
+                    format!("{}\nЭпоха {}\nLoss: {:.4}", name, epoch, value.y)
+                }
+            });
+        if std::mem::take(&mut self.reset) {
+            plot = plot.reset();
+        }
+
+        plot.show(ui, |plot_ui| {
+            for (name, points, color) in [("обучение", train, TRAIN_COLOR), ("валидация", validation, VALIDATION_COLOR)] {
+                if points.is_empty() {
+                    continue;
+                }
+                plot_ui.line(Line::new(PlotPoints::from(points.clone())).name(name).color(color).width(2.0));
+                plot_ui.points(Points::new(PlotPoints::from(points)).name(name).color(color).radius(2.5));
+            }
+        });
+    }
+
+    /// CSV с колонками `epoch,train_loss,validation_loss`
+    pub fn to_csv(history: &[EpochLoss]) -> String {
+        let mut csv = String::from("epoch,train_loss,validation_loss\n");
+        for loss in history {
+            let validation = loss.validation.map(|value| value.to_string()).unwrap_or_default();
+            csv.push_str(&format!("{},{},{}\n", loss.epoch, loss.train, validation));
+        }
+        csv
+    }
+
+    pub fn export_csv(history: &[EpochLoss], path: &Path) -> Result<(), String> {
+        fs::write(path, Self::to_csv(history)).map_err(|e| format!("✗ Ошибка записи {:?}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loss_csv() {
+        let history = vec![
+            EpochLoss { epoch: 1, train: 2.5, validation: Some(2.75) },
+            EpochLoss { epoch: 2, train: 1.25, validation: None },
+        ];
+        assert_eq!(
+            LossChart::to_csv(&history),
+            "epoch,train_loss,validation_loss\n1,2.5,2.75\n2,1.25,\n"
+        );
+    }
+}
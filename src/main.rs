@@ -1,10 +1,6 @@
-mod ai_model;
-mod file_processor;
-mod chat_ui;
+use adaptive_entity_engine::ChatUI;
 
 fn main() -> Result<(), eframe::Error> {
-    use chat_ui::ChatUI;
-
     let options = eframe::NativeOptions {
         initial_window_size: Some(eframe::epaint::Vec2::new(1200.0, 800.0)),
         ..Default::default()
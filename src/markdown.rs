@@ -0,0 +1,366 @@
+use eframe::egui;
+use egui::text::LayoutJob;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Color, FontStyle, Style, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Блок Markdown-разметки
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    /// Заголовок `#`…`######`
+    Heading(usize, Vec<Span>),
+    /// Подряд идущие строки текста (переводы строк сохраняются)
+    Paragraph(Vec<Span>),
+    /// Пункт списка: маркер (`•` или `1.`), уровень вложенности, текст
+    ListItem(String, usize, Vec<Span>),
+    Quote(Vec<Span>),
+    /// Блок кода в ```: язык и текст
+    Code(String, String),
+    /// Горизонтальная черта `---`
+    Rule,
+}
+
+/// Фрагмент строки с одним оформлением
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub code: bool,
+}
+
+impl Span {
+    fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Разбор подмножества Markdown, которое встречается в ответах модели
+///
+/// Поддерживаются заголовки, маркированные и нумерованные списки, цитаты,
+/// блоки кода, горизонтальные черты и внутри строк `**жирный**`, `*курсив*`
+/// и `` `код` ``. Незакрытые маркеры выводятся как обычный текст.
+pub fn parse(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<(String, Vec<&str>)> = None;
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(parse_inline(&paragraph.join("\n"))));
+            paragraph.clear();
+        }
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some((lang, lines)) = &mut code {
+            if trimmed.starts_with("```") {
+                blocks.push(Block::Code(std::mem::take(lang), lines.join("\n")));
+                code = None;
+            } else {
+                lines.push(line);
+            }
+            continue;
+        }
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            flush(&mut paragraph, &mut blocks);
+            code = Some((lang.trim().to_string(), Vec::new()));
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+            continue;
+        }
+
+        let block = if let Some(level) = heading_level(trimmed) {
+            Some(Block::Heading(level, parse_inline(trimmed[level..].trim())))
+        } else if is_rule(trimmed) {
+            Some(Block::Rule)
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            Some(Block::Quote(parse_inline(quote.trim())))
+        } else {
+            let depth = (line.len() - trimmed.len()) / 2;
+            list_item(trimmed).map(|(marker, rest)| Block::ListItem(marker, depth, parse_inline(rest)))
+        };
+
+        match block {
+            Some(block) => {
+                flush(&mut paragraph, &mut blocks);
+                blocks.push(block);
+            }
+            None => paragraph.push(line.trim_end()),
+        }
+    }
+
+    flush(&mut paragraph, &mut blocks);
+    // Незакрытый блок кода — до конца сообщения (например, ответ ещё генерируется)
+    if let Some((lang, lines)) = code {
+        blocks.push(Block::Code(lang, lines.join("\n")));
+    }
+    blocks
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    ((1..=6).contains(&level) && line[level..].starts_with(' ')).then_some(level)
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.trim_end();
+    line.len() >= 3 && (line.chars().all(|c| c == '-') || line.chars().all(|c| c == '*') || line.chars().all(|c| c == '_'))
+}
+
+/// Маркер пункта списка и текст после него
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ ", "• "] {
+        if let Some(rest) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), rest));
+        }
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = &line[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return Some((format!("{}.", &line[..digits]), &rest[2..]));
+    }
+    None
+}
+
+/// Разбор оформления внутри строки
+pub fn parse_inline(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    parse_inline_into(text, &Span::default(), &mut spans);
+    spans
+}
+
+fn parse_inline_into(text: &str, style: &Span, spans: &mut Vec<Span>) {
+    let mut plain = String::new();
+    let mut rest = text;
+
+    let flush = |plain: &mut String, spans: &mut Vec<Span>| {
+        if !plain.is_empty() {
+            spans.push(Span {
+                text: std::mem::take(plain),
+                ..style.clone()
+            });
+        }
+    };
+
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                flush(&mut plain, spans);
+                spans.push(Span {
+                    code: true,
+                    ..Span::plain(&rest[1..1 + end])
+                });
+                rest = &rest[end + 2..];
+                continue;
+            }
+        } else if let Some(inner) = rest.strip_prefix("**") {
+            if let Some(end) = inner.find("**").filter(|&end| end > 0) {
+                flush(&mut plain, spans);
+                parse_inline_into(&inner[..end], &Span { bold: true, ..style.clone() }, spans);
+                rest = &inner[end + 2..];
+                continue;
+            }
+        } else if c == '*' && !rest[1..].starts_with(char::is_whitespace) {
+            if let Some(end) = rest[1..].find('*').filter(|&end| end > 0) {
+                flush(&mut plain, spans);
+                parse_inline_into(&rest[1..1 + end], &Span { italic: true, ..style.clone() }, spans);
+                rest = &rest[end + 2..];
+                continue;
+            }
+        }
+
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    flush(&mut plain, spans);
+}
+
+/// Грамматики и темы syntect: загружаются один раз на всё приложение
+struct Highlighter {
+    syntaxes: SyntaxSet,
+    themes: ThemeSet,
+}
+
+fn highlighter() -> &'static Highlighter {
+    static HIGHLIGHTER: OnceLock<Highlighter> = OnceLock::new();
+    HIGHLIGHTER.get_or_init(|| Highlighter {
+        syntaxes: SyntaxSet::load_defaults_newlines(),
+        themes: ThemeSet::load_defaults(),
+    })
+}
+
+/// Разбиение кода на фрагменты с оформлением по грамматике syntect
+///
+/// Язык ищется по имени или расширению из заголовка блока (`rust`, `py`,
+/// `sh`); неизвестный язык выводится цветом текста темы. Склеенные фрагменты
+/// дают исходный код.
+pub fn highlight<'a>(code: &'a str, lang: &str, dark: bool) -> Vec<(Style, &'a str)> {
+    let Highlighter { syntaxes, themes } = highlighter();
+    let syntax = syntaxes.find_syntax_by_token(lang).unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let theme = &themes.themes[if dark { "base16-ocean.dark" } else { "InspiredGitHub" }];
+    let plain = Style {
+        foreground: theme.settings.foreground.unwrap_or(Color::BLACK),
+        ..Style::default()
+    };
+
+    let mut lines = HighlightLines::new(syntax, theme);
+    let mut fragments = Vec::new();
+    for line in LinesWithEndings::from(code) {
+        match lines.highlight_line(line, syntaxes) {
+            Ok(ranges) => fragments.extend(ranges),
+            Err(_) => fragments.push((plain, line)),
+        }
+    }
+    fragments
+}
+
+/// Блок кода с подсветкой и кнопкой копирования
+fn show_code(ui: &mut egui::Ui, lang: &str, code: &str, size: f32) {
+    egui::Frame::none()
+        .fill(ui.visuals().code_bg_color)
+        .rounding(egui::Rounding::same(6.0))
+        .inner_margin(egui::Margin::same(8.0))
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(lang).size(size - 2.0).color(ui.visuals().weak_text_color()));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("📋 Копировать").clicked() {
+                        ui.output_mut(|output| output.copied_text = code.to_string());
+                    }
+                });
+            });
+
+            let mut job = LayoutJob::default();
+            for (style, text) in highlight(code, lang, ui.visuals().dark_mode) {
+                let Color { r, g, b, .. } = style.foreground;
+                let format = egui::TextFormat {
+                    font_id: egui::FontId::monospace(size),
+                    color: egui::Color32::from_rgb(r, g, b),
+                    italics: style.font_style.contains(FontStyle::ITALIC),
+                    underline: if style.font_style.contains(FontStyle::UNDERLINE) {
+                        egui::Stroke::new(1.0, egui::Color32::from_rgb(r, g, b))
+                    } else {
+                        egui::Stroke::NONE
+                    },
+                    ..Default::default()
+                };
+                job.append(text, 0.0, format);
+            }
+            ui.label(job);
+        });
+}
+
+/// Вывод Markdown-текста; `size` — размер основного шрифта
+pub fn show(ui: &mut egui::Ui, text: &str, size: f32) {
+    for block in parse(text) {
+        match block {
+            Block::Heading(level, spans) => {
+                let heading_size = size + (8.0 - level as f32 * 1.5).max(1.0);
+                ui.label(layout(ui, &spans, heading_size, true));
+            }
+            Block::Paragraph(spans) => {
+                ui.label(layout(ui, &spans, size, false));
+            }
+            Block::ListItem(marker, depth, spans) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.add_space(depth as f32 * 16.0);
+                    ui.label(egui::RichText::new(marker).size(size));
+                    ui.label(layout(ui, &spans, size, false));
+                });
+            }
+            Block::Quote(spans) => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(egui::RichText::new("▍").size(size).color(ui.visuals().weak_text_color()));
+                    let mut job = layout(ui, &spans, size, false);
+                    for section in &mut job.sections {
+                        section.format.color = ui.visuals().weak_text_color();
+                    }
+                    ui.label(job);
+                });
+            }
+            Block::Code(lang, code) => show_code(ui, &lang, &code, size - 1.0),
+            Block::Rule => {
+                ui.separator();
+            }
+        }
+    }
+}
+
+fn layout(ui: &egui::Ui, spans: &[Span], size: f32, strong: bool) -> LayoutJob {
+    let visuals = ui.visuals();
+    let mut job = LayoutJob::default();
+    for span in spans {
+        let format = egui::TextFormat {
+            font_id: if span.code { egui::FontId::monospace(size - 1.0) } else { egui::FontId::proportional(size) },
+            // У шрифта по умолчанию нет жирного начертания — выделяем цветом
+            color: if span.bold || strong { visuals.strong_text_color() } else { visuals.text_color() },
+            italics: span.italic,
+            background: if span.code { visuals.code_bg_color } else { egui::Color32::TRANSPARENT },
+            ..Default::default()
+        };
+        job.append(&span.text, 0.0, format);
+    }
+    job.wrap.max_width = ui.available_width();
+    job
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_blocks_and_inline() {
+        let text = "# Заголовок\nОбычный **жирный** и *курсив*\nвторая строка\n\n- пункт с `кодом`\n  2. вложенный\n> цитата\n---\n```rust\nfn main() {}\n```\n2 * 3 = 6, **незакрыто";
+        let blocks = parse(text);
+
+        assert_eq!(blocks[0], Block::Heading(1, vec![Span::plain("Заголовок")]));
+        assert_eq!(
+            blocks[1],
+            Block::Paragraph(vec![
+                Span::plain("Обычный "),
+                Span { bold: true, ..Span::plain("жирный") },
+                Span::plain(" и "),
+                Span { italic: true, ..Span::plain("курсив") },
+                Span::plain("\nвторая строка"),
+            ])
+        );
+        assert_eq!(
+            blocks[2],
+            Block::ListItem("•".to_string(), 0, vec![Span::plain("пункт с "), Span { code: true, ..Span::plain("кодом") }])
+        );
+        assert_eq!(blocks[3], Block::ListItem("2.".to_string(), 1, vec![Span::plain("вложенный")]));
+        assert_eq!(blocks[4], Block::Quote(vec![Span::plain("цитата")]));
+        assert_eq!(blocks[5], Block::Rule);
+        assert_eq!(blocks[6], Block::Code("rust".to_string(), "fn main() {}".to_string()));
+        assert_eq!(blocks[7], Block::Paragraph(vec![Span::plain("2 * 3 = 6, **незакрыто")]));
+        assert_eq!(blocks.len(), 8);
+    }
+
+    #[test]
+    fn test_highlight_code() {
+        let code = "fn main() {\n    let s = \"текст\"; // конец\n}";
+        for dark in [false, true] {
+            let fragments = highlight(code, "rust", dark);
+            assert_eq!(fragments.iter().map(|(_, text)| *text).collect::<String>(), code);
+            let color = |needle: &str| fragments.iter().find(|(_, text)| text.contains(needle)).unwrap().0.foreground;
+            assert_ne!(color("fn"), color("main"));
+            assert_ne!(color("текст"), color("let"));
+        }
+
+        // Язык ищется и по расширению; неизвестный выводится одним цветом
+        assert!(highlight("def f():\n    return 'x'", "py", true).len() > 2);
+        let plain = highlight("fn main() {}\n", "нет такого", true);
+        assert_eq!(plain.len(), 1);
+    }
+}
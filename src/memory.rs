@@ -0,0 +1,135 @@
+use crate::ai_model::{cosine_similarity, AIModel};
+use crate::sentences::split_sentences;
+use serde::{Deserialize, Serialize};
+
+/// Хранилище фрагментов текста с семантическим поиском
+///
+/// Источник контекста для генерации с извлечением (RAG): по эмбеддингу
+/// запроса возвращает наиболее близкие сохранённые фрагменты.
+pub trait SemanticMemory {
+    /// До `limit` фрагментов, ближайших к `query`, по убыванию близости
+    fn find_similar(&self, query: &[f64], limit: usize) -> Vec<String>;
+}
+
+/// Простейшая память: фрагменты с эмбеддингами в векторе, поиск перебором
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SnippetMemory {
+    pub entries: Vec<MemoryEntry>,
+    /// Минимальная косинусная близость, ниже которой фрагмент не возвращается
+    pub min_similarity: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    pub embedding: Vec<f64>,
+    pub text: String,
+}
+
+impl SnippetMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Сохранение фрагмента с эмбеддингом, посчитанным моделью
+    pub fn remember(&mut self, model: &AIModel, text: impl Into<String>) {
+        let text = text.into();
+        self.entries.push(MemoryEntry {
+            embedding: model.embed(&text),
+            text,
+        });
+    }
+
+    /// Сохранение документа фрагментами до `max_words` слов; возвращает их число
+    pub fn remember_document(&mut self, model: &AIModel, text: &str, max_words: usize) -> usize {
+        let passages = split_passages(text, max_words);
+        let count = passages.len();
+        for passage in passages {
+            self.remember(model, passage);
+        }
+        count
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl SemanticMemory for SnippetMemory {
+    fn find_similar(&self, query: &[f64], limit: usize) -> Vec<String> {
+        let mut scored: Vec<(f64, &MemoryEntry)> = self
+            .entries
+            .iter()
+            .map(|entry| (cosine_similarity(query, &entry.embedding), entry))
+            .filter(|(similarity, _)| *similarity >= self.min_similarity)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, entry)| entry.text.clone())
+            .collect()
+    }
+}
+
+/// Разбиение текста на фрагменты до `max_words` слов по границам предложений
+///
+/// Предложение длиннее `max_words` режется по словам.
+pub fn split_passages(text: &str, max_words: usize) -> Vec<String> {
+    let max_words = max_words.max(1);
+    let mut passages = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for sentence in split_sentences(text) {
+        let words: Vec<&str> = sentence.split_whitespace().collect();
+        if !current.is_empty() && current.len() + words.len() > max_words {
+            passages.push(current.join(" "));
+            current.clear();
+        }
+        for chunk in words.chunks(max_words) {
+            if current.len() + chunk.len() > max_words {
+                passages.push(current.join(" "));
+                current.clear();
+            }
+            current.extend(chunk);
+        }
+    }
+    if !current.is_empty() {
+        passages.push(current.join(" "));
+    }
+    passages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_similar_orders_by_similarity() {
+        let memory = SnippetMemory {
+            entries: vec![
+                MemoryEntry { embedding: vec![0.0, 1.0], text: "далеко".to_string() },
+                MemoryEntry { embedding: vec![1.0, 0.1], text: "близко".to_string() },
+                MemoryEntry { embedding: vec![1.0, 0.5], text: "рядом".to_string() },
+            ],
+            min_similarity: 0.5,
+        };
+
+        assert_eq!(memory.find_similar(&[1.0, 0.0], 5), vec!["близко", "рядом"]);
+        assert_eq!(memory.find_similar(&[1.0, 0.0], 1), vec!["близко"]);
+    }
+
+    #[test]
+    fn test_split_passages() {
+        let text = "Первое предложение здесь. Второе тоже. Третье предложение немного длиннее остальных.";
+        assert_eq!(
+            split_passages(text, 5),
+            vec!["Первое предложение здесь. Второе тоже.", "Третье предложение немного длиннее остальных."]
+        );
+        assert_eq!(split_passages("раз два три четыре пять", 2), vec!["раз два", "три четыре", "пять"]);
+        assert!(split_passages("  ", 10).is_empty());
+    }
+}
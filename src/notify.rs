@@ -0,0 +1,61 @@
+//! Системные уведомления и звук
+//!
+//! Уведомления показывает `notify-rust`: D-Bus в Linux, Notification Center
+//! в macOS, WinRT в Windows. Звук, как и `speech`, проигрывают программы
+//! операционной системы: `paplay`/`canberra-gtk-play` в Linux, `afplay` в
+//! macOS, PowerShell в Windows. Процессы не блокируют интерфейс: их
+//! завершения ждёт отдельный поток.
+
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Имя приложения в уведомлениях
+const APP_NAME: &str = "AI Ассистент";
+
+/// Запуск первой доступной программы из списка
+fn spawn_first(candidates: Vec<(&str, Vec<String>)>) -> Result<(), String> {
+    let mut last_error = "нет подходящей программы".to_string();
+    for (program, args) in candidates {
+        let spawned = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match spawned {
+            Ok(mut child) => {
+                thread::spawn(move || child.wait());
+                return Ok(());
+            }
+            Err(e) => last_error = format!("{}: {}", program, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// Всплывающее уведомление рабочего стола
+pub fn desktop_notification(title: &str, body: &str) -> Result<(), String> {
+    notify_rust::Notification::new()
+        .appname(APP_NAME)
+        .summary(title)
+        .body(body)
+        .show()
+        .map(drop)
+        .map_err(|e| format!("✗ Уведомление не показано ({})", e))
+}
+
+/// Короткий системный звук завершения
+pub fn play_completion_sound() -> Result<(), String> {
+    let candidates = if cfg!(target_os = "windows") {
+        let script = "[System.Media.SystemSounds]::Asterisk.Play(); Start-Sleep -Seconds 1".to_string();
+        vec![("powershell", vec!["-NoProfile".to_string(), "-Command".to_string(), script])]
+    } else if cfg!(target_os = "macos") {
+        vec![("afplay", vec!["/System/Library/Sounds/Glass.aiff".to_string()])]
+    } else {
+        vec![
+            ("paplay", vec!["/usr/share/sounds/freedesktop/stereo/complete.oga".to_string()]),
+            ("canberra-gtk-play", vec!["-i".to_string(), "complete".to_string()]),
+        ]
+    };
+    spawn_first(candidates).map_err(|e| format!("✗ Звук не воспроизведён ({})", e))
+}
@@ -0,0 +1,127 @@
+//! Novelty search: reward voxels for behaving unlike anything seen before
+//!
+//! Each voxel is described by a `Behavior`: where it ended up, how it feels
+//! and how much it has eaten. Its novelty is the mean distance to the `k`
+//! nearest behaviors among the rest of the population and the archive; the
+//! archive keeps behaviors that were novel enough when they appeared.
+
+use crate::voxel::{Voxel, WORLD_EXTENT};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Behavior descriptor: position (scaled to the world extent), valence,
+/// arousal, dominance, kaif and log intake
+pub type Behavior = [f64; 8];
+
+/// Behavior descriptor of `voxel` at `position`
+pub fn behavior(voxel: &Voxel, position: [i32; 3]) -> Behavior {
+    let extent = f64::from(WORLD_EXTENT);
+    let [x, y, z] = position.map(|c| f64::from(c) / extent);
+    [
+        x,
+        y,
+        z,
+        voxel.emotion_valence,
+        voxel.emotion_arousal,
+        voxel.emotion_dominance,
+        voxel.kaif(),
+        voxel.intake().max(0.0).ln_1p(),
+    ]
+}
+
+fn distance(a: &Behavior, b: &Behavior) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt()
+}
+
+/// Novelty search settings and archive of an `EvolutionEngine`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NoveltySearch {
+    /// Select by novelty instead of fitness
+    pub enabled: bool,
+    /// Nearest behaviors novelty is measured against
+    pub k: usize,
+    /// Novelty a behavior needs to enter the archive
+    pub archive_threshold: f64,
+    /// Archived behaviors kept; the oldest go first
+    pub archive_capacity: usize,
+    pub archive: VecDeque<Behavior>,
+}
+
+impl NoveltySearch {
+    /// Novelty of every behavior in `behaviors`, archiving the novel ones
+    pub fn score(&mut self, behaviors: &[Behavior]) -> Vec<f64> {
+        let k = self.k.max(1);
+        let scores: Vec<f64> = behaviors
+            .iter()
+            .enumerate()
+            .map(|(i, behavior)| {
+                let mut distances: Vec<f64> = behaviors
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, other)| other)
+                    .chain(&self.archive)
+                    .map(|other| distance(behavior, other))
+                    .collect();
+                if distances.is_empty() {
+                    return 0.0;
+                }
+                distances.sort_by(f64::total_cmp);
+                distances.truncate(k);
+                distances.iter().sum::<f64>() / distances.len() as f64
+            })
+            .collect();
+        for (behavior, &novelty) in behaviors.iter().zip(&scores) {
+            if novelty >= self.archive_threshold {
+                while self.archive.len() >= self.archive_capacity.max(1) {
+                    self.archive.pop_front();
+                }
+                self.archive.push_back(*behavior);
+            }
+        }
+        scores
+    }
+    
+    pub fn clear(&mut self) {
+        self.archive.clear();
+    }
+}
+
+impl Default for NoveltySearch {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            k: 15,
+            archive_threshold: 0.5,
+            archive_capacity: 500,
+            archive: VecDeque::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_novelty_score_and_archive() {
+        let mut voxel = Voxel::new();
+        voxel.emotion_valence = 0.0;
+        let calm = behavior(&voxel, [0, 0, 0]);
+        let wanderer = behavior(&voxel, [WORLD_EXTENT, 0, 0]);
+        assert_eq!(wanderer[0], 1.0);
+        
+        let mut search = NoveltySearch { k: 1, archive_threshold: 0.5, archive_capacity: 2, ..NoveltySearch::default() };
+        let behaviors = [calm, calm, wanderer];
+        let scores = search.score(&behaviors);
+        assert_eq!(scores[0], 0.0);
+        assert!((scores[2] - 1.0).abs() < 1e-12);
+        assert_eq!(search.archive.len(), 1);
+        
+        // The wanderer is no longer new once it is archived
+        let scores = search.score(&[wanderer]);
+        assert_eq!(scores, [0.0]);
+        assert_eq!(search.score(&[]), Vec::<f64>::new());
+    }
+}
@@ -0,0 +1,348 @@
+use crate::spatial_grid::distance_sq;
+use serde::{Deserialize, Serialize};
+
+/// Axis-aligned box over integer positions, bounds inclusive
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Aabb {
+    pub min: [i32; 3],
+    pub max: [i32; 3],
+}
+
+impl Aabb {
+    pub fn point(position: [i32; 3]) -> Self {
+        Self { min: position, max: position }
+    }
+    
+    pub fn contains(&self, position: [i32; 3]) -> bool {
+        (0..3).all(|i| self.min[i] <= position[i] && position[i] <= self.max[i])
+    }
+    
+    pub fn include(&mut self, position: [i32; 3]) {
+        self.min = [0, 1, 2].map(|i| self.min[i].min(position[i]));
+        self.max = [0, 1, 2].map(|i| self.max[i].max(position[i]));
+    }
+    
+    /// An octant of a flat box along some axis can be empty (`min > max`)
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.min[i] > self.max[i])
+    }
+    
+    /// Squared distance from `position` to the nearest point of the box
+    pub fn distance_sq(&self, position: [i32; 3]) -> i64 {
+        if self.is_empty() {
+            return i64::MAX;
+        }
+        let nearest = [0, 1, 2].map(|i| position[i].clamp(self.min[i], self.max[i]));
+        distance_sq(nearest, position)
+    }
+    
+    fn center(&self) -> [i32; 3] {
+        [0, 1, 2].map(|i| (i64::from(self.min[i]) + (i64::from(self.max[i]) - i64::from(self.min[i])) / 2) as i32)
+    }
+    
+    fn is_point(&self) -> bool {
+        self.min == self.max
+    }
+    
+    /// Octant `index` (bit per axis: set = upper half)
+    fn octant(&self, index: usize) -> Self {
+        let center = self.center();
+        let upper = |axis: usize| index & (1 << axis) != 0;
+        Self {
+            min: [0, 1, 2].map(|axis| if upper(axis) { center[axis].saturating_add(1) } else { self.min[axis] }),
+            max: [0, 1, 2].map(|axis| if upper(axis) { self.max[axis] } else { center[axis] }),
+        }
+    }
+    
+    fn octant_of(&self, position: [i32; 3]) -> usize {
+        let center = self.center();
+        (0..3).filter(|&axis| position[axis] > center[axis]).map(|axis| 1 << axis).sum()
+    }
+    
+    /// Box twice as large along each axis, grown towards `position`
+    fn grown_towards(&self, position: [i32; 3]) -> Self {
+        let extent = [0, 1, 2].map(|axis| (self.max[axis] - self.min[axis]).saturating_add(1));
+        let above = |axis: usize| position[axis] > self.max[axis];
+        Self {
+            min: [0, 1, 2].map(|axis| if above(axis) { self.min[axis] } else { self.min[axis].saturating_sub(extent[axis]) }),
+            max: [0, 1, 2].map(|axis| if above(axis) { self.max[axis].saturating_add(extent[axis]) } else { self.max[axis] }),
+        }
+    }
+}
+
+/// Half-space `normal · p + d >= 0`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    pub normal: [f32; 3],
+    pub d: f32,
+}
+
+impl Plane {
+    pub fn distance(&self, point: [f32; 3]) -> f32 {
+        self.normal[0] * point[0] + self.normal[1] * point[1] + self.normal[2] * point[2] + self.d
+    }
+}
+
+/// Where a box lies relative to a frustum
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Containment {
+    Outside,
+    Intersects,
+    Inside,
+}
+
+/// Convex view volume as a set of inward-facing planes
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Frustum {
+    pub planes: Vec<Plane>,
+}
+
+impl Frustum {
+    /// Orthographic view rotated by `yaw` around the Y axis, with unbounded depth:
+    /// visible points project to `|x·cos yaw + z·sin yaw| <= half_width`, `|y| <= half_height`
+    pub fn orthographic(yaw: f32, half_width: f32, half_height: f32) -> Self {
+        let (sin, cos) = yaw.sin_cos();
+        Self {
+            planes: vec![
+                Plane { normal: [cos, 0.0, sin], d: half_width },
+                Plane { normal: [-cos, 0.0, -sin], d: half_width },
+                Plane { normal: [0.0, 1.0, 0.0], d: half_height },
+                Plane { normal: [0.0, -1.0, 0.0], d: half_height },
+            ],
+        }
+    }
+    
+    pub fn contains(&self, position: [i32; 3]) -> bool {
+        let point = position.map(|c| c as f32);
+        self.planes.iter().all(|plane| plane.distance(point) >= 0.0)
+    }
+    
+    pub fn classify(&self, bounds: &Aabb) -> Containment {
+        if bounds.is_empty() {
+            return Containment::Outside;
+        }
+        let mut result = Containment::Inside;
+        for plane in &self.planes {
+            // Corner furthest along the normal, and the opposite one
+            let corner = |far: bool| {
+                [0, 1, 2].map(|i| {
+                    let upper = (plane.normal[i] >= 0.0) == far;
+                    (if upper { bounds.max[i] } else { bounds.min[i] }) as f32
+                })
+            };
+            if plane.distance(corner(true)) < 0.0 {
+                return Containment::Outside;
+            }
+            if plane.distance(corner(false)) < 0.0 {
+                result = Containment::Intersects;
+            }
+        }
+        result
+    }
+}
+
+enum NodeKind<T> {
+    Leaf(Vec<(T, [i32; 3])>),
+    Branch(Box<[Node<T>; 8]>),
+}
+
+struct Node<T> {
+    bounds: Aabb,
+    kind: NodeKind<T>,
+}
+
+impl<T: Copy> Node<T> {
+    fn build(bounds: Aabb, items: Vec<(T, [i32; 3])>, leaf_size: usize) -> Self {
+        if items.len() <= leaf_size || bounds.is_point() {
+            return Self { bounds, kind: NodeKind::Leaf(items) };
+        }
+        let mut octants: [Vec<(T, [i32; 3])>; 8] = Default::default();
+        for item in items {
+            octants[bounds.octant_of(item.1)].push(item);
+        }
+        let mut index = 0;
+        let children = octants.map(|items| {
+            let child = Self::build(bounds.octant(index), items, leaf_size);
+            index += 1;
+            child
+        });
+        Self { bounds, kind: NodeKind::Branch(Box::new(children)) }
+    }
+    
+    fn insert(&mut self, item: T, position: [i32; 3], leaf_size: usize) {
+        match &mut self.kind {
+            NodeKind::Branch(children) => {
+                children[self.bounds.octant_of(position)].insert(item, position, leaf_size);
+            }
+            NodeKind::Leaf(items) => {
+                items.push((item, position));
+                if items.len() > leaf_size && !self.bounds.is_point() {
+                    let items = std::mem::take(items);
+                    *self = Self::build(self.bounds, items, leaf_size);
+                }
+            }
+        }
+    }
+    
+    fn collect(&self, found: &mut Vec<(T, [i32; 3])>) {
+        match &self.kind {
+            NodeKind::Leaf(items) => found.extend_from_slice(items),
+            NodeKind::Branch(children) => children.iter().for_each(|child| child.collect(found)),
+        }
+    }
+    
+    fn within(&self, position: [i32; 3], radius_sq: i64, found: &mut Vec<(T, [i32; 3])>) {
+        if self.bounds.distance_sq(position) > radius_sq {
+            return;
+        }
+        match &self.kind {
+            NodeKind::Leaf(items) => {
+                found.extend(items.iter().filter(|(_, p)| distance_sq(*p, position) <= radius_sq));
+            }
+            NodeKind::Branch(children) => {
+                children.iter().for_each(|child| child.within(position, radius_sq, found));
+            }
+        }
+    }
+    
+    fn visible(&self, frustum: &Frustum, found: &mut Vec<(T, [i32; 3])>) {
+        match frustum.classify(&self.bounds) {
+            Containment::Outside => {}
+            Containment::Inside => self.collect(found),
+            Containment::Intersects => match &self.kind {
+                NodeKind::Leaf(items) => found.extend(items.iter().filter(|(_, p)| frustum.contains(*p))),
+                NodeKind::Branch(children) => children.iter().for_each(|child| child.visible(frustum, found)),
+            },
+        }
+    }
+}
+
+/// Octree over integer positions for large, sparse worlds
+///
+/// Leaves hold up to `leaf_size` items and split into eight octants when they
+/// overflow. The root grows by doubling when an item lands outside it.
+pub struct Octree<T> {
+    pub leaf_size: usize,
+    root: Option<Node<T>>,
+    len: usize,
+}
+
+impl<T: Copy> Octree<T> {
+    pub fn new(leaf_size: usize) -> Self {
+        Self {
+            leaf_size: leaf_size.max(1),
+            root: None,
+            len: 0,
+        }
+    }
+    
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.root.as_ref().map(|root| root.bounds)
+    }
+    
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.len = 0;
+    }
+    
+    /// Replace the contents with `items`; bounds shrink to fit them
+    pub fn rebuild(&mut self, items: impl IntoIterator<Item = (T, [i32; 3])>) {
+        let items: Vec<(T, [i32; 3])> = items.into_iter().collect();
+        let Some(&(_, first)) = items.first() else {
+            self.clear();
+            return;
+        };
+        let mut bounds = Aabb::point(first);
+        items.iter().for_each(|&(_, position)| bounds.include(position));
+        self.len = items.len();
+        self.root = Some(Node::build(bounds, items, self.leaf_size));
+    }
+    
+    pub fn insert(&mut self, item: T, position: [i32; 3]) {
+        self.len += 1;
+        let Some(root) = &mut self.root else {
+            self.root = Some(Node::build(Aabb::point(position), vec![(item, position)], self.leaf_size));
+            return;
+        };
+        if root.bounds.contains(position) {
+            root.insert(item, position, self.leaf_size);
+            return;
+        }
+        
+        // Doubling keeps the number of rebuilds logarithmic in the world size
+        let mut bounds = root.bounds;
+        while !bounds.contains(position) {
+            bounds = bounds.grown_towards(position);
+        }
+        let mut items = Vec::with_capacity(self.len);
+        root.collect(&mut items);
+        items.push((item, position));
+        self.root = Some(Node::build(bounds, items, self.leaf_size));
+    }
+    
+    /// Items within Euclidean distance `radius` of `position` (inclusive)
+    pub fn neighbors_within(&self, position: [i32; 3], radius: i32) -> Vec<(T, [i32; 3])> {
+        let mut found = Vec::new();
+        let radius = i64::from(radius.max(0));
+        if let Some(root) = &self.root {
+            root.within(position, radius * radius, &mut found);
+        }
+        found
+    }
+    
+    /// Items inside the frustum; whole subtrees are accepted or rejected by their bounds
+    pub fn visible(&self, frustum: &Frustum) -> Vec<(T, [i32; 3])> {
+        let mut found = Vec::new();
+        if let Some(root) = &self.root {
+            root.visible(frustum, &mut found);
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    
+    #[test]
+    fn test_octree_matches_brute_force() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let positions: Vec<[i32; 3]> = (0..2000).map(|_| [0; 3].map(|_: i32| rng.gen_range(-500..=500))).collect();
+        
+        // Incremental inserts (with root growth) and a bulk rebuild give the same answers
+        let mut inserted = Octree::new(8);
+        for (index, &position) in positions.iter().enumerate() {
+            inserted.insert(index, position);
+        }
+        let mut rebuilt = Octree::new(8);
+        rebuilt.rebuild(positions.iter().copied().enumerate());
+        assert_eq!(inserted.len(), 2000);
+        assert!(positions.iter().all(|&p| inserted.bounds().unwrap().contains(p)));
+        
+        for &(center, radius) in &[([0, 0, 0], 100), ([500, -500, 500], 250), ([17, 3, -40], 0)] {
+            let expected = positions.iter().filter(|&&p| distance_sq(p, center) <= i64::from(radius).pow(2)).count();
+            assert_eq!(inserted.neighbors_within(center, radius).len(), expected);
+            assert_eq!(rebuilt.neighbors_within(center, radius).len(), expected);
+        }
+        
+        let frustum = Frustum::orthographic(0.6, 200.0, 120.0);
+        let expected = positions.iter().filter(|&&p| frustum.contains(p)).count();
+        assert_eq!(rebuilt.visible(&frustum).len(), expected);
+        assert_eq!(frustum.classify(&Aabb { min: [-10; 3], max: [10; 3] }), Containment::Inside);
+        assert_eq!(frustum.classify(&Aabb { min: [0, 500, 0], max: [10, 600, 10] }), Containment::Outside);
+        
+        // Many items at one position never split forever
+        let mut stacked = Octree::new(2);
+        (0..10).for_each(|i| stacked.insert(i, [1, 1, 1]));
+        assert_eq!(stacked.neighbors_within([1, 1, 1], 0).len(), 10);
+    }
+}
@@ -0,0 +1,137 @@
+//! Multi-objective evolution with NSGA-II style non-dominated sorting
+//!
+//! Every voxel is scored on several `Objectives`, all maximized. The
+//! population is sorted into fronts: the first holds voxels no other voxel
+//! beats on every objective, the second those only beaten by the first, and
+//! so on. Within a front, voxels in sparsely populated regions (large
+//! crowding distance) rank higher, which keeps the front spread out.
+
+use crate::voxel::Voxel;
+use serde::{Deserialize, Serialize};
+
+/// Energy, kaif and longevity (age in ticks)
+pub type Objectives = [f64; 3];
+
+pub fn objectives(voxel: &Voxel) -> Objectives {
+    [voxel.energy, voxel.kaif(), voxel.age_ticks as f64]
+}
+
+/// Whether `a` is at least as good as `b` on every objective and better on one
+pub fn dominates(a: &Objectives, b: &Objectives) -> bool {
+    a.iter().zip(b).all(|(a, b)| a >= b) && a.iter().zip(b).any(|(a, b)| a > b)
+}
+
+/// Indices of `points` grouped into fronts, best front first
+pub fn non_dominated_sort(points: &[Objectives]) -> Vec<Vec<usize>> {
+    let mut dominated_by = vec![0usize; points.len()];
+    let mut dominates_over: Vec<Vec<usize>> = vec![Vec::new(); points.len()];
+    for i in 0..points.len() {
+        for j in (i + 1)..points.len() {
+            if dominates(&points[i], &points[j]) {
+                dominates_over[i].push(j);
+                dominated_by[j] += 1;
+            } else if dominates(&points[j], &points[i]) {
+                dominates_over[j].push(i);
+                dominated_by[i] += 1;
+            }
+        }
+    }
+    
+    let mut fronts = Vec::new();
+    let mut front: Vec<usize> = (0..points.len()).filter(|&i| dominated_by[i] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &i in &front {
+            for &j in &dominates_over[i] {
+                dominated_by[j] -= 1;
+                if dominated_by[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+/// Crowding distance of each member of `front`, in `front` order; the
+/// extremes of every objective that varies get infinity
+pub fn crowding_distance(points: &[Objectives], front: &[usize]) -> Vec<f64> {
+    let mut distance = vec![0.0; front.len()];
+    if front.len() < 3 {
+        return vec![f64::INFINITY; front.len()];
+    }
+    let columns = (0..3).map(|objective| front.iter().map(|&i| points[i][objective]).collect::<Vec<f64>>());
+    for values in columns {
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        let (low, high) = (values[order[0]], values[order[front.len() - 1]]);
+        if high <= low {
+            continue;
+        }
+        distance[order[0]] = f64::INFINITY;
+        distance[order[front.len() - 1]] = f64::INFINITY;
+        for window in order.windows(3) {
+            distance[window[1]] += (values[window[2]] - values[window[0]]) / (high - low);
+        }
+    }
+    distance
+}
+
+/// Multi-objective settings of an `EvolutionEngine` and the Pareto front of
+/// the last generation
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParetoEvolution {
+    /// Select by Pareto rank instead of fitness
+    pub enabled: bool,
+    pub front: Vec<Objectives>,
+}
+
+impl ParetoEvolution {
+    /// Selection score per point: higher fronts score higher, crowding
+    /// distance breaks ties within a front. Remembers the first front.
+    pub fn score(&mut self, points: &[Objectives]) -> Vec<f64> {
+        let mut scores = vec![0.0; points.len()];
+        let fronts = non_dominated_sort(points);
+        for (rank, front) in fronts.iter().enumerate() {
+            for (&i, crowding) in front.iter().zip(crowding_distance(points, front)) {
+                // Crowding squashed into 0..0.5 so it never lifts a voxel into a better front
+                let spread = if crowding.is_finite() { 0.5 * crowding / (1.0 + crowding) } else { 0.5 };
+                scores[i] = spread - rank as f64;
+            }
+        }
+        self.front = fronts.first().map(|front| front.iter().map(|&i| points[i]).collect()).unwrap_or_default();
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_non_dominated_sort() {
+        let points = [
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.5, 0.5, 0.0],
+            [0.2, 0.2, 0.0],
+            [0.1, 0.1, 0.0],
+        ];
+        assert!(dominates(&points[2], &points[3]));
+        assert!(!dominates(&points[0], &points[1]));
+        assert!(!dominates(&points[0], &points[0]));
+        assert_eq!(non_dominated_sort(&points), [vec![0, 1, 2], vec![3], vec![4]]);
+        
+        let crowding = crowding_distance(&points, &[0, 1, 2]);
+        assert!(crowding[0].is_infinite() && crowding[1].is_infinite());
+        assert!((crowding[2] - 2.0).abs() < 1e-12);
+        
+        let mut pareto = ParetoEvolution::default();
+        let scores = pareto.score(&points);
+        assert!(scores[2] < scores[0] && scores[3] < scores[2] && scores[4] < scores[3]);
+        assert_eq!(pareto.front.len(), 3);
+    }
+}
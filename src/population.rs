@@ -0,0 +1,112 @@
+use crate::voxel::EmotionType;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Default number of ticks kept in `PopulationHistory`
+pub const STATS_HISTORY: usize = 1000;
+
+/// Population statistics of one tick
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct PopulationSample {
+    pub tick: u64,
+    pub count: usize,
+    /// Born during this tick
+    pub births: u64,
+    /// Removed during this tick
+    pub deaths: u64,
+    pub mean_energy: f64,
+    pub mean_kaif: f64,
+    /// Voxels per dominant emotion, in `EmotionType::ALL` order
+    pub emotions: [usize; EmotionType::ALL.len()],
+}
+
+/// Bounded per-tick history of `PopulationSample`s, oldest first
+#[derive(Clone, Debug)]
+pub struct PopulationHistory {
+    pub capacity: usize,
+    samples: VecDeque<PopulationSample>,
+}
+
+impl PopulationHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            samples: VecDeque::new(),
+        }
+    }
+    
+    pub fn push(&mut self, sample: PopulationSample) {
+        while self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+    
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &PopulationSample> {
+        self.samples.iter()
+    }
+    
+    pub fn latest(&self) -> Option<&PopulationSample> {
+        self.samples.back()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+    
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+    
+    /// The history as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("tick,count,births,deaths,mean_energy,mean_kaif");
+        for emotion in EmotionType::ALL {
+            csv.push(',');
+            csv.push_str(&format!("{:?}", emotion).to_lowercase());
+        }
+        csv.push('\n');
+        for sample in &self.samples {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}",
+                sample.tick, sample.count, sample.births, sample.deaths, sample.mean_energy, sample.mean_kaif
+            ));
+            for count in sample.emotions {
+                csv.push_str(&format!(",{}", count));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+impl Default for PopulationHistory {
+    fn default() -> Self {
+        Self::new(STATS_HISTORY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_history_is_bounded_and_exports_csv() {
+        let mut history = PopulationHistory::new(2);
+        for tick in 1..=3 {
+            history.push(PopulationSample { tick, count: tick as usize * 10, ..Default::default() });
+        }
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.latest().unwrap().tick, 3);
+        
+        let csv = history.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "tick,count,births,deaths,mean_energy,mean_kaif,neutral,joy,sadness,excitement,calm,confidence,fear");
+        assert_eq!(lines[1], "2,20,0,0,0,0,0,0,0,0,0,0,0");
+        assert_eq!(lines.len(), 3);
+    }
+}
@@ -0,0 +1,113 @@
+/// Сокращения, после которых предложение не заканчивается (без завершающей точки)
+const ABBREVIATIONS: &[&str] = &[
+    // Русские
+    "г", "гг", "ул", "д", "им", "проф", "акад", "доц", "см", "рис", "табл", "стр", "гл", "т", "напр", "тов", "ст",
+    "т.е", "т.к", "т.н", "т.ч", "и.о", "пр", "ок", "св",
+    // Английские
+    "mr", "mrs", "ms", "dr", "prof", "st", "jr", "sr", "vs", "e.g", "i.e", "fig", "no", "vol", "approx", "mt",
+];
+
+/// Разбиение текста на предложения для русского и английского
+///
+/// Предложение заканчивается на `.`, `!`, `?` или `…` (вместе с идущими следом
+/// кавычками и скобками), если дальше пробел и заглавная буква, цифра или
+/// открывающая кавычка, а также на переводе строки. Не разрывают предложение
+/// десятичные числа («3.14»), инициалы («А. С. Пушкин», «J. Smith») и
+/// сокращения из списка («т.е.», «Mr.», «г.»); «т.д.», «etc.» и подобные
+/// завершают предложение только перед заглавной буквой.
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        if c == '\n' {
+            push_sentence(&mut sentences, &text[start..idx]);
+            start = idx + 1;
+            continue;
+        }
+        if !matches!(c, '.' | '!' | '?' | '…') {
+            continue;
+        }
+
+        // Серия знаков («?!», «...») и закрывающие кавычки/скобки входят в предложение
+        let mut end = idx + c.len_utf8();
+        while let Some(&(next_idx, next)) = chars.peek() {
+            if matches!(next, '.' | '!' | '?' | '…' | '»' | '"' | '”' | '\'' | ')') {
+                end = next_idx + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let rest = &text[end..];
+        let after = rest.trim_start_matches([' ', '\t']);
+        let next = after.chars().next();
+        if next.is_some() && after.len() == rest.len() {
+            // Нет пробела: «3.14», «т.д», «example.com»
+            continue;
+        }
+        if next.is_some_and(|next| next != '\n' && !starts_sentence(next)) {
+            continue;
+        }
+        if c == '.' && next != Some('\n') && next.is_some() && is_abbreviation(&text[start..idx]) {
+            continue;
+        }
+
+        push_sentence(&mut sentences, &text[start..end]);
+        start = end;
+    }
+
+    push_sentence(&mut sentences, &text[start..]);
+    sentences
+}
+
+fn push_sentence<'a>(sentences: &mut Vec<&'a str>, sentence: &'a str) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+}
+
+fn starts_sentence(c: char) -> bool {
+    c.is_uppercase() || c.is_ascii_digit() || matches!(c, '«' | '"' | '“' | '(')
+}
+
+/// Заканчивается ли `before` (текст до точки) сокращением или инициалом
+fn is_abbreviation(before: &str) -> bool {
+    let word_start = before
+        .rfind(|c: char| !(c.is_alphabetic() || c == '.'))
+        .map_or(0, |idx| idx + before[idx..].chars().next().map_or(1, char::len_utf8));
+    let word = &before[word_start..];
+
+    let mut letters = word.chars();
+    if let (Some(first), None) = (letters.next(), letters.next()) {
+        if first.is_uppercase() {
+            return true;
+        }
+    }
+    ABBREVIATIONS.contains(&word.to_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("Пи равно 3.14 примерно. Книги, журналы и т.д. Всё это есть! А вы знали?"),
+            vec!["Пи равно 3.14 примерно.", "Книги, журналы и т.д.", "Всё это есть!", "А вы знали?"]
+        );
+        assert_eq!(
+            split_sentences("А. С. Пушкин родился в г. Москве, т.е. в столице. Mr. J. Smith agreed... Really?! Yes."),
+            vec!["А. С. Пушкин родился в г. Москве, т.е. в столице.", "Mr. J. Smith agreed...", "Really?!", "Yes."]
+        );
+        assert_eq!(
+            split_sentences("«Ура!» — сказал он. Первая строка\nВторая строка"),
+            vec!["«Ура!» — сказал он.", "Первая строка", "Вторая строка"]
+        );
+        assert!(split_sentences("  \n ").is_empty());
+    }
+}
@@ -0,0 +1,85 @@
+// Compute-шейдеры для AIModel: W * x + b (или W^T * x) и softmax
+//
+// Веса хранятся по столбцам (column-major), как в nalgebra::DMatrix.
+
+struct Dims {
+    rows: u32,
+    cols: u32,
+    transpose: u32,
+    _pad: u32,
+}
+
+@group(0) @binding(0) var<uniform> dims: Dims;
+@group(0) @binding(1) var<storage, read> weights: array<f32>;
+@group(0) @binding(2) var<storage, read> input: array<f32>;
+// Заранее заполнен смещениями (или нулями)
+@group(0) @binding(3) var<storage, read_write> output: array<f32>;
+
+@compute @workgroup_size(64)
+fn matvec(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (dims.transpose == 0u) {
+        if (i >= dims.rows) {
+            return;
+        }
+        var sum = output[i];
+        for (var j = 0u; j < dims.cols; j = j + 1u) {
+            sum = sum + weights[j * dims.rows + i] * input[j];
+        }
+        output[i] = sum;
+    } else {
+        if (i >= dims.cols) {
+            return;
+        }
+        var sum = output[i];
+        for (var j = 0u; j < dims.rows; j = j + 1u) {
+            sum = sum + weights[i * dims.rows + j] * input[j];
+        }
+        output[i] = sum;
+    }
+}
+
+const SOFTMAX_THREADS: u32 = 256u;
+var<workgroup> scratch: array<f32, 256>;
+
+// Softmax по `output[0..dims.rows]` одной рабочей группой
+@compute @workgroup_size(256)
+fn softmax(@builtin(local_invocation_id) lid: vec3<u32>) {
+    let t = lid.x;
+    let n = dims.rows;
+
+    var local_max = -3.4e38;
+    for (var i = t; i < n; i = i + SOFTMAX_THREADS) {
+        local_max = max(local_max, output[i]);
+    }
+    scratch[t] = local_max;
+    workgroupBarrier();
+    for (var s = SOFTMAX_THREADS / 2u; s > 0u; s = s >> 1u) {
+        if (t < s) {
+            scratch[t] = max(scratch[t], scratch[t + s]);
+        }
+        workgroupBarrier();
+    }
+    let max_val = scratch[0];
+    workgroupBarrier();
+
+    var local_sum = 0.0;
+    for (var i = t; i < n; i = i + SOFTMAX_THREADS) {
+        let e = exp(output[i] - max_val);
+        output[i] = e;
+        local_sum = local_sum + e;
+    }
+    scratch[t] = local_sum;
+    workgroupBarrier();
+    for (var s = SOFTMAX_THREADS / 2u; s > 0u; s = s >> 1u) {
+        if (t < s) {
+            scratch[t] = scratch[t] + scratch[t + s];
+        }
+        workgroupBarrier();
+    }
+    let total = scratch[0];
+
+    for (var i = t; i < n; i = i + SOFTMAX_THREADS) {
+        output[i] = output[i] / total;
+    }
+}
@@ -0,0 +1,37 @@
+// Integration step of VoxelWorld::update: movement, resonance energy and trauma mode
+//
+// One invocation per voxel; the layout of VoxelState matches GpuVoxel in voxel_gpu.rs.
+
+struct Params {
+    delta_time: f32,
+    trauma: u32,
+    count: u32,
+    _pad: u32,
+}
+
+struct VoxelState {
+    position: vec3<i32>,
+    energy: f32,
+    velocity: vec3<i32>,
+    resonance: f32,
+    arousal: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> voxels: array<VoxelState>;
+
+@compute @workgroup_size(64)
+fn integrate(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.count) {
+        return;
+    }
+    var voxel = voxels[i];
+    voxel.position = voxel.position + voxel.velocity;
+    voxel.energy = voxel.energy + voxel.resonance * params.delta_time;
+    if (params.trauma != 0u) {
+        voxel.energy = voxel.energy * 1.5;
+        voxel.arousal = voxel.arousal * 1.3;
+    }
+    voxels[i] = voxel;
+}
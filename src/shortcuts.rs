@@ -0,0 +1,206 @@
+use eframe::egui::{Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+/// Клавиши, которые можно назначить (в egui 0.23 нет списка всех клавиш)
+const KEYS: &[Key] = &[
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+    Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+    Key::Enter, Key::Tab, Key::Space, Key::Escape, Key::Backspace, Key::Delete, Key::Insert,
+    Key::Home, Key::End, Key::PageUp, Key::PageDown,
+    Key::ArrowUp, Key::ArrowDown, Key::ArrowLeft, Key::ArrowRight, Key::Minus, Key::PlusEquals,
+];
+
+/// Сочетание клавиш; в файле настроек хранится строкой вида `Ctrl+Shift+R`
+///
+/// `Ctrl` сохраняется как `Modifiers::COMMAND`, то есть в macOS это ⌘.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Shortcut(pub KeyboardShortcut);
+
+impl Shortcut {
+    pub const fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self(KeyboardShortcut::new(modifiers, key))
+    }
+
+    /// Сочетание из нажатой клавиши; модификаторы приводятся к Ctrl/Shift/Alt
+    pub fn from_event(key: Key, modifiers: Modifiers) -> Option<Self> {
+        KEYS.contains(&key).then(|| {
+            let mut normalized = if modifiers.command || modifiers.ctrl { Modifiers::COMMAND } else { Modifiers::NONE };
+            normalized.shift = modifiers.shift;
+            normalized.alt = modifiers.alt;
+            Self::new(normalized, key)
+        })
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        // «Ctrl++» — клавиша «+» (в egui она называется Plus)
+        if text.ends_with("++") {
+            parts.truncate(parts.len() - 2);
+            parts.push("Plus");
+        }
+        let key_name = parts.pop().filter(|name| !name.is_empty()).ok_or_else(|| format!("✗ Пустое сочетание клавиш «{}»", text))?;
+        let key = KEYS
+            .iter()
+            .copied()
+            .find(|key| key.name().eq_ignore_ascii_case(key_name))
+            .ok_or_else(|| format!("✗ Неизвестная клавиша «{}» в «{}»", key_name, text))?;
+
+        let mut modifiers = Modifiers::NONE;
+        for part in parts {
+            match part.to_lowercase().as_str() {
+                "ctrl" | "cmd" => modifiers = modifiers.plus(Modifiers::COMMAND),
+                "shift" => modifiers.shift = true,
+                "alt" => modifiers.alt = true,
+                _ => return Err(format!("✗ Неизвестный модификатор «{}» в «{}»", part, text)),
+            }
+        }
+        Ok(Self::new(modifiers, key))
+    }
+
+    pub fn format(&self) -> String {
+        let KeyboardShortcut { modifiers, key } = self.0;
+        let mut text = String::new();
+        if modifiers.command || modifiers.ctrl {
+            text.push_str("Ctrl+");
+        }
+        if modifiers.shift {
+            text.push_str("Shift+");
+        }
+        if modifiers.alt {
+            text.push_str("Alt+");
+        }
+        text.push_str(key.name());
+        text
+    }
+}
+
+impl TryFrom<String> for Shortcut {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        Self::parse(&text)
+    }
+}
+
+impl From<Shortcut> for String {
+    fn from(shortcut: Shortcut) -> Self {
+        shortcut.format()
+    }
+}
+
+/// Действие чата, вызываемое сочетанием клавиш
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    Send,
+    Newline,
+    SwitchMode,
+    /// Повторная генерация последнего ответа
+    Regenerate,
+    FocusInput,
+}
+
+impl ShortcutAction {
+    pub const ALL: [Self; 5] = [Self::Send, Self::Newline, Self::SwitchMode, Self::Regenerate, Self::FocusInput];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Send => "Отправить сообщение",
+            Self::Newline => "Новая строка",
+            Self::SwitchMode => "Следующий режим",
+            Self::Regenerate => "Сгенерировать ответ заново",
+            Self::FocusInput => "Перейти к полю ввода",
+        }
+    }
+}
+
+/// Назначенные сочетания клавиш
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Shortcuts {
+    pub send: Shortcut,
+    pub newline: Shortcut,
+    pub switch_mode: Shortcut,
+    pub regenerate: Shortcut,
+    pub focus_input: Shortcut,
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        Self {
+            send: Shortcut::new(Modifiers::NONE, Key::Enter),
+            newline: Shortcut::new(Modifiers::SHIFT, Key::Enter),
+            switch_mode: Shortcut::new(Modifiers::COMMAND, Key::M),
+            regenerate: Shortcut::new(Modifiers::COMMAND, Key::R),
+            focus_input: Shortcut::new(Modifiers::COMMAND, Key::L),
+        }
+    }
+}
+
+impl Shortcuts {
+    pub fn get(&self, action: ShortcutAction) -> Shortcut {
+        match action {
+            ShortcutAction::Send => self.send,
+            ShortcutAction::Newline => self.newline,
+            ShortcutAction::SwitchMode => self.switch_mode,
+            ShortcutAction::Regenerate => self.regenerate,
+            ShortcutAction::FocusInput => self.focus_input,
+        }
+    }
+
+    pub fn set(&mut self, action: ShortcutAction, shortcut: Shortcut) {
+        let slot = match action {
+            ShortcutAction::Send => &mut self.send,
+            ShortcutAction::Newline => &mut self.newline,
+            ShortcutAction::SwitchMode => &mut self.switch_mode,
+            ShortcutAction::Regenerate => &mut self.regenerate,
+            ShortcutAction::FocusInput => &mut self.focus_input,
+        };
+        *slot = shortcut;
+    }
+
+    /// Действия с одинаковыми сочетаниями (для предупреждения в настройках)
+    pub fn conflicts(&self) -> Vec<(ShortcutAction, ShortcutAction)> {
+        let mut conflicts = Vec::new();
+        for (i, &a) in ShortcutAction::ALL.iter().enumerate() {
+            for &b in &ShortcutAction::ALL[i + 1..] {
+                if self.get(a) == self.get(b) {
+                    conflicts.push((a, b));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcut_parse_and_format() {
+        let shortcut = Shortcut::parse("ctrl+shift+r").unwrap();
+        assert_eq!(shortcut, Shortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::R));
+        assert_eq!(shortcut.format(), "Ctrl+Shift+R");
+        assert_eq!(Shortcut::parse("Enter").unwrap().format(), "Enter");
+        assert_eq!(Shortcut::parse("Ctrl++").unwrap(), Shortcut::new(Modifiers::COMMAND, Key::PlusEquals));
+        assert!(Shortcut::parse("Hyper+R").is_err());
+        assert!(Shortcut::parse("Ctrl+").is_err());
+
+        // Ctrl из события приводится к COMMAND, чтобы совпадать с сохранённым
+        let recorded = Shortcut::from_event(Key::M, Modifiers::CTRL).unwrap();
+        assert_eq!(recorded, Shortcuts::default().switch_mode);
+
+        let json = serde_json::to_string(&Shortcuts::default()).unwrap();
+        assert!(json.contains(r#""newline":"Shift+Enter""#));
+        let mut shortcuts: Shortcuts = serde_json::from_str(r#"{"send": "Ctrl+Enter"}"#).unwrap();
+        assert_eq!(shortcuts.send.format(), "Ctrl+Enter");
+        assert_eq!(shortcuts.regenerate, Shortcuts::default().regenerate);
+        assert!(shortcuts.conflicts().is_empty());
+
+        shortcuts.set(ShortcutAction::FocusInput, shortcuts.regenerate);
+        assert_eq!(shortcuts.conflicts(), vec![(ShortcutAction::Regenerate, ShortcutAction::FocusInput)]);
+    }
+}
@@ -0,0 +1,71 @@
+/// Loudness below which a sound is no longer heard
+pub const AUDIBLE_THRESHOLD: f32 = 0.01;
+
+/// What made a sound
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundKind {
+    /// A voxel was born
+    Birth,
+    /// The user pinged the world
+    Ping,
+    /// Two voxels bumped into each other
+    Collision,
+    /// A voxel died
+    Death,
+}
+
+impl SoundKind {
+    /// Loudness at the source
+    pub fn loudness(self) -> f32 {
+        match self {
+            Self::Birth => 1.0,
+            Self::Ping => 100.0,
+            Self::Collision => 0.5,
+            Self::Death => 2.0,
+        }
+    }
+}
+
+/// A sound emitted somewhere in the world, heard by voxels on the next tick
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sound {
+    pub kind: SoundKind,
+    pub position: [i32; 3],
+    pub loudness: f32,
+}
+
+impl Sound {
+    pub fn new(kind: SoundKind, position: [i32; 3]) -> Self {
+        Self {
+            kind,
+            position,
+            loudness: kind.loudness(),
+        }
+    }
+    
+    /// Loudness at `distance` cells: inverse-square falloff, full within one cell
+    pub fn heard_at(&self, distance: f32) -> f32 {
+        self.loudness / distance.max(1.0).powi(2)
+    }
+    
+    /// Distance at which the sound fades below `AUDIBLE_THRESHOLD`
+    pub fn range(&self) -> i32 {
+        (self.loudness.max(0.0) / AUDIBLE_THRESHOLD).sqrt().ceil() as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_sound_attenuation() {
+        let sound = Sound::new(SoundKind::Birth, [0, 0, 0]);
+        assert_eq!(sound.heard_at(0.0), 1.0);
+        assert_eq!(sound.heard_at(1.0), 1.0);
+        assert_eq!(sound.heard_at(4.0), 1.0 / 16.0);
+        assert_eq!(sound.range(), 10);
+        assert!(sound.heard_at(sound.range() as f32) <= AUDIBLE_THRESHOLD);
+        assert_eq!(Sound::new(SoundKind::Ping, [0, 0, 0]).range(), 100);
+    }
+}
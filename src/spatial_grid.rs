@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+/// Uniform spatial hash grid for neighbor queries over integer positions
+///
+/// Space is split into cubic cells of `cell_size`; each cell lists the items
+/// inside it, so a radius query only visits the cells overlapping the query
+/// cube instead of every item in the world.
+#[derive(Clone, Debug)]
+pub struct SpatialGrid<T> {
+    pub cell_size: i32,
+    cells: HashMap<[i32; 3], Vec<(T, [i32; 3])>>,
+    len: usize,
+}
+
+impl<T: Copy> SpatialGrid<T> {
+    pub fn new(cell_size: i32) -> Self {
+        Self {
+            cell_size: cell_size.max(1),
+            cells: HashMap::new(),
+            len: 0,
+        }
+    }
+    
+    fn cell_of(&self, position: [i32; 3]) -> [i32; 3] {
+        position.map(|c| c.div_euclid(self.cell_size))
+    }
+    
+    pub fn insert(&mut self, item: T, position: [i32; 3]) {
+        let cell = self.cell_of(position);
+        self.cells.entry(cell).or_default().push((item, position));
+        self.len += 1;
+    }
+    
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.len = 0;
+    }
+    
+    /// Replace the contents with `items` (called once per tick after movement)
+    pub fn rebuild(&mut self, items: impl IntoIterator<Item = (T, [i32; 3])>) {
+        // Keep cell allocations around: most voxels stay in the same cell between ticks
+        for bucket in self.cells.values_mut() {
+            bucket.clear();
+        }
+        self.len = 0;
+        for (item, position) in items {
+            self.insert(item, position);
+        }
+        self.cells.retain(|_, bucket| !bucket.is_empty());
+    }
+    
+    pub fn iter(&self) -> impl Iterator<Item = &(T, [i32; 3])> {
+        self.cells.values().flatten()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    
+    /// Items within Euclidean distance `radius` of `position` (inclusive)
+    pub fn neighbors_within(&self, position: [i32; 3], radius: i32) -> Vec<(T, [i32; 3])> {
+        let mut found = Vec::new();
+        let radius = radius.max(0);
+        let radius_sq = i64::from(radius) * i64::from(radius);
+        let min = self.cell_of(position.map(|c| c.saturating_sub(radius)));
+        let max = self.cell_of(position.map(|c| c.saturating_add(radius)));
+        
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    let Some(bucket) = self.cells.get(&[x, y, z]) else {
+                        continue;
+                    };
+                    found.extend(bucket.iter().filter(|(_, p)| distance_sq(*p, position) <= radius_sq));
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Squared Euclidean distance without overflow for any i32 coordinates
+pub fn distance_sq(a: [i32; 3], b: [i32; 3]) -> i64 {
+    (0..3).map(|i| (i64::from(a[i]) - i64::from(b[i])).pow(2)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_neighbors_within() {
+        let mut grid = SpatialGrid::new(4);
+        let positions = [[0, 0, 0], [3, 0, 0], [5, 0, 0], [-2, -2, 0], [0, 0, -9], [100, 100, 100]];
+        grid.rebuild(positions.iter().copied().enumerate());
+        assert_eq!(grid.len(), 6);
+        
+        let mut near: Vec<usize> = grid.neighbors_within([0, 0, 0], 3).into_iter().map(|(i, _)| i).collect();
+        near.sort();
+        assert_eq!(near, [0, 1, 3]);
+        
+        // The query matches a brute-force scan for every radius
+        for radius in 0..12 {
+            let expected = positions.iter().filter(|p| distance_sq(**p, [1, -1, -3]) <= radius * radius).count();
+            assert_eq!(grid.neighbors_within([1, -1, -3], radius as i32).len(), expected);
+        }
+        
+        grid.rebuild([(0, [100, 100, 99])]);
+        assert_eq!(grid.len(), 1);
+        assert!(grid.neighbors_within([0, 0, 0], 10).is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+//! NEAT-style speciation of genomes
+//!
+//! Genomes within `threshold` concept distance of a species' representative
+//! belong to it. Members share their species' fitness, so one big lineage
+//! cannot crowd out the rest, and a young species keeps its champion for
+//! `protect_generations` generations while it finds its footing.
+
+use crate::voxel::Genome;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Jaccard distance between the concept sets of two genomes, 0..1
+pub fn genome_distance(a: &Genome, b: &Genome) -> f64 {
+    let a: HashSet<&String> = a.concepts.iter().collect();
+    let b: HashSet<&String> = b.concepts.iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    1.0 - a.intersection(&b).count() as f64 / union as f64
+}
+
+/// One species of the current generation
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Species {
+    pub id: u64,
+    /// Genome new members are compared against
+    pub representative: Genome,
+    /// Generations the species has existed for
+    pub age: u32,
+    pub size: usize,
+    pub best_fitness: f64,
+}
+
+/// Species bookkeeping of an `EvolutionEngine`
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Speciation {
+    pub enabled: bool,
+    /// Largest `genome_distance` to a representative that still joins its species
+    pub threshold: f64,
+    /// Generations a new species keeps its champion
+    pub protect_generations: u32,
+    pub species: Vec<Species>,
+    next_id: u64,
+}
+
+impl Speciation {
+    /// Assign every member of `population` to a species, founding new ones
+    /// as needed; returns the index into `species` per member
+    ///
+    /// Surviving species age by one generation and take their first member
+    /// as the next representative; species without members die out.
+    pub fn speciate(&mut self, population: &[(&Genome, f64)]) -> Vec<usize> {
+        for species in &mut self.species {
+            species.size = 0;
+            species.best_fitness = f64::NEG_INFINITY;
+        }
+        let mut assignment = Vec::with_capacity(population.len());
+        let mut first_member = vec![None; self.species.len()];
+        for (i, &(genome, fitness)) in population.iter().enumerate() {
+            let found = self.species
+                .iter()
+                .position(|species| genome_distance(genome, &species.representative) <= self.threshold);
+            let index = found.unwrap_or_else(|| {
+                self.species.push(Species {
+                    id: self.next_id,
+                    representative: genome.clone(),
+                    age: 0,
+                    size: 0,
+                    best_fitness: f64::NEG_INFINITY,
+                });
+                self.next_id += 1;
+                first_member.push(None);
+                self.species.len() - 1
+            });
+            let species = &mut self.species[index];
+            species.size += 1;
+            species.best_fitness = species.best_fitness.max(fitness);
+            first_member[index].get_or_insert(i);
+            assignment.push(index);
+        }
+        
+        // Drop extinct species and renumber the assignment
+        let mut renumbered = vec![0; self.species.len()];
+        let mut kept = 0;
+        for (index, first) in first_member.iter().enumerate() {
+            if let Some(first) = *first {
+                renumbered[index] = kept;
+                self.species.swap(kept, index);
+                let species = &mut self.species[kept];
+                species.representative = population[first].0.clone();
+                species.age += 1;
+                kept += 1;
+            }
+        }
+        self.species.truncate(kept);
+        assignment.iter().map(|&index| renumbered[index]).collect()
+    }
+    
+    /// Whether species `index` is still young enough to be protected
+    pub fn is_young(&self, index: usize) -> bool {
+        self.species.get(index).is_some_and(|species| species.age <= self.protect_generations)
+    }
+    
+    pub fn clear(&mut self) {
+        self.species.clear();
+    }
+}
+
+impl Default for Speciation {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 0.5,
+            protect_generations: 5,
+            species: Vec::new(),
+            next_id: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn genome(concepts: &[&str]) -> Genome {
+        let mut genome = Genome::new();
+        for concept in concepts {
+            genome.add_concept(concept.to_string());
+        }
+        genome
+    }
+    
+    #[test]
+    fn test_speciate() {
+        let a = genome(&["light", "sound"]);
+        let b = genome(&["light", "sound", "heat"]);
+        let c = genome(&["dark"]);
+        assert_eq!(genome_distance(&a, &a), 0.0);
+        assert!((genome_distance(&a, &b) - 1.0 / 3.0).abs() < 1e-12);
+        assert_eq!(genome_distance(&a, &c), 1.0);
+        assert_eq!(genome_distance(&Genome::new(), &Genome::new()), 0.0);
+        
+        let mut speciation = Speciation::default();
+        let assignment = speciation.speciate(&[(&a, 1.0), (&c, 0.5), (&b, 2.0)]);
+        assert_eq!(assignment, [0, 1, 0]);
+        assert_eq!(speciation.species.len(), 2);
+        assert_eq!((speciation.species[0].size, speciation.species[0].best_fitness), (2, 2.0));
+        assert!(speciation.is_young(0));
+        
+        // The dark lineage died out; the light one ages and keeps its id
+        let assignment = speciation.speciate(&[(&b, 1.0), (&a, 1.0)]);
+        assert_eq!(assignment, [0, 0]);
+        assert_eq!(speciation.species.len(), 1);
+        assert_eq!((speciation.species[0].id, speciation.species[0].age), (0, 2));
+        
+        speciation.protect_generations = 1;
+        assert!(!speciation.is_young(0));
+        assert_eq!(speciation.speciate(&[(&c, 0.0)]), [0]);
+        assert_eq!(speciation.species[0].id, 2);
+    }
+}
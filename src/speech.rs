@@ -0,0 +1,229 @@
+//! Озвучивание ответов через синтезатор речи операционной системы
+//!
+//! С feature `tts` речь синтезирует крейт `tts` (SAPI/WinRT, AVFoundation,
+//! Speech Dispatcher). Без неё текст передаётся на stdin внешней программы:
+//! `espeak-ng`/`espeak` в Linux, `say` в macOS и System.Speech через
+//! PowerShell в Windows. Новое озвучивание прерывает предыдущее.
+
+use crate::language::Language;
+use crate::markdown::{self, Block};
+#[cfg(not(feature = "tts"))]
+use std::io::{ErrorKind, Write};
+#[cfg(not(feature = "tts"))]
+use std::process::{Child, Command, Stdio};
+
+/// Программа синтеза речи с аргументами
+#[cfg(not(feature = "tts"))]
+struct SpeechCommand {
+    program: &'static str,
+    args: Vec<String>,
+}
+
+/// Кандидаты в порядке предпочтения для текущей ОС
+#[cfg(not(feature = "tts"))]
+fn speech_commands(language: Language) -> Vec<SpeechCommand> {
+    if cfg!(target_os = "windows") {
+        let script = "Add-Type -AssemblyName System.Speech; \
+                      $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+                      $s.Speak([Console]::In.ReadToEnd())";
+        vec![SpeechCommand {
+            program: "powershell",
+            args: vec!["-NoProfile".to_string(), "-Command".to_string(), script.to_string()],
+        }]
+    } else if cfg!(target_os = "macos") {
+        let mut args = vec!["-f".to_string(), "-".to_string()];
+        if language == Language::Russian {
+            args.extend(["-v".to_string(), "Milena".to_string()]);
+        }
+        vec![SpeechCommand { program: "say", args }]
+    } else {
+        let voice = match language {
+            Language::English => "en",
+            _ => "ru",
+        };
+        ["espeak-ng", "espeak"]
+            .into_iter()
+            .map(|program| SpeechCommand {
+                program,
+                args: vec!["--stdin".to_string(), "-v".to_string(), voice.to_string()],
+            })
+            .collect()
+    }
+}
+
+/// Текст сообщения без разметки, блоков кода и эмодзи
+pub fn speakable_text(text: &str) -> String {
+    let spans_text = |spans: &[markdown::Span]| spans.iter().map(|span| span.text.as_str()).collect::<String>();
+    let mut parts = Vec::new();
+    for block in markdown::parse(text) {
+        match block {
+            Block::Heading(_, spans) | Block::Paragraph(spans) | Block::ListItem(_, _, spans) | Block::Quote(spans) => {
+                parts.push(spans_text(&spans))
+            }
+            Block::Code(..) | Block::Rule => {}
+        }
+    }
+    parts
+        .join("\n")
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace() || c.is_ascii_punctuation() || "«»—–…".contains(*c))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Озвучивание текста
+///
+/// С feature `tts` речь синтезирует крейт `tts`; без неё запускается внешняя
+/// программа, процесс которой хранится до окончания озвучивания.
+#[derive(Default)]
+pub struct Speaker {
+    /// Синтезатор создаётся при первом озвучивании
+    #[cfg(feature = "tts")]
+    tts: Option<tts::Tts>,
+    #[cfg(not(feature = "tts"))]
+    child: Option<(&'static str, Child)>,
+}
+
+impl Speaker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Озвучивание сообщения (прерывает текущее)
+    pub fn speak(&mut self, text: &str) -> Result<(), String> {
+        self.stop();
+        let text = speakable_text(text);
+        if text.is_empty() {
+            return Ok(());
+        }
+        self.start(&text, Language::detect(&text))
+    }
+
+    #[cfg(feature = "tts")]
+    fn start(&mut self, text: &str, language: Language) -> Result<(), String> {
+        let tts = match &mut self.tts {
+            Some(tts) => tts,
+            None => self
+                .tts
+                .insert(tts::Tts::default().map_err(|e| format!("✗ Озвучивание недоступно: {}", e))?),
+        };
+        if tts.supported_features().voice {
+            let code = match language {
+                Language::English => "en",
+                _ => "ru",
+            };
+            let voice = tts
+                .voices()
+                .ok()
+                .and_then(|voices| voices.into_iter().find(|voice| voice.language().primary_language() == code));
+            if let Some(voice) = voice {
+                let _ = tts.set_voice(&voice);
+            }
+        }
+        tts.speak(text, true)
+            .map(|_| ())
+            .map_err(|e| format!("✗ Ошибка синтеза речи: {}", e))
+    }
+
+    #[cfg(not(feature = "tts"))]
+    fn start(&mut self, text: &str, language: Language) -> Result<(), String> {
+        let mut missing = Vec::new();
+        for command in speech_commands(language) {
+            let spawned = Command::new(command.program)
+                .args(&command.args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            let mut child = match spawned {
+                Ok(child) => child,
+                Err(e) if e.kind() == ErrorKind::NotFound => {
+                    missing.push(command.program);
+                    continue;
+                }
+                Err(e) => return Err(format!("✗ Не удалось запустить {}: {}", command.program, e)),
+            };
+            // Текст идёт только через stdin, в командную строку он не попадает
+            let written = child.stdin.take().map_or(Ok(()), |mut stdin| stdin.write_all(text.as_bytes()));
+            if let Err(e) = written {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("✗ Ошибка передачи текста в {}: {}", command.program, e));
+            }
+            self.child = Some((command.program, child));
+            return Ok(());
+        }
+        Err(format!("✗ Синтезатор речи не найден (искали: {})", missing.join(", ")))
+    }
+
+    pub fn stop(&mut self) {
+        #[cfg(feature = "tts")]
+        if let Some(tts) = &mut self.tts {
+            let _ = tts.stop();
+        }
+        #[cfg(not(feature = "tts"))]
+        if let Some((_, mut child)) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    /// Идёт ли озвучивание; ошибка, если синтезатор завершился с ошибкой
+    pub fn poll(&mut self) -> Result<bool, String> {
+        #[cfg(feature = "tts")]
+        {
+            match &self.tts {
+                Some(tts) if tts.supported_features().is_speaking => {
+                    tts.is_speaking().map_err(|e| format!("✗ Ошибка синтеза речи: {}", e))
+                }
+                _ => Ok(false),
+            }
+        }
+        #[cfg(not(feature = "tts"))]
+        {
+            let Some((program, child)) = &mut self.child else {
+                return Ok(false);
+            };
+            let status = match child.try_wait() {
+                Ok(None) => return Ok(true),
+                Ok(Some(status)) => Ok(status),
+                Err(e) => Err(e.to_string()),
+            };
+            let program = *program;
+            self.child = None;
+            match status {
+                Ok(status) if status.success() => Ok(false),
+                Ok(status) => Err(format!("✗ {} завершился с ошибкой ({})", program, status)),
+                Err(e) => Err(format!("✗ Ошибка ожидания {}: {}", program, e)),
+            }
+        }
+    }
+}
+
+impl Drop for Speaker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speakable_text() {
+        let text = "# Ответ 🤖\n\n**Важно:** это *пример*.\n\n```rust\nfn main() {}\n```\n\n- пункт — «один»";
+        assert_eq!(speakable_text(text), "Ответ Важно: это пример. пункт — «один»");
+        assert_eq!(speakable_text("🎉🎉"), "");
+    }
+
+    #[test]
+    fn test_speaker_without_text() {
+        // Нечего озвучивать — синтезатор не запускается
+        let mut speaker = Speaker::new();
+        assert_eq!(speaker.speak("🎉\n\n```\ncode\n```"), Ok(()));
+        assert_eq!(speaker.poll(), Ok(false));
+    }
+}
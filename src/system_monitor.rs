@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Система мониторинга ресурсов
@@ -9,6 +10,8 @@ pub struct SystemMonitor {
     pub vram_used: Arc<AtomicU64>,     // В MB
     pub vram_total: Arc<AtomicU64>,    // В MB
     pub fps: Arc<AtomicU64>,           // FPS (x100 для точности)
+    /// Прошлый замер счётчиков CPU (простой, всего) для расчёта загрузки
+    cpu_sample: Mutex<Option<(u64, u64)>>,
 }
 
 impl SystemMonitor {
@@ -20,6 +23,7 @@ impl SystemMonitor {
             vram_used: Arc::new(AtomicU64::new(0)),
             vram_total: Arc::new(AtomicU64::new(0)),
             fps: Arc::new(AtomicU64::new(0)),
+            cpu_sample: Mutex::new(None),
         };
         
         // Инициализируем начальные значения
@@ -51,9 +55,20 @@ impl SystemMonitor {
             }
         }
         
-        #[cfg(not(target_os = "windows"))]
+        #[cfg(target_os = "linux")]
         {
-            // Linux/Mac: используем sysinfo или примерные значения
+            if let Some((total_mb, used_mb)) = std::fs::read_to_string("/proc/meminfo")
+                .ok()
+                .and_then(|text| parse_meminfo(&text))
+            {
+                self.ram_total.store(total_mb, Ordering::Relaxed);
+                self.ram_used.store(used_mb, Ordering::Relaxed);
+            }
+        }
+        
+        #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+        {
+            // Mac: используем sysinfo или примерные значения
             // Для простоты используем фиксированные значения
             self.ram_total.store(16384, Ordering::Relaxed); // 16 GB
             self.ram_used.store(4096, Ordering::Relaxed);   // 4 GB
@@ -62,10 +77,30 @@ impl SystemMonitor {
     
     /// Обновить информацию о CPU
     pub fn update_cpu(&self) {
-        // Простая имитация загрузки CPU
-        // В реальном приложении использовать sysinfo crate
-        let usage = 15 + (rand::random::<u64>() % 30); // 15-45%
-        self.cpu_usage.store(usage, Ordering::Relaxed);
+        #[cfg(target_os = "linux")]
+        {
+            // Загрузка между двумя замерами счётчиков /proc/stat
+            let Some((idle, total)) = std::fs::read_to_string("/proc/stat")
+                .ok()
+                .and_then(|text| parse_cpu_times(&text))
+            else {
+                return;
+            };
+            let mut sample = self.cpu_sample.lock().unwrap();
+            if let Some((prev_idle, prev_total)) = sample.replace((idle, total)) {
+                let busy = (total - prev_total).saturating_sub(idle.saturating_sub(prev_idle));
+                let usage = busy * 100 / (total - prev_total).max(1);
+                self.cpu_usage.store(usage, Ordering::Relaxed);
+            }
+        }
+        
+        #[cfg(not(target_os = "linux"))]
+        {
+            // Простая имитация загрузки CPU
+            // В реальном приложении использовать sysinfo crate
+            let usage = 15 + (rand::random::<u64>() % 30); // 15-45%
+            self.cpu_usage.store(usage, Ordering::Relaxed);
+        }
     }
     
     /// Обновить информацию о VRAM (примерные значения)
@@ -78,6 +113,12 @@ impl SystemMonitor {
         self.vram_total.store(4096, Ordering::Relaxed); // 4 GB
     }
     
+    /// Задать занятый и общий объём VRAM в MB (0 — объём неизвестен)
+    pub fn set_vram(&self, used_mb: u64, total_mb: u64) {
+        self.vram_used.store(used_mb, Ordering::Relaxed);
+        self.vram_total.store(total_mb, Ordering::Relaxed);
+    }
+    
     /// Обновить FPS
     pub fn update_fps(&self, fps: f32) {
         // Храним FPS * 100 для точности
@@ -107,6 +148,11 @@ impl SystemMonitor {
         }
     }
     
+    /// Получить загрузку CPU в процентах
+    pub fn get_cpu_percent(&self) -> f32 {
+        self.cpu_usage.load(Ordering::Relaxed) as f32
+    }
+    
     /// Получить FPS
     pub fn get_fps(&self) -> f32 {
         let fps_x100 = self.fps.load(Ordering::Relaxed);
@@ -133,7 +179,72 @@ impl Default for SystemMonitor {
     }
 }
 
+/// Последние замеры метрик для графиков-спарклайнов
+pub struct MetricHistory {
+    pub ram_percent: VecDeque<f32>,
+    pub cpu_percent: VecDeque<f32>,
+    pub vram_mb: VecDeque<f32>,
+    pub fps: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl MetricHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ram_percent: VecDeque::with_capacity(capacity),
+            cpu_percent: VecDeque::with_capacity(capacity),
+            vram_mb: VecDeque::with_capacity(capacity),
+            fps: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+    
+    /// Добавить текущие значения монитора, вытесняя самые старые
+    pub fn record(&mut self, monitor: &SystemMonitor) {
+        let values = [
+            monitor.get_ram_percent(),
+            monitor.get_cpu_percent(),
+            monitor.vram_used.load(Ordering::Relaxed) as f32,
+            monitor.get_fps(),
+        ];
+        let capacity = self.capacity;
+        for (history, value) in [&mut self.ram_percent, &mut self.cpu_percent, &mut self.vram_mb, &mut self.fps]
+            .into_iter()
+            .zip(values)
+        {
+            if history.len() == capacity {
+                history.pop_front();
+            }
+            history.push_back(value);
+        }
+    }
+}
+
+/// Общий и занятый объём памяти в MB из /proc/meminfo
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo(text: &str) -> Option<(u64, u64)> {
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+    };
+    let total_kb = field("MemTotal:")?;
+    let available_kb = field("MemAvailable:").or_else(|| field("MemFree:"))?;
+    Some((total_kb / 1024, total_kb.saturating_sub(available_kb) / 1024))
+}
+
+/// Счётчики простоя и общего времени CPU из первой строки /proc/stat
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_times(text: &str) -> Option<(u64, u64)> {
+    let line = text.lines().find(|line| line.starts_with("cpu "))?;
+    let times: Vec<u64> = line.split_whitespace().skip(1).filter_map(|value| value.parse().ok()).collect();
+    // idle + iowait
+    let idle = times.get(3)? + times.get(4).copied().unwrap_or(0);
+    Some((idle, times.iter().sum()))
+}
+
 // Заглушка для rand без зависимости
+#[cfg(not(target_os = "linux"))]
 mod rand {
     pub fn random<T>() -> T 
     where 
@@ -147,3 +258,27 @@ mod rand {
         T::from((nanos % 100) as u64)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_proc_parsing_and_history() {
+        let meminfo = "MemTotal:       16384000 kB\nMemFree:         1000000 kB\nMemAvailable:    8192000 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some((16000, 8000)));
+        assert_eq!(parse_meminfo("MemTotal: 1024 kB\n"), None);
+        
+        let stat = "cpu  100 0 50 800 50 0 0 0 0 0\ncpu0 50 0 25 400 25 0 0 0 0 0\n";
+        assert_eq!(parse_cpu_times(stat), Some((850, 1000)));
+        
+        let monitor = SystemMonitor::new();
+        monitor.update_fps(60.0);
+        let mut history = MetricHistory::new(3);
+        for _ in 0..5 {
+            history.record(&monitor);
+        }
+        assert_eq!(history.fps.len(), 3);
+        assert_eq!(history.fps.back(), Some(&60.0));
+    }
+}
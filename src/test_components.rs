@@ -18,8 +18,8 @@ fn main() {
     
     // Test 1: Voxel creation
     println!("Test 1: Voxel Creation");
-    let voxel = Voxel::new([0, 0, 0]);
-    println!("  ✓ Voxel created at position [0, 0, 0]");
+    let voxel = Voxel::new();
+    println!("  ✓ Voxel created");
     println!("  ✓ Energy: {}", voxel.energy);
     println!("  ✓ Genome concepts: {}", voxel.genome.concepts.len());
     println!("  ✓ Resonance: {}", voxel.resonance.to_f32());
@@ -48,7 +48,7 @@ fn main() {
     println!("  ✓ Crossover rate: {}", evolution.crossover_rate);
     
     // Test fitness calculation
-    let mut test_voxel = Voxel::new([1, 1, 1]);
+    let mut test_voxel = Voxel::new();
     test_voxel.energy = 0.8;
     test_voxel.genome.add_concept("test".to_string());
     let fitness = evolution.fitness(&test_voxel);
@@ -0,0 +1,134 @@
+use crate::file_processor::FileProcessor;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Строки короче этого порога, повторяющиеся `BOILERPLATE_MIN_REPEATS` раз, считаются колонтитулами
+const BOILERPLATE_MAX_LEN: usize = 80;
+const BOILERPLATE_MIN_REPEATS: usize = 3;
+
+/// Очистка текста между чтением файла и извлечением обучающих примеров
+///
+/// Шаги выполняются по порядку: удаление разметки, удаление колонтитулов,
+/// отбрасывание строк по регулярным выражениям, схлопывание пробелов.
+/// По умолчанию все шаги выключены и текст не меняется.
+#[derive(Debug, Clone, Default)]
+pub struct TextCleaner {
+    /// Удалять HTML/XML-теги (вместе с содержимым `<script>` и `<style>`)
+    pub strip_markup: bool,
+    /// Удалять повторяющиеся короткие строки и номера страниц
+    pub remove_boilerplate: bool,
+    /// Схлопывать пробелы в строках и подряд идущие пустые строки
+    pub collapse_whitespace: bool,
+    drop_patterns: Vec<Regex>,
+}
+
+impl TextCleaner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Регулярные выражения для отбрасывания строк (пустые пропускаются)
+    pub fn set_drop_patterns(&mut self, patterns: &[String]) -> Result<(), String> {
+        self.drop_patterns = patterns
+            .iter()
+            .map(|pattern| pattern.trim())
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| Regex::new(pattern).map_err(|e| format!("✗ Некорректное выражение «{}»: {}", pattern, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
+
+    pub fn drop_patterns(&self) -> impl Iterator<Item = &str> {
+        self.drop_patterns.iter().map(Regex::as_str)
+    }
+
+    /// Есть ли хотя бы один включённый шаг
+    pub fn is_enabled(&self) -> bool {
+        self.strip_markup || self.remove_boilerplate || self.collapse_whitespace || !self.drop_patterns.is_empty()
+    }
+
+    pub fn clean(&self, text: &str) -> String {
+        if !self.is_enabled() {
+            return text.to_string();
+        }
+
+        let text = if self.strip_markup { Self::strip_markup(text) } else { text.to_string() };
+
+        let repeated = if self.remove_boilerplate { Self::repeated_lines(&text) } else { HashMap::new() };
+        let page_number = Regex::new(r"(?i)^[-–—\s]*(?:стр\.?|страница|page)?\s*\d+\s*(?:(?:/|из|of)\s*\d+)?[-–—\s]*$")
+            .expect("page number regex");
+
+        let mut lines = Vec::new();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            let boilerplate = self.remove_boilerplate
+                && !trimmed.is_empty()
+                && (repeated.contains_key(trimmed) || page_number.is_match(trimmed));
+            if boilerplate || self.drop_patterns.iter().any(|pattern| pattern.is_match(line)) {
+                continue;
+            }
+
+            if self.collapse_whitespace {
+                let collapsed = line.split_whitespace().collect::<Vec<_>>().join(" ");
+                // Не больше одной пустой строки подряд: границы абзацев сохраняются
+                if collapsed.is_empty() && lines.last().is_none_or(|last: &String| last.is_empty()) {
+                    continue;
+                }
+                lines.push(collapsed);
+            } else {
+                lines.push(line.to_string());
+            }
+        }
+
+        if self.collapse_whitespace {
+            while lines.last().is_some_and(|line| line.is_empty()) {
+                lines.pop();
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Теги удаляются без изменения переводов строк, сущности декодируются
+    fn strip_markup(text: &str) -> String {
+        let hidden = Regex::new(r"(?is)<script\b.*?</script\s*>|<style\b.*?</style\s*>|<!--.*?-->").expect("markup regex");
+        let tags = Regex::new(r"<[^<>]*>").expect("tag regex");
+        let text = hidden.replace_all(text, "");
+        FileProcessor::decode_html_entities(&tags.replace_all(&text, ""))
+    }
+
+    /// Короткие строки, встречающиеся не меньше `BOILERPLATE_MIN_REPEATS` раз
+    fn repeated_lines(text: &str) -> HashMap<&str, usize> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for line in text.lines().map(str::trim) {
+            if !line.is_empty() && line.chars().count() <= BOILERPLATE_MAX_LEN {
+                *counts.entry(line).or_insert(0) += 1;
+            }
+        }
+        counts.retain(|_, count| *count >= BOILERPLATE_MIN_REPEATS);
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleaner_steps() {
+        let text = "Журнал «Наука» №5\n<p>Первый   абзац &amp; текст</p>\n\n\n\n12\nЖурнал «Наука» №5\nВторой абзац\nРеклама: купите!\nЖурнал «Наука» №5\nСтр. 13 из 40";
+
+        assert_eq!(TextCleaner::new().clean(text), text);
+
+        let mut cleaner = TextCleaner {
+            strip_markup: true,
+            remove_boilerplate: true,
+            collapse_whitespace: true,
+            ..Default::default()
+        };
+        cleaner.set_drop_patterns(&["^Реклама:".to_string(), String::new()]).unwrap();
+        assert_eq!(cleaner.clean(text), "Первый абзац & текст\n\nВторой абзац");
+        assert_eq!(cleaner.drop_patterns().collect::<Vec<_>>(), vec!["^Реклама:"]);
+
+        assert!(cleaner.set_drop_patterns(&["(".to_string()]).is_err());
+    }
+}
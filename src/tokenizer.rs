@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use unicode_normalization::UnicodeNormalization;
+
+/// Маркер начала слова (как в SentencePiece)
+pub const WORD_MARKER: char = '▁';
+
+/// Служебные токены, которые всегда идут первыми в словаре
+pub const SPECIAL_TOKENS: [&str; 4] = ["<PAD>", "<START>", "<END>", "<UNK>"];
+
+/// Токен, которым заменяются числа при нормализации
+pub const NUM_TOKEN: &str = "<NUM>";
+
+/// Нормализация текста перед токенизацией (одна и та же при обучении и генерации)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextNormalizer {
+    pub lowercase: bool,
+    /// Отделять знаки препинания от слов пробелами ("привет!" → "привет !")
+    pub split_punctuation: bool,
+    /// Заменять числа на `<NUM>`
+    pub bucket_numbers: bool,
+}
+
+impl Default for TextNormalizer {
+    fn default() -> Self {
+        Self {
+            lowercase: true,
+            split_punctuation: true,
+            bucket_numbers: true,
+        }
+    }
+}
+
+impl TextNormalizer {
+    /// NFC, нижний регистр, отделение пунктуации и замена чисел
+    pub fn normalize(&self, text: &str) -> String {
+        let mut chars: Vec<char> = text.nfc().collect();
+        if self.lowercase {
+            chars = chars.into_iter().flat_map(char::to_lowercase).collect();
+        }
+        
+        let mut spaced = String::with_capacity(chars.len());
+        for (i, &c) in chars.iter().enumerate() {
+            if self.split_punctuation && is_punctuation(c) && !is_joiner(&chars, i) {
+                spaced.push(' ');
+                spaced.push(c);
+                spaced.push(' ');
+            } else {
+                spaced.push(c);
+            }
+        }
+        
+        spaced
+            .split_whitespace()
+            .map(|word| if self.bucket_numbers && is_number(word) { NUM_TOKEN } else { word })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation() || matches!(c, '«' | '»' | '¡' | '¿' | '\u{2010}'..='\u{2027}' | '\u{2030}'..='\u{205E}')
+}
+
+/// Знак внутри слова или числа, который не отделяется: "что-то", "3.14"
+fn is_joiner(chars: &[char], i: usize) -> bool {
+    let (Some(&prev), Some(&next)) = (i.checked_sub(1).and_then(|p| chars.get(p)), chars.get(i + 1)) else {
+        return false;
+    };
+    match chars[i] {
+        '-' | '\'' | '’' => prev.is_alphanumeric() && next.is_alphanumeric(),
+        '.' | ',' => prev.is_numeric() && next.is_numeric(),
+        _ => false,
+    }
+}
+
+fn is_number(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_numeric())
+        && word.chars().all(|c| c.is_numeric() || c == '.' || c == ',')
+}
+
+/// BPE (byte-pair encoding) токенизатор, обучаемый на корпусе
+///
+/// Слова разбиваются на символы, после чего самые частые пары соседних
+/// символов последовательно склеиваются. Неизвестные слова раскладываются
+/// на известные подслова, поэтому русская морфология не теряется в `<UNK>`.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct BpeTokenizer {
+    /// Правила слияния в порядке приоритета
+    pub merges: Vec<(String, String)>,
+    /// Все токены: служебные, базовые символы и результаты слияний
+    pub tokens: Vec<String>,
+}
+
+impl BpeTokenizer {
+    /// Обучение токенизатора до размера словаря `vocab_size`
+    pub fn train(texts: &[String], vocab_size: usize) -> Self {
+        // Частоты слов в корпусе
+        let mut word_freqs: HashMap<String, usize> = HashMap::new();
+        for text in texts {
+            for word in text.split_whitespace().filter(|word| *word != NUM_TOKEN) {
+                *word_freqs.entry(word.to_lowercase()).or_insert(0) += 1;
+            }
+        }
+
+        // Слова как последовательности символов; сортируем для детерминизма
+        let mut words: Vec<(Vec<String>, usize)> = word_freqs
+            .into_iter()
+            .map(|(word, freq)| (Self::split_chars(&word), freq))
+            .collect();
+        words.sort();
+
+        let mut tokens: Vec<String> = SPECIAL_TOKENS.iter().map(|t| t.to_string()).collect();
+        tokens.push(format!("{}{}", WORD_MARKER, NUM_TOKEN));
+        let mut alphabet: Vec<String> = words
+            .iter()
+            .flat_map(|(symbols, _)| symbols.iter().cloned())
+            .collect();
+        alphabet.sort();
+        alphabet.dedup();
+        tokens.extend(alphabet);
+
+        let mut merges = Vec::new();
+        while tokens.len() < vocab_size {
+            // Подсчёт частот пар
+            let mut pair_counts: HashMap<(&str, &str), usize> = HashMap::new();
+            for (symbols, freq) in &words {
+                for pair in symbols.windows(2) {
+                    *pair_counts.entry((&pair[0], &pair[1])).or_insert(0) += freq;
+                }
+            }
+
+            let best = pair_counts
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                .map(|((left, right), count)| ((left.to_string(), right.to_string()), count));
+
+            let (pair, count) = match best {
+                Some(best) => best,
+                None => break,
+            };
+            if count < 2 {
+                break;
+            }
+
+            for (symbols, _) in &mut words {
+                Self::apply_merge(symbols, &pair);
+            }
+            tokens.push(format!("{}{}", pair.0, pair.1));
+            merges.push(pair);
+        }
+
+        Self { merges, tokens }
+    }
+
+    /// Размер словаря токенизатора
+    pub fn vocab_size(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Разбиение текста на подслова
+    pub fn encode(&self, text: &str) -> Vec<String> {
+        let ranks: HashMap<(&str, &str), usize> = self
+            .merges
+            .iter()
+            .enumerate()
+            .map(|(rank, (left, right))| ((left.as_str(), right.as_str()), rank))
+            .collect();
+
+        let mut pieces = Vec::new();
+        for word in text.split_whitespace() {
+            if word == NUM_TOKEN {
+                pieces.push(format!("{}{}", WORD_MARKER, NUM_TOKEN));
+                continue;
+            }
+            let mut symbols = Self::split_chars(&word.to_lowercase());
+
+            // Жадно применяем слияние с наименьшим рангом
+            loop {
+                let best = symbols
+                    .windows(2)
+                    .filter_map(|pair| ranks.get(&(pair[0].as_str(), pair[1].as_str())))
+                    .min()
+                    .copied();
+                match best {
+                    Some(rank) => Self::apply_merge(&mut symbols, &self.merges[rank]),
+                    None => break,
+                }
+            }
+
+            pieces.extend(symbols);
+        }
+        pieces
+    }
+
+    /// Склейка подслов обратно в текст
+    pub fn decode(&self, pieces: &[String]) -> String {
+        pieces
+            .iter()
+            .map(|piece| piece.as_str())
+            .collect::<String>()
+            .replace(WORD_MARKER, " ")
+            .trim()
+            .to_string()
+    }
+
+    fn split_chars(word: &str) -> Vec<String> {
+        std::iter::once(WORD_MARKER)
+            .chain(word.chars())
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    fn apply_merge(symbols: &mut Vec<String>, pair: &(String, String)) {
+        let mut i = 0;
+        while i + 1 < symbols.len() {
+            if symbols[i] == pair.0 && symbols[i + 1] == pair.1 {
+                let right = symbols.remove(i + 1);
+                symbols[i].push_str(&right);
+            }
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bpe_learns_merges() {
+        let texts = vec!["привет приветствую приветик".to_string(); 3];
+        let tokenizer = BpeTokenizer::train(&texts, 100);
+        assert!(!tokenizer.merges.is_empty());
+        assert!(tokenizer.tokens.contains(&"▁привет".to_string()));
+    }
+
+    #[test]
+    fn test_bpe_roundtrip_unseen_word() {
+        let texts = vec!["делать сделать переделать делали".to_string(); 4];
+        let tokenizer = BpeTokenizer::train(&texts, 60);
+        let pieces = tokenizer.encode("Переделали дела");
+        assert!(pieces.iter().all(|p| tokenizer.tokens.contains(p)));
+        assert_eq!(tokenizer.decode(&pieces), "переделали дела");
+    }
+
+    #[test]
+    fn test_normalizer_splits_punctuation_and_numbers() {
+        let normalizer = TextNormalizer::default();
+        assert_eq!(normalizer.normalize("Привет, мир!"), "привет , мир !");
+        assert_eq!(normalizer.normalize("Что-то «важное»…"), "что-то « важное » …");
+        assert_eq!(normalizer.normalize("Пи = 3.14, а не 42"), "пи = <NUM> , а не <NUM>");
+        // "е" + комбинируемая диереза → "ё" (NFC)
+        assert_eq!(normalizer.normalize("Е\u{0308}ж"), "ёж");
+    }
+
+    #[test]
+    fn test_bpe_keeps_num_token_whole() {
+        let texts = vec!["цена <NUM> рублей".to_string(); 3];
+        let tokenizer = BpeTokenizer::train(&texts, 60);
+        assert!(tokenizer.encode("цена <NUM>").contains(&format!("{}{}", WORD_MARKER, NUM_TOKEN)));
+    }
+}
@@ -0,0 +1,123 @@
+use crate::chat_history::DEFAULT_HISTORY_PATH;
+use crate::shortcuts::Shortcuts;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Файл настроек оформления по умолчанию
+pub const DEFAULT_UI_SETTINGS_PATH: &str = "ui_settings.json";
+
+/// Настройки оформления и доступности, сохраняемые между запусками
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    /// Масштаб всего интерфейса относительно системного
+    pub ui_scale: f32,
+    /// Размер шрифта сообщений чата
+    pub chat_font_size: f32,
+    /// Без анимаций и мигающего курсора
+    pub reduced_motion: bool,
+    /// Озвучивать ответы сразу после генерации
+    pub auto_speak: bool,
+    pub shortcuts: Shortcuts,
+    /// Уведомление рабочего стола по окончании обучения
+    pub notify_training: bool,
+    /// Звук по окончании обучения
+    pub training_sound: bool,
+    /// Файл истории чатов
+    pub history_path: String,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            chat_font_size: 14.0,
+            reduced_motion: false,
+            auto_speak: false,
+            shortcuts: Shortcuts::default(),
+            notify_training: true,
+            training_sound: false,
+            history_path: DEFAULT_HISTORY_PATH.to_string(),
+        }
+    }
+}
+
+impl UiSettings {
+    pub const SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.5..=3.0;
+    pub const FONT_SIZE_RANGE: std::ops::RangeInclusive<f32> = 10.0..=36.0;
+
+    /// Значения, приведённые к допустимым диапазонам
+    pub fn clamped(self) -> Self {
+        let clamp = |value: f32, range: &std::ops::RangeInclusive<f32>, default: f32| {
+            if value.is_finite() {
+                value.clamp(*range.start(), *range.end())
+            } else {
+                default
+            }
+        };
+        let defaults = Self::default();
+        Self {
+            ui_scale: clamp(self.ui_scale, &Self::SCALE_RANGE, defaults.ui_scale),
+            chat_font_size: clamp(self.chat_font_size, &Self::FONT_SIZE_RANGE, defaults.chat_font_size),
+            reduced_motion: self.reduced_motion,
+            auto_speak: self.auto_speak,
+            shortcuts: self.shortcuts,
+            notify_training: self.notify_training,
+            training_sound: self.training_sound,
+            history_path: if self.history_path.trim().is_empty() { defaults.history_path } else { self.history_path },
+        }
+    }
+
+    /// Загрузка настроек; `Ok(None)`, если файла ещё нет
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("✗ Ошибка чтения настроек {:?}: {}", path, e)),
+        };
+        serde_json::from_str::<Self>(&json)
+            .map(|settings| Some(settings.clamped()))
+            .map_err(|e| format!("✗ Повреждённый файл настроек {:?}: {}", path, e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("✗ Ошибка сериализации настроек: {}", e))?;
+        fs::write(path, json).map_err(|e| format!("✗ Ошибка сохранения настроек {:?}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ui_settings_roundtrip() {
+        let path = std::env::temp_dir().join(format!("ui_settings_test_{}.json", std::process::id()));
+        assert!(UiSettings::load(&path).unwrap().is_none());
+
+        let settings = UiSettings {
+            ui_scale: 2.0,
+            chat_font_size: 20.0,
+            reduced_motion: true,
+            auto_speak: true,
+            shortcuts: Shortcuts::default(),
+            notify_training: false,
+            training_sound: true,
+            history_path: "chats/history.json".to_string(),
+        };
+        settings.save(&path).unwrap();
+        assert_eq!(UiSettings::load(&path).unwrap(), Some(settings));
+
+        // Недостающие поля берутся по умолчанию, значения вне диапазона обрезаются
+        fs::write(&path, r#"{"ui_scale": 10.0}"#).unwrap();
+        let loaded = UiSettings::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.ui_scale, 3.0);
+        assert_eq!(loaded.chat_font_size, 14.0);
+        assert!(!loaded.reduced_motion);
+        assert!(!loaded.auto_speak);
+        assert!(loaded.notify_training);
+        assert_eq!(loaded.history_path, DEFAULT_HISTORY_PATH);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+use std::ops::Range;
+
+/// Сообщений в одной странице истории, подгружаемой при прокрутке вверх
+pub const PAGE_SIZE: usize = 100;
+
+/// Список строк разной высоты, из которых раскладываются только видимые
+///
+/// Высота строки запоминается после первой отрисовки; до этого используется
+/// оценка. Смена ширины или размера шрифта сбрасывает замеры, так как перенос
+/// строк меняется. Загружаются только строки начиная с `first_loaded`,
+/// более ранние добавляются страницами по `PAGE_SIZE`.
+#[derive(Debug, Clone)]
+pub struct VirtualList {
+    heights: Vec<Option<f32>>,
+    estimate: f32,
+    /// Ширина и размер шрифта, при которых сделаны замеры
+    layout: (f32, f32),
+    first_loaded: usize,
+}
+
+impl VirtualList {
+    pub fn new(estimate: f32) -> Self {
+        Self {
+            heights: Vec::new(),
+            estimate,
+            layout: (0.0, 0.0),
+            first_loaded: 0,
+        }
+    }
+
+    /// Подгонка под число строк и сброс замеров при смене раскладки
+    pub fn prepare(&mut self, len: usize, width: f32, font_size: f32) {
+        if (self.layout.0 - width).abs() > 1.0 || self.layout.1 != font_size {
+            self.layout = (width, font_size);
+            self.heights.clear();
+        }
+        self.heights.resize(len, None);
+        self.first_loaded = self.first_loaded.min(len.saturating_sub(1));
+    }
+
+    /// Показ только последней страницы (новый или открытый заново чат)
+    pub fn reset(&mut self, len: usize) {
+        self.heights.clear();
+        self.heights.resize(len, None);
+        self.first_loaded = len.saturating_sub(PAGE_SIZE);
+    }
+
+    pub fn first_loaded(&self) -> usize {
+        self.first_loaded
+    }
+
+    /// Подгрузка предыдущей страницы; возвращает оценку её высоты,
+    /// на которую нужно сдвинуть прокрутку, чтобы вид остался на месте
+    pub fn load_previous_page(&mut self) -> f32 {
+        let start = self.first_loaded.saturating_sub(PAGE_SIZE);
+        let added = self.offset(start..self.first_loaded);
+        self.first_loaded = start;
+        added
+    }
+
+    pub fn height(&self, index: usize) -> f32 {
+        self.heights.get(index).copied().flatten().unwrap_or(self.estimate)
+    }
+
+    pub fn set_height(&mut self, index: usize, height: f32) {
+        if let Some(slot) = self.heights.get_mut(index) {
+            *slot = Some(height);
+        }
+    }
+
+    /// Суммарная высота строк из диапазона
+    pub fn offset(&self, rows: Range<usize>) -> f32 {
+        rows.map(|index| self.height(index)).sum()
+    }
+
+    /// Высота всех загруженных строк
+    pub fn total_height(&self) -> f32 {
+        self.offset(self.first_loaded..self.heights.len())
+    }
+
+    /// Загруженные строки, пересекающие полосу `top..bottom` (от начала первой загруженной)
+    pub fn visible_range(&self, top: f32, bottom: f32) -> Range<usize> {
+        let mut y = 0.0;
+        let mut start = self.heights.len();
+        for index in self.first_loaded..self.heights.len() {
+            let height = self.height(index);
+            if y + height > top {
+                start = index;
+                break;
+            }
+            y += height;
+        }
+        let mut end = start;
+        while end < self.heights.len() && y < bottom {
+            y += self.height(end);
+            end += 1;
+        }
+        start..end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_list_ranges_and_pages() {
+        let mut list = VirtualList::new(50.0);
+        list.prepare(250, 600.0, 14.0);
+        list.reset(250);
+        assert_eq!(list.first_loaded(), 150);
+        assert_eq!(list.total_height(), 100.0 * 50.0);
+
+        // Первая загруженная строка выше оценки
+        list.set_height(150, 200.0);
+        assert_eq!(list.visible_range(0.0, 100.0), 150..151);
+        assert_eq!(list.visible_range(190.0, 260.0), 150..153);
+        assert_eq!(list.visible_range(1e6, 2e6), 250..250);
+
+        assert_eq!(list.load_previous_page(), 100.0 * 50.0);
+        assert_eq!(list.first_loaded(), 50);
+        assert_eq!(list.load_previous_page(), 50.0 * 50.0);
+        assert_eq!(list.first_loaded(), 0);
+        assert_eq!(list.load_previous_page(), 0.0);
+
+        // Смена ширины сбрасывает замеры
+        list.prepare(250, 800.0, 14.0);
+        assert_eq!(list.height(150), 50.0);
+        list.prepare(10, 800.0, 14.0);
+        assert_eq!(list.visible_range(0.0, 1e6), 0..10);
+    }
+}
@@ -0,0 +1,250 @@
+//! Голосовой ввод: запись с микрофона (cpal) и офлайн-распознавание
+//! (whisper.cpp через whisper-rs)
+//!
+//! Запись идёт, пока удерживается кнопка микрофона; после отпускания звук
+//! сводится в моно 16 кГц и распознаётся в фоновом потоке. Без feature
+//! `voice` модуль собирается как заглушка: `VoiceInput::new` возвращает
+//! ошибку, и кнопка микрофона неактивна.
+
+use std::sync::mpsc::Sender;
+#[cfg(feature = "voice")]
+use std::sync::{Arc, Mutex};
+
+/// Модель whisper.cpp по умолчанию (ggml-файл)
+pub const DEFAULT_WHISPER_MODEL: &str = "models/ggml-base.bin";
+/// Частота дискретизации, которую ожидает whisper
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+/// Записанный звук: моно-отсчёты в диапазоне -1..1
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+}
+
+impl Recording {
+    pub fn duration_secs(&self) -> f32 {
+        if self.sample_rate == 0 {
+            0.0
+        } else {
+            self.samples.len() as f32 / self.sample_rate as f32
+        }
+    }
+}
+
+/// Идущая запись: поток микрофона, накопленные отсчёты и их частота
+#[cfg(feature = "voice")]
+type Capture = (cpal::Stream, Arc<Mutex<Vec<f32>>>, u32);
+
+/// Микрофон и модель распознавания
+pub struct VoiceInput {
+    #[cfg(feature = "voice")]
+    context: Arc<whisper_rs::WhisperContext>,
+    #[cfg(feature = "voice")]
+    capture: Option<Capture>,
+}
+
+/// Распознаватель, который можно передать в фоновый поток
+#[derive(Clone)]
+pub struct Transcriber {
+    #[cfg(feature = "voice")]
+    context: Arc<whisper_rs::WhisperContext>,
+}
+
+impl VoiceInput {
+    /// Собран ли голосовой ввод (feature `voice`)
+    pub const fn available() -> bool {
+        cfg!(feature = "voice")
+    }
+}
+
+#[cfg(not(feature = "voice"))]
+impl VoiceInput {
+    /// Без feature `voice` всегда ошибка
+    pub fn new(_model_path: &str) -> Result<Self, String> {
+        Err("✗ Голосовой ввод не собран: пересоберите с --features voice".to_string())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        false
+    }
+
+    pub fn start(&mut self, _errors: Sender<Result<String, String>>) -> Result<(), String> {
+        Err("✗ Голосовой ввод не собран".to_string())
+    }
+
+    pub fn stop(&mut self) -> Result<Recording, String> {
+        Err("✗ Голосовой ввод не собран".to_string())
+    }
+
+    pub fn transcriber(&self) -> Transcriber {
+        Transcriber {}
+    }
+}
+
+#[cfg(not(feature = "voice"))]
+impl Transcriber {
+    pub fn transcribe(&self, _recording: &Recording, _language: &str) -> Result<String, String> {
+        Err("✗ Голосовой ввод не собран".to_string())
+    }
+}
+
+#[cfg(feature = "voice")]
+impl VoiceInput {
+    /// Загрузка модели whisper.cpp
+    pub fn new(model_path: &str) -> Result<Self, String> {
+        let context = whisper_rs::WhisperContext::new_with_params(model_path, whisper_rs::WhisperContextParameters::default())
+            .map_err(|e| format!("✗ Не удалось загрузить модель распознавания {:?}: {}", model_path, e))?;
+        Ok(Self {
+            context: Arc::new(context),
+            capture: None,
+        })
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.capture.is_some()
+    }
+
+    /// Начало записи с микрофона по умолчанию; ошибки потока записи
+    /// уходят в `errors`, их показывает чат
+    pub fn start(&mut self, errors: Sender<Result<String, String>>) -> Result<(), String> {
+        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+        if self.capture.is_some() {
+            return Ok(());
+        }
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or("✗ Микрофон не найден")?;
+        let config = device
+            .default_input_config()
+            .map_err(|e| format!("✗ Ошибка настройки микрофона: {}", e))?;
+        let channels = config.channels() as usize;
+        let sample_rate = config.sample_rate().0;
+
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&buffer);
+        let on_error = move |e| {
+            let _ = errors.send(Err(format!("✗ Ошибка записи звука: {}", e)));
+        };
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &config.into(),
+                move |data: &[f32], _: &_| sink.lock().unwrap().extend(to_mono(data, channels)),
+                on_error,
+                None,
+            ),
+            cpal::SampleFormat::I16 => device.build_input_stream(
+                &config.into(),
+                move |data: &[i16], _: &_| {
+                    let data: Vec<f32> = data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
+                    sink.lock().unwrap().extend(to_mono(&data, channels));
+                },
+                on_error,
+                None,
+            ),
+            format => return Err(format!("✗ Неподдерживаемый формат звука: {:?}", format)),
+        }
+        .map_err(|e| format!("✗ Ошибка открытия микрофона: {}", e))?;
+        stream.play().map_err(|e| format!("✗ Ошибка запуска записи: {}", e))?;
+
+        self.capture = Some((stream, buffer, sample_rate));
+        Ok(())
+    }
+
+    /// Остановка записи; звук приводится к 16 кГц
+    pub fn stop(&mut self) -> Result<Recording, String> {
+        let (stream, buffer, sample_rate) = self.capture.take().ok_or("✗ Запись не идёт")?;
+        drop(stream);
+        let samples = std::mem::take(&mut *buffer.lock().unwrap());
+        Ok(Recording {
+            samples: resample(&samples, sample_rate, WHISPER_SAMPLE_RATE),
+            sample_rate: WHISPER_SAMPLE_RATE,
+        })
+    }
+
+    pub fn transcriber(&self) -> Transcriber {
+        Transcriber {
+            context: Arc::clone(&self.context),
+        }
+    }
+}
+
+#[cfg(feature = "voice")]
+impl Transcriber {
+    /// Распознавание записи; `language` — код языка (`ru`, `en`) или `auto`
+    pub fn transcribe(&self, recording: &Recording, language: &str) -> Result<String, String> {
+        use whisper_rs::{FullParams, SamplingStrategy};
+
+        let mut state = self
+            .context
+            .create_state()
+            .map_err(|e| format!("✗ Ошибка распознавания: {}", e))?;
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some(language));
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_special(false);
+        state
+            .full(params, &recording.samples)
+            .map_err(|e| format!("✗ Ошибка распознавания: {}", e))?;
+
+        let segments = state.full_n_segments().map_err(|e| format!("✗ Ошибка распознавания: {}", e))?;
+        let mut text = String::new();
+        for segment in 0..segments {
+            if let Ok(piece) = state.full_get_segment_text(segment) {
+                text.push_str(&piece);
+            }
+        }
+        Ok(text.trim().to_string())
+    }
+}
+
+/// Сведение чередующихся каналов в моно усреднением
+pub fn to_mono(samples: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Линейная передискретизация
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || from_rate == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let ratio = from_rate as f64 / to_rate as f64;
+    let len = (samples.len() as f64 / ratio).floor() as usize;
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let next = samples.get(idx + 1).copied().unwrap_or(samples[idx]);
+            samples[idx] * (1.0 - frac) + next * frac
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mono_and_resample() {
+        assert_eq!(to_mono(&[1.0, 0.0, 0.5, 0.5], 2), vec![0.5, 0.5]);
+        assert_eq!(to_mono(&[0.25, 0.75], 1), vec![0.25, 0.75]);
+
+        let samples: Vec<f32> = (0..48).map(|i| i as f32).collect();
+        let resampled = resample(&samples, 48_000, WHISPER_SAMPLE_RATE);
+        assert_eq!(resampled.len(), 16);
+        assert_eq!(resampled[1], 3.0);
+        assert_eq!(resample(&samples, 16_000, 16_000), samples);
+
+        let recording = Recording { samples: vec![0.0; 8000], sample_rate: WHISPER_SAMPLE_RATE };
+        assert_eq!(recording.duration_secs(), 0.5);
+    }
+}
@@ -1,10 +1,53 @@
+use crate::ai_model::AIModel;
+use crate::chunks::{chunk_of, ChunkStore, FrozenVoxel, StreamingRules};
+use crate::controller::NeuralController;
+use crate::environment::Environment;
+use crate::events::{EventLog, WorldEvent};
+use crate::evolution::{EvolutionEngine, MutationRateStats};
+use crate::islands::IslandModel;
+use crate::octree::{Aabb, Frustum, Octree};
+use crate::pareto::{self, Objectives};
+use crate::population::{PopulationHistory, PopulationSample};
+use crate::sound::{Sound, SoundKind};
+use crate::spatial_grid::{distance_sq, SpatialGrid};
+use crate::voxel_gpu::{GpuVoxel, VoxelGpu};
+use crate::voxel_language::{self, LanguageRules, Utterance, UTTERANCE_HISTORY};
+use crate::voxel_memory::{Episode, EpisodeKind, MemorySummary, VoxelMemory};
+use crate::voxel_soa::{self, Position, Velocity};
 use bevy_ecs::prelude::*;
 use half::f16;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Edge of a spatial grid cell; about the default sensing radius
+pub const GRID_CELL_SIZE: i32 = 4;
+/// Default octree leaf capacity
+pub const OCTREE_LEAF_SIZE: usize = 16;
+/// Neighbor count at which `perception_other` saturates at 1.0
+const CROWD_SATURATION: f32 = 8.0;
+/// Acceleration units (`i8`) per one step of velocity change
+const FORCE_SCALE: f64 = 16.0;
+/// Speed limit per axis, in cells per tick
+pub const MAX_SPEED: i8 = 3;
+/// Smallest nutrient gradient (per cell) that hungry voxels follow
+const FORAGE_GRADIENT: f32 = 1e-3;
+/// Half the edge of the default world box
+pub const WORLD_EXTENT: i32 = 1024;
+/// Smallest population integrated on the GPU; below it the upload costs more than it saves
+pub const GPU_MIN_VOXELS: usize = 100_000;
+/// Length of `Genome::genes`, one gene per `GeneTraits` field
+pub const GENE_COUNT: usize = 3;
+/// Neighbor centroid offset (cells) below which clustering and fleeing voxels hold still
+const CLUSTER_DEAD_ZONE: f64 = 1.0;
+/// Emotions weaker than this on every axis count as neutral
+const NEUTRAL_EMOTION: f64 = 0.1;
 
 /// Voxel component: 9-13 KB per voxel
-#[derive(Component, Clone)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Voxel {
     // FP64 for energy/emotions (8 bytes)
     pub energy: f64,
@@ -24,10 +67,8 @@ pub struct Voxel {
     pub perception_self: f16,
     pub perception_other: f16,
     
-    // INT8 for physics (1 byte each)
-    pub velocity_x: i8,
-    pub velocity_y: i8,
-    pub velocity_z: i8,
+    // INT8 for physics (1 byte each); position and velocity are the
+    // `Position` and `Velocity` components, see `voxel_soa`
     pub acceleration_x: i8,
     pub acceleration_y: i8,
     pub acceleration_z: i8,
@@ -49,15 +90,20 @@ pub struct Voxel {
     pub echo: [u8; 16],
     pub resonance: f16,
     
-    // Position (12 bytes for i32 x3)
-    pub position: [i32; 3],
+    // Ticks lived, for `LifeStage` (8 bytes)
+    #[serde(default)]
+    pub age_ticks: u64,
     
     // Additional metadata (~100-200 bytes)
     pub metadata: HashMap<String, String>,
+    
+    // Episodic memory (up to 16 recent + 32 long-term episodes)
+    #[serde(default)]
+    pub memory: VoxelMemory,
 }
 
 impl Voxel {
-    pub fn new(position: [i32; 3]) -> Self {
+    pub fn new() -> Self {
         Self {
             energy: 0.0,
             emotion_valence: 0.0,
@@ -73,9 +119,6 @@ impl Voxel {
             perception_space: f16::ZERO,
             perception_self: f16::ZERO,
             perception_other: f16::ZERO,
-            velocity_x: 0,
-            velocity_y: 0,
-            velocity_z: 0,
             acceleration_x: 0,
             acceleration_y: 0,
             acceleration_z: 0,
@@ -90,8 +133,9 @@ impl Voxel {
             genome: Genome::new(),
             echo: [0; 16],
             resonance: f16::ZERO,
-            position,
+            age_ticks: 0,
             metadata: HashMap::new(),
+            memory: VoxelMemory::default(),
         }
     }
     
@@ -102,7 +146,128 @@ impl Voxel {
         let metadata_size: usize = self.metadata.iter()
             .map(|(k, v)| k.len() + v.len() + 16)
             .sum();
-        base + genome_size + metadata_size
+        let memory_size = (self.memory.episodes.len() + self.memory.long_term.len()) * std::mem::size_of::<Episode>();
+        base + genome_size + metadata_size + memory_size
+    }
+    
+    /// Momentary pleasure in 0..1: positive valence, amplified by arousal
+    pub fn kaif(&self) -> f64 {
+        let arousal = self.emotion_arousal.clamp(0.0, 1.0);
+        (self.emotion_valence.clamp(0.0, 1.0) * (0.5 + 0.5 * arousal)).min(1.0)
+    }
+    
+    /// Generation number from the `generation` metadata; 0 for spawned voxels
+    pub fn generation(&self) -> u64 {
+        self.metadata.get("generation").and_then(|g| g.parse().ok()).unwrap_or(0)
+    }
+    
+    /// Stage of life by `age_ticks`
+    pub fn life_stage(&self, rules: &LifecycleRules) -> LifeStage {
+        if self.age_ticks >= rules.elder_at {
+            LifeStage::Elder
+        } else if self.age_ticks >= rules.adult_at {
+            LifeStage::Adult
+        } else {
+            LifeStage::Juvenile
+        }
+    }
+    
+    /// Role from the `role` metadata; prey unless marked otherwise
+    pub fn role(&self) -> Role {
+        match self.metadata.get("role").map(String::as_str) {
+            Some("predator") => Role::Predator,
+            _ => Role::Prey,
+        }
+    }
+    
+    pub fn set_role(&mut self, role: Role) {
+        self.metadata.insert("role".to_string(), role.as_str().to_string());
+    }
+    
+    /// Island from the `island` metadata, see `IslandModel`
+    pub fn island(&self) -> Option<usize> {
+        self.metadata.get("island").and_then(|island| island.parse().ok())
+    }
+    
+    pub fn set_island(&mut self, island: usize) {
+        self.metadata.insert("island".to_string(), island.to_string());
+    }
+    
+    /// Energy gained from food or prey over the voxel's life (`intake` metadata)
+    pub fn intake(&self) -> f64 {
+        self.metadata.get("intake").and_then(|v| v.parse().ok()).unwrap_or(0.0)
+    }
+    
+    pub fn add_intake(&mut self, energy: f64) {
+        if energy > 0.0 {
+            let total = self.intake() + energy;
+            self.metadata.insert("intake".to_string(), total.to_string());
+        }
+    }
+    
+    /// Structured summary for the inspector and for logging, see `VoxelWorld::inspect`
+    pub fn inspect(&self, position: [i32; 3], velocity: [i8; 3]) -> VoxelSnapshot {
+        VoxelSnapshot {
+            position,
+            velocity,
+            energy: self.energy,
+            temperature: self.temperature,
+            generation: self.generation(),
+            age_ticks: self.age_ticks,
+            role: self.role(),
+            intake: self.intake(),
+            dominant_emotion: self.dominant_emotion(),
+            emotion: [self.emotion_valence, self.emotion_arousal, self.emotion_dominance],
+            kaif: self.kaif(),
+            senses: [
+                ("visual", self.perception_visual),
+                ("auditory", self.perception_auditory),
+                ("tactile", self.perception_tactile),
+                ("thermal", self.perception_thermal),
+                ("chemical", self.perception_chemical),
+                ("pressure", self.perception_pressure),
+                ("time", self.perception_time),
+                ("space", self.perception_space),
+                ("self", self.perception_self),
+                ("other", self.perception_other),
+            ]
+            .map(|(name, value)| (name, value.to_f32())),
+            resonance: self.resonance.to_f32(),
+            genome: self.genome.concepts.clone(),
+            memory: self.memory.summarize(),
+        }
+    }
+    
+    /// Behavior for this tick: fear makes a voxel flee, hunger (energy below
+    /// `hungry_below`) sends it foraging, joy makes it seek company
+    pub fn policy(&self, hungry_below: f64) -> Policy {
+        match self.dominant_emotion() {
+            EmotionType::Fear => Policy::Flee,
+            _ if self.energy < hungry_below => Policy::Forage,
+            EmotionType::Joy => Policy::Cluster,
+            _ => Policy::Idle,
+        }
+    }
+    
+    /// Strongest of the valence, arousal and dominance axes, by sign
+    pub fn dominant_emotion(&self) -> EmotionType {
+        let axes = [self.emotion_valence, self.emotion_arousal, self.emotion_dominance];
+        let (axis, value) = axes
+            .into_iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+            .unwrap_or((0, 0.0));
+        if value.abs() < NEUTRAL_EMOTION {
+            return EmotionType::Neutral;
+        }
+        match (axis, value > 0.0) {
+            (0, true) => EmotionType::Joy,
+            (0, false) => EmotionType::Sadness,
+            (1, true) => EmotionType::Excitement,
+            (1, false) => EmotionType::Calm,
+            (_, true) => EmotionType::Confidence,
+            (_, false) => EmotionType::Fear,
+        }
     }
     
     pub fn get_energy_color(&self, max_energy: f64) -> [f32; 3] {
@@ -113,11 +278,134 @@ impl Voxel {
     }
 }
 
-/// Genome: up to 10 concepts (strings)
-#[derive(Clone)]
+impl Default for Voxel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A voxel outside the ECS world (saved, frozen in a chunk or being evolved)
+/// together with its hot components; serialized flat, in the same shape as
+/// before `position` and the velocity moved off `Voxel`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VoxelRecord {
+    pub position: Position,
+    #[serde(flatten)]
+    pub velocity: Velocity,
+    #[serde(flatten)]
+    pub voxel: Voxel,
+}
+
+impl VoxelRecord {
+    /// A resting voxel at `position`
+    pub fn new(voxel: Voxel, position: [i32; 3]) -> Self {
+        Self { position: Position(position), velocity: Velocity::default(), voxel }
+    }
+}
+
+/// Emotion named after the strongest axis of a voxel's emotional state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EmotionType {
+    Neutral,
+    /// Positive valence
+    Joy,
+    /// Negative valence
+    Sadness,
+    /// High arousal
+    Excitement,
+    /// Negative arousal
+    Calm,
+    /// Positive dominance
+    Confidence,
+    /// Negative dominance
+    Fear,
+}
+
+impl EmotionType {
+    pub const ALL: [Self; 7] = [
+        Self::Neutral,
+        Self::Joy,
+        Self::Sadness,
+        Self::Excitement,
+        Self::Calm,
+        Self::Confidence,
+        Self::Fear,
+    ];
+    
+    /// Position in `ALL`
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Place in the food chain, stored in the `role` metadata
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Role {
+    /// Eats from the nutrient field
+    #[default]
+    Prey,
+    /// Drains energy from prey it touches
+    Predator,
+}
+
+impl Role {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prey => "prey",
+            Self::Predator => "predator",
+        }
+    }
+}
+
+/// Movement policy chosen by `Voxel::policy`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Policy {
+    Idle,
+    /// Up the nutrient gradient
+    Forage,
+    /// Away from the neighbors
+    Flee,
+    /// Towards the neighbors
+    Cluster,
+}
+
+/// Point-in-time summary of one voxel, see `Voxel::inspect`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct VoxelSnapshot {
+    pub position: [i32; 3],
+    pub velocity: [i8; 3],
+    pub energy: f64,
+    pub temperature: i8,
+    pub generation: u64,
+    pub age_ticks: u64,
+    pub role: Role,
+    pub intake: f64,
+    pub dominant_emotion: EmotionType,
+    /// Valence, arousal, dominance
+    pub emotion: [f64; 3],
+    pub kaif: f64,
+    /// Perception channels by name
+    pub senses: [(&'static str, f32); 10],
+    pub resonance: f32,
+    pub genome: Vec<String>,
+    pub memory: MemorySummary,
+}
+
+/// Genome: up to 10 concepts (strings) and `GENE_COUNT` real-valued genes
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Genome {
     pub concepts: Vec<String>,
     pub max_concepts: usize,
+    /// Continuous traits, see `traits`; 0.0 is the neutral value
+    #[serde(default)]
+    pub genes: Vec<f64>,
+    /// Evolved steering, used when `VoxelWorld::neural_control` is on
+    #[serde(default)]
+    pub controller: NeuralController,
+    /// Own mutation rate, used and evolved when
+    /// `EvolutionEngine::self_adaptive` is on; `None` until then
+    #[serde(default)]
+    pub mutation_rate: Option<f64>,
 }
 
 impl Genome {
@@ -125,11 +413,25 @@ impl Genome {
         Self {
             concepts: Vec::new(),
             max_concepts: 10,
+            genes: vec![0.0; GENE_COUNT],
+            controller: NeuralController::default(),
+            mutation_rate: None,
         }
     }
     
     pub fn size_bytes(&self) -> usize {
-        self.concepts.iter().map(|s| s.len() + 8).sum::<usize>() + 16
+        self.concepts.iter().map(|s| s.len() + 8).sum::<usize>() + (self.genes.len() + self.controller.weights.len()) * 8 + 16
+    }
+    
+    /// Multipliers encoded by the genes: `exp(gene)`, with genes clamped to
+    /// -2..2 and missing ones neutral
+    pub fn traits(&self) -> GeneTraits {
+        let trait_of = |i: usize| self.genes.get(i).copied().unwrap_or(0.0).clamp(-2.0, 2.0).exp();
+        GeneTraits {
+            acuity: trait_of(0),
+            sociability: trait_of(1),
+            metabolism: trait_of(2),
+        }
     }
     
     pub fn add_concept(&mut self, concept: String) -> bool {
@@ -142,6 +444,234 @@ impl Genome {
     }
 }
 
+impl Default for Genome {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Inherited multipliers of voxel physics and senses, see `Genome::traits`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct GeneTraits {
+    /// Sensing and hearing range
+    pub acuity: f64,
+    /// Strength of emotional attraction and repulsion
+    pub sociability: f64,
+    /// Energy burned with age when the lifecycle is on
+    pub metabolism: f64,
+}
+
+/// Which spatial index a saved world uses (the index itself is rebuilt on load)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndexKind {
+    Grid { cell_size: i32 },
+    Octree { leaf_size: usize },
+}
+
+/// Index over voxel positions: a uniform grid for dense worlds,
+/// an octree for large sparse ones
+pub enum SpatialIndex {
+    Grid(SpatialGrid<Entity>),
+    Octree(Octree<Entity>),
+}
+
+impl SpatialIndex {
+    pub fn insert(&mut self, entity: Entity, position: [i32; 3]) {
+        match self {
+            Self::Grid(grid) => grid.insert(entity, position),
+            Self::Octree(octree) => octree.insert(entity, position),
+        }
+    }
+    
+    pub fn rebuild(&mut self, items: impl IntoIterator<Item = (Entity, [i32; 3])>) {
+        match self {
+            Self::Grid(grid) => grid.rebuild(items),
+            Self::Octree(octree) => octree.rebuild(items),
+        }
+    }
+    
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Grid(grid) => grid.len(),
+            Self::Octree(octree) => octree.len(),
+        }
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    pub fn neighbors_within(&self, position: [i32; 3], radius: i32) -> Vec<(Entity, [i32; 3])> {
+        match self {
+            Self::Grid(grid) => grid.neighbors_within(position, radius),
+            Self::Octree(octree) => octree.neighbors_within(position, radius),
+        }
+    }
+    
+    /// Items inside the frustum; the grid has no hierarchy and tests every item
+    pub fn visible(&self, frustum: &Frustum) -> Vec<(Entity, [i32; 3])> {
+        match self {
+            Self::Grid(grid) => grid.iter().filter(|(_, p)| frustum.contains(*p)).copied().collect(),
+            Self::Octree(octree) => octree.visible(frustum),
+        }
+    }
+    
+    pub fn kind(&self) -> IndexKind {
+        match self {
+            Self::Grid(grid) => IndexKind::Grid { cell_size: grid.cell_size },
+            Self::Octree(octree) => IndexKind::Octree { leaf_size: octree.leaf_size },
+        }
+    }
+    
+    pub fn bounds(&self) -> Option<Aabb> {
+        match self {
+            Self::Grid(grid) => grid.iter().map(|&(_, p)| p).fold(None, |bounds: Option<Aabb>, p| {
+                let mut bounds = bounds.unwrap_or(Aabb::point(p));
+                bounds.include(p);
+                Some(bounds)
+            }),
+            Self::Octree(octree) => octree.bounds(),
+        }
+    }
+}
+
+/// When and how voxels reproduce
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReproductionRules {
+    pub enabled: bool,
+    /// Minimum parent energy
+    pub energy_threshold: f64,
+    /// Minimum parent `Voxel::kaif`
+    pub kaif_threshold: f64,
+    /// Share of the parent's energy passed to the child
+    pub offspring_share: f64,
+    /// No births above this population
+    pub max_population: usize,
+}
+
+impl Default for ReproductionRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            energy_threshold: 1.0,
+            kaif_threshold: 0.2,
+            offspring_share: 0.5,
+            max_population: 100_000,
+        }
+    }
+}
+
+/// Stage of a voxel's life, see `Voxel::life_stage`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LifeStage {
+    Juvenile,
+    Adult,
+    Elder,
+}
+
+/// How a life stage scales a voxel
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StageTraits {
+    /// Multiplier for `LifecycleRules::metabolic_rate`
+    pub metabolism: f64,
+    /// Multiplier for the mutation rate of the voxel's offspring
+    pub mutation: f64,
+    /// Multiplier for the sense radius and for heard loudness
+    pub acuity: f64,
+}
+
+/// Age-dependent metabolism, fertility and senses
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifecycleRules {
+    pub enabled: bool,
+    /// Age in ticks at which a juvenile becomes an adult
+    pub adult_at: u64,
+    /// Age in ticks at which an adult becomes an elder
+    pub elder_at: u64,
+    /// Energy an adult burns per second
+    pub metabolic_rate: f64,
+    /// Age in ticks at which a voxel dies of old age; 0 means never
+    pub lifespan: u64,
+    pub juvenile: StageTraits,
+    pub adult: StageTraits,
+    pub elder: StageTraits,
+}
+
+impl LifecycleRules {
+    pub fn traits(&self, stage: LifeStage) -> &StageTraits {
+        match stage {
+            LifeStage::Juvenile => &self.juvenile,
+            LifeStage::Adult => &self.adult,
+            LifeStage::Elder => &self.elder,
+        }
+    }
+}
+
+impl Default for LifecycleRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            adult_at: 200,
+            elder_at: 2000,
+            metabolic_rate: 0.01,
+            lifespan: 10_000,
+            // Juveniles grow fast and see little; elders are slow, half-blind
+            // and pass on more mutations
+            juvenile: StageTraits { metabolism: 1.5, mutation: 1.0, acuity: 0.5 },
+            adult: StageTraits { metabolism: 1.0, mutation: 1.0, acuity: 1.0 },
+            elder: StageTraits { metabolism: 0.7, mutation: 2.0, acuity: 0.6 },
+        }
+    }
+}
+
+/// Boundary behavior of the world box
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Boundary {
+    /// Stop at the wall
+    Clamp,
+    /// Reflect off the wall using `elasticity` and `friction`
+    #[default]
+    Bounce,
+    /// Re-enter from the opposite side (toroidal world)
+    Wrap,
+}
+
+/// How predators feed on prey
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PredationRules {
+    pub enabled: bool,
+    /// Energy a predator drains per second from each prey it touches
+    pub drain_rate: f64,
+    /// Share of the drained energy the predator gains
+    pub efficiency: f64,
+    /// Share of predators among newly spawned voxels
+    pub predator_share: f64,
+}
+
+impl Default for PredationRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            drain_rate: 0.5,
+            efficiency: 0.8,
+            predator_share: 0.1,
+        }
+    }
+}
+
+/// Restitution or friction coefficient (0..1) from an `i8` material property
+fn coefficient(property: i8) -> f64 {
+    (f64::from(property) / 127.0).clamp(0.0, 1.0)
+}
+
+/// Round to a velocity within the speed limit
+fn to_velocity(velocity: f64) -> i8 {
+    velocity.round().clamp(-f64::from(MAX_SPEED), f64::from(MAX_SPEED)) as i8
+}
+
 /// Voxel World System
 #[derive(Resource)]
 pub struct VoxelWorld {
@@ -149,6 +679,63 @@ pub struct VoxelWorld {
     pub world: World,
     pub max_points: usize,
     pub trauma_mode: bool,
+    /// Voxel positions as of the last tick, for neighbor queries and culling
+    pub spatial: SpatialIndex,
+    /// Radius within which voxels sense each other
+    pub sense_radius: i32,
+    /// Radius of the interaction pass (forces and chemical/tactile signals)
+    pub interaction_radius: i32,
+    /// Multiplier for inter-voxel forces; 0 disables them (signals still flow)
+    pub interaction_strength: f64,
+    pub reproduction: ReproductionRules,
+    /// Mutates offspring genomes and evolves the population on demand
+    pub evolution: EvolutionEngine,
+    /// Sub-populations `evolve` runs instead of `evolution` when there are any
+    pub islands: IslandModel,
+    /// Voxels born since the world was created or cleared
+    pub births: u64,
+    /// Voxels removed since the world was created or cleared
+    pub deaths: u64,
+    /// Temperature, light and nutrient fields the voxels live in
+    pub environment: Environment,
+    /// Sounds emitted since the last tick, heard on the next one
+    pub sounds: Vec<Sound>,
+    /// Voxels bump into each other instead of passing through
+    pub collisions: bool,
+    /// Box the voxels live in; unbounded when `None`
+    pub bounds: Option<Aabb>,
+    /// What happens to voxels that leave `bounds`
+    pub boundary: Boundary,
+    /// Ticks simulated since the world was created
+    pub ticks: u64,
+    /// Latest spawns, deaths, births and collisions
+    pub events: EventLog,
+    /// Voxels follow their movement `Policy`
+    pub policies: bool,
+    /// Voxels also steer by their genome's `NeuralController`
+    pub neural_control: bool,
+    pub predation: PredationRules,
+    pub lifecycle: LifecycleRules,
+    /// GPU integration path, see `enable_gpu`
+    pub gpu: Option<Arc<VoxelGpu>>,
+    /// Populations from this size on are integrated on the GPU when it is enabled
+    pub gpu_min_voxels: usize,
+    /// Shared model voxels talk with, see `enable_language`
+    pub language: Option<Arc<AIModel>>,
+    pub language_rules: LanguageRules,
+    /// Latest utterances, oldest first
+    pub utterances: VecDeque<Utterance>,
+    /// Which chunks of the world keep running, see `stream`
+    pub streaming: StreamingRules,
+    /// Voxels of the frozen chunks
+    pub chunks: ChunkStore,
+    /// Ticks each live chunk has been out of focus and quiet
+    quiet_chunks: HashMap<[i32; 3], u64>,
+    /// Voxels that starved or aged out this tick, removed at its end
+    dying: Vec<Entity>,
+    stats_history: PopulationHistory,
+    /// `births` and `deaths` as of the last recorded sample
+    stats_baseline: (u64, u64),
 }
 
 impl VoxelWorld {
@@ -161,37 +748,1031 @@ impl VoxelWorld {
             world,
             max_points: 1_500_000_000, // 1.5 billion points
             trauma_mode: false,
+            spatial: SpatialIndex::Grid(SpatialGrid::new(GRID_CELL_SIZE)),
+            sense_radius: GRID_CELL_SIZE,
+            interaction_radius: 3,
+            interaction_strength: 1.0,
+            reproduction: ReproductionRules::default(),
+            evolution: EvolutionEngine::new(),
+            islands: IslandModel::default(),
+            births: 0,
+            deaths: 0,
+            environment: Environment::default(),
+            sounds: Vec::new(),
+            collisions: false,
+            bounds: None,
+            boundary: Boundary::Bounce,
+            ticks: 0,
+            events: EventLog::default(),
+            policies: true,
+            neural_control: false,
+            predation: PredationRules::default(),
+            lifecycle: LifecycleRules::default(),
+            gpu: None,
+            gpu_min_voxels: GPU_MIN_VOXELS,
+            language: None,
+            language_rules: LanguageRules::default(),
+            utterances: VecDeque::new(),
+            streaming: StreamingRules::default(),
+            chunks: ChunkStore::default(),
+            quiet_chunks: HashMap::new(),
+            dying: Vec::new(),
+            stats_history: PopulationHistory::default(),
+            stats_baseline: (0, 0),
         }
     }
     
     pub fn add_voxel(&mut self, position: [i32; 3]) -> Entity {
-        let entity = self.world.spawn(Voxel::new(position)).id();
+        let entity = self.world.spawn((Voxel::new(), Position(position), Velocity::default())).id();
         self.voxels.push(entity);
+        self.spatial.insert(entity, position);
+        self.events.push(self.ticks, WorldEvent::Spawned { entity, position });
         entity
     }
     
+    /// Remove a voxel from the world; it stays in neighbor queries until the
+    /// next tick, when its neighbors hear its death
+    pub fn remove_voxel(&mut self, entity: Entity) -> Option<VoxelRecord> {
+        let (voxel, position, velocity) = self.world.get_entity_mut(entity)?.take::<(Voxel, Position, Velocity)>()?;
+        self.world.despawn(entity);
+        self.voxels.retain(|&other| other != entity);
+        self.deaths += 1;
+        self.record(WorldEvent::Died { entity, position: position.0 });
+        Some(VoxelRecord { position, velocity, voxel })
+    }
+    
+    pub fn position(&self, entity: Entity) -> Option<[i32; 3]> {
+        self.world.get::<Position>(entity).map(|position| position.0)
+    }
+    
+    pub fn velocity(&self, entity: Entity) -> Option<[i8; 3]> {
+        self.world.get::<Velocity>(entity).map(|velocity| velocity.0)
+    }
+    
+    pub fn set_velocity(&mut self, entity: Entity, velocity: [i8; 3]) {
+        if let Some(mut current) = self.world.get_mut::<Velocity>(entity) {
+            current.0 = velocity;
+        }
+    }
+    
+    /// Inspector summary of a live voxel
+    pub fn inspect(&self, entity: Entity) -> Option<VoxelSnapshot> {
+        let voxel = self.world.get::<Voxel>(entity)?;
+        Some(voxel.inspect(self.position(entity)?, self.velocity(entity)?))
+    }
+    
+    /// Log an event together with the sound it makes, if any
+    fn record(&mut self, event: WorldEvent) {
+        if let WorldEvent::Died { position, .. } = event {
+            self.sounds.push(Sound::new(SoundKind::Death, position));
+        }
+        self.events.push(self.ticks, event);
+    }
+    
+    /// Remove every voxel, keeping the world's settings
+    pub fn clear(&mut self) {
+        self.world.clear_entities();
+        self.voxels.clear();
+        self.births = 0;
+        self.deaths = 0;
+        self.stats_history.clear();
+        self.stats_baseline = (0, 0);
+        self.sounds.clear();
+        self.events.clear();
+        self.dying.clear();
+        self.utterances.clear();
+        self.chunks.clear();
+        self.quiet_chunks.clear();
+        self.evolution.speciation.clear();
+        self.evolution.novelty.clear();
+        self.evolution.pareto.front.clear();
+        self.rebuild_index();
+    }
+    
+    /// Voxels within `radius` of `position`, as of the last tick
+    pub fn neighbors_within(&self, position: [i32; 3], radius: i32) -> Vec<Entity> {
+        self.spatial
+            .neighbors_within(position, radius)
+            .into_iter()
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+    
+    /// Every live voxel with its entity, in spawn order
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &Voxel)> {
+        self.voxels
+            .iter()
+            .filter_map(|&entity| self.world.get::<Voxel>(entity).map(|voxel| (entity, voxel)))
+    }
+    
+    /// Voxels inside the box between `min` and `max` (inclusive)
+    pub fn voxels_in_box(&self, min: [i32; 3], max: [i32; 3]) -> impl Iterator<Item = (Entity, &Voxel)> {
+        let region = Aabb { min, max };
+        self.iter().filter(move |&(entity, _)| self.position(entity).is_some_and(|position| region.contains(position)))
+    }
+    
+    pub fn voxels_by_dominant_emotion(&self, emotion: EmotionType) -> impl Iterator<Item = (Entity, &Voxel)> {
+        self.iter().filter(move |(_, voxel)| voxel.dominant_emotion() == emotion)
+    }
+    
+    /// Up to `n` voxels with the least energy, weakest first
+    pub fn weakest(&self, n: usize) -> impl Iterator<Item = (Entity, &Voxel)> {
+        let mut ranked: Vec<_> = self.iter().collect();
+        ranked.sort_by(|(_, a), (_, b)| a.energy.total_cmp(&b.energy));
+        ranked.into_iter().take(n)
+    }
+    
+    /// Up to `n` voxels with the most energy, strongest first
+    pub fn strongest(&self, n: usize) -> impl Iterator<Item = (Entity, &Voxel)> {
+        let mut ranked: Vec<_> = self.iter().collect();
+        ranked.sort_by(|(_, a), (_, b)| b.energy.total_cmp(&a.energy));
+        ranked.into_iter().take(n)
+    }
+    
+    /// Re-index voxel positions after movement
+    pub fn rebuild_index(&mut self) {
+        let world = &self.world;
+        let positions = self.voxels
+            .iter()
+            .filter_map(|&entity| world.get::<Position>(entity).map(|position| (entity, position.0)));
+        self.spatial.rebuild(positions);
+    }
+    
+    /// Switch neighbor queries and culling to a uniform grid
+    pub fn use_grid(&mut self, cell_size: i32) {
+        self.spatial = SpatialIndex::Grid(SpatialGrid::new(cell_size));
+        self.rebuild_index();
+    }
+    
+    /// Switch neighbor queries and culling to an octree
+    pub fn use_octree(&mut self, leaf_size: usize) {
+        self.spatial = SpatialIndex::Octree(Octree::new(leaf_size));
+        self.rebuild_index();
+    }
+    
+    /// Neighbor sensors: crowding goes to `perception_other`,
+    /// distance to the nearest neighbor (relative to the radius) to `perception_space`
+    ///
+    /// The radius is scaled by the voxel's acuity, see `acuity`.
+    fn sense_neighbors(&mut self) {
+        for &entity in &self.voxels {
+            let (Some(position), Some(voxel)) = (self.position(entity), self.world.get::<Voxel>(entity)) else {
+                continue;
+            };
+            let acuity = self.acuity(voxel);
+            let radius = ((f64::from(self.sense_radius) * acuity).round() as i32).max(1);
+            let neighbors = self.spatial.neighbors_within(position, radius);
+            let others = neighbors.iter().filter(|(other, _)| *other != entity);
+            let count = others.clone().count();
+            let nearest = others
+                .map(|(_, p)| (distance_sq(*p, position) as f32).sqrt())
+                .fold(radius as f32, f32::min);
+            
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(entity) {
+                voxel.perception_other = f16::from_f32((count as f32 / CROWD_SATURATION).min(1.0));
+                voxel.perception_space = f16::from_f32(nearest / radius as f32);
+            }
+        }
+    }
+    
+    /// Queue a sound; voxels hear it during the next tick
+    pub fn emit_sound(&mut self, kind: SoundKind, position: [i32; 3]) {
+        self.sounds.push(Sound::new(kind, position));
+    }
+    
+    /// Hearing pass: every voxel's `perception_auditory` becomes the total
+    /// loudness of the queued sounds at its position (saturating at 1.0);
+    /// the queue is emptied
+    fn hear_sounds(&mut self) {
+        let mut heard: HashMap<Entity, f32> = HashMap::new();
+        for sound in self.sounds.drain(..) {
+            for (entity, position) in self.spatial.neighbors_within(sound.position, sound.range()) {
+                let distance = (distance_sq(position, sound.position) as f32).sqrt();
+                *heard.entry(entity).or_default() += sound.heard_at(distance);
+            }
+        }
+        for &entity in &self.voxels {
+            let acuity = self.world.get::<Voxel>(entity).map_or(1.0, |voxel| self.acuity(voxel));
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(entity) {
+                let loudness = heard.get(&entity).copied().unwrap_or(0.0) * acuity as f32;
+                voxel.perception_auditory = f16::from_f32(loudness.min(1.0));
+            }
+        }
+    }
+    
+    /// Language pass, right after `hear_sounds`: with `chance` per tick a
+    /// voxel says something (see `voxel_language::speak`), and neighbors
+    /// within `hearing_radius` add the tone of its words to `perception_auditory`
+    fn converse(&mut self) {
+        let Some(model) = self.language.clone() else {
+            return;
+        };
+        let rules = self.language_rules.clone();
+        let mut rng = rand::thread_rng();
+        let said: Vec<Utterance> = self
+            .iter()
+            .filter(|_| rng.gen_bool(rules.chance.clamp(0.0, 1.0)))
+            .filter_map(|(speaker, voxel)| {
+                Some(Utterance {
+                    tick: self.ticks,
+                    speaker,
+                    position: self.position(speaker)?,
+                    text: voxel_language::speak(&model, voxel, rules.max_tokens),
+                })
+            })
+            .collect();
+        
+        for utterance in said {
+            if let Some(tone) = voxel_language::hear(&model, &utterance.text) {
+                for (listener, _) in self.spatial.neighbors_within(utterance.position, rules.hearing_radius.max(1)) {
+                    if listener == utterance.speaker {
+                        continue;
+                    }
+                    if let Some(mut voxel) = self.world.get_mut::<Voxel>(listener) {
+                        let heard = voxel.perception_auditory.to_f32() + tone;
+                        voxel.perception_auditory = f16::from_f32(heard.clamp(-1.0, 1.0));
+                    }
+                }
+            }
+            while self.utterances.len() >= UTTERANCE_HISTORY {
+                self.utterances.pop_front();
+            }
+            self.utterances.push_back(utterance);
+        }
+    }
+    
+    /// Sensor acuity of `voxel`: its genes' acuity, scaled by its life stage
+    /// when the lifecycle is on
+    fn acuity(&self, voxel: &Voxel) -> f64 {
+        let inherited = voxel.genome.traits().acuity;
+        if !self.lifecycle.enabled {
+            return inherited;
+        }
+        self.lifecycle.traits(voxel.life_stage(&self.lifecycle)).acuity.max(0.0) * inherited
+    }
+    
+    /// Enable the GPU integration path; returns the adapter name or `None` (stays on the CPU)
+    pub fn enable_gpu(&mut self) -> Option<String> {
+        if self.gpu.is_none() {
+            self.gpu = VoxelGpu::new().map(Arc::new);
+        }
+        self.gpu.as_ref().map(|gpu| gpu.adapter_name().to_string())
+    }
+    
+    pub fn disable_gpu(&mut self) {
+        self.gpu = None;
+    }
+    
+    /// Let the voxels talk, building the shared lexicon model on first use
+    pub fn enable_language(&mut self) {
+        if self.language.is_none() {
+            self.language = Some(Arc::new(voxel_language::lexicon_model()));
+        }
+    }
+    
+    pub fn disable_language(&mut self) {
+        self.language = None;
+    }
+    
+    /// Integration step on the GPU; `false` means it has to run on the CPU
+    fn integrate_gpu(&mut self, delta_time: f32) -> bool {
+        let Some(gpu) = self.gpu.clone() else {
+            return false;
+        };
+        if self.voxels.len() < self.gpu_min_voxels {
+            return false;
+        }
+        let mut query = self.world.query::<(&mut Voxel, &mut Position, &Velocity)>();
+        let mut states: Vec<GpuVoxel> = query
+            .iter(&self.world)
+            .map(|(voxel, position, velocity)| GpuVoxel::new(voxel, position.0, velocity.0))
+            .collect();
+        if !gpu.integrate(&mut states, delta_time, self.trauma_mode) {
+            return false;
+        }
+        // Same query state, so the same order as the upload
+        for ((mut voxel, mut position, _), state) in query.iter_mut(&mut self.world).zip(&states) {
+            state.apply(&mut voxel, &mut position);
+        }
+        true
+    }
+    
     pub fn update(&mut self, delta_time: f32) {
-        // Update voxel physics and evolution
-        // Use entity IDs to avoid borrowing issues
-        for &entity in &self.voxels.clone() {
+        self.ticks += 1;
+        self.stream();
+        if !self.integrate_gpu(delta_time) {
+            self.integrate(delta_time);
+        }
+        
+        self.confine();
+        self.resolve_collisions();
+        self.rebuild_index();
+        self.sense_neighbors();
+        self.hear_sounds();
+        self.converse();
+        self.interact();
+        self.apply_environment(delta_time);
+        self.predation(delta_time);
+        self.apply_policies();
+        self.apply_controllers();
+        self.reproduce();
+        self.grow_older(delta_time);
+        self.fade_memories(delta_time);
+        self.bury_dead();
+        self.record_stats();
+    }
+    
+    /// Aging pass: every voxel gets a tick older; with the lifecycle on it
+    /// burns `metabolic_rate` energy per second, scaled by its stage, starves
+    /// once that leaves it without energy and dies past `lifespan`
+    fn grow_older(&mut self, delta_time: f32) {
+        let rules = &self.lifecycle;
+        for &entity in &self.voxels {
             if let Some(mut voxel) = self.world.get_mut::<Voxel>(entity) {
-                // Update physics
-                voxel.position[0] += voxel.velocity_x as i32;
-                voxel.position[1] += voxel.velocity_y as i32;
-                voxel.position[2] += voxel.velocity_z as i32;
-                
-                // Update energy based on resonance
-                voxel.energy += voxel.resonance.to_f32() as f64 * delta_time as f64;
-                
-                // Apply trauma mode intensity
-                if self.trauma_mode {
-                    voxel.energy *= 1.5;
-                    voxel.emotion_arousal *= 1.3;
+                voxel.age_ticks += 1;
+                if rules.enabled {
+                    let metabolism = rules.traits(voxel.life_stage(rules)).metabolism * voxel.genome.traits().metabolism;
+                    let burn = rules.metabolic_rate * metabolism * f64::from(delta_time);
+                    voxel.energy = (voxel.energy - burn.max(0.0)).max(0.0);
+                    let starved = burn > 0.0 && voxel.energy <= 0.0;
+                    let aged_out = rules.lifespan > 0 && voxel.age_ticks >= rules.lifespan;
+                    if starved || aged_out {
+                        self.dying.push(entity);
+                    }
                 }
             }
         }
     }
     
+    /// Removes the voxels that starved or aged out this tick, see `remove_voxel`
+    fn bury_dead(&mut self) {
+        let mut dying = std::mem::take(&mut self.dying);
+        dying.sort_unstable();
+        dying.dedup();
+        for entity in dying {
+            self.remove_voxel(entity);
+        }
+    }
+    
+    /// Forgetting pass: long-term memories fade, see `VoxelMemory::decay`
+    fn fade_memories(&mut self, delta_time: f32) {
+        for &entity in &self.voxels {
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(entity) {
+                voxel.memory.decay(delta_time);
+            }
+        }
+    }
+    
+    /// Streaming pass, before anything moves: only hot chunks are simulated
+    ///
+    /// A chunk is hot while it is in focus (see `StreamingRules::in_focus`) or
+    /// has at least `min_activity` moving voxels. Live chunks that stay cold
+    /// for `cool_down` ticks freeze: their voxels leave the ECS world for
+    /// `chunks` (births and deaths are not counted). Frozen chunks thaw when
+    /// they come into focus or a live voxel wanders in. With streaming off,
+    /// everything thaws.
+    fn stream(&mut self) {
+        if !self.streaming.enabled {
+            if !self.chunks.is_empty() {
+                let keys: Vec<[i32; 3]> = self.chunks.keys().collect();
+                keys.into_iter().for_each(|key| self.thaw_chunk(key));
+                self.quiet_chunks.clear();
+            }
+            return;
+        }
+        let rules = self.streaming.clone();
+        let mut live: HashMap<[i32; 3], (Vec<Entity>, usize)> = HashMap::new();
+        for &entity in &self.voxels {
+            let (Some(position), Some(velocity)) = (self.position(entity), self.velocity(entity)) else {
+                continue;
+            };
+            let (members, moving) = live.entry(chunk_of(position, rules.chunk_size)).or_default();
+            members.push(entity);
+            if velocity != [0; 3] {
+                *moving += 1;
+            }
+        }
+        
+        let waking: Vec<[i32; 3]> = self.chunks
+            .keys()
+            .filter(|&key| rules.in_focus(key) || live.contains_key(&key))
+            .collect();
+        for key in waking {
+            self.thaw_chunk(key);
+        }
+        
+        self.quiet_chunks.retain(|key, _| live.contains_key(key));
+        let mut frozen = HashSet::new();
+        for (key, (members, moving)) in live {
+            if rules.in_focus(key) || moving >= rules.min_activity {
+                self.quiet_chunks.remove(&key);
+                continue;
+            }
+            let quiet = self.quiet_chunks.entry(key).or_default();
+            *quiet += 1;
+            if *quiet < rules.cool_down {
+                continue;
+            }
+            self.quiet_chunks.remove(&key);
+            let voxels: Vec<FrozenVoxel> = members
+                .into_iter()
+                .filter_map(|entity| {
+                    let (voxel, position, velocity) = self.world.get_entity_mut(entity)?.take::<(Voxel, Position, Velocity)>()?;
+                    self.world.despawn(entity);
+                    frozen.insert(entity);
+                    Some((entity.to_bits(), VoxelRecord { position, velocity, voxel }))
+                })
+                .collect();
+            self.chunks.freeze(key, voxels, &rules);
+        }
+        if !frozen.is_empty() {
+            self.voxels.retain(|entity| !frozen.contains(entity));
+        }
+    }
+    
+    /// Bring the voxels of a frozen chunk back, re-tagging memories about them
+    fn thaw_chunk(&mut self, key: [i32; 3]) {
+        let mut renamed = HashMap::new();
+        for (saved, record) in self.chunks.thaw(key) {
+            let position = record.position.0;
+            let entity = self.world.spawn((record.voxel, record.position, record.velocity)).id();
+            self.voxels.push(entity);
+            self.spatial.insert(entity, position);
+            renamed.insert(saved, entity.to_bits());
+        }
+        if renamed.is_empty() {
+            return;
+        }
+        for &entity in &self.voxels {
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(entity) {
+                voxel.memory.retag(|id| Some(renamed.get(&id).copied().unwrap_or(id)));
+            }
+        }
+    }
+    
+    /// Per-tick population statistics, oldest first
+    pub fn stats_history(&self) -> &PopulationHistory {
+        &self.stats_history
+    }
+    
+    /// Sample the population; births and deaths count everything since the previous sample
+    fn record_stats(&mut self) {
+        let (births, deaths) = self.stats_baseline;
+        self.stats_baseline = (self.births, self.deaths);
+        let mut sample = PopulationSample {
+            tick: self.ticks,
+            births: self.births.saturating_sub(births),
+            deaths: self.deaths.saturating_sub(deaths),
+            ..Default::default()
+        };
+        for (_, voxel) in self.iter() {
+            sample.count += 1;
+            sample.mean_energy += voxel.energy;
+            sample.mean_kaif += voxel.kaif();
+            sample.emotions[voxel.dominant_emotion().index()] += 1;
+        }
+        if sample.count > 0 {
+            sample.mean_energy /= sample.count as f64;
+            sample.mean_kaif /= sample.count as f64;
+        }
+        self.stats_history.push(sample);
+    }
+    
+    /// Integration step on the CPU: movement, resonance energy and trauma mode,
+    /// see `voxel_soa::integrate`
+    fn integrate(&mut self, delta_time: f32) {
+        voxel_soa::integrate(&mut self.world, delta_time, self.trauma_mode);
+    }
+    
+    /// Boundary pass, right after movement: voxels that left `bounds` are
+    /// handled according to `boundary`
+    fn confine(&mut self) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+        let mut query = self.world.query::<(&Voxel, &mut Position, &mut Velocity)>();
+        for (voxel, mut cell, mut velocity) in query.iter_mut(&mut self.world) {
+            if bounds.contains(cell.0) {
+                continue;
+            }
+            let position = cell.0;
+            if self.boundary == Boundary::Wrap {
+                cell.0 = [0, 1, 2].map(|axis| {
+                    let (min, max) = (i64::from(bounds.min[axis]), i64::from(bounds.max[axis]));
+                    (min + (i64::from(position[axis]) - min).rem_euclid(max - min + 1)) as i32
+                });
+                continue;
+            }
+            
+            cell.0 = [0, 1, 2].map(|axis| position[axis].clamp(bounds.min[axis], bounds.max[axis]));
+            let hit = [0, 1, 2].map(|axis| cell.0[axis] != position[axis]);
+            let (restitution, grip) = match self.boundary {
+                Boundary::Bounce => (coefficient(voxel.elasticity), coefficient(voxel.friction)),
+                _ => (0.0, 0.0),
+            };
+            let before = velocity.0.map(f64::from);
+            velocity.0 = [0, 1, 2].map(|axis| {
+                to_velocity(before[axis] * if hit[axis] { -restitution } else { 1.0 - grip })
+            });
+        }
+    }
+    
+    /// Collision pass, right after `confine`
+    ///
+    /// Voxels are unit cubes on the lattice. Two voxels in the same cell collide:
+    /// the one that arrived later steps back to where it came from when that
+    /// cell is free (leftover overlaps resolve on later ticks), their relative
+    /// velocity along the axis of impact is reversed and scaled by the mean
+    /// `elasticity`, and the mean `friction` evens out the other axes.
+    fn resolve_collisions(&mut self) {
+        if !self.collisions {
+            return;
+        }
+        
+        let mut occupied: HashMap<[i32; 3], Entity> = HashMap::with_capacity(self.voxels.len());
+        let mut contacts = Vec::new();
+        for &entity in &self.voxels {
+            let Some(position) = self.position(entity) else {
+                continue;
+            };
+            match occupied.entry(position) {
+                Entry::Vacant(slot) => {
+                    slot.insert(entity);
+                }
+                Entry::Occupied(slot) => contacts.push((*slot.get(), entity)),
+            }
+        }
+        
+        for (resting, arriving) in contacts {
+            let (Some(a), Some(b)) = (self.world.get::<Voxel>(resting), self.world.get::<Voxel>(arriving)) else {
+                continue;
+            };
+            let (Some(velocity_a), Some(velocity_b), Some(position)) =
+                (self.velocity(resting), self.velocity(arriving), self.position(arriving))
+            else {
+                continue;
+            };
+            let (va, vb) = (velocity_a.map(f64::from), velocity_b.map(f64::from));
+            let restitution = (coefficient(a.elasticity) + coefficient(b.elasticity)) / 2.0;
+            let grip = (coefficient(a.friction) + coefficient(b.friction)) / 2.0;
+            let came_from = [0, 1, 2].map(|axis| position[axis].saturating_sub(i32::from(velocity_b[axis])));
+            let normal = (0..3)
+                .max_by(|&i, &j| (va[i] - vb[i]).abs().total_cmp(&(va[j] - vb[j]).abs()))
+                .unwrap_or(0);
+            let after = [0, 1, 2].map(|axis| {
+                let mean = (va[axis] + vb[axis]) / 2.0;
+                let half = (va[axis] - vb[axis]) / 2.0;
+                let kept = if axis == normal { -restitution } else { 1.0 - grip };
+                (to_velocity(mean + half * kept), to_velocity(mean - half * kept))
+            });
+            
+            self.set_velocity(resting, after.map(|(a, _)| a));
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(resting) {
+                voxel.memory.store(Episode::new(self.ticks, EpisodeKind::Collision, -0.2, 0.2).about(arriving.to_bits()));
+            }
+            self.set_velocity(arriving, after.map(|(_, b)| b));
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(arriving) {
+                voxel.memory.store(Episode::new(self.ticks, EpisodeKind::Collision, -0.2, 0.2).about(resting.to_bits()));
+            }
+            if let Entry::Vacant(slot) = occupied.entry(came_from) {
+                slot.insert(arriving);
+                if let Some(mut cell) = self.world.get_mut::<Position>(arriving) {
+                    cell.0 = came_from;
+                }
+            }
+            self.sounds.push(Sound::new(SoundKind::Collision, position));
+            self.events.push(self.ticks, WorldEvent::Collided { first: resting, second: arriving, position });
+        }
+    }
+    
+    /// Environment pass: sample the fields into the sensors and eat nutrients
+    ///
+    /// Temperature goes to `perception_thermal` (and the `temperature` property),
+    /// light to `perception_visual`, local nutrients and chemical signals are
+    /// added to `perception_chemical`. Each voxel eats up to `consumption_rate`
+    /// nutrients per second from its cell and emits a signal signed by its
+    /// valence.
+    fn apply_environment(&mut self, delta_time: f32) {
+        if !self.environment.enabled {
+            return;
+        }
+        self.environment.update(delta_time);
+        let environment = &mut self.environment;
+        let bite = environment.consumption_rate * delta_time;
+        let emission = environment.emission_rate * delta_time;
+        
+        let mut query = self.world.query::<(&mut Voxel, &Position)>();
+        for &entity in &self.voxels {
+            let Ok((mut voxel, cell)) = query.get_mut(&mut self.world, entity) else {
+                continue;
+            };
+            let position = cell.0.map(|c| c as f32);
+            let temperature = environment.temperature.sample(position);
+            voxel.perception_thermal = f16::from_f32(temperature);
+            voxel.temperature = (temperature * 100.0).round().clamp(-128.0, 127.0) as i8;
+            voxel.perception_visual = f16::from_f32(environment.light.sample(position));
+            let food = environment.nutrients.sample(position);
+            let signal = environment.signals.sample(position);
+            let chemical = voxel.perception_chemical.to_f32() + food + signal;
+            voxel.perception_chemical = f16::from_f32(chemical.clamp(-1.0, 1.0));
+            
+            // Only prey eat from the field; predators feed on prey
+            if voxel.role() == Role::Prey {
+                let eaten = f64::from(environment.nutrients.take(cell.0, bite)) * environment.food_energy;
+                voxel.energy += eaten;
+                voxel.add_intake(eaten);
+            }
+            let valence = voxel.emotion_valence.clamp(-1.0, 1.0) as f32;
+            environment.signals.add(cell.0, emission * valence);
+        }
+    }
+    
+    /// Predation pass: every predator drains up to `drain_rate` energy per
+    /// second from each prey voxel it touches (distance 1 or less) and gains
+    /// `efficiency` of it; prey drained to zero dies at the end of the tick
+    fn predation(&mut self, delta_time: f32) {
+        let rules = self.predation.clone();
+        if !rules.enabled {
+            return;
+        }
+        let bite = rules.drain_rate.max(0.0) * f64::from(delta_time);
+        let predators: Vec<(Entity, [i32; 3])> = self
+            .iter()
+            .filter(|(_, voxel)| voxel.role() == Role::Predator)
+            .filter_map(|(entity, _)| Some((entity, self.position(entity)?)))
+            .collect();
+        
+        for (predator, position) in predators {
+            let mut drained = 0.0;
+            let mut victims = Vec::new();
+            for (other, _) in self.spatial.neighbors_within(position, 1) {
+                let Some(mut prey) = self.world.get_mut::<Voxel>(other).filter(|voxel| voxel.role() == Role::Prey) else {
+                    continue;
+                };
+                let bitten = prey.energy.clamp(0.0, bite);
+                prey.energy -= bitten;
+                drained += bitten;
+                if bitten > 0.0 {
+                    prey.memory.store(Episode::new(self.ticks, EpisodeKind::Bitten, -0.8, 0.8).about(predator.to_bits()));
+                    if prey.energy <= 0.0 {
+                        self.dying.push(other);
+                    }
+                    victims.push(other);
+                }
+            }
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(predator) {
+                let gained = drained * rules.efficiency.clamp(0.0, 1.0);
+                voxel.energy += gained;
+                voxel.add_intake(gained);
+                for victim in victims {
+                    voxel.memory.store(Episode::new(self.ticks, EpisodeKind::Fed, 0.6, 0.6).about(victim.to_bits()));
+                }
+            }
+        }
+    }
+    
+    /// Behavior pass: every voxel follows its `Voxel::policy`
+    ///
+    /// Foragers accelerate up the nutrient gradient (while the environment is
+    /// on), hungry predators towards the nearest prey within `sense_radius`;
+    /// fleeing voxels accelerate away from the centroid of their neighbors
+    /// within `sense_radius`, clustering ones towards it. Each axis changes by
+    /// at most one velocity step per tick.
+    fn apply_policies(&mut self) {
+        if !self.policies {
+            return;
+        }
+        let hungry_below = self.reproduction.energy_threshold;
+        let radius = self.sense_radius.max(1);
+        
+        for &entity in &self.voxels {
+            let (Some(voxel), Some(position)) = (self.world.get::<Voxel>(entity), self.position(entity)) else {
+                continue;
+            };
+            let direction = match voxel.policy(hungry_below) {
+                Policy::Idle => continue,
+                Policy::Forage if voxel.role() == Role::Predator => {
+                    let prey = self.spatial
+                        .neighbors_within(position, radius)
+                        .into_iter()
+                        .filter(|&(other, _)| self.world.get::<Voxel>(other).is_some_and(|v| v.role() == Role::Prey))
+                        .min_by_key(|&(_, p)| distance_sq(p, position));
+                    let Some((_, target)) = prey else {
+                        continue;
+                    };
+                    [0, 1, 2].map(|axis| (target[axis] - position[axis]).signum() as i8)
+                }
+                Policy::Forage if !self.environment.enabled => continue,
+                Policy::Forage => self.environment
+                    .nutrients
+                    .gradient(position.map(|c| c as f32))
+                    .map(|slope| if slope.abs() < FORAGE_GRADIENT { 0 } else { slope.signum() as i8 }),
+                policy @ (Policy::Flee | Policy::Cluster) => {
+                    let neighbors: Vec<[i32; 3]> = self.spatial
+                        .neighbors_within(position, radius)
+                        .into_iter()
+                        .filter(|&(other, _)| other != entity)
+                        .map(|(_, p)| p)
+                        .collect();
+                    if neighbors.is_empty() {
+                        continue;
+                    }
+                    let away = if policy == Policy::Flee { -1 } else { 1 };
+                    [0, 1, 2].map(|axis| {
+                        let offset = neighbors.iter().map(|p| f64::from(p[axis] - position[axis])).sum::<f64>()
+                            / neighbors.len() as f64;
+                        if offset.abs() < CLUSTER_DEAD_ZONE { 0 } else { away * offset.signum() as i8 }
+                    })
+                }
+            };
+            if let Some(mut velocity) = self.world.get_mut::<Velocity>(entity) {
+                velocity.0 = [0, 1, 2].map(|axis| velocity.0[axis].saturating_add(direction[axis]).clamp(-MAX_SPEED, MAX_SPEED));
+            }
+        }
+    }
+    
+    /// Neural steering pass: each voxel's velocity takes the step its
+    /// genome's controller asks for; voxels without one get a random one, so
+    /// enabling the pass seeds the population for evolution to refine
+    fn apply_controllers(&mut self) {
+        if !self.neural_control {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        let mut query = self.world.query::<(&mut Voxel, &mut Velocity)>();
+        for &entity in &self.voxels {
+            let Ok((mut voxel, mut velocity)) = query.get_mut(&mut self.world, entity) else {
+                continue;
+            };
+            if !voxel.genome.controller.is_active() {
+                voxel.genome.controller = NeuralController::random(&mut rng, 1.0);
+            }
+            let step = voxel.genome.controller.steer(&voxel, velocity.0);
+            velocity.0 = [0, 1, 2].map(|axis| velocity.0[axis].saturating_add(step[axis]).clamp(-MAX_SPEED, MAX_SPEED));
+        }
+    }
+    
+    /// Voxels above the energy and kaif thresholds spawn one child each
+    ///
+    /// The child appears in an adjacent cell and takes `offspring_share` of the
+    /// parent's energy. Its genome is a crossover with the nearest neighbor when
+    /// there is one (otherwise a copy of the parent's), then mutated.
+    /// Returns the number of births.
+    pub fn reproduce(&mut self) -> usize {
+        let rules = self.reproduction.clone();
+        if !rules.enabled {
+            return 0;
+        }
+        let capacity = rules.max_population.saturating_sub(self.voxels.len());
+        let parents: Vec<Entity> = self.voxels
+            .iter()
+            .copied()
+            .filter(|&entity| {
+                self.world.get::<Voxel>(entity).is_some_and(|voxel| {
+                    voxel.energy >= rules.energy_threshold && voxel.kaif() >= rules.kaif_threshold
+                })
+            })
+            .take(capacity)
+            .collect();
+        
+        let mut rng = rand::thread_rng();
+        for &parent in &parents {
+            let Some(position) = self.position(parent) else {
+                continue;
+            };
+            let Some(mut voxel) = self.world.get_mut::<Voxel>(parent) else {
+                continue;
+            };
+            let share = voxel.energy * rules.offspring_share.clamp(0.0, 1.0);
+            voxel.energy -= share;
+            let generation = voxel.generation();
+            let mutation = if self.lifecycle.enabled {
+                self.lifecycle.traits(voxel.life_stage(&self.lifecycle)).mutation
+            } else {
+                1.0
+            };
+            let mut child = Voxel::new();
+            child.energy = share;
+            child.emotion_valence = voxel.emotion_valence;
+            child.emotion_arousal = voxel.emotion_arousal;
+            child.resonance = voxel.resonance;
+            child.genome = voxel.genome.clone();
+            child.set_role(voxel.role());
+            if let Some(island) = voxel.island() {
+                child.set_island(island);
+            }
+            
+            let partner = self.spatial
+                .neighbors_within(position, self.interaction_radius.max(1))
+                .into_iter()
+                .filter(|&(other, _)| other != parent)
+                .min_by_key(|&(_, p)| distance_sq(p, position))
+                .and_then(|(other, _)| self.world.get::<Voxel>(other));
+            if let Some(partner) = partner {
+                child.genome = self.evolution.combine(&child.genome, &partner.genome);
+            }
+            let mut evolution = self.evolution.clone();
+            evolution.mutation_rate = (evolution.mutation_rate * mutation).clamp(0.0, 1.0);
+            evolution.mutate(&mut child.genome);
+            
+            let offset = loop {
+                let offset = [0; 3].map(|_: i32| rng.gen_range(-1..=1));
+                if offset != [0, 0, 0] {
+                    break offset;
+                }
+            };
+            let child_position = [0, 1, 2].map(|axis| position[axis].saturating_add(offset[axis]));
+            child.metadata.insert("generation".to_string(), (generation + 1).to_string());
+            
+            let entity = self.world.spawn((child, Position(child_position), Velocity::default())).id();
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(parent) {
+                voxel.memory.store(Episode::new(self.ticks, EpisodeKind::Birth, 0.8, 0.9).about(entity.to_bits()));
+            }
+            self.voxels.push(entity);
+            self.spatial.insert(entity, child_position);
+            self.sounds.push(Sound::new(SoundKind::Birth, child_position));
+            self.events.push(self.ticks, WorldEvent::Reproduced { parent, child: entity, position: child_position });
+        }
+        self.births += parents.len() as u64;
+        parents.len()
+    }
+    
+    /// One generation of genome evolution over the live population
+    ///
+    /// Voxels are ranked by `EvolutionEngine::scores` and bred by
+    /// `EvolutionEngine::breed`; replaced voxels get their offspring genome
+    /// in place. With islands, `evolve_islands` runs instead. Returns the
+    /// number of replaced genomes.
+    pub fn evolve(&mut self) -> usize {
+        if !self.islands.is_empty() {
+            return self.evolve_islands();
+        }
+        let offspring = {
+            let world = &self.world;
+            let members: Vec<(Entity, &Voxel, [i32; 3])> = self.voxels
+                .iter()
+                .filter_map(|&entity| Some((entity, world.get::<Voxel>(entity)?, world.get::<Position>(entity)?.0)))
+                .collect();
+            let scores = self.evolution.scores(&members.iter().map(|&(_, voxel, position)| (voxel, position)).collect::<Vec<_>>());
+            let population: Vec<(&Genome, f64)> = members
+                .iter()
+                .map(|(_, voxel, _)| &voxel.genome)
+                .zip(scores)
+                .collect();
+            self.evolution
+                .breed(&population)
+                .into_iter()
+                .map(|(i, genome)| (members[i].0, genome))
+                .collect::<Vec<_>>()
+        };
+        for (entity, genome) in &offspring {
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(*entity) {
+                voxel.genome = genome.clone();
+            }
+        }
+        offspring.len()
+    }
+    
+    /// One generation of the island model
+    ///
+    /// Voxels without an island are dealt out round-robin and keep it (their
+    /// offspring inherit it); each island's voxels are evolved on their own
+    /// thread and get their new genomes back in place.
+    pub fn evolve_islands(&mut self) -> usize {
+        let count = self.islands.islands.len();
+        if count == 0 {
+            return 0;
+        }
+        let mut members: Vec<Vec<Entity>> = vec![Vec::new(); count];
+        let mut populations: Vec<Vec<VoxelRecord>> = vec![Vec::new(); count];
+        for (i, &entity) in self.voxels.iter().enumerate() {
+            let (Some(position), Some(velocity)) = (self.position(entity), self.velocity(entity)) else {
+                continue;
+            };
+            let Some(mut voxel) = self.world.get_mut::<Voxel>(entity) else {
+                continue;
+            };
+            let island = match voxel.island() {
+                Some(island) if island < count => island,
+                _ => {
+                    voxel.set_island(i % count);
+                    i % count
+                }
+            };
+            members[island].push(entity);
+            populations[island].push(VoxelRecord { position: Position(position), velocity: Velocity(velocity), voxel: voxel.clone() });
+        }
+        let replaced = self.islands.evolve(&mut populations);
+        for (entities, voxels) in members.iter().zip(populations) {
+            for (&entity, evolved) in entities.iter().zip(voxels) {
+                if let Some(mut voxel) = self.world.get_mut::<Voxel>(entity) {
+                    voxel.genome = evolved.voxel.genome;
+                }
+            }
+        }
+        replaced
+    }
+    
+    /// Distribution of the live voxels' own mutation rates
+    pub fn mutation_rates(&self) -> MutationRateStats {
+        MutationRateStats::of(self.iter().map(|(_, voxel)| &voxel.genome))
+    }
+    
+    /// Voxels on the current Pareto front of energy, kaif and longevity:
+    /// no other live voxel beats them on all three
+    pub fn pareto_front(&self) -> Vec<Entity> {
+        let members: Vec<(Entity, Objectives)> = self.iter().map(|(entity, voxel)| (entity, pareto::objectives(voxel))).collect();
+        let points: Vec<Objectives> = members.iter().map(|&(_, objectives)| objectives).collect();
+        pareto::non_dominated_sort(&points)
+            .first()
+            .map(|front| front.iter().map(|&i| members[i].0).collect())
+            .unwrap_or_default()
+    }
+    
+    /// Interaction pass between nearby voxels
+    ///
+    /// Voxels with same-sign valence attract, opposite-sign ones repel; arousal of
+    /// both sides amplifies the force, which falls off linearly with distance
+    /// and is scaled by the voxel's inherited sociability. Each voxel also receives its neighbors' valence in `perception_chemical`
+    /// and contact pressure from adjacent neighbors in `perception_tactile`.
+    fn interact(&mut self) {
+        let radius = self.interaction_radius.max(1);
+        // Snapshot so the result does not depend on iteration order
+        let states: HashMap<Entity, ([i32; 3], f64, f64, f64)> = self.voxels
+            .iter()
+            .filter_map(|&entity| {
+                let voxel = self.world.get::<Voxel>(entity)?;
+                let valence = voxel.emotion_valence.clamp(-1.0, 1.0);
+                let arousal = voxel.emotion_arousal.clamp(0.0, 1.0);
+                Some((entity, (self.position(entity)?, valence, arousal, voxel.genome.traits().sociability)))
+            })
+            .collect();
+        
+        for &entity in &self.voxels {
+            let Some(&(position, valence, arousal, sociability)) = states.get(&entity) else {
+                continue;
+            };
+            let mut force = [0.0f64; 3];
+            let mut chemical = 0.0;
+            let mut contacts = 0.0;
+            
+            for (other, other_position) in self.spatial.neighbors_within(position, radius) {
+                let Some(&(_, other_valence, other_arousal, _)) = states.get(&other).filter(|_| other != entity) else {
+                    continue;
+                };
+                let distance = (distance_sq(position, other_position) as f64).sqrt();
+                let falloff = 1.0 - distance / f64::from(radius + 1);
+                chemical += other_valence * falloff;
+                if distance <= 1.0 {
+                    contacts += 1.0;
+                }
+                if distance > 0.0 {
+                    // Grudges push away, affinities pull closer
+                    let feeling = self.world
+                        .get_mut::<Voxel>(entity)
+                        .and_then(|mut voxel| voxel.memory.recall_about(other.to_bits()))
+                        .unwrap_or(0.0);
+                    let mood = valence * other_valence * (arousal + other_arousal) / 2.0;
+                    let magnitude = self.interaction_strength * sociability * (mood + feeling) * falloff;
+                    for axis in 0..3 {
+                        force[axis] += f64::from(other_position[axis] - position[axis]) / distance * magnitude;
+                    }
+                }
+            }
+            
+            let acceleration = force.map(|f| (f * FORCE_SCALE).round().clamp(-127.0, 127.0) as i8);
+            if let Some(mut velocity) = self.world.get_mut::<Velocity>(entity) {
+                velocity.0 = [0, 1, 2].map(|axis| {
+                    let change = (f64::from(acceleration[axis]) / FORCE_SCALE).round() as i8;
+                    velocity.0[axis].saturating_add(change).clamp(-MAX_SPEED, MAX_SPEED)
+                });
+            }
+            if let Some(mut voxel) = self.world.get_mut::<Voxel>(entity) {
+                voxel.acceleration_x = acceleration[0];
+                voxel.acceleration_y = acceleration[1];
+                voxel.acceleration_z = acceleration[2];
+                voxel.perception_chemical = f16::from_f64(chemical.clamp(-1.0, 1.0));
+                voxel.perception_tactile = f16::from_f32((contacts / CROWD_SATURATION as f64).min(1.0) as f32);
+            }
+        }
+    }
+    
+    /// Point cloud of the voxels inside `frustum`, colored like `get_point_cloud_data`
+    pub fn visible_points(&self, frustum: &Frustum) -> Vec<([f32; 3], [f32; 3])> {
+        let max_energy = self.voxels
+            .iter()
+            .filter_map(|&entity| self.world.get::<Voxel>(entity))
+            .map(|voxel| voxel.energy)
+            .fold(0.0, f64::max);
+        
+        self.spatial
+            .visible(frustum)
+            .into_iter()
+            .filter_map(|(entity, position)| self.world.get::<Voxel>(entity).map(|voxel| (position, voxel)))
+            .map(|(position, voxel)| (position.map(|c| c as f32), voxel.get_energy_color(max_energy)))
+            .collect()
+    }
+    
     pub fn get_point_cloud_data(&self) -> Vec<([f32; 3], [f32; 3])> {
         let mut points = Vec::new();
         
@@ -200,7 +1781,8 @@ impl VoxelWorld {
         let voxel_data: Vec<([i32; 3], f64)> = self.voxels.iter()
             .filter_map(|&entity| {
                 self.world.get::<Voxel>(entity)
-                    .map(|v| (v.position, v.energy))
+                    .zip(self.position(entity))
+                    .map(|(v, position)| (position, v.energy))
             })
             .collect();
         
@@ -217,7 +1799,7 @@ impl VoxelWorld {
             // Create temporary voxel for color calculation
             let temp_voxel = Voxel {
                 energy,
-                ..Voxel::new([0, 0, 0])
+                ..Voxel::new()
             };
             let color = temp_voxel.get_energy_color(max_energy);
             points.push((pos, color));
@@ -227,8 +1809,896 @@ impl VoxelWorld {
     }
 }
 
+/// Saved world: settings plus every voxel; entities are re-created on load
+#[derive(Serialize)]
+struct WorldSnapshotRef<'a> {
+    version: u32,
+    max_points: usize,
+    trauma_mode: bool,
+    sense_radius: i32,
+    interaction_radius: i32,
+    interaction_strength: f64,
+    reproduction: &'a ReproductionRules,
+    evolution: &'a EvolutionEngine,
+    islands: &'a IslandModel,
+    births: u64,
+    deaths: u64,
+    environment: &'a Environment,
+    collisions: bool,
+    bounds: Option<Aabb>,
+    boundary: Boundary,
+    policies: bool,
+    neural_control: bool,
+    predation: &'a PredationRules,
+    lifecycle: &'a LifecycleRules,
+    language: bool,
+    language_rules: &'a LanguageRules,
+    streaming: &'a StreamingRules,
+    index: IndexKind,
+    /// Saved entity of every voxel, to re-tag social memories on load
+    entities: Vec<u64>,
+    voxels: Vec<VoxelRecord>,
+    /// Voxels of the frozen chunks, with their saved entities
+    frozen: Vec<FrozenVoxel>,
+}
+
+#[derive(Deserialize)]
+struct WorldSnapshot {
+    version: u32,
+    max_points: usize,
+    trauma_mode: bool,
+    sense_radius: i32,
+    interaction_radius: i32,
+    interaction_strength: f64,
+    #[serde(default)]
+    reproduction: ReproductionRules,
+    #[serde(default)]
+    evolution: EvolutionEngine,
+    #[serde(default)]
+    islands: IslandModel,
+    #[serde(default)]
+    births: u64,
+    #[serde(default)]
+    deaths: u64,
+    #[serde(default)]
+    environment: Environment,
+    #[serde(default = "default_true")]
+    collisions: bool,
+    #[serde(default)]
+    bounds: Option<Aabb>,
+    #[serde(default)]
+    boundary: Boundary,
+    #[serde(default = "default_true")]
+    policies: bool,
+    #[serde(default)]
+    neural_control: bool,
+    #[serde(default)]
+    predation: PredationRules,
+    #[serde(default)]
+    lifecycle: LifecycleRules,
+    #[serde(default)]
+    language: bool,
+    #[serde(default)]
+    language_rules: LanguageRules,
+    #[serde(default)]
+    streaming: StreamingRules,
+    index: IndexKind,
+    #[serde(default)]
+    entities: Vec<u64>,
+    voxels: Vec<VoxelRecord>,
+    #[serde(default)]
+    frozen: Vec<FrozenVoxel>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Version of the saved world format
+const WORLD_FORMAT_VERSION: u32 = 1;
+
+impl VoxelWorld {
+    /// Save the world as JSON; written to a temporary file first so a crash
+    /// never leaves a truncated save behind
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let (entities, voxels): (Vec<u64>, Vec<VoxelRecord>) = self
+            .iter()
+            .filter_map(|(entity, voxel)| {
+                let position = Position(self.position(entity)?);
+                let velocity = Velocity(self.velocity(entity)?);
+                Some((entity.to_bits(), VoxelRecord { position, velocity, voxel: voxel.clone() }))
+            })
+            .unzip();
+        let snapshot = WorldSnapshotRef {
+            version: WORLD_FORMAT_VERSION,
+            max_points: self.max_points,
+            trauma_mode: self.trauma_mode,
+            sense_radius: self.sense_radius,
+            interaction_radius: self.interaction_radius,
+            interaction_strength: self.interaction_strength,
+            reproduction: &self.reproduction,
+            evolution: &self.evolution,
+            islands: &self.islands,
+            births: self.births,
+            deaths: self.deaths,
+            environment: &self.environment,
+            collisions: self.collisions,
+            bounds: self.bounds,
+            boundary: self.boundary,
+            policies: self.policies,
+            neural_control: self.neural_control,
+            predation: &self.predation,
+            lifecycle: &self.lifecycle,
+            language: self.language.is_some(),
+            language_rules: &self.language_rules,
+            streaming: &self.streaming,
+            index: self.spatial.kind(),
+            entities,
+            voxels,
+            frozen: self.chunks.snapshot()?,
+        };
+        let path = path.as_ref();
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(&snapshot)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+    
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let data = std::fs::read_to_string(path)?;
+        let snapshot: WorldSnapshot = serde_json::from_str(&data)?;
+        if snapshot.version > WORLD_FORMAT_VERSION {
+            return Err(format!("unsupported world format version {}", snapshot.version).into());
+        }
+        
+        let mut world = Self {
+            max_points: snapshot.max_points,
+            trauma_mode: snapshot.trauma_mode,
+            sense_radius: snapshot.sense_radius,
+            interaction_radius: snapshot.interaction_radius,
+            interaction_strength: snapshot.interaction_strength,
+            reproduction: snapshot.reproduction,
+            evolution: snapshot.evolution,
+            islands: snapshot.islands,
+            births: snapshot.births,
+            deaths: snapshot.deaths,
+            environment: snapshot.environment,
+            collisions: snapshot.collisions,
+            bounds: snapshot.bounds,
+            boundary: snapshot.boundary,
+            policies: snapshot.policies,
+            neural_control: snapshot.neural_control,
+            predation: snapshot.predation,
+            lifecycle: snapshot.lifecycle,
+            language_rules: snapshot.language_rules,
+            streaming: snapshot.streaming,
+            ..Self::new()
+        };
+        if snapshot.language {
+            world.enable_language();
+        }
+        world.stats_baseline = (world.births, world.deaths);
+        world.spatial = match snapshot.index {
+            IndexKind::Grid { cell_size } => SpatialIndex::Grid(SpatialGrid::new(cell_size)),
+            IndexKind::Octree { leaf_size } => SpatialIndex::Octree(Octree::new(leaf_size)),
+        };
+        // Frozen voxels come back live; streaming freezes them again
+        let (frozen_entities, frozen): (Vec<u64>, Vec<VoxelRecord>) = snapshot.frozen.into_iter().unzip();
+        world.voxels = snapshot.voxels
+            .into_iter()
+            .chain(frozen)
+            .map(|record| world.world.spawn((record.voxel, record.position, record.velocity)).id())
+            .collect();
+        let renamed: HashMap<u64, u64> = snapshot.entities
+            .iter()
+            .chain(&frozen_entities)
+            .zip(&world.voxels)
+            .map(|(&saved, entity)| (saved, entity.to_bits()))
+            .collect();
+        for &entity in &world.voxels {
+            if let Some(mut voxel) = world.world.get_mut::<Voxel>(entity) {
+                voxel.memory.retag(|id| renamed.get(&id).copied());
+            }
+        }
+        world.rebuild_index();
+        Ok(world)
+    }
+}
+
 impl Default for VoxelWorld {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::{CONTROLLER_INPUTS, CONTROLLER_WEIGHTS};
+    
+    #[test]
+    fn test_neighbor_queries_and_sensors() {
+        let mut world = VoxelWorld::new();
+        let a = world.add_voxel([0, 0, 0]);
+        let b = world.add_voxel([2, 0, 0]);
+        let far = world.add_voxel([50, 0, 0]);
+        world.set_velocity(b, [1, 0, 0]);
+        
+        // Newly added voxels are queryable before the first tick
+        assert_eq!(world.neighbors_within([0, 0, 0], 2).len(), 2);
+        
+        world.update(0.1);
+        let mut near = world.neighbors_within([0, 0, 0], 3);
+        near.sort();
+        assert_eq!(near, vec![a, b]);
+        
+        let voxel = world.world.get::<Voxel>(a).unwrap();
+        assert_eq!(voxel.perception_other.to_f32(), 1.0 / CROWD_SATURATION);
+        assert_eq!(voxel.perception_space.to_f32(), 0.75);
+        let lonely = world.world.get::<Voxel>(far).unwrap();
+        assert_eq!(lonely.perception_other.to_f32(), 0.0);
+        assert_eq!(lonely.perception_space.to_f32(), 1.0);
+        
+        // The octree answers the same queries and culls the far voxel
+        world.use_octree(1);
+        assert_eq!(world.neighbors_within([0, 0, 0], 3).len(), 2);
+        assert_eq!(world.visible_points(&Frustum::orthographic(0.0, 10.0, 10.0)).len(), 2);
+    }
+    
+    #[test]
+    fn test_interaction_forces_and_signals() {
+        let mut world = VoxelWorld::new();
+        world.interaction_strength = 4.0;
+        let spawn = |world: &mut VoxelWorld, position, valence| {
+            let entity = world.add_voxel(position);
+            let mut voxel = world.world.get_mut::<Voxel>(entity).unwrap();
+            voxel.emotion_valence = valence;
+            voxel.emotion_arousal = 1.0;
+            entity
+        };
+        // Like-minded pair attracts, opposite pair repels
+        let a = spawn(&mut world, [0, 0, 0], 0.9);
+        let b = spawn(&mut world, [2, 0, 0], 0.8);
+        let c = spawn(&mut world, [100, 0, 0], 0.9);
+        let d = spawn(&mut world, [101, 0, 0], -0.9);
+        // Without any mood, a grudge alone pushes away from the offender
+        let e = spawn(&mut world, [200, 0, 0], 0.0);
+        let f = spawn(&mut world, [202, 0, 0], 0.0);
+        world.world.get_mut::<Voxel>(e).unwrap().memory.store(Episode::new(0, EpisodeKind::Bitten, -0.8, 0.8).about(f.to_bits()));
+        
+        world.update(0.0);
+        let velocity = |entity| world.velocity(entity).unwrap()[0];
+        assert!(velocity(a) > 0 && velocity(b) < 0);
+        assert!(velocity(c) < 0 && velocity(d) > 0);
+        assert!(velocity(e) < 0 && velocity(e) < velocity(f));
+        
+        let voxel = world.world.get::<Voxel>(c).unwrap();
+        assert!(voxel.perception_chemical.to_f32() < 0.0);
+        assert!(voxel.perception_tactile.to_f32() > 0.0);
+        assert!(voxel.acceleration_x < 0);
+        let voxel = world.world.get::<Voxel>(a).unwrap();
+        assert!(voxel.perception_chemical.to_f32() > 0.0);
+        assert_eq!(voxel.perception_tactile.to_f32(), 0.0);
+    }
+    
+    #[test]
+    fn test_reproduction() {
+        let mut world = VoxelWorld::new();
+        world.reproduction.enabled = true;
+        world.reproduction.max_population = 3;
+        let parent = world.add_voxel([0, 0, 0]);
+        {
+            let mut voxel = world.world.get_mut::<Voxel>(parent).unwrap();
+            voxel.energy = 4.0;
+            voxel.emotion_valence = 0.8;
+            voxel.emotion_arousal = 1.0;
+            voxel.genome.add_concept("light".to_string());
+        }
+        // Low-energy voxels do not reproduce
+        world.add_voxel([20, 0, 0]);
+        
+        assert_eq!(world.reproduce(), 1);
+        assert_eq!(world.voxels.len(), 3);
+        assert_eq!(world.world.get::<Voxel>(parent).unwrap().energy, 2.0);
+        
+        let child = world.world.get::<Voxel>(world.voxels[2]).unwrap();
+        assert_eq!(child.energy, 2.0);
+        assert_eq!(child.metadata["generation"], "1");
+        assert!((1..=3).contains(&distance_sq(world.position(world.voxels[2]).unwrap(), [0, 0, 0])));
+        assert_eq!(world.neighbors_within([0, 0, 0], 2).len(), 2);
+        assert_eq!(world.sounds.len(), 1);
+        
+        // Population cap reached
+        assert_eq!(world.reproduce(), 0);
+        assert_eq!(world.births, 1);
+        
+        world.clear();
+        assert!(world.voxels.is_empty() && world.spatial.is_empty());
+        assert_eq!(world.reproduction.max_population, 3);
+    }
+    
+    #[test]
+    fn test_environment_sensing_and_foraging() {
+        let mut world = VoxelWorld::new();
+        world.environment = Environment::new(8, 4);
+        world.environment.regrowth_rate = 0.0;
+        // Food grows towards +X
+        world.environment.nutrients.fill_with(|p| (p[0] + 8) as f32 / 16.0);
+        let entity = world.add_voxel([0, 8, 0]);
+        
+        world.update(1.0);
+        let voxel = world.world.get::<Voxel>(entity).unwrap();
+        assert_eq!(voxel.perception_thermal.to_f32(), 1.0);
+        assert_eq!(voxel.temperature, 100);
+        assert_eq!(voxel.perception_visual.to_f32(), 1.0);
+        assert_eq!(voxel.perception_chemical.to_f32(), 0.5);
+        assert_eq!(voxel.energy, 0.5);
+        assert_eq!(world.environment.nutrients.sample([0.0, 8.0, 0.0]), 0.0);
+        // Still hungry: heads up the gradient, away from the cell it just emptied
+        assert_eq!(world.velocity(entity), Some([1, -1, 0]));
+        
+        world.environment.enabled = false;
+        world.update(1.0);
+        assert_eq!(world.position(entity), Some([1, 7, 0]));
+    }
+    
+    #[test]
+    fn test_chemical_signals_reach_distant_voxels() {
+        let mut world = VoxelWorld::new();
+        world.environment = Environment::new(8, 4);
+        world.environment.nutrients.fill_with(|_| 0.0);
+        world.environment.nutrient_capacity.fill_with(|_| 0.0);
+        world.environment.emission_rate = 1.0;
+        let sender = world.add_voxel([-4, 0, 0]);
+        world.world.get_mut::<Voxel>(sender).unwrap().emotion_valence = 1.0;
+        // Two lattice points away, well outside the interaction radius
+        let receiver = world.add_voxel([4, 0, 0]);
+        
+        world.update(1.0);
+        world.update(1.0);
+        assert_eq!(world.world.get::<Voxel>(receiver).unwrap().perception_chemical.to_f32(), 0.0);
+        world.update(1.0);
+        assert!(world.world.get::<Voxel>(receiver).unwrap().perception_chemical.to_f32() > 0.0);
+        assert!(world.environment.signals.sum() > 0.0);
+    }
+    
+    #[test]
+    fn test_sounds_reach_auditory_sensors() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        let near = world.add_voxel([0, 0, 0]);
+        let middle = world.add_voxel([20, 0, 0]);
+        let far = world.add_voxel([200, 0, 0]);
+        let auditory = |world: &VoxelWorld, entity| world.world.get::<Voxel>(entity).unwrap().perception_auditory.to_f32();
+        
+        world.emit_sound(SoundKind::Ping, [0, 0, 0]);
+        world.update(0.1);
+        assert_eq!(auditory(&world, near), 1.0);
+        assert_eq!(auditory(&world, middle), 100.0 / 400.0);
+        assert_eq!(auditory(&world, far), 0.0);
+        assert!(world.sounds.is_empty());
+        
+        // Silence on the next tick
+        world.update(0.1);
+        assert_eq!(auditory(&world, near), 0.0);
+    }
+    
+    #[test]
+    fn test_collisions_and_bounds() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.collisions = true;
+        // Opt-in rules are off by default
+        assert!(!world.reproduction.enabled && !world.predation.enabled && world.bounds.is_none());
+        let spawn = |world: &mut VoxelWorld, position, velocity, elasticity| {
+            let entity = world.add_voxel(position);
+            world.set_velocity(entity, velocity);
+            world.world.get_mut::<Voxel>(entity).unwrap().elasticity = elasticity;
+            entity
+        };
+        // Elastic head-on pair bounces apart, the later one steps back
+        let a = spawn(&mut world, [0, 0, 0], [1, 0, 0], 127);
+        let b = spawn(&mut world, [2, 0, 0], [-1, 0, 0], 127);
+        // Inelastic pair stops dead
+        let c = spawn(&mut world, [100, 0, 0], [2, 0, 0], 0);
+        let d = spawn(&mut world, [104, 0, 0], [-2, 0, 0], 0);
+        
+        world.update(0.1);
+        let voxel = |world: &VoxelWorld, entity| world.world.get::<Voxel>(entity).unwrap().clone();
+        let state = |world: &VoxelWorld, entity| (world.position(entity).unwrap(), world.velocity(entity).unwrap());
+        assert_eq!(state(&world, a), ([1, 0, 0], [-1, 0, 0]));
+        assert_eq!(state(&world, b), ([2, 0, 0], [1, 0, 0]));
+        assert_eq!(state(&world, c), ([102, 0, 0], [0, 0, 0]));
+        assert_eq!(state(&world, d), ([104, 0, 0], [0, 0, 0]));
+        // Both collisions were heard on the spot
+        assert_eq!(voxel(&world, a).perception_auditory.to_f32(), 0.5);
+        assert_eq!(voxel(&world, d).perception_auditory.to_f32(), 0.5 / 4.0);
+        
+        // A wall reflects the normal velocity and keeps the tangential one
+        world.collisions = false;
+        world.bounds = Some(Aabb { min: [-5; 3], max: [5; 3] });
+        world.set_velocity(a, [-3, 1, 0]);
+        world.world.get_mut::<Position>(a).unwrap().0 = [-4, 0, 0];
+        world.update(0.1);
+        assert_eq!(state(&world, a), ([-5, 1, 0], [3, 1, 0]));
+        // Everything outside the box is pulled back in
+        assert_eq!(world.position(c), Some([5, 0, 0]));
+    }
+    
+    #[test]
+    fn test_evolve_live_population() {
+        let mut world = VoxelWorld::new();
+        world.evolution.mutation_rate = 0.0;
+        world.evolution.crossover_rate = 0.0;
+        for (i, energy) in [4.0, 0.0, 3.0, 0.0].into_iter().enumerate() {
+            let entity = world.add_voxel([i as i32 * 10, 0, 0]);
+            let mut voxel = world.world.get_mut::<Voxel>(entity).unwrap();
+            voxel.energy = energy;
+            voxel.genome.add_concept(format!("concept_{}", i));
+        }
+        
+        assert_eq!(world.evolve(), 2);
+        let concepts: Vec<String> = world.voxels
+            .iter()
+            .map(|&entity| world.world.get::<Voxel>(entity).unwrap().genome.concepts[0].clone())
+            .collect();
+        // The fittest keep their genomes, the weakest inherit one of them
+        assert_eq!(concepts[0], "concept_0");
+        assert_eq!(concepts[2], "concept_2");
+        for weak in [&concepts[1], &concepts[3]] {
+            assert!(weak == "concept_0" || weak == "concept_2");
+        }
+        
+        // Two islands: even and odd voxels evolve apart
+        world.islands = IslandModel::spread(2);
+        for island in &mut world.islands.islands {
+            island.engine.mutation_rate = 0.0;
+            island.engine.crossover_rate = 0.0;
+        }
+        assert_eq!(world.evolve(), 2);
+        let islands: Vec<Option<usize>> = world.iter().map(|(_, voxel)| voxel.island()).collect();
+        assert_eq!(islands, [Some(0), Some(1), Some(0), Some(1)]);
+        assert_eq!(world.islands.islands[1].stats.members, 2);
+        world.islands = IslandModel::default();
+        
+        // The richest and the oldest are both on the Pareto front
+        world.world.get_mut::<Voxel>(world.voxels[2]).unwrap().age_ticks = 5;
+        assert_eq!(world.pareto_front(), [world.voxels[0], world.voxels[2]]);
+        
+        world.clear();
+        world.add_voxel([0, 0, 0]);
+        assert_eq!(world.evolve(), 0);
+    }
+    
+    #[test]
+    fn test_neural_control() {
+        let mut world = VoxelWorld::new();
+        world.policies = false;
+        let steered = world.add_voxel([0, 0, 0]);
+        let seeded = world.add_voxel([100, 0, 0]);
+        let mut controller = NeuralController { weights: vec![0.0; CONTROLLER_WEIGHTS] };
+        controller.weights[2 * CONTROLLER_INPUTS + CONTROLLER_INPUTS - 1] = 2.0;
+        world.world.get_mut::<Voxel>(steered).unwrap().genome.controller = controller;
+        
+        world.update(0.1);
+        assert_eq!(world.velocity(steered), Some([0, 0, 0]));
+        assert!(!world.world.get::<Voxel>(seeded).unwrap().genome.controller.is_active());
+        
+        // The bias pushes the steered voxel up; the other gets a random controller
+        world.neural_control = true;
+        world.update(0.1);
+        assert_eq!(world.velocity(steered), Some([0, 0, 1]));
+        assert!(world.world.get::<Voxel>(seeded).unwrap().genome.controller.is_active());
+    }
+    
+    #[test]
+    fn test_world_events() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.collisions = true;
+        let a = world.add_voxel([0, 0, 0]);
+        let b = world.add_voxel([2, 0, 0]);
+        world.set_velocity(b, [-2, 0, 0]);
+        assert_eq!(world.events.iter().next().unwrap().event, WorldEvent::Spawned { entity: a, position: [0, 0, 0] });
+        
+        let cursor = world.events.next_seq();
+        world.update(0.1);
+        let records: Vec<_> = world.events.since(cursor).collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].tick, 1);
+        assert_eq!(records[0].event, WorldEvent::Collided { first: a, second: b, position: [0, 0, 0] });
+        
+        let cursor = world.events.next_seq();
+        assert_eq!(world.remove_voxel(a).unwrap().position, Position([0, 0, 0]));
+        assert!(world.remove_voxel(a).is_none());
+        assert_eq!(world.voxels, [b]);
+        let events: Vec<WorldEvent> = world.events.since(cursor).map(|record| record.event).collect();
+        assert_eq!(events, [WorldEvent::Died { entity: a, position: [0, 0, 0] }]);
+        assert_eq!(world.sounds.last().unwrap().kind, SoundKind::Death);
+        world.update(0.1);
+        assert!(world.world.get::<Voxel>(b).unwrap().perception_auditory.to_f32() > 0.0);
+    }
+    
+    #[test]
+    fn test_voxel_queries() {
+        let mut world = VoxelWorld::new();
+        let spawn = |world: &mut VoxelWorld, position, energy, valence, dominance| {
+            let entity = world.add_voxel(position);
+            let mut voxel = world.world.get_mut::<Voxel>(entity).unwrap();
+            voxel.energy = energy;
+            voxel.emotion_valence = valence;
+            voxel.emotion_dominance = dominance;
+            entity
+        };
+        let happy = spawn(&mut world, [0, 0, 0], 2.0, 0.8, 0.1);
+        let scared = spawn(&mut world, [5, 5, 5], 0.5, 0.2, -0.9);
+        let calm = spawn(&mut world, [-5, 0, 0], 1.0, 0.05, 0.0);
+        let sad = spawn(&mut world, [50, 0, 0], 3.0, -0.6, 0.0);
+        
+        let entities = |found: Vec<(Entity, &Voxel)>| found.into_iter().map(|(entity, _)| entity).collect::<Vec<_>>();
+        assert_eq!(entities(world.voxels_in_box([-5, -1, -1], [5, 5, 5]).collect()), [happy, scared, calm]);
+        assert_eq!(world.voxels_in_box([1, 1, 1], [0, 0, 0]).count(), 0);
+        assert_eq!(entities(world.voxels_by_dominant_emotion(EmotionType::Joy).collect()), [happy]);
+        assert_eq!(entities(world.voxels_by_dominant_emotion(EmotionType::Fear).collect()), [scared]);
+        assert_eq!(entities(world.voxels_by_dominant_emotion(EmotionType::Neutral).collect()), [calm]);
+        assert_eq!(entities(world.voxels_by_dominant_emotion(EmotionType::Sadness).collect()), [sad]);
+        assert_eq!(entities(world.weakest(2).collect()), [scared, calm]);
+        assert_eq!(entities(world.strongest(2).collect()), [sad, happy]);
+        assert_eq!(world.strongest(10).count(), 4);
+    }
+    
+    #[test]
+    fn test_inspect() {
+        let mut voxel = Voxel::new();
+        voxel.energy = 1.5;
+        voxel.emotion_valence = 0.8;
+        voxel.emotion_arousal = 1.0;
+        voxel.perception_auditory = f16::from_f32(0.25);
+        voxel.genome.add_concept("light".to_string());
+        voxel.metadata.insert("generation".to_string(), "4".to_string());
+        
+        let snapshot = voxel.inspect([3, -1, 2], [0, 2, 0]);
+        assert_eq!(snapshot.position, [3, -1, 2]);
+        assert_eq!(snapshot.velocity, [0, 2, 0]);
+        assert_eq!(snapshot.generation, 4);
+        assert_eq!(snapshot.dominant_emotion, EmotionType::Excitement);
+        assert_eq!(snapshot.kaif, 0.8);
+        assert_eq!(snapshot.senses[1], ("auditory", 0.25));
+        assert_eq!(snapshot.genome, ["light"]);
+        
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["dominant_emotion"], "Excitement");
+        assert_eq!(json["energy"], 1.5);
+    }
+    
+    #[test]
+    fn test_update_with_gpu_or_fallback() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.gpu_min_voxels = 0;
+        let entity = world.add_voxel([0, 0, 0]);
+        {
+            world.set_velocity(entity, [2, 0, 0]);
+            world.world.get_mut::<Voxel>(entity).unwrap().resonance = f16::from_f32(0.5);
+        }
+        
+        // Without an adapter the world stays on the CPU; the result is the same
+        match world.enable_gpu() {
+            Some(_) => assert!(world.gpu.is_some()),
+            None => assert!(world.gpu.is_none()),
+        }
+        world.update(0.5);
+        assert_eq!(world.position(entity), Some([2, 0, 0]));
+        assert!((world.world.get::<Voxel>(entity).unwrap().energy - 0.25).abs() < 1e-6);
+        
+        world.disable_gpu();
+        assert!(world.gpu.is_none());
+    }
+    
+    #[test]
+    fn test_movement_policies() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.reproduction.enabled = false;
+        world.interaction_strength = 0.0;
+        let spawn = |world: &mut VoxelWorld, position, valence, dominance| {
+            let entity = world.add_voxel(position);
+            let mut voxel = world.world.get_mut::<Voxel>(entity).unwrap();
+            voxel.energy = 2.0;
+            voxel.emotion_valence = valence;
+            voxel.emotion_dominance = dominance;
+            entity
+        };
+        let scared = spawn(&mut world, [0, 0, 0], 0.0, -0.9);
+        let bystander = spawn(&mut world, [2, 0, 0], 0.0, 0.0);
+        let happy = spawn(&mut world, [100, 0, 0], 0.9, 0.0);
+        spawn(&mut world, [103, 0, 0], 0.0, 0.0);
+        
+        let voxel = |entity| world.world.get::<Voxel>(entity).unwrap().clone();
+        assert_eq!(voxel(scared).policy(1.0), Policy::Flee);
+        assert_eq!(voxel(happy).policy(1.0), Policy::Cluster);
+        assert_eq!(voxel(happy).policy(5.0), Policy::Forage);
+        assert_eq!(voxel(bystander).policy(1.0), Policy::Idle);
+        
+        world.update(0.1);
+        assert_eq!(world.velocity(scared), Some([-1, 0, 0]));
+        assert_eq!(world.velocity(happy), Some([1, 0, 0]));
+        assert_eq!(world.velocity(bystander), Some([0, 0, 0]));
+        
+        world.policies = false;
+        world.update(0.1);
+        assert_eq!(world.velocity(scared), Some([-1, 0, 0]));
+    }
+    
+    #[test]
+    fn test_predators_drain_prey() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.predation.enabled = true;
+        let predator = world.add_voxel([0, 0, 0]);
+        world.world.get_mut::<Voxel>(predator).unwrap().set_role(Role::Predator);
+        let prey = world.add_voxel([1, 0, 0]);
+        world.world.get_mut::<Voxel>(prey).unwrap().energy = 1.0;
+        let distant = world.add_voxel([10, 0, 0]);
+        world.world.get_mut::<Voxel>(distant).unwrap().energy = 1.0;
+        
+        world.update(1.0);
+        let voxel = |entity| world.world.get::<Voxel>(entity).unwrap().clone();
+        assert_eq!(voxel(prey).energy, 0.5);
+        assert_eq!(voxel(distant).energy, 1.0);
+        assert!((voxel(predator).energy - 0.4).abs() < 1e-12);
+        assert!((voxel(predator).intake() - 0.4).abs() < 1e-12);
+        // Still hungry: closes in on the prey
+        assert_eq!(world.velocity(predator), Some([1, 0, 0]));
+        // Both remember the encounter
+        assert_eq!(voxel(predator).memory.summarize().dominant_kind, Some(EpisodeKind::Fed));
+        assert_eq!(voxel(prey).memory.summarize().dominant_kind, Some(EpisodeKind::Bitten));
+        assert!(voxel(distant).memory.episodes.is_empty());
+        
+        // Feeding counts towards fitness
+        let mut fed = voxel(predator);
+        fed.energy = 0.0;
+        let fitness = world.evolution.fitness(&fed);
+        fed.metadata.remove("intake");
+        assert!(fitness > world.evolution.fitness(&fed));
+        
+        // The second bite drains the prey dry: it dies and is removed
+        let cursor = world.events.next_seq();
+        world.update(1.0);
+        assert!(world.world.get::<Voxel>(prey).is_none());
+        assert!(!world.voxels.contains(&prey));
+        assert_eq!(world.deaths, 1);
+        assert_eq!(world.stats_history().latest().unwrap().deaths, 1);
+        let died: Vec<WorldEvent> = world
+            .events
+            .since(cursor)
+            .map(|record| record.event)
+            .filter(|event| matches!(event, WorldEvent::Died { .. }))
+            .collect();
+        assert_eq!(died, [WorldEvent::Died { entity: prey, position: [1, 0, 0] }]);
+    }
+    
+    #[test]
+    fn test_boundary_behaviors() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.collisions = false;
+        world.bounds = Some(Aabb { min: [0; 3], max: [9; 3] });
+        let entity = world.add_voxel([8, 5, 1]);
+        let state = |world: &VoxelWorld| (world.position(entity).unwrap(), world.velocity(entity).unwrap());
+        let launch = |world: &mut VoxelWorld, boundary| {
+            world.boundary = boundary;
+            world.world.get_mut::<Position>(entity).unwrap().0 = [8, 5, 1];
+            world.set_velocity(entity, [3, 1, -2]);
+            world.world.get_mut::<Voxel>(entity).unwrap().elasticity = 127;
+        };
+        
+        launch(&mut world, Boundary::Clamp);
+        world.update(0.1);
+        assert_eq!(state(&world), ([9, 6, 0], [0, 1, 0]));
+        
+        launch(&mut world, Boundary::Bounce);
+        world.update(0.1);
+        assert_eq!(state(&world), ([9, 6, 0], [-3, 1, 2]));
+        
+        launch(&mut world, Boundary::Wrap);
+        world.update(0.1);
+        assert_eq!(state(&world), ([1, 6, 9], [3, 1, -2]));
+    }
+    
+    #[test]
+    fn test_stats_history() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.reproduction.enabled = true;
+        let parent = world.add_voxel([0, 0, 0]);
+        {
+            let mut voxel = world.world.get_mut::<Voxel>(parent).unwrap();
+            voxel.energy = 4.0;
+            voxel.emotion_valence = 0.8;
+            voxel.emotion_arousal = 1.0;
+        }
+        let doomed = world.add_voxel([50, 0, 0]);
+        
+        world.update(0.1);
+        world.remove_voxel(doomed);
+        world.update(0.1);
+        
+        let samples: Vec<_> = world.stats_history().iter().copied().collect();
+        assert_eq!(samples.len(), 2);
+        assert_eq!((samples[0].tick, samples[0].count, samples[0].births, samples[0].deaths), (1, 3, 1, 0));
+        assert_eq!((samples[1].tick, samples[1].deaths), (2, 1));
+        // Parent and child share the parent's excitement, the other voxel is neutral
+        assert_eq!(samples[0].emotions[EmotionType::Excitement.index()], 2);
+        assert_eq!(samples[0].emotions[EmotionType::Neutral.index()], 1);
+        assert!((samples[0].mean_energy - 4.0 / 3.0).abs() < 1e-9);
+        assert!(world.stats_history().to_csv().starts_with("tick,count,births,deaths"));
+        
+        world.clear();
+        assert!(world.stats_history().is_empty());
+    }
+    
+    #[test]
+    fn test_language() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.reproduction.enabled = false;
+        let speaker = world.add_voxel([0, 0, 0]);
+        world.world.get_mut::<Voxel>(speaker).unwrap().emotion_valence = 0.9;
+        let listener = world.add_voxel([2, 0, 0]);
+        let far = world.add_voxel([50, 0, 0]);
+        world.update(0.1);
+        // Silent until enabled
+        assert!(world.utterances.is_empty());
+        
+        world.enable_language();
+        world.language_rules.chance = 1.0;
+        world.language_rules.max_tokens = 0;
+        world.update(0.1);
+        assert_eq!(world.utterances.len(), 3);
+        let said = world.utterances.iter().find(|u| u.speaker == speaker).unwrap();
+        assert_eq!(said.text, "joy");
+        
+        let auditory = |entity| world.world.get::<Voxel>(entity).unwrap().perception_auditory.to_f32();
+        assert!((auditory(listener) - 0.8).abs() < 0.01);
+        assert_eq!(auditory(far), 0.0);
+        
+        world.clear();
+        assert!(world.utterances.is_empty());
+    }
+    
+    #[test]
+    fn test_lifecycle_stages() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.reproduction.enabled = false;
+        world.lifecycle = LifecycleRules {
+            enabled: true,
+            adult_at: 2,
+            elder_at: 3,
+            metabolic_rate: 1.0,
+            ..LifecycleRules::default()
+        };
+        let entity = world.add_voxel([0, 0, 0]);
+        let neighbor = world.add_voxel([3, 0, 0]);
+        world.world.get_mut::<Voxel>(entity).unwrap().energy = 10.0;
+        world.world.get_mut::<Voxel>(neighbor).unwrap().energy = 10.0;
+        let voxel = |world: &VoxelWorld| world.world.get::<Voxel>(entity).unwrap().clone();
+        
+        // A juvenile burns 1.5x and senses half as far: the neighbor at 3 is out of reach
+        world.update(0.1);
+        assert!((voxel(&world).energy - 9.85).abs() < 1e-6);
+        assert_eq!(voxel(&world).perception_other.to_f32(), 0.0);
+        assert_eq!(voxel(&world).life_stage(&world.lifecycle), LifeStage::Juvenile);
+        
+        world.update(0.1);
+        assert_eq!(voxel(&world).age_ticks, 2);
+        assert_eq!(voxel(&world).life_stage(&world.lifecycle), LifeStage::Adult);
+        // Senses come before aging: the new adult senses the full radius on the next tick
+        world.update(0.1);
+        assert_eq!(voxel(&world).life_stage(&world.lifecycle), LifeStage::Elder);
+        assert!(voxel(&world).perception_other.to_f32() > 0.0);
+        assert!((voxel(&world).energy - (9.85 - 0.1 - 0.07)).abs() < 1e-6);
+        assert_eq!(world.inspect(entity).unwrap().age_ticks, 3);
+        
+        // Old age
+        world.lifecycle.lifespan = 4;
+        world.update(0.1);
+        assert!(world.world.get::<Voxel>(entity).is_none());
+        assert_eq!(world.deaths, 2);
+    }
+    
+    #[test]
+    fn test_chunk_streaming() {
+        let mut world = VoxelWorld::new();
+        world.environment.enabled = false;
+        world.reproduction.enabled = false;
+        world.streaming = StreamingRules {
+            enabled: true,
+            chunk_size: 16,
+            focus: Some([0, 0, 0]),
+            focus_radius: 10,
+            cool_down: 2,
+            ..StreamingRules::default()
+        };
+        let near = world.add_voxel([1, 0, 0]);
+        let far = world.add_voxel([100, 0, 0]);
+        world.world.get_mut::<Voxel>(far).unwrap().energy = 0.7;
+        world.world.get_mut::<Voxel>(near).unwrap().memory.store(Episode::new(0, EpisodeKind::Collision, -0.2, 0.2).about(far.to_bits()));
+        
+        // The quiet chunk out of focus freezes after the cool-down
+        world.update(0.1);
+        assert_eq!(world.voxels.len(), 2);
+        world.update(0.1);
+        assert_eq!(world.voxels, [near]);
+        assert_eq!((world.chunks.len(), world.chunks.voxel_count()), (1, 1));
+        
+        // Frozen voxels are saved with the world
+        let path = std::env::temp_dir().join(format!("voxel_chunks_world_{}.json", std::process::id()));
+        world.save(&path).unwrap();
+        let loaded = VoxelWorld::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.voxels.len(), 2);
+        
+        // Moving the focus thaws it; memories about it follow the new entity
+        world.streaming.focus = Some([100, 0, 0]);
+        world.update(0.1);
+        assert_eq!(world.voxels.len(), 2);
+        assert!(world.chunks.is_empty());
+        let thawed = world.voxels[1];
+        assert_eq!(world.world.get::<Voxel>(thawed).unwrap().energy, 0.7);
+        let memory = &world.world.get::<Voxel>(near).unwrap().memory;
+        assert_eq!(memory.episodes[0].about, Some(thawed.to_bits()));
+        
+        // Turning streaming off thaws everything
+        world.update(0.1);
+        world.update(0.1);
+        assert_eq!(world.voxels, [thawed]);
+        world.streaming.enabled = false;
+        world.update(0.1);
+        assert_eq!(world.voxels.len(), 2);
+    }
+    
+    #[test]
+    fn test_save_and_load() {
+        let mut world = VoxelWorld::new();
+        // A freed slot, so the saved entities differ from the loaded ones
+        let gone = world.add_voxel([9, 9, 9]);
+        world.remove_voxel(gone);
+        world.trauma_mode = true;
+        world.reproduction.kaif_threshold = 0.7;
+        world.use_octree(4);
+        let entity = world.add_voxel([1, -2, 3]);
+        {
+            let mut voxel = world.world.get_mut::<Voxel>(entity).unwrap();
+            voxel.energy = 0.25;
+            voxel.resonance = f16::from_f32(0.5);
+            voxel.genome.add_concept("echo".to_string());
+            voxel.metadata.insert("generation".to_string(), "2".to_string());
+        }
+        world.set_velocity(entity, [0, 0, -1]);
+        let friend = world.add_voxel([40, 0, 0]);
+        world.world.get_mut::<Voxel>(entity).unwrap().memory.store(Episode::new(1, EpisodeKind::Fed, 0.6, 0.6).about(friend.to_bits()));
+        
+        let path = std::env::temp_dir().join(format!("voxel_world_{}.json", std::process::id()));
+        world.save(&path).unwrap();
+        let loaded = VoxelWorld::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert!(loaded.trauma_mode);
+        assert_eq!(loaded.reproduction, world.reproduction);
+        assert_eq!(loaded.spatial.kind(), IndexKind::Octree { leaf_size: 4 });
+        assert_eq!(loaded.voxels.len(), 2);
+        assert_eq!(loaded.neighbors_within([1, -2, 3], 0).len(), 1);
+        
+        let voxel = loaded.world.get::<Voxel>(loaded.voxels[0]).unwrap();
+        assert_eq!(loaded.position(loaded.voxels[0]), Some([1, -2, 3]));
+        assert_eq!(loaded.velocity(loaded.voxels[0]), Some([0, 0, -1]));
+        assert_eq!(voxel.energy, 0.25);
+        assert_eq!(voxel.resonance.to_f32(), 0.5);
+        assert_eq!(voxel.genome.concepts, ["echo"]);
+        assert_eq!(voxel.metadata["generation"], "2");
+        // Social memories follow the re-created entities
+        assert_eq!(voxel.memory.episodes[0].about, Some(loaded.voxels[1].to_bits()));
+    }
+}
@@ -0,0 +1,290 @@
+//! GPU compute path for the integration step of `VoxelWorld::update`
+//!
+//! Movement, resonance energy and trauma mode run in `shaders/voxel_update.wgsl`
+//! with one invocation per voxel, in f32. Without the `gpu` feature the module
+//! builds as a stub: no adapter is found and the world integrates on the CPU.
+
+use crate::voxel::Voxel;
+use crate::voxel_soa::Position;
+
+/// Voxel state uploaded to the GPU; matches `VoxelState` in the shader
+/// (48 bytes: vec3 members are 16-byte aligned)
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GpuVoxel {
+    pub position: [i32; 3],
+    pub energy: f32,
+    pub velocity: [i32; 3],
+    pub resonance: f32,
+    pub arousal: f32,
+    _pad: [f32; 3],
+}
+
+impl GpuVoxel {
+    pub fn new(voxel: &Voxel, position: [i32; 3], velocity: [i8; 3]) -> Self {
+        Self {
+            position,
+            energy: voxel.energy as f32,
+            velocity: velocity.map(i32::from),
+            resonance: voxel.resonance.to_f32(),
+            arousal: voxel.emotion_arousal as f32,
+            _pad: [0.0; 3],
+        }
+    }
+
+    /// Copy the integrated state back into the voxel
+    pub fn apply(&self, voxel: &mut Voxel, position: &mut Position) {
+        position.0 = self.position;
+        voxel.energy = f64::from(self.energy);
+        voxel.emotion_arousal = f64::from(self.arousal);
+    }
+}
+
+/// GPU context for voxel updates
+pub struct VoxelGpu {
+    adapter_name: String,
+    #[cfg(feature = "gpu")]
+    device: wgpu::Device,
+    #[cfg(feature = "gpu")]
+    queue: wgpu::Queue,
+    #[cfg(feature = "gpu")]
+    layout: wgpu::BindGroupLayout,
+    #[cfg(feature = "gpu")]
+    integrate: wgpu::ComputePipeline,
+    #[cfg(feature = "gpu")]
+    max_buffer_size: u64,
+}
+
+impl VoxelGpu {
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+}
+
+#[cfg(not(feature = "gpu"))]
+impl VoxelGpu {
+    /// Without the `gpu` feature no adapter is ever found
+    pub fn new() -> Option<Self> {
+        None
+    }
+
+    /// Unavailable without the `gpu` feature: always `false` (integrate on the CPU)
+    pub fn integrate(&self, _voxels: &mut [GpuVoxel], _delta_time: f32, _trauma: bool) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "gpu")]
+impl VoxelGpu {
+    const WORKGROUP_SIZE: usize = 64;
+
+    /// Find an adapter and compile the shader; `None` when no GPU is available
+    pub fn new() -> Option<Self> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))?;
+
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("VoxelWorld compute"),
+                required_features: wgpu::Features::empty(),
+                required_limits: adapter.limits(),
+            },
+            None,
+        ))
+        .ok()?;
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("voxel_update.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/voxel_update.wgsl").into()),
+        });
+
+        let buffer_entry = |binding, ty| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("VoxelWorld compute layout"),
+            entries: &[
+                buffer_entry(0, wgpu::BufferBindingType::Uniform),
+                buffer_entry(1, wgpu::BufferBindingType::Storage { read_only: false }),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("VoxelWorld compute pipeline layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let integrate = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("integrate"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: "integrate",
+        });
+
+        let max_buffer_size = device.limits().max_storage_buffer_binding_size as u64;
+        Some(Self {
+            adapter_name: adapter.get_info().name,
+            device,
+            queue,
+            layout,
+            integrate,
+            max_buffer_size,
+        })
+    }
+
+    /// One integration step over `voxels` in place
+    ///
+    /// Returns `false` (nothing changed) when the batch is empty, does not fit
+    /// the device limits or the readback fails.
+    pub fn integrate(&self, voxels: &mut [GpuVoxel], delta_time: f32, trauma: bool) -> bool {
+        use wgpu::util::DeviceExt;
+
+        let size = std::mem::size_of_val(voxels) as u64;
+        if voxels.is_empty() || size > self.max_buffer_size {
+            return false;
+        }
+
+        let params: Vec<u8> = [delta_time.to_bits(), trauma as u32, voxels.len() as u32, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let params = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: &params,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let states = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("voxels"),
+            contents: &to_bytes(voxels),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("VoxelWorld compute bind group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: params.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: states.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("integrate") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("integrate"),
+                timestamp_writes: None,
+            });
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.set_pipeline(&self.integrate);
+            pass.dispatch_workgroups(voxels.len().div_ceil(Self::WORKGROUP_SIZE) as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&states, 0, &readback, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        if !matches!(receiver.recv(), Ok(Ok(()))) {
+            return false;
+        }
+
+        for (voxel, bytes) in voxels.iter_mut().zip(slice.get_mapped_range().chunks_exact(std::mem::size_of::<GpuVoxel>())) {
+            *voxel = from_bytes(bytes);
+        }
+        readback.unmap();
+        true
+    }
+}
+
+#[cfg(feature = "gpu")]
+fn to_bytes(voxels: &[GpuVoxel]) -> Vec<u8> {
+    voxels
+        .iter()
+        .flat_map(|voxel| {
+            let words = [
+                voxel.position[0] as u32,
+                voxel.position[1] as u32,
+                voxel.position[2] as u32,
+                voxel.energy.to_bits(),
+                voxel.velocity[0] as u32,
+                voxel.velocity[1] as u32,
+                voxel.velocity[2] as u32,
+                voxel.resonance.to_bits(),
+                voxel.arousal.to_bits(),
+                0,
+                0,
+                0,
+            ];
+            words.into_iter().flat_map(u32::to_le_bytes)
+        })
+        .collect()
+}
+
+#[cfg(feature = "gpu")]
+fn from_bytes(bytes: &[u8]) -> GpuVoxel {
+    let word = |i: usize| u32::from_le_bytes([bytes[i * 4], bytes[i * 4 + 1], bytes[i * 4 + 2], bytes[i * 4 + 3]]);
+    GpuVoxel {
+        position: [word(0) as i32, word(1) as i32, word(2) as i32],
+        energy: f32::from_bits(word(3)),
+        velocity: [word(4) as i32, word(5) as i32, word(6) as i32],
+        resonance: f32::from_bits(word(7)),
+        arousal: f32::from_bits(word(8)),
+        _pad: [0.0; 3],
+    }
+}
+
+#[cfg(all(test, feature = "gpu"))]
+mod tests {
+    use super::*;
+    use crate::voxel_soa::{self, Velocity};
+    use bevy_ecs::prelude::*;
+    use half::f16;
+
+    #[test]
+    fn test_gpu_integration_matches_cpu() {
+        // Nothing to check without an adapter (CI, headless)
+        let Some(gpu) = VoxelGpu::new() else { return };
+
+        let mut world = World::new();
+        let mut state = Vec::new();
+        let entities: Vec<Entity> = (0..100)
+            .map(|i| {
+                let mut voxel = Voxel::new();
+                voxel.energy = f64::from(i) * 0.1;
+                voxel.resonance = f16::from_f32(0.5);
+                voxel.emotion_arousal = 0.4;
+                let (position, velocity) = ([i, -i, 7], [1, -2, (i % 3) as i8 - 1]);
+                state.push(GpuVoxel::new(&voxel, position, velocity));
+                world.spawn((voxel, Position(position), Velocity(velocity))).id()
+            })
+            .collect();
+        voxel_soa::integrate(&mut world, 0.1, true);
+
+        assert!(gpu.integrate(&mut state, 0.1, true));
+        for (entity, gpu) in entities.into_iter().zip(&state) {
+            let voxel = world.get::<Voxel>(entity).unwrap();
+            assert_eq!(world.get::<Position>(entity), Some(&Position(gpu.position)));
+            assert!((f64::from(gpu.energy) - voxel.energy).abs() < 1e-5);
+            assert!((f64::from(gpu.arousal) - voxel.emotion_arousal).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Voxel "language": short utterances generated by a tiny shared `AIModel`
+//!
+//! The model knows only `LEXICON`, words for emotions and remembered
+//! episodes, each with a tone. A speaking voxel says its dominant emotion and
+//! what it remembers most, and the model babbles on for a few tokens;
+//! listeners tokenize what they hear and feel the mean tone of the words.
+
+use crate::ai_model::{AIModel, AIModelBuilder, ActivationType, GenerationConfig, Vocabulary};
+use crate::tokenizer::SPECIAL_TOKENS;
+use crate::voxel::Voxel;
+use crate::voxel_memory::EpisodeKind;
+use bevy_ecs::entity::Entity;
+use serde::{Deserialize, Serialize};
+
+/// Default number of utterances kept by the world
+pub const UTTERANCE_HISTORY: usize = 32;
+
+/// Every word a voxel can say, with its tone in -1..1
+pub const LEXICON: [(&str, f32); 11] = [
+    ("neutral", 0.0),
+    ("joy", 0.8),
+    ("sadness", -0.6),
+    ("excitement", 0.4),
+    ("calm", 0.2),
+    ("confidence", 0.5),
+    ("fear", -0.8),
+    ("collision", -0.2),
+    ("fed", 0.6),
+    ("bitten", -0.8),
+    ("birth", 0.8),
+];
+
+/// Tone of a lexicon word; `None` for anything else
+pub fn tone(word: &str) -> Option<f32> {
+    LEXICON.iter().find(|(known, _)| *known == word).map(|&(_, tone)| tone)
+}
+
+/// Untrained model over `LEXICON`: small enough to run for many voxels per tick
+pub fn lexicon_model() -> AIModel {
+    let tokens = SPECIAL_TOKENS
+        .iter()
+        .chain(LEXICON.iter().map(|(word, _)| word))
+        .map(|word| word.to_string())
+        .collect();
+    AIModelBuilder::new()
+        .embedding_dim(8)
+        .context_length(4)
+        .hidden_layer(16, ActivationType::Tanh)
+        .vocabulary(Vocabulary { tokens, tokenizer: None })
+        .build()
+        .expect("lexicon model dimensions are valid")
+}
+
+/// What a voxel starts saying: its dominant emotion, then the kind of episode
+/// it remembers most
+pub fn prompt(voxel: &Voxel) -> String {
+    let emotion = format!("{:?}", voxel.dominant_emotion()).to_lowercase();
+    match voxel.memory.summarize().dominant_kind {
+        Some(kind) => format!("{} {}", emotion, episode_word(kind)),
+        None => emotion,
+    }
+}
+
+fn episode_word(kind: EpisodeKind) -> &'static str {
+    match kind {
+        EpisodeKind::Collision => "collision",
+        EpisodeKind::Fed => "fed",
+        EpisodeKind::Bitten => "bitten",
+        EpisodeKind::Birth => "birth",
+    }
+}
+
+/// `prompt` followed by up to `max_tokens` words from `model`; anything
+/// outside the lexicon is dropped
+pub fn speak(model: &AIModel, voxel: &Voxel, max_tokens: usize) -> String {
+    let prompt = prompt(voxel);
+    if max_tokens == 0 {
+        return prompt;
+    }
+    let config = GenerationConfig {
+        temperature: 1.0,
+        top_k: 0,
+        top_p: 1.0,
+        max_tokens,
+        ..GenerationConfig::default()
+    };
+    let babble = model.generate(&prompt, &config);
+    std::iter::once(prompt.as_str())
+        .chain(babble.split_whitespace().filter(|word| tone(word).is_some()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Mean tone of the words `model` recognizes in `text`; `None` when there are none
+pub fn hear(model: &AIModel, text: &str) -> Option<f32> {
+    let tones: Vec<f32> = model
+        .tokenize(text)
+        .iter()
+        .filter_map(|token| model.reverse_vocab.get(token))
+        .filter_map(|word| tone(word.as_str()))
+        .collect();
+    (!tones.is_empty()).then(|| tones.iter().sum::<f32>() / tones.len() as f32)
+}
+
+/// How often voxels talk and how far they are heard
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanguageRules {
+    /// Chance per voxel and tick to say something
+    pub chance: f64,
+    /// Neighbors within this radius hear it
+    pub hearing_radius: i32,
+    /// Words the model may add after the prompt
+    pub max_tokens: usize,
+}
+
+impl Default for LanguageRules {
+    fn default() -> Self {
+        Self {
+            chance: 0.05,
+            hearing_radius: 4,
+            max_tokens: 2,
+        }
+    }
+}
+
+/// Something a voxel said
+#[derive(Clone, Debug, PartialEq)]
+pub struct Utterance {
+    pub tick: u64,
+    pub speaker: Entity,
+    pub position: [i32; 3],
+    pub text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel_memory::Episode;
+    
+    #[test]
+    fn test_speak_and_hear() {
+        let model = lexicon_model();
+        let mut voxel = Voxel::new();
+        voxel.emotion_dominance = -0.9;
+        assert_eq!(prompt(&voxel), "fear");
+        voxel.memory.store(Episode::new(1, EpisodeKind::Bitten, -0.8, 0.8));
+        assert_eq!(speak(&model, &voxel, 0), "fear bitten");
+        
+        let said = speak(&model, &voxel, 3);
+        assert!(said.starts_with("fear bitten"));
+        assert!(said.split_whitespace().all(|word| tone(word).is_some()));
+        
+        assert_eq!(hear(&model, "fear bitten"), Some(-0.8));
+        assert!((hear(&model, "joy sadness").unwrap() - 0.1).abs() < 1e-6);
+        assert_eq!(hear(&model, "<PAD> hello"), None);
+    }
+}
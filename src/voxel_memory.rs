@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Default number of recent episodes a voxel keeps
+pub const EPISODE_CAPACITY: usize = 16;
+/// Default number of consolidated long-term memories
+pub const LONG_TERM_CAPACITY: usize = 32;
+
+/// What a remembered episode was about
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EpisodeKind {
+    /// Bumped into another voxel
+    Collision,
+    /// Drained energy from prey
+    Fed,
+    /// Lost energy to a predator
+    Bitten,
+    /// Gave birth
+    Birth,
+}
+
+/// One remembered experience
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Episode {
+    pub tick: u64,
+    pub kind: EpisodeKind,
+    /// How it felt, -1..1
+    pub valence: f64,
+    /// How much it mattered, 0..1
+    pub importance: f64,
+    /// Long-term strength: starts at `importance`, fades unless recalled
+    pub strength: f64,
+    pub recalls: u32,
+    /// Voxel the episode was about (`Entity::to_bits`), if any
+    #[serde(default)]
+    pub about: Option<u64>,
+}
+
+impl Episode {
+    pub fn new(tick: u64, kind: EpisodeKind, valence: f64, importance: f64) -> Self {
+        let importance = importance.clamp(0.0, 1.0);
+        Self {
+            tick,
+            kind,
+            valence: valence.clamp(-1.0, 1.0),
+            importance,
+            strength: importance,
+            recalls: 0,
+            about: None,
+        }
+    }
+    
+    /// Tag the episode with the voxel it was about
+    pub fn about(mut self, other: u64) -> Self {
+        self.about = Some(other);
+        self
+    }
+}
+
+/// Episodic memory of a voxel
+///
+/// Recent episodes live in a FIFO of `capacity`. An episode pushed out of it
+/// is consolidated into `long_term` when its importance reaches
+/// `consolidation_threshold`, otherwise forgotten. Long-term memories lose
+/// `forgetting_rate` strength per second, slowed down by every recall, and
+/// are dropped once they fade out or when the weakest must make room.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoxelMemory {
+    pub episodes: VecDeque<Episode>,
+    pub long_term: Vec<Episode>,
+    pub capacity: usize,
+    pub long_term_capacity: usize,
+    pub consolidation_threshold: f64,
+    pub forgetting_rate: f64,
+}
+
+impl VoxelMemory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            episodes: VecDeque::new(),
+            long_term: Vec::new(),
+            capacity: capacity.max(1),
+            long_term_capacity: LONG_TERM_CAPACITY,
+            consolidation_threshold: 0.5,
+            forgetting_rate: 0.01,
+        }
+    }
+    
+    pub fn store(&mut self, episode: Episode) {
+        while self.episodes.len() >= self.capacity.max(1) {
+            if let Some(evicted) = self.episodes.pop_front() {
+                self.consolidate_episode(evicted);
+            }
+        }
+        self.episodes.push_back(episode);
+    }
+    
+    fn consolidate_episode(&mut self, episode: Episode) {
+        if episode.importance < self.consolidation_threshold {
+            return;
+        }
+        if self.long_term.len() >= self.long_term_capacity {
+            let weakest = self.long_term
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| a.strength.total_cmp(&b.strength))
+                .map(|(i, weakest)| (i, weakest.strength));
+            match weakest {
+                Some((i, strength)) if strength < episode.strength => {
+                    self.long_term.swap_remove(i);
+                }
+                _ => return,
+            }
+        }
+        self.long_term.push(episode);
+    }
+    
+    /// Fade long-term memories by `forgetting_rate` per second (divided by
+    /// one plus the number of recalls) and drop those that faded out
+    pub fn decay(&mut self, delta_time: f32) {
+        if self.long_term.is_empty() {
+            return;
+        }
+        let fade = self.forgetting_rate * f64::from(delta_time);
+        for memory in &mut self.long_term {
+            memory.strength -= fade / f64::from(1 + memory.recalls);
+        }
+        self.long_term.retain(|memory| memory.strength > 0.0);
+    }
+    
+    /// Strongest memory of `kind`, recent or long-term; recalling a long-term
+    /// memory slows down its forgetting
+    pub fn recall(&mut self, kind: EpisodeKind) -> Option<&Episode> {
+        let recent = self.episodes.iter().rposition(|episode| episode.kind == kind);
+        let remembered = self.long_term
+            .iter()
+            .enumerate()
+            .filter(|(_, memory)| memory.kind == kind)
+            .max_by(|(_, a), (_, b)| a.strength.total_cmp(&b.strength))
+            .map(|(i, _)| i);
+        match (recent, remembered) {
+            (_, Some(i)) => {
+                let memory = &mut self.long_term[i];
+                memory.recalls += 1;
+                Some(memory)
+            }
+            (Some(i), None) => self.episodes.get(i),
+            (None, None) => None,
+        }
+    }
+    
+    /// Feeling about voxel `other` in -1..1: valence of the episodes about it,
+    /// weighted by importance (strength for long-term memories); `None` for
+    /// a stranger. Counts as a recall of the long-term memories involved.
+    pub fn recall_about(&mut self, other: u64) -> Option<f64> {
+        let mut weight = 0.0;
+        let mut feeling = 0.0;
+        for episode in self.episodes.iter().filter(|episode| episode.about == Some(other)) {
+            weight += episode.importance;
+            feeling += episode.valence * episode.importance;
+        }
+        for memory in self.long_term.iter_mut().filter(|memory| memory.about == Some(other)) {
+            memory.recalls += 1;
+            weight += memory.strength;
+            feeling += memory.valence * memory.strength;
+        }
+        (weight > 0.0).then(|| feeling / weight)
+    }
+    
+    /// Rewrite the `about` tags through `map`, dropping tags it returns `None` for
+    pub fn retag(&mut self, map: impl Fn(u64) -> Option<u64>) {
+        for episode in self.episodes.iter_mut().chain(self.long_term.iter_mut()) {
+            episode.about = episode.about.and_then(&map);
+        }
+    }
+    
+    /// The `n` most important episodes, recent and long-term together
+    pub fn top_episodes(&self, n: usize) -> Vec<Episode> {
+        let mut all: Vec<Episode> = self.episodes.iter().chain(&self.long_term).cloned().collect();
+        all.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+        all.truncate(n);
+        all
+    }
+    
+    pub fn summarize(&self) -> MemorySummary {
+        let all = || self.episodes.iter().chain(&self.long_term);
+        let total = self.episodes.len() + self.long_term.len();
+        let mean_valence = if total == 0 {
+            0.0
+        } else {
+            all().map(|episode| episode.valence).sum::<f64>() / total as f64
+        };
+        let dominant_kind = [EpisodeKind::Collision, EpisodeKind::Fed, EpisodeKind::Bitten, EpisodeKind::Birth]
+            .into_iter()
+            .map(|kind| (kind, all().filter(|episode| episode.kind == kind).count()))
+            .filter(|&(_, count)| count > 0)
+            .max_by_key(|&(_, count)| count)
+            .map(|(kind, _)| kind);
+        MemorySummary {
+            recent: self.episodes.len(),
+            long_term: self.long_term.len(),
+            mean_valence,
+            dominant_kind,
+            strongest: self.long_term.iter().max_by(|a, b| a.strength.total_cmp(&b.strength)).cloned(),
+        }
+    }
+}
+
+impl Default for VoxelMemory {
+    fn default() -> Self {
+        Self::new(EPISODE_CAPACITY)
+    }
+}
+
+/// Overview of a voxel's memory, see `VoxelMemory::summarize`
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MemorySummary {
+    pub recent: usize,
+    pub long_term: usize,
+    pub mean_valence: f64,
+    /// Most frequent kind of episode
+    pub dominant_kind: Option<EpisodeKind>,
+    pub strongest: Option<Episode>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_consolidation_and_forgetting() {
+        let mut memory = VoxelMemory::new(2);
+        memory.long_term_capacity = 2;
+        memory.store(Episode::new(1, EpisodeKind::Birth, 0.8, 0.9));
+        memory.store(Episode::new(2, EpisodeKind::Collision, -0.2, 0.1));
+        memory.store(Episode::new(3, EpisodeKind::Collision, -0.2, 0.1));
+        // The important birth was consolidated, nothing was lost yet
+        assert_eq!(memory.episodes.len(), 2);
+        assert_eq!(memory.long_term.len(), 1);
+        assert_eq!(memory.long_term[0].kind, EpisodeKind::Birth);
+        
+        // Unimportant episodes are forgotten when pushed out
+        memory.store(Episode::new(4, EpisodeKind::Bitten, -0.9, 0.6));
+        memory.store(Episode::new(5, EpisodeKind::Fed, 0.5, 0.7));
+        memory.store(Episode::new(6, EpisodeKind::Fed, 0.5, 0.7));
+        assert_eq!(memory.long_term.len(), 2);
+        
+        // A full long-term store replaces its weakest memory with a stronger one
+        memory.store(Episode::new(7, EpisodeKind::Fed, 0.5, 0.7));
+        let kinds: Vec<EpisodeKind> = memory.long_term.iter().map(|m| m.kind).collect();
+        assert!(kinds.contains(&EpisodeKind::Birth) && kinds.contains(&EpisodeKind::Fed));
+        
+        // Recalled memories fade slower
+        assert_eq!(memory.recall(EpisodeKind::Birth).unwrap().recalls, 1);
+        memory.forgetting_rate = 0.7;
+        memory.decay(1.0);
+        let kinds: Vec<EpisodeKind> = memory.long_term.iter().map(|m| m.kind).collect();
+        assert_eq!(kinds, [EpisodeKind::Birth]);
+        assert!((memory.long_term[0].strength - 0.55).abs() < 1e-9);
+        
+        let summary = memory.summarize();
+        assert_eq!((summary.recent, summary.long_term), (2, 1));
+        assert_eq!(summary.dominant_kind, Some(EpisodeKind::Fed));
+        assert_eq!(summary.strongest.unwrap().kind, EpisodeKind::Birth);
+        assert_eq!(memory.top_episodes(1)[0].kind, EpisodeKind::Birth);
+    }
+    
+    #[test]
+    fn test_social_memory() {
+        let mut memory = VoxelMemory::new(4);
+        memory.consolidation_threshold = 0.0;
+        memory.store(Episode::new(1, EpisodeKind::Bitten, -0.8, 0.8).about(7));
+        memory.store(Episode::new(2, EpisodeKind::Collision, -0.2, 0.2).about(7));
+        memory.store(Episode::new(3, EpisodeKind::Fed, 0.6, 0.6).about(9));
+        memory.store(Episode::new(4, EpisodeKind::Collision, -0.2, 0.2));
+        
+        // A grudge against 7, affinity for 9, nothing about a stranger
+        assert!((memory.recall_about(7).unwrap() + 0.68).abs() < 1e-9);
+        assert!((memory.recall_about(9).unwrap() - 0.6).abs() < 1e-9);
+        assert_eq!(memory.recall_about(8), None);
+        
+        // Recalled from long-term memory once the episode is pushed out
+        memory.store(Episode::new(5, EpisodeKind::Collision, -0.2, 0.2));
+        assert_eq!(memory.long_term[0].about, Some(7));
+        assert!(memory.recall_about(7).unwrap() < 0.0);
+        assert_eq!(memory.long_term[0].recalls, 1);
+        
+        memory.retag(|id| (id == 7).then_some(70));
+        assert!(memory.recall_about(70).is_some());
+        assert_eq!(memory.recall_about(9), None);
+    }
+}
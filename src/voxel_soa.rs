@@ -0,0 +1,96 @@
+//! Hot voxel state in components of its own
+//!
+//! `Voxel` carries the cold state (genome, metadata, memory episodes, senses).
+//! The kinematic state every physics pass touches lives in `Position` and
+//! `Velocity`: bevy_ecs keeps each component type in its own table column, so
+//! the movement loop of `integrate` walks two packed arrays instead of whole
+//! voxels. Energy and emotions stay on `Voxel`, where fitness, policies and
+//! evolution read them together with the genome.
+
+use crate::voxel::Voxel;
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Cell a voxel occupies
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Position(pub [i32; 3]);
+
+/// Cells per tick along each axis; saved as `velocity_x`, `velocity_y` and
+/// `velocity_z`, like the fields it replaced on `Voxel`
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "AxisVelocity", into = "AxisVelocity")]
+pub struct Velocity(pub [i8; 3]);
+
+#[derive(Serialize, Deserialize)]
+struct AxisVelocity {
+    #[serde(default)]
+    velocity_x: i8,
+    #[serde(default)]
+    velocity_y: i8,
+    #[serde(default)]
+    velocity_z: i8,
+}
+
+impl From<AxisVelocity> for Velocity {
+    fn from(axes: AxisVelocity) -> Self {
+        Self([axes.velocity_x, axes.velocity_y, axes.velocity_z])
+    }
+}
+
+impl From<Velocity> for AxisVelocity {
+    fn from(Velocity([velocity_x, velocity_y, velocity_z]): Velocity) -> Self {
+        Self { velocity_x, velocity_y, velocity_z }
+    }
+}
+
+/// Integration step over every voxel of `world`: movement over the
+/// `Position` and `Velocity` columns, then resonance energy and trauma mode
+pub fn integrate(world: &mut World, delta_time: f32, trauma: bool) {
+    let mut movement = world.query::<(&mut Position, &Velocity)>();
+    for (mut position, velocity) in movement.iter_mut(world) {
+        for (coordinate, step) in position.0.iter_mut().zip(velocity.0) {
+            *coordinate += i32::from(step);
+        }
+    }
+    let delta_time = f64::from(delta_time);
+    let mut voxels = world.query::<&mut Voxel>();
+    for mut voxel in voxels.iter_mut(world) {
+        voxel.energy += f64::from(voxel.resonance.to_f32()) * delta_time;
+        if trauma {
+            voxel.energy *= 1.5;
+            voxel.emotion_arousal *= 1.3;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use half::f16;
+    
+    #[test]
+    fn test_integrate() {
+        let mut world = World::new();
+        let mut voxel = Voxel::new();
+        voxel.energy = 1.0;
+        voxel.resonance = f16::from_f32(0.5);
+        voxel.emotion_arousal = 0.5;
+        let entity = world.spawn((voxel, Position([1, 2, 3]), Velocity([1, -1, 3]))).id();
+        let resting = world.spawn((Voxel::new(), Position([0, 0, 0]), Velocity::default())).id();
+        
+        integrate(&mut world, 2.0, true);
+        assert_eq!(world.get::<Position>(entity), Some(&Position([2, 1, 6])));
+        assert_eq!(world.get::<Position>(resting), Some(&Position([0, 0, 0])));
+        let voxel = world.get::<Voxel>(entity).unwrap();
+        assert_eq!(voxel.energy, 3.0);
+        assert!((voxel.emotion_arousal - 0.65).abs() < 1e-12);
+    }
+    
+    #[test]
+    fn test_velocity_keeps_the_axis_fields() {
+        let json = serde_json::to_value(Velocity([1, -2, 3])).unwrap();
+        assert_eq!(json, serde_json::json!({ "velocity_x": 1, "velocity_y": -2, "velocity_z": 3 }));
+        assert_eq!(serde_json::from_value::<Velocity>(json).unwrap(), Velocity([1, -2, 3]));
+        assert_eq!(serde_json::from_str::<Position>("[4, 5, 6]").unwrap(), Position([4, 5, 6]));
+    }
+}